@@ -28,7 +28,7 @@ pub(crate) async fn run(options: &Options, raw_session: Option<&str>) -> Result<
         .map_err(|error| crate::Error::Message(format_control_error(error)))?;
 
     let shutdown = micromux::CancellationToken::new();
-    crate::spawn_shutdown_handler(shutdown.clone());
+    crate::spawn_shutdown_handler(shutdown.clone(), micromux::CancellationToken::new());
     let _log_guard = crate::setup_logging(options)?;
     let app = micromux_tui::App::new(
         micromux_tui::SessionSource::Remote(remote),
@@ -140,6 +140,7 @@ mod tests {
                 services: Vec::new(),
                 services_truncated: false,
                 micromux_version: "test".to_string(),
+                profiles: Vec::new(),
                 capabilities: None,
             }],
         });
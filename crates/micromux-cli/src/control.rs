@@ -44,6 +44,7 @@ pub fn spawn(
     config_path: &CanonicalConfigPath,
     working_dir: &Path,
     name: Option<String>,
+    profiles: Vec<String>,
     shutdown: CancellationToken,
 ) -> SpawnStatus {
     if !micromux_control::transport_supported() {
@@ -71,7 +72,8 @@ pub fn spawn(
         }
     };
 
-    let identity = SessionIdentity::new(session_name(name, working_dir), working_dir, config_path);
+    let identity = SessionIdentity::new(session_name(name, working_dir), working_dir, config_path)
+        .with_profiles(profiles);
     let server = Arc::new(ControlServer::new(
         handles.reader.clone(),
         handles.service_control(),
@@ -1,6 +1,7 @@
 //! The `micromux ctl` client: a thin dogfood of the control protocol for humans and scripts.
 
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use micromux_control::{
     Client, ControlEndpoint, EndpointProbe, EndpointProbeResult, Request, Response,
@@ -10,6 +11,9 @@ use micromux_control::{
 
 use crate::options::CtlAction;
 
+/// How often `ctl run` polls the session for the result of the task it started.
+const TASK_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
 fn request_for(action: &CtlAction) -> Request {
     match action {
         CtlAction::Ls => Request::ListServices,
@@ -17,10 +21,12 @@ fn request_for(action: &CtlAction) -> Request {
             service,
             run_generation,
             tail,
+            stream,
         } => Request::GetLogs {
             service: service.clone(),
             run_generation: *run_generation,
             tail: *tail,
+            stream: *stream,
         },
         CtlAction::LogRuns { service } => Request::ListLogRuns {
             service: service.clone(),
@@ -35,10 +41,24 @@ fn request_for(action: &CtlAction) -> Request {
         CtlAction::Disable { service } => Request::Disable {
             service: service.clone(),
         },
+        CtlAction::Signal { service, signal } => Request::Signal {
+            service: service.clone(),
+            signal: *signal,
+        },
+        CtlAction::Pause { service } => Request::Pause {
+            service: service.clone(),
+        },
+        CtlAction::Resume { service } => Request::Resume {
+            service: service.clone(),
+        },
         CtlAction::StopDynamic { service } => Request::StopDynamicService {
             service: service.clone(),
         },
         CtlAction::Reconcile { dry_run } => Request::ReconcileConfig { dry_run: *dry_run },
+        CtlAction::Scale { group, replicas } => Request::ScaleReplicas {
+            group: group.clone(),
+            replicas: *replicas,
+        },
         CtlAction::Health {
             service,
             history: false,
@@ -51,6 +71,17 @@ fn request_for(action: &CtlAction) -> Request {
         } => Request::GetHealthHistory {
             service: service.clone(),
         },
+        CtlAction::Tasks => Request::ListTasks,
+        CtlAction::Run { task } => Request::RunTask { task: task.clone() },
+        CtlAction::TaskLogs {
+            task,
+            run_generation,
+            tail,
+        } => Request::GetTaskLogs {
+            task: task.clone(),
+            run_generation: *run_generation,
+            tail: *tail,
+        },
         CtlAction::Describe => Request::Describe,
         CtlAction::Stop => Request::Shutdown,
     }
@@ -63,6 +94,7 @@ fn service_origin_label(service: &micromux::ServiceSnapshot) -> String {
             format!("dynamic(revision={})", dynamic.revision)
         }
         (micromux::OriginKind::Dynamic, None) => "dynamic".to_string(),
+        (micromux::OriginKind::External, _) => "external".to_string(),
         (micromux::OriginKind::Unknown, _) => "unknown".to_string(),
     }
 }
@@ -84,8 +116,12 @@ fn print_services(services: &[micromux::ServiceSnapshot]) {
         let health = service
             .health
             .map_or_else(|| "-".to_string(), |health| health.to_string());
+        let group = service
+            .replica_group
+            .as_ref()
+            .map_or_else(String::new, |group| format!(" group={group}"));
         println!(
-            "{:<20} id={} origin={} desired={:?} execution={:?} health={} generation={}{}",
+            "{:<20} id={} origin={} desired={:?} execution={:?} health={} generation={}{}{}",
             service.name,
             service.id,
             service_origin_label(service),
@@ -93,6 +129,7 @@ fn print_services(services: &[micromux::ServiceSnapshot]) {
             service.execution,
             health,
             service.run_generation,
+            group,
             service_retired_label(service)
         );
     }
@@ -105,6 +142,36 @@ fn dynamic_receipt_line(receipt: &micromux_control::DynamicServiceAck) -> String
     )
 }
 
+fn task_result_label(result: Option<micromux::HealthResult>) -> String {
+    match result {
+        None => "running".to_string(),
+        Some(result) if result.cancelled => "cancelled".to_string(),
+        Some(result) => format!("success={} exit_code={}", result.success, result.exit_code),
+    }
+}
+
+fn print_tasks(tasks: &[micromux::TaskSnapshot]) {
+    if tasks.is_empty() {
+        println!("no tasks configured");
+    }
+    for task in tasks {
+        let latest = task.latest_run().map_or_else(
+            || "never run".to_string(),
+            |run| {
+                format!(
+                    "generation={} {}",
+                    run.run_generation,
+                    task_result_label(run.result)
+                )
+            },
+        );
+        println!("{:<20} {latest}  `{}`", task.name, task.command);
+        if let Some(description) = &task.description {
+            println!("  {description}");
+        }
+    }
+}
+
 fn print_health_attempt(attempt: &micromux::HealthAttempt) {
     println!(
         "attempt {} `{}` -> {}",
@@ -112,7 +179,23 @@ fn print_health_attempt(attempt: &micromux::HealthAttempt) {
         attempt.command,
         attempt.result.map_or_else(
             || "running".to_string(),
-            |result| format!("success={} exit_code={}", result.success, result.exit_code)
+            |result| {
+                [
+                    Some(format!("success={}", result.success)),
+                    Some(format!("exit_code={}", result.exit_code)),
+                    result.status_code.map(|status| format!("status={status}")),
+                    result
+                        .failed_expectation
+                        .map(|expectation| format!("failed={expectation}")),
+                    result
+                        .latency_ms
+                        .map(|latency| format!("latency={latency}ms")),
+                ]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join(" ")
+            }
         )
     );
     for line in &attempt.output {
@@ -120,6 +203,42 @@ fn print_health_attempt(attempt: &micromux::HealthAttempt) {
     }
 }
 
+fn print_description(info: &SessionInfo) {
+    println!("{} (pid {})", info.name, info.pid);
+    println!("  config:  {}", info.config_path);
+    println!("  cwd:     {}", info.working_dir);
+    println!("  version: {}", info.micromux_version);
+    if !info.profiles.is_empty() {
+        println!("  profiles: {}", info.profiles.join(", "));
+    }
+    println!("  services:");
+    for service in &info.services {
+        println!("    - {} ({})", service.name, service.id);
+    }
+    if info.services_truncated {
+        println!("    … service index truncated by server limits");
+    }
+}
+
+fn print_reconcile(receipt: &micromux::ReconcileReceipt) {
+    println!(
+        "reconcile {}{}",
+        receipt.config_path,
+        if receipt.dry_run { " (dry run)" } else { "" }
+    );
+    if receipt.actions.is_empty() {
+        println!("  no changes");
+    }
+    for action in &receipt.actions {
+        let action_name = match action.action {
+            micromux::ReconcileActionKind::Added => "added",
+            micromux::ReconcileActionKind::Removed => "removed",
+            micromux::ReconcileActionKind::Changed => "changed",
+        };
+        println!("  {action_name:<7} {}: {}", action.service, action.detail);
+    }
+}
+
 fn print_response(response: &Response) -> Result<(), crate::Error> {
     match response {
         Response::Services(services) => print_services(services),
@@ -162,19 +281,7 @@ fn print_response(response: &Response) -> Result<(), crate::Error> {
                 eprintln!("event response truncated by server limits");
             }
         }
-        Response::Description(info) => {
-            println!("{} (pid {})", info.name, info.pid);
-            println!("  config:  {}", info.config_path);
-            println!("  cwd:     {}", info.working_dir);
-            println!("  version: {}", info.micromux_version);
-            println!("  services:");
-            for service in &info.services {
-                println!("    - {} ({})", service.name, service.id);
-            }
-            if info.services_truncated {
-                println!("    … service index truncated by server limits");
-            }
-        }
+        Response::Description(info) => print_description(info),
         Response::Accepted { services } => {
             if services.is_empty() {
                 println!("accepted (no services affected)");
@@ -189,27 +296,14 @@ fn print_response(response: &Response) -> Result<(), crate::Error> {
         Response::DynamicService(receipt) => {
             println!("{}", dynamic_receipt_line(receipt));
         }
-        Response::Reconcile(receipt) => {
-            println!(
-                "reconcile {}{}",
-                receipt.config_path,
-                if receipt.dry_run { " (dry run)" } else { "" }
-            );
-            if receipt.actions.is_empty() {
-                println!("  no changes");
-            }
-            for action in &receipt.actions {
-                let action_name = match action.action {
-                    micromux::ReconcileActionKind::Added => "added",
-                    micromux::ReconcileActionKind::Removed => "removed",
-                    micromux::ReconcileActionKind::Changed => "changed",
-                };
-                println!("  {action_name:<7} {}: {}", action.service, action.detail);
-            }
-        }
+        Response::Reconcile(receipt) => print_reconcile(receipt),
         Response::Error { code, message } => {
             return Err(crate::Error::Message(format!("{code:?}: {message}")));
         }
+        Response::Tasks(tasks) => print_tasks(tasks),
+        Response::TaskStarted(ack) => {
+            println!("started {} (generation {})", ack.task, ack.run_generation);
+        }
         Response::ShuttingDown => {
             println!("session is shutting down");
         }
@@ -425,12 +519,79 @@ pub async fn run(action: CtlAction, config_path: Option<&Path>) -> Result<(), cr
         connect_project_session(&dir_statuses, &runtime_dirs, &working_dir, &config_path).await?;
 
     let response = client.request(request_for(&action)).await?;
+    if let (CtlAction::Run { task }, Response::TaskStarted(ack)) = (&action, &response) {
+        return wait_for_task(&mut client, task, ack.run_generation).await;
+    }
     print_response(&response)
 }
 
+/// Poll until run `run_generation` of `task` finishes, print its output, and fail unless it
+/// succeeded.
+async fn wait_for_task(
+    client: &mut Client,
+    task: &str,
+    run_generation: u64,
+) -> Result<(), crate::Error> {
+    let result = loop {
+        let response = client.request(Request::ListTasks).await?;
+        let Response::Tasks(tasks) = response else {
+            print_response(&response)?;
+            return Err(crate::Error::Message(format!(
+                "unexpected response while waiting for task `{task}`"
+            )));
+        };
+        let Some(run) = tasks
+            .iter()
+            .find(|snapshot| snapshot.name == task)
+            .and_then(|snapshot| {
+                snapshot
+                    .runs
+                    .iter()
+                    .find(|run| run.run_generation == run_generation)
+            })
+        else {
+            return Err(crate::Error::Message(format!(
+                "task `{task}` run {run_generation} is no longer retained"
+            )));
+        };
+        if let Some(result) = run.result {
+            break result;
+        }
+        tokio::time::sleep(TASK_POLL_INTERVAL).await;
+    };
+
+    let logs = client
+        .request(Request::GetTaskLogs {
+            task: task.to_string(),
+            run_generation: Some(run_generation),
+            tail: None,
+        })
+        .await?;
+    print_response(&logs)?;
+    task_outcome(task, result)
+}
+
+fn task_outcome(task: &str, result: micromux::HealthResult) -> Result<(), crate::Error> {
+    if result.cancelled {
+        return Err(crate::Error::Message(format!(
+            "task `{task}` was cancelled"
+        )));
+    }
+    if !result.success {
+        return Err(crate::Error::Message(format!(
+            "task `{task}` exited with code {}",
+            result.exit_code
+        )));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{dynamic_receipt_line, request_for, service_origin_label, service_retired_label};
+    use super::{
+        dynamic_receipt_line, request_for, service_origin_label, service_retired_label,
+        task_result_label,
+    };
     use crate::options::CtlAction;
     use micromux_control::{DynamicServiceAck, Request};
     use similar_asserts::assert_eq;
@@ -447,6 +608,47 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn scale_maps_to_the_control_request() {
+        assert!(matches!(
+            request_for(&CtlAction::Scale {
+                group: "worker".to_string(),
+                replicas: 3,
+            }),
+            Request::ScaleReplicas { group, replicas: 3 } if group == "worker"
+        ));
+    }
+
+    #[test]
+    fn signal_maps_to_the_control_request() {
+        assert!(matches!(
+            request_for(&CtlAction::Signal {
+                service: "api".to_string(),
+                signal: micromux::ServiceSignal::Usr1,
+            }),
+            Request::Signal {
+                service,
+                signal: micromux::ServiceSignal::Usr1,
+            } if service == "api"
+        ));
+    }
+
+    #[test]
+    fn pause_and_resume_map_to_control_requests() {
+        assert!(matches!(
+            request_for(&CtlAction::Pause {
+                service: "api".to_string(),
+            }),
+            Request::Pause { service } if service == "api"
+        ));
+        assert!(matches!(
+            request_for(&CtlAction::Resume {
+                service: "api".to_string(),
+            }),
+            Request::Resume { service } if service == "api"
+        ));
+    }
+
     #[test]
     fn health_maps_to_latest_or_history_by_flag() {
         assert!(matches!(
@@ -465,6 +667,47 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn tasks_map_to_control_requests_and_label_their_result() {
+        assert!(matches!(request_for(&CtlAction::Tasks), Request::ListTasks));
+        assert!(matches!(
+            request_for(&CtlAction::Run {
+                task: "seed".to_string(),
+            }),
+            Request::RunTask { task } if task == "seed"
+        ));
+        assert!(matches!(
+            request_for(&CtlAction::TaskLogs {
+                task: "seed".to_string(),
+                run_generation: None,
+                tail: Some(20),
+            }),
+            Request::GetTaskLogs {
+                task,
+                run_generation: None,
+                tail: Some(20),
+            } if task == "seed"
+        ));
+
+        assert_eq!(task_result_label(None), "running");
+        let result = micromux::HealthResult {
+            success: false,
+            exit_code: 3,
+            cancelled: false,
+            latency_ms: None,
+            status_code: None,
+            failed_expectation: None,
+        };
+        assert_eq!(task_result_label(Some(result)), "success=false exit_code=3");
+        assert_eq!(
+            task_result_label(Some(micromux::HealthResult {
+                cancelled: true,
+                ..result
+            })),
+            "cancelled"
+        );
+    }
+
     #[test]
     fn retirement_is_visible_for_every_origin_without_debug_formatting() {
         let mut snapshot = micromux::ServiceSnapshot::initial(
@@ -15,7 +15,7 @@ mod options;
 
 use clap::Parser;
 use codespan_reporting::diagnostic::Diagnostic;
-use micromux::{Printer as DiagnosticsPrinter, ToDiagnostics};
+use micromux::Printer as DiagnosticsPrinter;
 
 #[derive(Debug, thiserror::Error)]
 enum Error {
@@ -40,53 +40,78 @@ enum Error {
     Message(String),
 }
 
-fn spawn_shutdown_handler(shutdown: micromux::CancellationToken) {
-    tokio::spawn(async move {
-        let ctrl_c = async {
-            let _ = tokio::signal::ctrl_c().await;
-        };
+/// The process signals that ask the session to shut down: SIGINT, SIGTERM, and SIGHUP.
+struct ShutdownSignals {
+    #[cfg(unix)]
+    terminate: Option<tokio::signal::unix::Signal>,
+    #[cfg(unix)]
+    hangup: Option<tokio::signal::unix::Signal>,
+}
 
+impl ShutdownSignals {
+    fn install() -> Self {
         #[cfg(unix)]
-        let terminate = async {
+        {
             use tokio::signal::unix::{SignalKind, signal};
-            match signal(SignalKind::terminate()) {
-                Ok(mut sigterm) => {
-                    sigterm.recv().await;
-                }
-                Err(err) => {
-                    tracing::warn!(?err, "failed to install SIGTERM handler");
-                    std::future::pending::<()>().await;
-                }
+            let install = |kind, name: &str| {
+                signal(kind)
+                    .inspect_err(|err| tracing::warn!(?err, "failed to install {name} handler"))
+                    .ok()
+            };
+            Self {
+                terminate: install(SignalKind::terminate(), "SIGTERM"),
+                hangup: install(SignalKind::hangup(), "SIGHUP"),
             }
-        };
-
+        }
         #[cfg(not(unix))]
-        let terminate = std::future::pending::<()>();
+        Self {}
+    }
 
+    async fn recv(&mut self) {
         #[cfg(unix)]
-        let hangup = async {
-            use tokio::signal::unix::{SignalKind, signal};
-            match signal(SignalKind::hangup()) {
-                Ok(mut sighup) => {
-                    sighup.recv().await;
-                }
-                Err(err) => {
-                    tracing::warn!(?err, "failed to install SIGHUP handler");
-                    std::future::pending::<()>().await;
-                }
+        let other = async {
+            tokio::select! {
+                () = recv_signal(self.terminate.as_mut()) => {},
+                () = recv_signal(self.hangup.as_mut()) => {},
             }
         };
-
         #[cfg(not(unix))]
-        let hangup = std::future::pending::<()>();
+        let other = std::future::pending::<()>();
 
         tokio::select! {
-            () = ctrl_c => {},
-            () = terminate => {},
-            () = hangup => {},
+            _ = tokio::signal::ctrl_c() => {},
+            () = other => {},
         }
+    }
+}
+
+#[cfg(unix)]
+async fn recv_signal(signal: Option<&mut tokio::signal::unix::Signal>) {
+    match signal {
+        Some(signal) => {
+            signal.recv().await;
+        }
+        // A handler that failed to install never fires.
+        None => std::future::pending::<()>().await,
+    }
+}
 
-        shutdown.cancel();
+/// Cancel `shutdown` on the first shutdown signal. A signal received once shutdown has begun,
+/// however it began, cancels `cleanup_abort`, which kills cleanup hooks still running instead of
+/// waiting out their timeout.
+fn spawn_shutdown_handler(
+    shutdown: micromux::CancellationToken,
+    cleanup_abort: micromux::CancellationToken,
+) {
+    tokio::spawn(async move {
+        let mut signals = ShutdownSignals::install();
+        tokio::select! {
+            () = signals.recv() => shutdown.cancel(),
+            () = shutdown.cancelled() => {},
+        }
+        signals.recv().await;
+        tracing::warn!("second shutdown signal; killing cleanup hooks");
+        cleanup_abort.cancel();
     });
 }
 
@@ -163,25 +188,21 @@ async fn load_config(
     let config_path = config_path
         .ok_or_else(|| Error::Message("missing config file".to_string()))?
         .canonicalize()?;
-    let config_dir = config_path
-        .parent()
-        .ok_or_else(|| Error::Message("failed to get config file".to_string()))?;
-
-    let raw_config = micromux::read_config_file_async(&config_path).await?;
 
-    let diagnostic_printer = DiagnosticsPrinter::new(color_choice);
-    let file_id = diagnostic_printer.add_source_file(&config_path, raw_config.clone());
+    let mut diagnostic_printer = DiagnosticsPrinter::new(color_choice);
     let mut diagnostics: Vec<Diagnostic<usize>> = vec![];
 
-    let config = match micromux::from_str(
-        &raw_config,
-        config_dir,
-        file_id,
+    // Also merges the config's `include:` files and its override file, registering each with the
+    // printer so diagnostics point into the right file.
+    let config = match micromux::load_config_file(
+        &config_path,
         options.strict,
+        &mut diagnostic_printer,
         &mut diagnostics,
     ) {
+        Err(micromux::LoadError::Io { source, .. }) => return Err(source.into()),
         Err(err) => {
-            diagnostics.extend(err.to_diagnostics(file_id));
+            diagnostics.extend(err.to_diagnostics());
             None
         }
         Ok(config) => Some(config),
@@ -201,7 +222,7 @@ async fn load_config(
         return Err(Error::Message("failed to parse config".to_string()));
     }
 
-    config.config_path = Some(config_path);
+    config.profiles.clone_from(&options.profiles);
     Ok(config)
 }
 
@@ -230,7 +251,8 @@ async fn run() -> Result<(), Error> {
     }
 
     let shutdown = micromux::CancellationToken::new();
-    spawn_shutdown_handler(shutdown.clone());
+    let cleanup_abort = micromux::CancellationToken::new();
+    spawn_shutdown_handler(shutdown.clone(), cleanup_abort.clone());
 
     let color_choice = options.color_choice.unwrap_or(termcolor::ColorChoice::Auto);
     // Hold the guard for the whole program: dropping it shuts down the non-blocking log writer
@@ -239,7 +261,8 @@ async fn run() -> Result<(), Error> {
 
     let config = load_config(&options, color_choice).await?;
 
-    let mux = std::sync::Arc::new(micromux::Micromux::new(&config)?);
+    let mux =
+        std::sync::Arc::new(micromux::Micromux::new(&config)?.with_cleanup_abort(cleanup_abort));
     let (runner, handles) = mux.clone().start(shutdown.clone());
 
     // Default-on control plane, opt out via `--no-control` or `control: { enabled: false }`.
@@ -253,6 +276,7 @@ async fn run() -> Result<(), Error> {
                     &config_path,
                     &working_dir,
                     config.config.name.clone(),
+                    config.profiles.clone(),
                     shutdown.clone(),
                 ) {
                     control::SpawnStatus::Started => {}
@@ -324,14 +348,16 @@ async fn run() -> Result<(), Error> {
 /// `start_session` tool.
 async fn run_headless(options: options::Options) -> Result<(), Error> {
     let shutdown = micromux::CancellationToken::new();
-    spawn_shutdown_handler(shutdown.clone());
+    let cleanup_abort = micromux::CancellationToken::new();
+    spawn_shutdown_handler(shutdown.clone(), cleanup_abort.clone());
 
     // Hold the guard for the whole run: dropping it stops the non-blocking log writer.
     let _log_guard = setup_logging(&options)?;
     let color_choice = options.color_choice.unwrap_or(termcolor::ColorChoice::Auto);
     let config = load_config(&options, color_choice).await?;
 
-    let mux = std::sync::Arc::new(micromux::Micromux::new(&config)?);
+    let mux =
+        std::sync::Arc::new(micromux::Micromux::new(&config)?.with_cleanup_abort(cleanup_abort));
     let (runner, handles) = mux.clone().start(shutdown.clone());
 
     // The control plane is the only way to reach a headless session, so it is mandatory here — the
@@ -344,6 +370,7 @@ async fn run_headless(options: options::Options) -> Result<(), Error> {
         &config_path,
         &working_dir,
         config.config.name.clone(),
+        config.profiles.clone(),
         shutdown.clone(),
     );
     if !matches!(status, control::SpawnStatus::Started) {
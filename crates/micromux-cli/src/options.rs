@@ -40,6 +40,15 @@ pub struct Options {
     #[arg(long = "strict", env = "MICROMUX_STRICT", help = "enable strict mode")]
     pub strict: Option<bool>,
 
+    #[arg(
+        long = "profile",
+        env = "MICROMUX_PROFILES",
+        value_delimiter = ',',
+        global = true,
+        help = "activate a service profile; services assigned to other profiles start disabled (repeatable)"
+    )]
+    pub profiles: Vec<String>,
+
     #[arg(
         long = "color",
         env = "MICROMUX_COLOR",
@@ -108,7 +117,7 @@ pub enum Command {
 
 #[cfg(test)]
 mod tests {
-    use super::{Command, Options};
+    use super::{Command, CtlAction, Options};
     use clap::Parser;
     use similar_asserts::assert_eq;
 
@@ -138,6 +147,89 @@ mod tests {
         ));
         Ok(())
     }
+
+    #[test]
+    fn ctl_signal_parses_names_and_rejects_unsupported_signals() -> Result<(), clap::Error> {
+        let options = Options::try_parse_from(["micromux", "ctl", "signal", "api", "usr1"])?;
+        assert!(matches!(
+            options.command,
+            Some(Command::Ctl {
+                action: CtlAction::Signal {
+                    service,
+                    signal: micromux::ServiceSignal::Usr1,
+                },
+            }) if service == "api"
+        ));
+
+        assert!(Options::try_parse_from(["micromux", "ctl", "signal", "api", "STOP"]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn ctl_logs_parses_an_output_stream() -> Result<(), clap::Error> {
+        let options =
+            Options::try_parse_from(["micromux", "ctl", "logs", "api", "--stream", "stderr"])?;
+        assert!(matches!(
+            options.command,
+            Some(Command::Ctl {
+                action: CtlAction::Logs {
+                    stream: Some(micromux::OutputStream::Stderr),
+                    ..
+                },
+            })
+        ));
+
+        assert!(
+            Options::try_parse_from(["micromux", "ctl", "logs", "api", "--stream", "tty"]).is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn ctl_run_and_task_logs_parse_a_task_name() -> Result<(), clap::Error> {
+        let options = Options::try_parse_from(["micromux", "ctl", "run", "seed-db"])?;
+        assert!(matches!(
+            options.command,
+            Some(Command::Ctl {
+                action: CtlAction::Run { task },
+            }) if task == "seed-db"
+        ));
+
+        let options = Options::try_parse_from([
+            "micromux",
+            "ctl",
+            "task-logs",
+            "seed-db",
+            "--run-generation",
+            "2",
+        ])?;
+        assert!(matches!(
+            options.command,
+            Some(Command::Ctl {
+                action: CtlAction::TaskLogs {
+                    task,
+                    run_generation: Some(2),
+                    tail: None,
+                },
+            }) if task == "seed-db"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn profiles_repeat_split_on_commas_and_apply_to_serve() -> Result<(), clap::Error> {
+        let options = Options::try_parse_from([
+            "micromux",
+            "serve",
+            "--profile",
+            "backend,jobs",
+            "--profile",
+            "debug",
+        ])?;
+        assert_eq!(options.profiles, vec!["backend", "jobs", "debug"]);
+        assert!(matches!(options.command, Some(Command::Serve)));
+        Ok(())
+    }
 }
 
 /// An action for the `micromux ctl` client.
@@ -155,6 +247,10 @@ pub enum CtlAction {
         /// Bound the result to the most recent lines.
         #[arg(long)]
         tail: Option<usize>,
+        /// Keep only lines from one output stream (`stdout` or `stderr`). Only services with
+        /// `tty: false` separate their streams.
+        #[arg(long, value_parser = parse_output_stream)]
+        stream: Option<micromux::OutputStream>,
     },
     /// List retained log runs for a service.
     LogRuns {
@@ -163,19 +259,36 @@ pub enum CtlAction {
     },
     /// Restart a service.
     Restart {
-        /// The service to restart.
+        /// The service, or replica group, to restart.
         service: String,
     },
     /// Restart all enabled services.
     RestartAll,
     /// Enable (and start) a service.
     Enable {
-        /// The service to enable.
+        /// The service, or replica group, to enable.
         service: String,
     },
     /// Disable a service.
     Disable {
-        /// The service to disable.
+        /// The service, or replica group, to disable.
+        service: String,
+    },
+    /// Send a signal to a running service's process group.
+    Signal {
+        /// The service to signal.
+        service: String,
+        /// The signal name, with or without the `SIG` prefix (e.g. `HUP`, `SIGUSR1`).
+        signal: micromux::ServiceSignal,
+    },
+    /// Pause a running service (SIGSTOP its process group).
+    Pause {
+        /// The service to pause.
+        service: String,
+    },
+    /// Resume a paused service (SIGCONT its process group).
+    Resume {
+        /// The service to resume.
         service: String,
     },
     /// Retire a dynamic service while preserving its post-mortem state.
@@ -189,6 +302,13 @@ pub enum CtlAction {
         #[arg(long)]
         dry_run: bool,
     },
+    /// Scale a replica group to a number of instances.
+    Scale {
+        /// The replica group: the name of the service that sets `replicas`.
+        group: String,
+        /// The number of instances to run.
+        replicas: usize,
+    },
     /// Show the latest healthcheck attempt for a service's current live run.
     Health {
         /// The service to inspect.
@@ -197,8 +317,34 @@ pub enum CtlAction {
         #[arg(long)]
         history: bool,
     },
+    /// List the configured on-demand tasks and their latest runs.
+    Tasks,
+    /// Run an on-demand task, wait for it to finish, and print its output.
+    Run {
+        /// The task to run.
+        task: String,
+    },
+    /// Print the output of a retained task run.
+    TaskLogs {
+        /// The task to read output from.
+        task: String,
+        /// Read a specific retained run generation instead of the latest run.
+        #[arg(long)]
+        run_generation: Option<u64>,
+        /// Bound the result to the most recent lines.
+        #[arg(long)]
+        tail: Option<usize>,
+    },
     /// Show the session identity.
     Describe,
     /// Stop the session: stop all services and exit, freeing its ports.
     Stop,
 }
+
+fn parse_output_stream(raw: &str) -> Result<micromux::OutputStream, String> {
+    match raw.to_ascii_lowercase().as_str() {
+        "stdout" => Ok(micromux::OutputStream::Stdout),
+        "stderr" => Ok(micromux::OutputStream::Stderr),
+        _ => Err(format!("unknown stream `{raw}`; expected stdout or stderr")),
+    }
+}
@@ -491,6 +491,7 @@ mod tests {
             services: Vec::new(),
             services_truncated: false,
             micromux_version: env!("CARGO_PKG_VERSION").to_string(),
+            profiles: Vec::new(),
             capabilities: None,
         }
     }
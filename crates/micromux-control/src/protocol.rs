@@ -1,14 +1,15 @@
 //! The control wire protocol: newline-delimited JSON request/response envelopes.
 //!
 //! Domain payloads (`ServiceSnapshot`, `HealthAttempt`, `LogLine`, `SessionChange`,
-//! `ServiceCommandAck`, `DynamicServiceAck`, `ReconcileReceipt`, `ServiceEvent`, and
-//! `DynamicServiceParams`) are the stable core types reused directly — no DTO mirror. The session
-//! and the proxy accept peers that speak the same major protocol version, so additive payload
-//! changes do not orphan already-running sessions.
+//! `ServiceCommandAck`, `DynamicServiceAck`, `ReconcileReceipt`, `ServiceEvent`, `TaskSnapshot`,
+//! `TaskRunAck`, and `DynamicServiceParams`) are the stable core types reused directly — no DTO
+//! mirror. The session and the proxy accept peers that speak the same major protocol version, so
+//! additive payload changes do not orphan already-running sessions.
 
 use micromux::{
     DynamicServiceAck, DynamicServiceParams, HealthAttempt, Lease, LogLine, LogRunSummary,
-    ReconcileReceipt, ServiceCommandAck, ServiceEvent, ServiceID, ServiceSnapshot, SessionChange,
+    OutputStream, ReconcileReceipt, ServiceCommandAck, ServiceEvent, ServiceID, ServiceSignal,
+    ServiceSnapshot, SessionChange, TaskRunAck, TaskSnapshot,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -17,7 +18,7 @@ use serde::{Deserialize, Serialize};
 ///
 /// Bump the minor for additive changes (new optional/defaulted fields, new tools that reuse
 /// existing requests), and bump the major for incompatible request/response semantics.
-pub const PROTOCOL_VERSION: ProtocolVersion = ProtocolVersion::new(3, 9);
+pub const PROTOCOL_VERSION: ProtocolVersion = ProtocolVersion::new(3, 28);
 
 pub(crate) const fn supports_versioned_subscriptions(version: ProtocolVersion) -> bool {
     version.major() == PROTOCOL_VERSION.major() && version.minor() >= 7
@@ -88,6 +89,10 @@ pub enum Request {
         run_generation: Option<u64>,
         /// Bound the result to the most recent records.
         tail: Option<usize>,
+        /// Keep only records from this output stream. Terminal (`tty: true`) output and supervisor
+        /// banners carry no stream and never match.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        stream: Option<OutputStream>,
     },
     /// Return log records strictly after a monotonic cursor, for gap-free incremental following.
     FollowLogs {
@@ -144,6 +149,23 @@ pub enum Request {
         /// Target service.
         service: ServiceID,
     },
+    /// Send a signal to a running service's process group.
+    Signal {
+        /// Target service.
+        service: ServiceID,
+        /// Signal to deliver.
+        signal: ServiceSignal,
+    },
+    /// Suspend a running service's process group.
+    Pause {
+        /// Target service.
+        service: ServiceID,
+    },
+    /// Continue a paused service's process group.
+    Resume {
+        /// Target service.
+        service: ServiceID,
+    },
     /// Create and start a dynamic service.
     StartDynamicService {
         /// Definition, clone source, and lease request.
@@ -180,6 +202,32 @@ pub enum Request {
         /// field: omission must never silently mean "apply".
         dry_run: bool,
     },
+    /// Scale a replica group to a number of instances, adding or retiring instances like config
+    /// reconciliation does. The count lasts for the session unless the config file changes the
+    /// group's own `replicas`.
+    ScaleReplicas {
+        /// Replica group: the name of the service definition that sets `replicas`.
+        group: String,
+        /// Instance count to scale to.
+        replicas: usize,
+    },
+    /// List the configured on-demand tasks with their retained runs.
+    ListTasks,
+    /// Start a run of an on-demand task. Acknowledged with [`Response::TaskStarted`] once the run
+    /// has started; poll [`Request::ListTasks`] for its result.
+    RunTask {
+        /// Task name.
+        task: String,
+    },
+    /// Return the retained log of one task run.
+    GetTaskLogs {
+        /// Task name.
+        task: String,
+        /// Run to read. Omit to read the latest run.
+        run_generation: Option<u64>,
+        /// Bound the result to the most recent records.
+        tail: Option<usize>,
+    },
     /// Stop the whole session: stop every service and exit the session process (graceful, like the
     /// operator pressing Ctrl-C), freeing its ports. Acknowledged with [`Response::ShuttingDown`]
     /// just before the endpoint goes away.
@@ -211,6 +259,8 @@ impl Request {
                 | Self::GetHealth { .. }
                 | Self::GetHealthHistory { .. }
                 | Self::GetEvents { .. }
+                | Self::ListTasks
+                | Self::GetTaskLogs { .. }
                 | Self::ReconcileConfig { dry_run: true }
         )
     }
@@ -246,6 +296,9 @@ pub struct SessionInfo {
     /// The canonical config path that keys this session's endpoint.
     #[serde(default)]
     pub config_path: String,
+    /// Profiles the session was started with; empty when none were selected.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub profiles: Vec<String>,
     /// The services this session supervises.
     #[serde(default)]
     pub services: Vec<ServiceBrief>,
@@ -346,6 +399,8 @@ pub enum ErrorCode {
     UnknownService,
     /// The requested service run is no longer retained or never existed.
     UnknownRun,
+    /// No task with the given name is configured.
+    UnknownTask,
     /// No session answered the request.
     NoSession,
     /// More than one live session matched the selector.
@@ -433,6 +488,10 @@ pub enum Response {
     DynamicService(DynamicServiceAck),
     /// Reply to a config reconciliation.
     Reconcile(ReconcileReceipt),
+    /// Reply to [`Request::ListTasks`].
+    Tasks(Vec<TaskSnapshot>),
+    /// Reply to [`Request::RunTask`]: the run has started.
+    TaskStarted(TaskRunAck),
     /// A streamed change notification (only after [`Request::Subscribe`]).
     Change(SessionChange),
     /// Acknowledgement of [`Request::Shutdown`], written just before the session begins exiting.
@@ -477,6 +536,7 @@ mod tests {
             services: Vec::new(),
             services_truncated: false,
             micromux_version: env!("CARGO_PKG_VERSION").to_string(),
+            profiles: Vec::new(),
             capabilities: None,
         }
     }
@@ -495,14 +555,14 @@ mod tests {
     fn protocol_version_uses_major_minor_shape_and_accepts_same_major() {
         assert_eq!(
             serde_json::to_value(PROTOCOL_VERSION).unwrap(),
-            json!({ "major": 3, "minor": 9 })
+            json!({ "major": 3, "minor": 28 })
         );
         assert_eq!(
             serde_json::from_value::<ProtocolVersion>(json!({ "major": 1, "minor": 0 })).unwrap(),
             ProtocolVersion::new(1, 0)
         );
 
-        assert!(PROTOCOL_VERSION.is_compatible_with(ProtocolVersion::new(3, 10)));
+        assert!(PROTOCOL_VERSION.is_compatible_with(ProtocolVersion::new(3, 29)));
         assert!(!PROTOCOL_VERSION.is_compatible_with(ProtocolVersion::new(2, 9)));
         assert!(!supports_versioned_subscriptions(ProtocolVersion::new(
             3, 6
@@ -552,9 +612,27 @@ mod tests {
         assert!(info.services.is_empty());
         assert!(!info.services_truncated);
         assert_eq!(info.micromux_version, "");
+        assert!(info.profiles.is_empty());
         assert!(info.capabilities.is_none());
     }
 
+    #[test]
+    fn session_info_omits_profiles_when_none_were_selected() {
+        let mut info = session("abc", 42, 99, "demo");
+        assert!(
+            serde_json::to_value(&info)
+                .unwrap()
+                .get("profiles")
+                .is_none()
+        );
+
+        info.profiles = vec!["backend".to_string()];
+        assert_eq!(
+            serde_json::to_value(&info).unwrap()["profiles"],
+            json!(["backend"])
+        );
+    }
+
     #[test]
     fn disk_log_read_health_accepts_missing_timeout_history() {
         let health = serde_json::from_value::<DiskLogReadHealth>(json!({
@@ -712,7 +790,7 @@ mod tests {
     fn protocol_version_ignores_additive_fields() {
         let version = serde_json::from_value::<ProtocolVersion>(json!({
             "major": 3,
-            "minor": 9,
+            "minor": 28,
             "future_capability": true
         }))
         .unwrap();
@@ -730,8 +808,43 @@ mod tests {
             .is_retry_safe()
         );
         assert!(Request::ReconcileConfig { dry_run: true }.is_retry_safe());
+        assert!(Request::ListTasks.is_retry_safe());
+        assert!(
+            Request::GetTaskLogs {
+                task: "seed".to_string(),
+                run_generation: None,
+                tail: None,
+            }
+            .is_retry_safe()
+        );
         assert!(!Request::RestartAll.is_retry_safe());
+        assert!(
+            !Request::RunTask {
+                task: "seed".to_string(),
+            }
+            .is_retry_safe()
+        );
+        assert!(
+            !Request::Signal {
+                service: "api".to_string(),
+                signal: micromux::ServiceSignal::Hup,
+            }
+            .is_retry_safe()
+        );
+        assert!(
+            !Request::Pause {
+                service: "api".to_string(),
+            }
+            .is_retry_safe()
+        );
         assert!(!Request::ReconcileConfig { dry_run: false }.is_retry_safe());
+        assert!(
+            !Request::ScaleReplicas {
+                group: "worker".to_string(),
+                replicas: 3,
+            }
+            .is_retry_safe()
+        );
         assert!(!Request::Shutdown.is_retry_safe());
     }
 
@@ -45,6 +45,8 @@ pub struct SessionIdentity {
     pub config_path: String,
     /// The micromux version of the session binary.
     pub micromux_version: String,
+    /// Profiles the session was started with.
+    pub profiles: Vec<String>,
 }
 
 impl SessionIdentity {
@@ -59,8 +61,16 @@ impl SessionIdentity {
             working_dir: working_dir.to_string_lossy().into_owned(),
             config_path: config_path.to_string_lossy().into_owned(),
             micromux_version: env!("CARGO_PKG_VERSION").to_string(),
+            profiles: Vec::new(),
         }
     }
+
+    /// Record the profiles the session was started with, reported by `Describe`.
+    #[must_use]
+    pub fn with_profiles(mut self, profiles: Vec<String>) -> Self {
+        self.profiles = profiles;
+        self
+    }
 }
 
 fn session_start_token() -> u64 {
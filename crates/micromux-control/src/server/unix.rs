@@ -461,6 +461,12 @@ async fn stream_changes<S>(
             }
             change = changes.recv() => {
                 match change {
+                    // Hook history is not served over the protocol and task runs are polled with
+                    // `ListTasks`, so their changes are noise.
+                    Ok(SessionChange {
+                        kind: ChangeKind::Hooks | ChangeKind::Tasks,
+                        ..
+                    }) => {}
                     Ok(change) => {
                         if !send_stream_change(&mut sink, change, &shutdown).await {
                             break;
@@ -528,30 +534,13 @@ async fn dispatch(server: &ControlServer, request: Request) -> Response {
     match request {
         Request::Describe => Response::Description(describe(server)),
         Request::ListServices => Response::Services(server.reader.services()),
-        Request::GetService { service } => {
-            let snapshots = server.reader.services();
-            snapshots
-                .iter()
-                .find(|snapshot| snapshot.id == service)
-                .or_else(|| snapshots.iter().find(|snapshot| snapshot.name == service))
-                .cloned()
-                .map_or_else(
-                    || unknown_service(&service),
-                    |snapshot| Response::Service(Box::new(snapshot)),
-                )
-        }
+        Request::GetService { service } => get_service(&server.reader, &service),
         Request::GetLogs {
             service,
             run_generation,
             tail,
-        } => {
-            if run_generation.is_none() {
-                get_logs(&server.reader, &service, None, tail)
-            } else {
-                let reader = server.reader.clone();
-                log_reads::run(move || get_logs(&reader, &service, run_generation, tail)).await
-            }
-        }
+            stream,
+        } => read_logs(server, service, run_generation, tail, stream).await,
         Request::FollowLogs {
             service,
             run_generation,
@@ -568,14 +557,7 @@ async fn dispatch(server: &ControlServer, request: Request) -> Response {
             let reader = server.reader.clone();
             log_reads::run(move || list_log_runs(&reader, &service)).await
         }
-        Request::GetHealth { service } => {
-            if server.reader.service(&service).is_none() {
-                return unknown_service(&service);
-            }
-            let mut attempt = server.reader.latest_health(&service);
-            bound_health_attempt(&mut attempt);
-            Response::Health(attempt)
-        }
+        Request::GetHealth { service } => get_health(&server.reader, &service),
         Request::GetHealthHistory { service } => get_health_history(&server.reader, &service),
         Request::GetEvents {
             service,
@@ -586,6 +568,11 @@ async fn dispatch(server: &ControlServer, request: Request) -> Response {
         Request::RestartAll => acknowledge(server.control.restart_all().await),
         Request::Enable { service } => acknowledge(server.control.enable(&service).await),
         Request::Disable { service } => acknowledge(server.control.disable(&service).await),
+        Request::Signal { service, signal } => {
+            acknowledge(server.control.signal(&service, signal).await)
+        }
+        Request::Pause { service } => acknowledge(server.control.pause(&service).await),
+        Request::Resume { service } => acknowledge(server.control.resume(&service).await),
         Request::StartDynamicService { params } => {
             acknowledge_dynamic(server.control.start_dynamic(params).await)
         }
@@ -613,6 +600,21 @@ async fn dispatch(server: &ControlServer, request: Request) -> Response {
             acknowledge_dynamic(server.control.stop_dynamic(&service).await)
         }
         Request::ReconcileConfig { dry_run } => reconcile(server, dry_run).await,
+        Request::ListTasks => Response::Tasks(server.reader.tasks()),
+        Request::RunTask { task } => {
+            acknowledge_with(server.control.run_task(&task).await, Response::TaskStarted)
+        }
+        Request::GetTaskLogs {
+            task,
+            run_generation,
+            tail,
+        } => {
+            let reader = server.reader.clone();
+            log_reads::run(move || get_task_logs(&reader, &task, run_generation, tail)).await
+        }
+        Request::ScaleReplicas { group, replicas } => {
+            acknowledge_reconcile(server.control.scale_replicas(&group, replicas).await)
+        }
         // Subscribe is intercepted before dispatch; reaching here is a protocol misuse.
         Request::Subscribe | Request::SubscribeWithVersion { .. } => Response::error(
             ErrorCode::BadRequest,
@@ -625,6 +627,34 @@ async fn dispatch(server: &ControlServer, request: Request) -> Response {
     }
 }
 
+/// Read a service's logs, off the runtime when that means opening a retained run's log file.
+async fn read_logs(
+    server: &ControlServer,
+    service: String,
+    run_generation: Option<u64>,
+    tail: Option<usize>,
+    stream: Option<micromux::OutputStream>,
+) -> Response {
+    if run_generation.is_none() {
+        return get_logs(&server.reader, &service, None, tail, stream);
+    }
+    let reader = server.reader.clone();
+    log_reads::run(move || get_logs(&reader, &service, run_generation, tail, stream)).await
+}
+
+fn get_service(reader: &SessionModelReader, service: &str) -> Response {
+    let snapshots = reader.services();
+    snapshots
+        .iter()
+        .find(|snapshot| snapshot.id == service)
+        .or_else(|| snapshots.iter().find(|snapshot| snapshot.name == service))
+        .cloned()
+        .map_or_else(
+            || unknown_service(service),
+            |snapshot| Response::Service(Box::new(snapshot)),
+        )
+}
+
 async fn reconcile(server: &ControlServer, dry_run: bool) -> Response {
     acknowledge_reconcile(server.control.reconcile_config(dry_run).await)
 }
@@ -659,6 +689,7 @@ fn get_logs(
     service: &str,
     run_generation: Option<u64>,
     tail: Option<usize>,
+    stream: Option<micromux::OutputStream>,
 ) -> Response {
     if reader.service(service).is_none() {
         return unknown_service(service);
@@ -670,19 +701,26 @@ fn get_logs(
     });
     let tail = requested_tail.min(MAX_LOG_TAIL);
     let mut truncated = false;
+    // A stream filter selects from everything retained, so the tail counts matching records.
+    let read_tail = stream.is_none().then_some(tail);
     let mut lines = match run_generation {
         Some(run_generation) => {
-            let run = match reader.try_run_log(service, run_generation, Some(tail)) {
+            let run = match reader.try_run_log(service, run_generation, read_tail) {
                 Ok(Some(run)) => run,
                 Ok(None) => return unknown_run(service, run_generation),
                 Err(err) => return log_read_error(err),
             };
             run.lines
         }
-        None => reader.logs(service, Some(tail)),
+        None => reader.logs(service, read_tail),
     };
+    if let Some(stream) = stream {
+        lines.retain(|line| line.stream == Some(stream));
+        lines.drain(..lines.len().saturating_sub(tail));
+    }
     truncated |= requested_tail > MAX_LOG_TAIL && lines.len() >= tail;
     if let Some(run_generation) = run_generation
+        && stream.is_none()
         && tail == MAX_LOG_TAIL
     {
         truncated |= reader
@@ -699,6 +737,45 @@ fn get_logs(
     logs_response(lines, truncated, first_retained_seq)
 }
 
+fn get_task_logs(
+    reader: &SessionModelReader,
+    task: &str,
+    run_generation: Option<u64>,
+    tail: Option<usize>,
+) -> Response {
+    let Some(snapshot) = reader.task(task) else {
+        return unknown_task(task);
+    };
+    let Some(run_generation) =
+        run_generation.or_else(|| snapshot.latest_run().map(|run| run.run_generation))
+    else {
+        return Response::error(
+            ErrorCode::UnknownRun,
+            format!("task `{task}` has not run yet"),
+        );
+    };
+    let requested_tail = tail.unwrap_or(MAX_LOG_TAIL);
+    let tail = requested_tail.min(MAX_LOG_TAIL);
+    let mut lines = match reader.try_task_run_log(task, run_generation, Some(tail)) {
+        Ok(Some(run)) => run.lines,
+        Ok(None) => {
+            return Response::error(
+                ErrorCode::UnknownRun,
+                format!("task `{task}` has no retained run `{run_generation}`"),
+            );
+        }
+        Err(err) => return log_read_error(err),
+    };
+    let line_count = snapshot
+        .runs
+        .iter()
+        .find(|run| run.run_generation == run_generation)
+        .map_or(0, |run| run.log.line_count);
+    let mut truncated = line_count > lines.len();
+    truncated |= bound_tail_response_lines(&mut lines, None);
+    logs_response(lines, truncated, None)
+}
+
 fn follow_logs(
     reader: &SessionModelReader,
     service: &str,
@@ -802,6 +879,7 @@ fn describe(server: &ControlServer) -> SessionInfo {
         services,
         services_truncated: false,
         micromux_version: server.identity.micromux_version.clone(),
+        profiles: server.identity.profiles.clone(),
         capabilities: Some(crate::SessionCapabilities {
             dynamic_services,
             disk_log_reads: Some(log_reads::health()),
@@ -816,6 +894,10 @@ fn unknown_service(service: &str) -> Response {
     )
 }
 
+fn unknown_task(task: &str) -> Response {
+    Response::error(ErrorCode::UnknownTask, format!("unknown task `{task}`"))
+}
+
 fn log_read_error(error: micromux::LogRunReadError) -> Response {
     Response::error(ErrorCode::Busy, error.to_string())
 }
@@ -986,6 +1068,15 @@ fn bound_health_attempt(attempt: &mut Option<micromux::HealthAttempt>) {
     trim_health_attempt_to_fit(attempt, |attempt| health_response_fits(Some(attempt)));
 }
 
+fn get_health(reader: &SessionModelReader, service: &str) -> Response {
+    if reader.service(service).is_none() {
+        return unknown_service(service);
+    }
+    let mut attempt = reader.latest_health(service);
+    bound_health_attempt(&mut attempt);
+    Response::Health(attempt)
+}
+
 fn get_health_history(reader: &SessionModelReader, service: &str) -> Response {
     if reader.service(service).is_none() {
         return unknown_service(service);
@@ -1138,6 +1229,7 @@ fn rejection_response(rejection: CommandRejection) -> Response {
         CommandRejection::UnknownService => {
             Response::error(ErrorCode::UnknownService, "unknown service")
         }
+        CommandRejection::UnknownTask => Response::error(ErrorCode::UnknownTask, "unknown task"),
         CommandRejection::InvalidState(message) => {
             Response::error(ErrorCode::InvalidState, message)
         }
@@ -1216,6 +1308,7 @@ mod tests {
             seq,
             run_generation: 1,
             timestamp_unix_ms: 1_700_000_000_000 + seq,
+            stream: None,
             line: "x".repeat(len),
         }
     }
@@ -1320,6 +1413,10 @@ mod tests {
                 pid: None,
                 delay_ms: None,
                 blocked_on: None,
+                changed_paths: None,
+                signal: None,
+                caused_by: None,
+                probe_output: None,
             })
             .collect::<Vec<_>>();
         assert!(events.iter().map(|event| event.detail.len()).sum::<usize>() < RESPONSE_MAX_BYTES);
@@ -1418,6 +1515,7 @@ mod tests {
             services,
             services_truncated: false,
             micromux_version: "test".to_string(),
+            profiles: Vec::new(),
             capabilities: None,
         };
 
@@ -1485,6 +1583,7 @@ mod tests {
             seq: 1,
             run_generation: 1,
             timestamp_unix_ms: 0,
+            stream: None,
             line: "\0".repeat(RESPONSE_MAX_BYTES / 2),
         }];
 
@@ -203,6 +203,10 @@ where
 }
 
 #[tokio::test]
+#[expect(
+    clippy::too_many_lines,
+    reason = "one session walks the whole request surface so each check names its own request"
+)]
 async fn describe_list_logs_and_restart_over_the_socket() -> eyre::Result<()> {
     let dir = unique_dir("e2e")?;
     let session = build_session(dir.path(), "echo hello-from-svc; sleep 60")?;
@@ -245,6 +249,7 @@ async fn describe_list_logs_and_restart_over_the_socket() -> eyre::Result<()> {
             service: "svc".to_string(),
             run_generation: None,
             tail: None,
+            stream: None,
         },
         |response| {
             matches!(response, Response::Logs { lines, .. }
@@ -282,11 +287,26 @@ async fn describe_list_logs_and_restart_over_the_socket() -> eyre::Result<()> {
         }
     ));
 
+    let unknown_signal = client
+        .request(Request::Signal {
+            service: "nope".to_string(),
+            signal: micromux::ServiceSignal::Hup,
+        })
+        .await?;
+    assert!(matches!(
+        unknown_signal,
+        Response::Error {
+            code: micromux_control::ErrorCode::UnknownService,
+            ..
+        }
+    ));
+
     let unknown_logs = client
         .request(Request::GetLogs {
             service: "nope".to_string(),
             run_generation: None,
             tail: None,
+            stream: None,
         })
         .await?;
     assert!(matches!(
@@ -616,6 +636,7 @@ async fn retained_run_logs_are_queryable_after_restart() -> eyre::Result<()> {
             service: "svc".to_string(),
             run_generation: None,
             tail: None,
+            stream: None,
         },
         |response| {
             matches!(response, Response::Logs { lines, .. }
@@ -658,6 +679,7 @@ async fn retained_run_logs_are_queryable_after_restart() -> eyre::Result<()> {
             service: "svc".to_string(),
             run_generation: Some(1),
             tail: None,
+            stream: None,
         })
         .await?;
     assert!(matches!(previous, Response::Logs { lines, .. }
@@ -2,7 +2,7 @@
 
 use micromux::{
     Desired, Execution, Health, HealthAttempt, LogLine, LogRunSummary, ServiceCommandAck,
-    ServiceEvent, ServiceSnapshot,
+    ServiceEvent, ServiceSnapshot, TaskRunAck, TaskSnapshot,
 };
 use micromux_control::{ErrorCode, Response};
 
@@ -32,6 +32,7 @@ fn remote_error(code: ErrorCode, message: String) -> ToolError {
         }
         ErrorCode::UnknownService
         | ErrorCode::UnknownRun
+        | ErrorCode::UnknownTask
         | ErrorCode::NoSession
         | ErrorCode::PolicyDenied
         | ErrorCode::LimitExceeded
@@ -182,6 +183,32 @@ pub fn reconcile(response: Response) -> Result<micromux::ReconcileReceipt, ToolE
     }
 }
 
+/// Extract the configured on-demand tasks.
+///
+/// # Errors
+///
+/// Returns a [`ToolError`] if the session replied with an error or an unexpected response.
+pub fn tasks(response: Response) -> Result<Vec<TaskSnapshot>, ToolError> {
+    match response {
+        Response::Tasks(tasks) => Ok(tasks),
+        Response::Error { code, message } => Err(remote_error(code, message)),
+        other => Err(ToolError::Unexpected(format!("{other:?}"))),
+    }
+}
+
+/// Extract the acknowledgement of a started task run.
+///
+/// # Errors
+///
+/// Returns a [`ToolError`] if the session replied with an error or an unexpected response.
+pub fn task_started(response: Response) -> Result<TaskRunAck, ToolError> {
+    match response {
+        Response::TaskStarted(ack) => Ok(ack),
+        Response::Error { code, message } => Err(remote_error(code, message)),
+        other => Err(ToolError::Unexpected(format!("{other:?}"))),
+    }
+}
+
 /// Confirm a shutdown was acknowledged.
 ///
 /// # Errors
@@ -246,6 +273,7 @@ pub fn evaluate(snapshot: &ServiceSnapshot, after: Option<u64>) -> WaitOutcome {
         | Execution::Blocked
         | Execution::Starting
         | Execution::Stopping
+        | Execution::Paused
         | Execution::Unknown => WaitOutcome::Pending,
     }
 }
@@ -21,7 +21,7 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use micromux::{Desired, Execution, Health, HealthAttempt, ServiceSnapshot};
+use micromux::{Desired, Execution, Health, HealthAttempt, OutputStream, ServiceSnapshot};
 use micromux_control::{
     Client, ControlEndpoint, ControlError, ErrorCode, Request, Response, SessionInfo, endpoint_for,
     runtime_dir_statuses, transport_supported, usable_runtime_dirs,
@@ -93,7 +93,9 @@ given, tools target the current project's session. Use `find_service` to locate
 sessions; service-scoped errors also point at matching sibling sessions. Validate a candidate file \
 with `validate_config`; for an active session's on-disk edits, call `reconcile_config` with \
 `dry_run=true` before applying it. Reconciliation adds, retires, and updates configured services but \
-does not restart changed processes. Before creating a runtime service, use `list_sessions` and check \
+does not restart changed processes. Services with `replicas` run as instances `<name>-1..N`; \
+`restart_service`, `enable_service` and `disable_service` accept the group name, and \
+`scale_replicas` changes the count at runtime. Before creating a runtime service, use `list_sessions` and check \
 `capabilities.dynamic_services`. Supply an `idempotency_key` so creation retries are safe. Leases \
 are bounded by session policy unless it explicitly permits `expires_after=\"none\"`; use \
 `renew_dynamic_service` to extend a live lease without restarting. For servers, use \
@@ -107,9 +109,12 @@ with its per-service cursor map to follow ordering across the session. `restart_
 usual cursor/restart/wait/log flow. Use `wait_for_log` after external actions, `diagnose` for a \
 one-shot failure summary, `get_health_history` when a flapping probe needs more than the latest \
 attempt, `list_log_runs` for retained runs, and `log_cursors` plus \
-`follow_all_logs` around hot reloads. Log tools strip ANSI by default and support regex, context, \
+`follow_all_logs` around hot reloads. `signal_service` delivers a signal such as SIGHUP without \
+restarting the process; `pause_service` and `resume_service` freeze and continue it. Log tools strip ANSI by default and support regex, context, \
 time, trace-id, compact JSON, and minimum-level filters. Actions go through micromux and retain its \
-dependency and restart semantics. Use `start_session` to create a project's headless session; a \
+dependency and restart semantics. `list_tasks` shows the config's on-demand tasks; `run_task` runs \
+one to completion and returns its exit code and output, and `get_task_output` reads a retained run \
+afterwards. Use `start_session` to create a project's headless session; a \
 human can run `micromux attach` to observe it. Use `stop_session` to stop it explicitly.";
 
 /// The MCP server handler. Cheap to clone; holds no supervision state.
@@ -140,6 +145,47 @@ struct ReconcileConfigArgs {
     dry_run: bool,
 }
 
+#[derive(Debug, Deserialize, JsonSchema)]
+struct ScaleReplicasArgs {
+    /// The replica group: the name of the configured service that sets `replicas`.
+    group: String,
+    /// The number of instances to run.
+    replicas: usize,
+    /// Optional session selector; omit for the current project.
+    #[serde(default)]
+    session: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct RunTaskArgs {
+    /// The name of a task from the config's `tasks:` section.
+    task: String,
+    /// Optional session selector; omit for the current project.
+    #[serde(default)]
+    session: Option<String>,
+    /// Maximum seconds to wait for the run to finish (default 60, capped at 600).
+    #[serde(default)]
+    timeout_secs: Option<u64>,
+    /// Maximum trailing output lines to return (default 100, capped at 1000).
+    #[serde(default)]
+    output_limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct TaskOutputArgs {
+    /// The name of the task.
+    task: String,
+    /// Optional session selector; omit for the current project.
+    #[serde(default)]
+    session: Option<String>,
+    /// The retained run to read; omit for the latest run.
+    #[serde(default)]
+    run_generation: Option<u64>,
+    /// Maximum trailing output lines to return (default 100, capped at 1000).
+    #[serde(default)]
+    output_limit: Option<usize>,
+}
+
 #[derive(Debug, Deserialize, JsonSchema)]
 struct ServiceArgs {
     /// The id of the target service.
@@ -149,6 +195,17 @@ struct ServiceArgs {
     session: Option<String>,
 }
 
+#[derive(Debug, Deserialize, JsonSchema)]
+struct SignalServiceArgs {
+    /// The id of the target service.
+    service: String,
+    /// The signal to send, e.g. `SIGHUP`.
+    signal: micromux::ServiceSignal,
+    /// Optional session selector (see `list_sessions`); omit for the current project.
+    #[serde(default)]
+    session: Option<String>,
+}
+
 #[derive(Debug, Deserialize, JsonSchema)]
 struct ServiceEventsArgs {
     /// The id of the target service, or `*` to merge events from every service.
@@ -237,6 +294,10 @@ struct LogFilterArgs {
     /// Keep only entries containing this trace/correlation id.
     #[serde(default)]
     trace_id: Option<String>,
+    /// Keep only records from this output stream (`Stdout` or `Stderr`). Only services configured
+    /// with `tty: false` separate their streams; terminal output and supervisor banners never match.
+    #[serde(default)]
+    stream: Option<OutputStream>,
     /// Output format. `compact` turns structured JSON records into token-efficient text plus typed
     /// `message`/`fields`; `full` returns the cleaned logical record.
     #[serde(default)]
@@ -305,6 +366,10 @@ struct StartArgs {
     /// micromux config; omit to use the MCP server's working directory.
     #[serde(default)]
     path: Option<String>,
+    /// Profiles to activate, like `micromux serve --profile`. Services assigned only to other
+    /// profiles start disabled. Ignored when a session is already running.
+    #[serde(default)]
+    profiles: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -615,6 +680,60 @@ struct ReconcileConfigResult {
     actions: Vec<micromux::ReconcileAction>,
 }
 
+#[derive(Serialize, JsonSchema)]
+struct TaskListResult {
+    #[serde(flatten)]
+    session_ref: SessionRef,
+    tasks: Vec<micromux::TaskSnapshot>,
+}
+
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum RunTaskStatus {
+    Exited,
+    Cancelled,
+    Timeout,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct RunTaskResult {
+    #[serde(flatten)]
+    session_ref: SessionRef,
+    task: String,
+    run_generation: u64,
+    status: RunTaskStatus,
+    success: bool,
+    /// The exit code, or `-1` when the task was killed; absent while it is still running.
+    exit_code: Option<i32>,
+    /// Trailing output lines; stderr lines are prefixed with `[stderr]`.
+    output: Vec<String>,
+    /// Whether older output was cut by the limit; read it with `get_task_output`.
+    output_truncated: bool,
+    waited_secs: u64,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct TaskOutputResult {
+    #[serde(flatten)]
+    session_ref: SessionRef,
+    task: String,
+    run_generation: u64,
+    /// How the run ended; absent while it is still running.
+    result: Option<micromux::HealthResult>,
+    /// Trailing output lines; stderr lines are prefixed with `[stderr]`.
+    output: Vec<String>,
+    output_truncated: bool,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct ScaleReplicasResult {
+    #[serde(flatten)]
+    session_ref: SessionRef,
+    group: String,
+    replicas: usize,
+    actions: Vec<micromux::ReconcileAction>,
+}
+
 #[derive(Serialize, JsonSchema)]
 struct StopSessionResult {
     stopped: bool,
@@ -637,6 +756,8 @@ struct StartSessionResult {
     id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pid: Option<u32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    profiles: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     endpoint: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -859,7 +980,7 @@ fn classify_exit(snapshot: &ServiceSnapshot, after_generation: Option<u64>) -> O
     let qualifying_run_in_flight = generation_ready
         && matches!(
             snapshot.execution,
-            Execution::Starting | Execution::Running | Execution::Stopping
+            Execution::Starting | Execution::Running | Execution::Paused | Execution::Stopping
         );
     (unavailable && !qualifying_run_in_flight).then_some(ExitVerdict::InvalidState)
 }
@@ -971,6 +1092,57 @@ impl SessionConn {
     }
 }
 
+/// The retained run `run_generation` of `task`, as listed by the session.
+fn task_run(
+    tasks: &[micromux::TaskSnapshot],
+    task: &str,
+    run_generation: Option<u64>,
+) -> Result<micromux::TaskRun, ToolError> {
+    let snapshot = tasks
+        .iter()
+        .find(|snapshot| snapshot.name == task)
+        .ok_or_else(|| ToolError::Remote {
+            code: ErrorCode::UnknownTask,
+            message: format!("unknown task `{task}`"),
+        })?;
+    match run_generation {
+        Some(run_generation) => snapshot
+            .runs
+            .iter()
+            .find(|run| run.run_generation == run_generation),
+        None => snapshot.latest_run(),
+    }
+    .cloned()
+    .ok_or_else(|| ToolError::Remote {
+        code: ErrorCode::UnknownRun,
+        message: format!(
+            "task `{task}` has no retained run `{}`",
+            run_generation.map_or_else(|| "latest".to_string(), |run| run.to_string())
+        ),
+    })
+}
+
+/// The trailing output lines of one task run and whether older lines were cut.
+async fn task_output(
+    conn: &mut SessionConn,
+    task: &str,
+    run_generation: u64,
+    limit: usize,
+) -> Result<(Vec<String>, bool), ToolError> {
+    let response = conn
+        .request(Request::GetTaskLogs {
+            task: task.to_string(),
+            run_generation: Some(run_generation),
+            tail: Some(limit),
+        })
+        .await?;
+    let logs = convert::logs(response)?;
+    Ok((
+        logs.lines.into_iter().map(|line| line.line).collect(),
+        logs.truncated,
+    ))
+}
+
 /// A copy-pasteable session selector (`hash:<id>`) that resolves back to this exact session.
 fn session_selector(info: &SessionInfo) -> String {
     format!("hash:{}", info.id)
@@ -1102,6 +1274,7 @@ struct LogFilters {
     min_level: Option<logproc::Level>,
     since_unix_ms: Option<u64>,
     trace_id: Option<String>,
+    stream: Option<OutputStream>,
     format: logproc::LogFormat,
 }
 
@@ -1113,6 +1286,7 @@ impl LogFilters {
             min_level: parse_min_level(args.min_level.as_deref())?,
             since_unix_ms: parse_since(args.since.as_deref(), args.since_unix_ms)?,
             trace_id: args.trace_id.clone(),
+            stream: args.stream,
             format: args.format,
         })
     }
@@ -1125,6 +1299,7 @@ impl LogFilters {
             min_level: self.min_level,
             since_unix_ms: self.since_unix_ms,
             trace_id: self.trace_id.as_deref(),
+            stream: self.stream,
             limit,
             format: self.format,
         }
@@ -1188,7 +1363,11 @@ impl McpServer {
         }))
     }
 
-    async fn start_for_target(&self, target: PathBuf) -> Result<StartSessionResult, ErrorData> {
+    async fn start_for_target(
+        &self,
+        target: PathBuf,
+        profiles: &[String],
+    ) -> Result<StartSessionResult, ErrorData> {
         if !self.allow_session_start {
             return Err(ErrorData::invalid_params(
                 "session startup is disabled; launch `micromux mcp --allow-session-start` to permit process spawning",
@@ -1212,12 +1391,13 @@ impl McpServer {
             tokio::time::sleep(SESSION_START_WINDOW).await;
             drop(budget);
         });
-        self.start_for_target_unchecked(target).await
+        self.start_for_target_unchecked(target, profiles).await
     }
 
     async fn start_for_target_unchecked(
         &self,
         target: PathBuf,
+        profiles: &[String],
     ) -> Result<StartSessionResult, ErrorData> {
         if !transport_supported() {
             return Err(error_data(ToolError::Unsupported));
@@ -1257,7 +1437,7 @@ impl McpServer {
             return Ok(report);
         }
 
-        let spawned = spawn_detached_serve(&config_path).map_err(|err| {
+        let spawned = spawn_detached_serve(&config_path, profiles).map_err(|err| {
             // On a platform without the control transport, surface the canonical unsupported error
             // rather than a generic spawn failure.
             if err.kind() == std::io::ErrorKind::Unsupported {
@@ -1423,9 +1603,10 @@ impl McpServer {
     #[tool(
         description = "Validate a micromux config without starting a session. Runs the same parse, \
         service normalization, environment-file/interpolation, port, and dependency-graph checks \
-        used at startup and returns structured plus ANSI-free rendered diagnostics. Environment \
-        interpolation uses the MCP process environment, so unset-variable warnings can differ \
-        from the eventual session environment."
+        used at startup and returns structured plus ANSI-free rendered diagnostics, and for each \
+        service which config layer (top-level or service env_file/environment) set each \
+        environment variable, never the values. Environment interpolation uses the MCP process \
+        environment, so unset-variable warnings can differ from the eventual session environment."
     )]
     async fn validate_config(
         &self,
@@ -1482,6 +1663,158 @@ impl McpServer {
         }))
     }
 
+    #[tool(
+        description = "Scale a replica group (a configured service with `replicas`) to a number of \
+        instances named <group>-1..N. Returns the instances added or retired; retired instances \
+        keep their logs and revive when the group is scaled back up. The count lasts for the \
+        session unless micromux.yaml changes the group's own replicas."
+    )]
+    async fn scale_replicas(
+        &self,
+        args: Parameters<ScaleReplicasArgs>,
+    ) -> ToolResult<ScaleReplicasResult> {
+        let Parameters(args) = args;
+        let resolved = select::resolve(&self.cwd, args.session)
+            .await
+            .map_err(error_data)?;
+        let response = send_request(
+            &resolved.endpoint,
+            Request::ScaleReplicas {
+                group: args.group.clone(),
+                replicas: args.replicas,
+            },
+        )
+        .await
+        .map_err(error_data)?;
+        let receipt = convert::reconcile(response).map_err(error_data)?;
+        Ok(Json(ScaleReplicasResult {
+            session_ref: SessionRef::from(&resolved.info),
+            group: args.group,
+            replicas: args.replicas,
+            actions: receipt.actions,
+        }))
+    }
+
+    #[tool(
+        description = "List the on-demand tasks from the config's `tasks:` section with their \
+        command, working directory, and retained runs (generation, start time, result, and log \
+        summary)."
+    )]
+    async fn list_tasks(&self, args: Parameters<SessionArgs>) -> ToolResult<TaskListResult> {
+        let Parameters(args) = args;
+        let resolved = select::resolve(&self.cwd, args.session)
+            .await
+            .map_err(error_data)?;
+        let response = send_request(&resolved.endpoint, Request::ListTasks)
+            .await
+            .map_err(error_data)?;
+        let tasks = convert::tasks(response).map_err(error_data)?;
+        Ok(Json(TaskListResult {
+            session_ref: SessionRef::from(&resolved.info),
+            tasks,
+        }))
+    }
+
+    #[tool(
+        description = "Run an on-demand task and wait for it to finish. Returns its exit code and \
+        the trailing lines of its output. A task never runs concurrently with itself; running one \
+        that is still in progress is rejected. On timeout the run keeps going: read its result \
+        later with get_task_output using the returned run_generation."
+    )]
+    async fn run_task(&self, args: Parameters<RunTaskArgs>) -> ToolResult<RunTaskResult> {
+        let Parameters(args) = args;
+        let resolved = select::resolve(&self.cwd, args.session)
+            .await
+            .map_err(error_data)?;
+        let timeout = Duration::from_secs(
+            args.timeout_secs
+                .unwrap_or(DEFAULT_WAIT_TIMEOUT_SECS)
+                .min(MAX_WAIT_TIMEOUT_SECS),
+        );
+        let output_limit = args
+            .output_limit
+            .unwrap_or(DEFAULT_RESTART_LOG_LIMIT)
+            .clamp(1, MAX_RESTART_LOG_LIMIT);
+        let mut conn = SessionConn::connect(&resolved.endpoint)
+            .await
+            .map_err(error_data)?;
+        let response = conn
+            .request(Request::RunTask {
+                task: args.task.clone(),
+            })
+            .await
+            .map_err(error_data)?;
+        let ack = convert::task_started(response).map_err(error_data)?;
+
+        let started = tokio::time::Instant::now();
+        let result = loop {
+            let response = conn.request(Request::ListTasks).await.map_err(error_data)?;
+            let tasks = convert::tasks(response).map_err(error_data)?;
+            let run = task_run(&tasks, &args.task, Some(ack.run_generation)).map_err(error_data)?;
+            if run.result.is_some() || started.elapsed() >= timeout {
+                break run.result;
+            }
+            tokio::time::sleep(WAIT_LOG_POLL).await;
+        };
+        let (output, output_truncated) =
+            task_output(&mut conn, &args.task, ack.run_generation, output_limit)
+                .await
+                .map_err(error_data)?;
+        let status = match result {
+            None => RunTaskStatus::Timeout,
+            Some(result) if result.cancelled => RunTaskStatus::Cancelled,
+            Some(_) => RunTaskStatus::Exited,
+        };
+
+        Ok(Json(RunTaskResult {
+            session_ref: SessionRef::from(&resolved.info),
+            task: args.task,
+            run_generation: ack.run_generation,
+            status,
+            success: result.is_some_and(|result| result.success),
+            exit_code: result.map(|result| result.exit_code),
+            output,
+            output_truncated,
+            waited_secs: started.elapsed().as_secs(),
+        }))
+    }
+
+    #[tool(
+        description = "Return the result and trailing output of a retained on-demand task run. \
+        Omit run_generation to read the latest run; list_tasks shows which runs are retained."
+    )]
+    async fn get_task_output(
+        &self,
+        args: Parameters<TaskOutputArgs>,
+    ) -> ToolResult<TaskOutputResult> {
+        let Parameters(args) = args;
+        let resolved = select::resolve(&self.cwd, args.session)
+            .await
+            .map_err(error_data)?;
+        let output_limit = args
+            .output_limit
+            .unwrap_or(DEFAULT_RESTART_LOG_LIMIT)
+            .clamp(1, MAX_RESTART_LOG_LIMIT);
+        let mut conn = SessionConn::connect(&resolved.endpoint)
+            .await
+            .map_err(error_data)?;
+        let response = conn.request(Request::ListTasks).await.map_err(error_data)?;
+        let tasks = convert::tasks(response).map_err(error_data)?;
+        let run = task_run(&tasks, &args.task, args.run_generation).map_err(error_data)?;
+        let (output, output_truncated) =
+            task_output(&mut conn, &args.task, run.run_generation, output_limit)
+                .await
+                .map_err(error_data)?;
+        Ok(Json(TaskOutputResult {
+            session_ref: SessionRef::from(&resolved.info),
+            task: args.task,
+            run_generation: run.run_generation,
+            result: run.result,
+            output,
+            output_truncated,
+        }))
+    }
+
     #[tool(
         description = "List the services in a session with their desired/execution state, health, \
         advertised ports, pid, start time, config drift, uptime, restart state/policy, last exit \
@@ -1550,7 +1883,8 @@ impl McpServer {
         bounded tail of a current or previous disk-backed run (single-service only). Use follow_logs to page through a \
         retained run with a cursor. ANSI color is stripped by default (raw=true keeps it), terminal \
         padding is trimmed, and tail counts logical log entries, not wrapped terminal rows. Filter \
-        with grep (regex), grep_context, since, trace_id, or, for JSON-log services, min_level. Use \
+        with grep (regex), grep_context, since, trace_id, stream (for `tty: false` services), or, for \
+        JSON-log services, min_level. Use \
         format=\"compact\" for token-efficient structured JSON logs; entries carry micromux \
         ingestion timestamps, a detected `level`, optional parsed service timestamp, and parsed \
         `message`/`fields` for structured JSON logs."
@@ -1760,7 +2094,8 @@ impl McpServer {
         log position; pass it to follow_logs as after_seq to read exactly the new run's output. \
         Cursor capture is best-effort and never prevents the restart. Reloads the latest micromux \
         config before spawning the replacement, so edited command flags, healthchecks, and log \
-        retention take effect. Restarting a disabled service is rejected."
+        retention take effect. Restarting a disabled service is rejected. Naming a replica group \
+        restarts each of its enabled instances; accepted lists them."
     )]
     async fn restart_service(&self, args: Parameters<ServiceArgs>) -> ToolResult<MutationResult> {
         let Parameters(args) = args;
@@ -1915,7 +2250,7 @@ impl McpServer {
         plus the pre-enable log position in log_cursor when cursor capture succeeds (pass it to \
         follow_logs as after_seq). Cursor capture is best-effort and never prevents the enable. \
         Reloads the latest micromux config before spawning, so edited command flags, healthchecks, \
-        and log retention take effect."
+        and log retention take effect. A replica group name enables every instance."
     )]
     async fn enable_service(&self, args: Parameters<ServiceArgs>) -> ToolResult<MutationResult> {
         let Parameters(args) = args;
@@ -1929,7 +2264,10 @@ impl McpServer {
         .await
     }
 
-    #[tool(description = "Disable a service (stop it and keep it stopped).")]
+    #[tool(
+        description = "Disable a service (stop it and keep it stopped). A replica group name \
+        disables every instance."
+    )]
     async fn disable_service(&self, args: Parameters<ServiceArgs>) -> ToolResult<MutationResult> {
         let Parameters(args) = args;
         self.mutate(
@@ -1942,6 +2280,60 @@ impl McpServer {
         .await
     }
 
+    #[tool(
+        description = "Send a signal (for example SIGHUP to reload configuration, or SIGUSR1) to a \
+        running service's process group without restarting it. The delivery is recorded as a \
+        Signaled lifecycle event. log_cursor is the pre-signal log position; pass it to \
+        follow_logs as after_seq to read how the process reacted. Signaling a service with no \
+        live process is rejected."
+    )]
+    async fn signal_service(
+        &self,
+        args: Parameters<SignalServiceArgs>,
+    ) -> ToolResult<MutationResult> {
+        let Parameters(args) = args;
+        self.mutate(
+            args.session,
+            Request::Signal {
+                service: args.service.clone(),
+                signal: args.signal,
+            },
+            &args.service,
+        )
+        .await
+    }
+
+    #[tool(
+        description = "Pause a running service: SIGSTOP its whole process group and report it as \
+        Paused. Healthchecks are held while paused, so the service is not marked unhealthy or \
+        restarted. Resume it with resume_service; stopping or restarting a paused service \
+        continues it first."
+    )]
+    async fn pause_service(&self, args: Parameters<ServiceArgs>) -> ToolResult<MutationResult> {
+        let Parameters(args) = args;
+        self.mutate(
+            args.session,
+            Request::Pause {
+                service: args.service.clone(),
+            },
+            &args.service,
+        )
+        .await
+    }
+
+    #[tool(description = "Resume a paused service (SIGCONT its process group).")]
+    async fn resume_service(&self, args: Parameters<ServiceArgs>) -> ToolResult<MutationResult> {
+        let Parameters(args) = args;
+        self.mutate(
+            args.session,
+            Request::Resume {
+                service: args.service.clone(),
+            },
+            &args.service,
+        )
+        .await
+    }
+
     #[tool(
         description = "Create and start a runtime service in the target session. Requires \
         control.dynamic_services.enabled in that session's micromux.yaml. Dynamic services stop \
@@ -2170,7 +2562,9 @@ impl McpServer {
         description = "Start a new headless micromux session for a project (brings its services \
         up). Spawns `micromux serve` detached for the project's config and returns once the session \
         is reachable; a no-op if one is already running for that config. `path` is a project \
-        directory or a config file — omit for the MCP server's directory. If another worktree's \
+        directory or a config file — omit for the MCP server's directory. `profiles` activates \
+        service profiles; services assigned only to other profiles start disabled but can be \
+        enabled later. If another worktree's \
         session binds the same ports, stop it first with stop_session."
     )]
     async fn start_session(&self, args: Parameters<StartArgs>) -> ToolResult<StartSessionResult> {
@@ -2179,7 +2573,9 @@ impl McpServer {
             .path
             .as_deref()
             .map_or_else(|| self.cwd.clone(), PathBuf::from);
-        self.start_for_target(target).await.map(Json)
+        self.start_for_target(target, &args.profiles)
+            .await
+            .map(Json)
     }
 
     #[tool(
@@ -2760,7 +3156,7 @@ impl McpServer {
                     .path
                     .as_deref()
                     .map_or_else(|| self.cwd.clone(), PathBuf::from);
-                let report = self.start_for_target(target).await?;
+                let report = self.start_for_target(target, &[]).await?;
                 let id = report.id.clone().ok_or_else(|| {
                     ErrorData::internal_error(
                         "start_session did not identify the reachable session",
@@ -3083,9 +3479,9 @@ mod tests {
     use super::{
         DynamicServiceArgs, EnsureAction, EnsureActionKind, EnsureReadyArgs, LogFilterArgs,
         LogsArgs, ReconcileConfigArgs, RenewDynamicServiceArgs, ReplaceDynamicServiceArgs,
-        ServiceArgs, ServiceEventsArgs, ServiceEventsResult, SessionArgs,
-        SessionServiceEventsResult, StartDynamicAndWaitArgs, WaitForExitArgs, WaitStatus,
-        capture_mutation_cursor,
+        RunTaskArgs, RunTaskStatus, ServiceArgs, ServiceEventsArgs, ServiceEventsResult,
+        SessionArgs, SessionServiceEventsResult, StartDynamicAndWaitArgs, TaskOutputArgs,
+        WaitForExitArgs, WaitStatus, capture_mutation_cursor,
     };
 
     #[cfg(unix)]
@@ -3286,6 +3682,7 @@ services:
                     since: None,
                     since_unix_ms: None,
                     trace_id: None,
+                    stream: None,
                     format: crate::logproc::LogFormat::Full,
                 },
             }))
@@ -3572,9 +3969,16 @@ services:
             "get_service_events",
             "validate_config",
             "reconcile_config",
+            "scale_replicas",
             "ensure_service_ready",
             "wait_for_exit",
             "get_health_history",
+            "signal_service",
+            "pause_service",
+            "resume_service",
+            "list_tasks",
+            "run_task",
+            "get_task_output",
         ] {
             assert!(
                 tools.iter().any(|tool| tool.name == name),
@@ -3596,11 +4000,13 @@ services:
             "idempotency_key",
             "renew_dynamic_service",
             "reconcile_config",
+            "scale_replicas",
             "validate_config",
             "get_service_events",
             "ensure_service_ready",
             "wait_for_exit",
             "get_health_history",
+            "run_task",
             "micromux attach",
         ] {
             assert!(
@@ -3671,6 +4077,75 @@ services:
         session.finish().await
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn run_task_returns_exit_code_and_bounded_output() -> eyre::Result<()> {
+        let project = tempfile::tempdir()?;
+        let yaml = r#"version: 1
+services:
+  svc:
+    command: ["sh", "-c", "sleep 60"]
+tasks:
+  seed:
+    command: ["sh", "-c", "echo one; echo two; echo three >&2; exit 4"]
+"#;
+        let (session, selector) =
+            boot_mcp_session_with_yaml(project.path(), "mcp-run-task-test", yaml)?;
+        let mut server = McpServer::new();
+        server.cwd = project.path().to_path_buf();
+
+        let Json(result) = server
+            .run_task(Parameters(RunTaskArgs {
+                task: "seed".to_string(),
+                session: Some(selector.clone()),
+                timeout_secs: Some(10),
+                output_limit: Some(2),
+            }))
+            .await
+            .map_err(|err| eyre::eyre!("run_task: {err:?}"))?;
+        assert!(matches!(result.status, RunTaskStatus::Exited));
+        assert!(!result.success);
+        assert_eq!(result.exit_code, Some(4));
+        assert_eq!(result.run_generation, 1);
+        assert_eq!(result.output.len(), 2);
+        assert!(result.output_truncated);
+
+        let Json(output) = server
+            .get_task_output(Parameters(TaskOutputArgs {
+                task: "seed".to_string(),
+                session: Some(selector.clone()),
+                run_generation: None,
+                output_limit: None,
+            }))
+            .await
+            .map_err(|err| eyre::eyre!("get_task_output: {err:?}"))?;
+        assert_eq!(output.run_generation, 1);
+        assert_eq!(output.result.map(|result| result.exit_code), Some(4));
+        let mut lines = output.output;
+        lines.sort();
+        assert_eq!(lines, vec!["[stderr] three", "one", "two"]);
+
+        let Json(listed) = server
+            .list_tasks(Parameters(SessionArgs {
+                session: Some(selector.clone()),
+            }))
+            .await
+            .map_err(|err| eyre::eyre!("list_tasks: {err:?}"))?;
+        assert_eq!(listed.tasks.len(), 1);
+        assert_eq!(listed.tasks[0].runs.len(), 1);
+
+        let unknown = server
+            .run_task(Parameters(RunTaskArgs {
+                task: "missing".to_string(),
+                session: Some(selector),
+                timeout_secs: None,
+                output_limit: None,
+            }))
+            .await;
+        assert!(unknown.is_err());
+        session.finish().await
+    }
+
     #[cfg(unix)]
     #[tokio::test]
     async fn reconcile_config_round_trips_through_the_mcp_tool() -> eyre::Result<()> {
@@ -3948,6 +4423,7 @@ services:
                 .collect(),
             services_truncated: false,
             micromux_version: env!("CARGO_PKG_VERSION").to_string(),
+            profiles: Vec::new(),
             capabilities: None,
         }
     }
@@ -4545,12 +5021,14 @@ services:
                 seq: 10,
                 run_generation: 1,
                 timestamp_unix_ms: 1_700_000_000_010,
+                stream: None,
                 line: "a".to_string(),
             },
             LogLine {
                 seq: 11,
                 run_generation: 1,
                 timestamp_unix_ms: 1_700_000_000_011,
+                stream: None,
                 line: "b".to_string(),
             },
         ];
@@ -4566,6 +5044,7 @@ services:
             seq: 15,
             run_generation: 2,
             timestamp_unix_ms: 1_700_000_000_015,
+            stream: None,
             line: "newest retained".to_string(),
         }];
 
@@ -4660,6 +5139,7 @@ services:
             service: Some(service.to_string()),
             seq,
             run_generation: 1,
+            stream: None,
             timestamp_unix_ms,
             source_timestamp_unix_ms: None,
             line: format!("{service}-{seq}"),
@@ -4861,7 +5341,7 @@ services:
     #[tokio::test]
     async fn session_start_is_disabled_by_default() {
         let result = McpServer::new()
-            .start_for_target(std::path::PathBuf::from("."))
+            .start_for_target(std::path::PathBuf::from("."), &[])
             .await;
 
         assert!(result.is_err());
@@ -10,9 +10,9 @@
 use std::collections::{BTreeMap, VecDeque};
 
 use micromux::{
-    FIELDS_KEY, LogLine, MESSAGE_KEYS, StructuredLogLevel, find_fields_object, find_key,
-    is_structured_log_level_key, key_matches, render_scalar as render_value, sanitize_text,
-    structured_log_level_in_record,
+    FIELDS_KEY, LogLine, MESSAGE_KEYS, OutputStream, StructuredLogLevel, find_fields_object,
+    find_key, is_structured_log_level_key, key_matches, render_scalar as render_value,
+    sanitize_text, structured_log_level_in_record,
 };
 use regex::Regex;
 use schemars::JsonSchema;
@@ -334,6 +334,9 @@ pub struct ProcessedEntry {
     /// Monotonic record cursor. Pass this as `after_seq` to resume after this entry.
     pub seq: u64,
     pub run_generation: u64,
+    /// Output stream of a `tty: false` service; absent for terminal output and banners.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream: Option<OutputStream>,
     /// Wall-clock time when micromux ingested this record, in Unix milliseconds.
     pub timestamp_unix_ms: u64,
     /// Wall-clock time parsed from a structured JSON log record, in Unix milliseconds.
@@ -377,6 +380,8 @@ pub struct Shape<'a> {
     pub since_unix_ms: Option<u64>,
     /// Keep only entries containing this trace/correlation id.
     pub trace_id: Option<&'a str>,
+    /// Keep only records from this output stream.
+    pub stream: Option<OutputStream>,
     /// After filtering, keep only the last `limit` entries.
     pub limit: Option<usize>,
     /// Output format.
@@ -393,6 +398,12 @@ pub fn shape(records: &[LogLine], options: &Shape) -> Vec<ProcessedEntry> {
     let mut candidates = Vec::new();
     let apply_grep_per_entry = options.grep.is_none() || options.context == 0;
     for record in records {
+        if options
+            .stream
+            .is_some_and(|stream| record.stream != Some(stream))
+        {
+            continue;
+        }
         let stripped = normalize_record_text(&strip_ansi(&record.line), true);
         let segments = record_segments(&stripped);
         let raw_line = (options.raw && segments.len() == 1)
@@ -479,6 +490,7 @@ fn push_shaped_segment(
             service: None,
             seq: record.seq,
             run_generation: record.run_generation,
+            stream: record.stream,
             timestamp_unix_ms: record.timestamp_unix_ms,
             source_timestamp_unix_ms,
             line,
@@ -693,6 +705,7 @@ mod tests {
             seq,
             run_generation: 1,
             timestamp_unix_ms: 1_700_000_000_000 + seq,
+            stream: None,
             line: line.to_string(),
         }
     }
@@ -763,6 +776,33 @@ mod tests {
         assert_eq!(lines(&out), vec!["", "after"]);
     }
 
+    #[test]
+    fn stream_filter_keeps_only_matching_pipe_records() {
+        let records = vec![
+            LogLine {
+                stream: Some(OutputStream::Stdout),
+                ..record(1, "out")
+            },
+            LogLine {
+                stream: Some(OutputStream::Stderr),
+                ..record(2, "err")
+            },
+            record(3, "terminal"),
+        ];
+        let out = shape(
+            &records,
+            &Shape {
+                stream: Some(OutputStream::Stderr),
+                ..Shape::default()
+            },
+        );
+        assert_eq!(lines(&out), vec!["err"]);
+        assert_eq!(out[0].stream, Some(OutputStream::Stderr));
+
+        let all = shape(&records, &Shape::default());
+        assert_eq!(lines(&all), vec!["out", "err", "terminal"]);
+    }
+
     #[test]
     fn tail_limit_counts_records_not_visual_lines() {
         let records = vec![record(1, "a\nb\nc\nd"), record(2, "next")];
@@ -1014,6 +1054,7 @@ mod tests {
             service: Some(service.to_string()),
             seq,
             run_generation: 1,
+            stream: None,
             timestamp_unix_ms: source_ts,
             source_timestamp_unix_ms: Some(source_ts),
             line: format!("{service}:{seq}@{source_ts}"),
@@ -910,6 +910,7 @@ mod tests {
                 }],
                 services_truncated: false,
                 micromux_version: env!("CARGO_PKG_VERSION").to_string(),
+                profiles: Vec::new(),
                 capabilities: None,
             }
         }
@@ -958,6 +959,7 @@ mod tests {
             services: Vec::new(),
             services_truncated: false,
             micromux_version: env!("CARGO_PKG_VERSION").to_string(),
+            profiles: Vec::new(),
             capabilities: None,
         };
 
@@ -993,6 +995,7 @@ mod tests {
             services: Vec::new(),
             services_truncated: false,
             micromux_version: env!("CARGO_PKG_VERSION").to_string(),
+            profiles: Vec::new(),
             capabilities: None,
         };
 
@@ -1028,6 +1031,7 @@ mod tests {
             services: Vec::new(),
             services_truncated: false,
             micromux_version: env!("CARGO_PKG_VERSION").to_string(),
+            profiles: Vec::new(),
             capabilities: None,
         };
 
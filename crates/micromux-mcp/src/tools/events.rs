@@ -137,6 +137,10 @@ mod tests {
             pid: None,
             delay_ms: None,
             blocked_on: None,
+            changed_paths: None,
+            signal: None,
+            caused_by: None,
+            probe_output: None,
         }
     }
 
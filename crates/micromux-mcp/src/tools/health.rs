@@ -4,14 +4,14 @@ use std::net::{Ipv4Addr, SocketAddrV4};
 use std::time::Duration;
 
 use micromux::{
-    ChangeKind, Execution, Health, HealthAttempt, ServiceEvent, ServiceSnapshot, SessionChange,
+    ChangeKind, Execution, Health, HealthAttempt, RestartPhase, ServiceEvent, ServiceSnapshot,
+    SessionChange,
 };
 use micromux_control::{Client, ControlEndpoint, ErrorCode, Request};
 use schemars::JsonSchema;
 use serde::Serialize;
 
 use crate::select::ToolError;
-use crate::tools::portowner;
 use crate::{DIAGNOSE_LOG_SCAN, SessionConn, WAIT_POLL_FLOOR, convert, logproc, select};
 
 const PORT_PROBE_BUDGET: Duration = Duration::from_millis(50);
@@ -31,6 +31,8 @@ pub(crate) enum SignalKind {
     CrashLoop,
     HealthcheckTimeout,
     PortUnavailable,
+    PortConflict,
+    StartupTimeout,
 }
 
 #[derive(Serialize, JsonSchema)]
@@ -119,6 +121,19 @@ pub(crate) fn diagnosis_hint(
     latest_healthcheck: Option<&HealthAttempt>,
     error_log_tail: &[logproc::ProcessedEntry],
 ) -> String {
+    if let Some(conflict) = &snapshot.port_conflict {
+        return if conflict.waiting {
+            format!(
+                "service is waiting for port {} to be released; see signals for its holder",
+                conflict.port
+            )
+        } else {
+            format!(
+                "service could not start because port {} is held; see signals for its holder",
+                conflict.port
+            )
+        };
+    }
     match snapshot.execution {
         Execution::Exited => {
             if error_log_tail.is_empty() {
@@ -145,6 +160,9 @@ pub(crate) fn diagnosis_hint(
             "service has not finished starting; inspect recent logs".to_string()
         }
         Execution::Stopping => "service is stopping".to_string(),
+        Execution::Paused => {
+            "service is paused; healthchecks resume after resume_service".to_string()
+        }
         Execution::Unknown => "service reported an unknown execution state".to_string(),
         Execution::Running => {
             "service state needs attention; inspect snapshot and logs".to_string()
@@ -179,6 +197,15 @@ fn crash_loop_signal(snapshot: &ServiceSnapshot) -> Option<Signal> {
         || "unbounded".to_string(),
         |remaining| remaining.to_string(),
     );
+    if restart.phase == RestartPhase::CrashLoop {
+        return Some(Signal {
+            kind: SignalKind::CrashLoop,
+            detail: "the crash-loop breaker stopped automatic restarts; the service stays exited \
+                     until restarted"
+                .to_string(),
+            next_probe: "fix the cause from the exit signal and retained logs, then restart_service",
+        });
+    }
     Some(Signal {
         kind: SignalKind::CrashLoop,
         detail: format!(
@@ -189,6 +216,49 @@ fn crash_loop_signal(snapshot: &ServiceSnapshot) -> Option<Signal> {
     })
 }
 
+fn port_conflict_signal(snapshot: &ServiceSnapshot) -> Option<Signal> {
+    let conflict = snapshot.port_conflict.as_ref()?;
+    let (outcome, next_probe) = if conflict.waiting {
+        (
+            "the start is held until it is released (port_conflict: wait)",
+            "stop the holder if it is stale — micromux never kills it for you",
+        )
+    } else {
+        (
+            "the start failed (port_conflict: fail)",
+            "stop the holder or change the port, then restart_service",
+        )
+    };
+    Some(Signal {
+        kind: SignalKind::PortConflict,
+        detail: format!("{}; {outcome}", conflict.describe()),
+        next_probe,
+    })
+}
+
+fn startup_timeout_signal(snapshot: &ServiceSnapshot) -> Option<Signal> {
+    if snapshot.startup_timed_out {
+        return Some(Signal {
+            kind: SignalKind::StartupTimeout,
+            detail: "the latest run did not become healthy within its startup_timeout and was \
+                     stopped; the restart policy decides whether it starts again"
+                .to_string(),
+            next_probe: "inspect latest_healthcheck for why the probe never passed",
+        });
+    }
+    if snapshot.timed_out_dependencies.is_empty() {
+        return None;
+    }
+    Some(Signal {
+        kind: SignalKind::StartupTimeout,
+        detail: format!(
+            "blocked on {}, whose latest start timed out before becoming healthy",
+            snapshot.timed_out_dependencies.join(", ")
+        ),
+        next_probe: "diagnose the timed-out dependency; this service starts once it is healthy",
+    })
+}
+
 fn healthcheck_signal(
     snapshot: &ServiceSnapshot,
     latest_healthcheck: Option<&HealthAttempt>,
@@ -216,12 +286,18 @@ fn healthcheck_signal(
                 result.exit_code
             )
         }
-        Some(result) => {
-            format!(
+        Some(result) => match (result.failed_expectation, result.status_code) {
+            (Some(expectation), Some(status)) => format!(
+                "latest completed attempt failed its {expectation} expectation with HTTP status {status}"
+            ),
+            (Some(expectation), None) => {
+                format!("latest completed attempt failed its {expectation} expectation")
+            }
+            (None, _) => format!(
                 "latest completed attempt failed with exit status {}",
                 result.exit_code
-            )
-        }
+            ),
+        },
         None => "no healthcheck attempt has completed yet".to_string(),
     };
     Some(Signal {
@@ -258,8 +334,8 @@ async fn probe_port(port: u16) -> PortProbe {
 }
 
 async fn port_signals(snapshot: &ServiceSnapshot) -> Vec<Signal> {
-    // Probe only when micromux holds no live process for this service: a Running service has
-    // plausibly bound its own port, a Stopping one is still draining and may hold it too, and
+    // Probe only when micromux holds no live process for this service: a Running or Paused service
+    // has plausibly bound its own port, a Stopping one is still draining and may hold it too, and
     // Unknown (a newer peer's state) gives no basis to attribute the holder. A held port while
     // Pending/Blocked/Starting/Exited is the useful fact — a foreign process or an orphaned child of
     // a previous run will make the next bind fail.
@@ -286,7 +362,7 @@ async fn port_signals(snapshot: &ServiceSnapshot) -> Vec<Signal> {
             }
             let process_owner = tokio::task::spawn_blocking({
                 let port = *port;
-                move || portowner::listening_owner(port)
+                move || micromux::listening_owner(port)
             })
             .await
             .ok()
@@ -334,8 +410,10 @@ async fn micromux_port_owner(port: u16) -> Option<MicromuxPortOwner> {
             continue;
         };
         if let Some(service) = services.into_iter().find(|service| {
-            matches!(service.execution, Execution::Running | Execution::Stopping)
-                && service.advertised_ports.contains(&port)
+            matches!(
+                service.execution,
+                Execution::Running | Execution::Paused | Execution::Stopping
+            ) && service.advertised_ports.contains(&port)
         }) {
             return Some(MicromuxPortOwner {
                 service: service.id,
@@ -354,6 +432,8 @@ pub(crate) async fn supervisor_signals(
     let mut signals = [
         exit_signal(snapshot),
         crash_loop_signal(snapshot),
+        port_conflict_signal(snapshot),
+        startup_timeout_signal(snapshot),
         healthcheck_signal(snapshot, latest_healthcheck),
     ]
     .into_iter()
@@ -467,7 +547,10 @@ pub(crate) async fn wait_for_snapshot<V>(
                                 || change.service_id == SessionChange::SESSION_WIDE)
                                 && !matches!(
                                     change.kind,
-                                    ChangeKind::Logs | ChangeKind::Heartbeat
+                                    ChangeKind::Logs
+                                        | ChangeKind::Hooks
+                                        | ChangeKind::Tasks
+                                        | ChangeKind::Heartbeat
                                 ) =>
                         {
                             return true;
@@ -564,6 +647,14 @@ pub(crate) fn timeout_hint(snapshot: &ServiceSnapshot) -> &'static str {
                  get_logs"
             }
         }
+        Execution::Blocked if snapshot.port_conflict.is_some() => {
+            "the service is waiting for a held port to be released — inspect \
+             snapshot.port_conflict for the process holding it"
+        }
+        Execution::Blocked if !snapshot.timed_out_dependencies.is_empty() => {
+            "the service is waiting on a dependency whose start timed out — inspect \
+             snapshot.timed_out_dependencies and diagnose them"
+        }
         Execution::Blocked => {
             "the service is waiting on a dependency that has not met its condition — inspect \
              get_service_events for the blocking dependencies, or wait_for_healthy on them first"
@@ -573,6 +664,9 @@ pub(crate) fn timeout_hint(snapshot: &ServiceSnapshot) -> &'static str {
              get_logs"
         }
         Execution::Stopping => "the service is stopping",
+        Execution::Paused => {
+            "the service is paused and its healthchecks are held — call resume_service first"
+        }
         Execution::Unknown => {
             "the session reported an unknown service state — inspect list_services"
         }
@@ -675,6 +769,7 @@ async fn fetch_log_tail(
             service: service.to_string(),
             run_generation,
             tail: Some(DIAGNOSE_LOG_SCAN),
+            stream: None,
         })
         .await?;
     convert::logs(response)
@@ -743,6 +838,7 @@ mod tests {
     fn crash_loop_signal_reports_backoff_and_remaining_budget() {
         let mut snapshot = snapshot(Execution::Exited);
         snapshot.restart_state = Some(RestartState {
+            phase: RestartPhase::Backoff,
             backoff_delay: Duration::from_millis(500),
             restarts_remaining: Some(2),
         });
@@ -756,6 +852,59 @@ mod tests {
         assert!(signal.as_ref().is_some_and(|signal| {
             signal.detail.contains("500ms") && signal.detail.contains('2')
         }));
+
+        snapshot.restart_state = Some(RestartState {
+            phase: RestartPhase::CrashLoop,
+            backoff_delay: Duration::ZERO,
+            restarts_remaining: None,
+        });
+        let parked = crash_loop_signal(&snapshot);
+        assert!(parked.as_ref().is_some_and(|signal| {
+            signal.detail.contains("crash-loop breaker")
+                && signal.next_probe.contains("restart_service")
+        }));
+    }
+
+    #[test]
+    fn port_conflict_signal_names_the_holder_and_the_policy_outcome() {
+        let mut snapshot = snapshot(Execution::Blocked);
+        assert_eq!(port_conflict_signal(&snapshot), None);
+
+        snapshot.port_conflict = Some(micromux::PortConflict {
+            port: 8080,
+            pid: Some(4242),
+            command: Some("node".to_string()),
+            waiting: true,
+        });
+        let signal = port_conflict_signal(&snapshot);
+        assert!(signal.as_ref().is_some_and(|signal| {
+            signal.kind == SignalKind::PortConflict
+                && signal
+                    .detail
+                    .contains("port 8080 is held by pid 4242 (node)")
+                && signal.detail.contains("port_conflict: wait")
+        }));
+        assert!(diagnosis_hint(&snapshot, None, &[]).contains("waiting for port 8080"));
+    }
+
+    #[test]
+    fn startup_timeout_signal_covers_the_service_and_its_blocked_dependents() {
+        let mut dependent = snapshot(Execution::Blocked);
+        assert_eq!(startup_timeout_signal(&dependent), None);
+
+        dependent.timed_out_dependencies = vec!["db".to_string()];
+        let blocked = startup_timeout_signal(&dependent);
+        assert!(blocked.as_ref().is_some_and(|signal| {
+            signal.kind == SignalKind::StartupTimeout && signal.detail.contains("blocked on db")
+        }));
+        assert!(timeout_hint(&dependent).contains("timed_out_dependencies"));
+
+        let mut timed_out = snapshot(Execution::Exited);
+        timed_out.startup_timed_out = true;
+        let signal = startup_timeout_signal(&timed_out);
+        assert!(signal.as_ref().is_some_and(|signal| {
+            signal.detail.contains("startup_timeout") && signal.next_probe.contains("healthcheck")
+        }));
     }
 
     #[test]
@@ -786,6 +935,9 @@ mod tests {
                 success: false,
                 exit_code: 7,
                 cancelled: false,
+                latency_ms: Some(12),
+                status_code: None,
+                failed_expectation: None,
             }),
         };
         let failing = healthcheck_signal(&snapshot, Some(&attempt));
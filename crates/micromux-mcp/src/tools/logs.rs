@@ -189,6 +189,7 @@ pub(crate) async fn fetch_and_shape_tail(
             service: service_id.to_string(),
             run_generation,
             tail: Some(fetch_tail),
+            stream: filters.stream,
         })
         .await
         .map_err(error_data)?;
@@ -222,6 +223,7 @@ pub(crate) async fn current_service_cursor(
             service: service.to_string(),
             run_generation: None,
             tail: Some(1),
+            stream: None,
         })
         .await?;
     Ok(convert::logs(response)?
@@ -1,5 +1,4 @@
 pub(crate) mod events;
 pub(crate) mod health;
 pub(crate) mod logs;
-pub(crate) mod portowner;
 pub(crate) mod sessions;
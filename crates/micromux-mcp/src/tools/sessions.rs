@@ -117,11 +117,15 @@ pub(crate) struct SpawnedServe {
 /// file so a startup failure can be quoted back to the agent — the caller deletes it on success
 /// (`remove_captured_stderr`) and keeps + quotes it on failure (`start_failure_message`).
 /// `--config` pins the same endpoint hash the proxy derives, regardless of the child's working
-/// directory. Using `tokio::process` means the runtime reaps the child in the background once it
+/// directory; each profile is passed as `--profile=<name>` so a leading dash cannot read as a
+/// flag. Using `tokio::process` means the runtime reaps the child in the background once it
 /// exits (no zombie), so a later `kill(pid, 0)` reports it truly gone, and lets `start_session`
 /// observe an early exit.
 #[cfg(unix)]
-pub(crate) fn spawn_detached_serve(config_path: &Path) -> std::io::Result<SpawnedServe> {
+pub(crate) fn spawn_detached_serve(
+    config_path: &Path,
+    profiles: &[String],
+) -> std::io::Result<SpawnedServe> {
     use std::process::Stdio;
 
     let exe = std::env::current_exe()?;
@@ -142,6 +146,11 @@ pub(crate) fn spawn_detached_serve(config_path: &Path) -> std::io::Result<Spawne
         .arg("serve")
         .arg("--config")
         .arg(config_path)
+        .args(
+            profiles
+                .iter()
+                .map(|profile| format!("--profile={profile}")),
+        )
         .current_dir(project_dir)
         .stdin(Stdio::null())
         .stdout(Stdio::null())
@@ -158,7 +167,10 @@ pub(crate) fn spawn_detached_serve(config_path: &Path) -> std::io::Result<Spawne
 }
 
 #[cfg(not(unix))]
-pub(crate) fn spawn_detached_serve(_config_path: &Path) -> std::io::Result<SpawnedServe> {
+pub(crate) fn spawn_detached_serve(
+    _config_path: &Path,
+    _profiles: &[String],
+) -> std::io::Result<SpawnedServe> {
     Err(std::io::Error::new(
         std::io::ErrorKind::Unsupported,
         "start_session is only supported on unix",
@@ -242,6 +254,7 @@ pub(crate) async fn already_running_any(
                 session: None,
                 id: None,
                 pid: None,
+                profiles: Vec::new(),
                 endpoint: Some(probe.endpoint.to_string()),
                 reason: Some(reason),
             }));
@@ -277,6 +290,7 @@ pub(crate) fn start_session_report(info: &SessionInfo, started: bool) -> StartSe
             session: Some(info.name.clone()),
             id: Some(info.id.clone()),
             pid: Some(info.pid),
+            profiles: info.profiles.clone(),
             endpoint: None,
             reason: None,
         }
@@ -294,6 +308,7 @@ fn already_running_report(info: &SessionInfo) -> StartSessionResult {
         session: Some(info.name.clone()),
         id: Some(info.id.clone()),
         pid: Some(info.pid),
+        profiles: info.profiles.clone(),
         endpoint: None,
         reason: None,
     }
@@ -7,8 +7,9 @@ const INPUT_QUEUE_CAPACITY: usize = 1024;
 
 /// Representation of all possible input events.
 ///
-/// The TUI redraws on each of these and on every model [`micromux::SessionChange`], so there is no
-/// periodic tick — nothing in the view is time-animated, and live output arrives as model changes.
+/// The TUI redraws on each of these and on every model [`micromux::SessionChange`]. Live output
+/// arrives as model changes, so the only periodic redraw is the once-a-second refresh of a
+/// scheduled service's countdown.
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Hash)]
 pub enum Input {
     /// Crossterm events emitted by the terminal.
@@ -27,6 +27,8 @@ pub use source::{LocalSource, SessionSource};
 
 const FRAME_INTERVAL: Duration = Duration::from_millis(16);
 const PENDING_PTY_INPUT_RETRY: Duration = Duration::from_millis(5);
+/// How often the view redraws while a scheduled service counts down to its next run.
+const COUNTDOWN_REFRESH: Duration = Duration::from_secs(1);
 const TERMINAL_INPUT_CLOSED_NOTICE: &str = "terminal input closed; send SIGINT or SIGTERM to exit";
 
 fn format_byte_limit(bytes: usize) -> String {
@@ -73,7 +75,15 @@ pub struct App {
     healthcheck_view: crate::render::log_view::LogView,
     show_healthcheck_pane: bool,
     pretty_json_logs: bool,
+    /// Show only log lines from this output stream; `None` shows every line.
+    stream_filter: Option<micromux::OutputStream>,
+    /// Whether restart and disable/enable address the selected instance's whole replica group.
+    group_scope: bool,
     pty_input_mode: bool,
+    /// Whether the next key picks a signal for the selected service instead of running a command.
+    signal_picker: bool,
+    /// Whether the next key picks an on-demand task to run instead of running a command.
+    task_picker: bool,
     focus: Focus,
     terminal_cols: u16,
     terminal_rows: u16,
@@ -132,7 +142,11 @@ impl App {
             healthcheck_view,
             show_healthcheck_pane: false,
             pretty_json_logs,
+            stream_filter: None,
+            group_scope: false,
             pty_input_mode: false,
+            signal_picker: false,
+            task_picker: false,
             focus: Focus::Services,
             terminal_cols: 80,
             terminal_rows: 24,
@@ -375,6 +389,8 @@ impl App {
             /// The change broadcast lagged (or this is the initial draw): re-read everything.
             Resync,
             PendingInput,
+            /// A scheduled service's countdown needs redrawing.
+            Clock,
         }
 
         let area = terminal.size()?;
@@ -391,6 +407,11 @@ impl App {
 
         while self.is_running() {
             let retry_pending_input = !self.pending_pty_input.is_empty();
+            let counting_down = self
+                .state
+                .services
+                .iter()
+                .any(|service| service.snapshot.next_run_at_unix_ms.is_some());
             let wake = tokio::select! {
                 () = self.shutdown.cancelled() => None,
                 input = self.input_event_handler.next() => {
@@ -408,6 +429,13 @@ impl App {
                         std::future::pending::<()>().await;
                     }
                 } => Some(Wake::PendingInput),
+                () = async {
+                    if counting_down {
+                        tokio::time::sleep(COUNTDOWN_REFRESH).await;
+                    } else {
+                        std::future::pending::<()>().await;
+                    }
+                } => Some(Wake::Clock),
             };
 
             let mut needs_resync = false;
@@ -416,6 +444,7 @@ impl App {
                 Some(Wake::Change(change)) => needs_resync |= self.apply_change(&change),
                 Some(Wake::Resync) => needs_resync = true,
                 Some(Wake::PendingInput) => self.flush_pending_pty_input(),
+                Some(Wake::Clock) => {}
                 None => {
                     self.running = false;
                     continue;
@@ -473,7 +502,7 @@ impl App {
             }
             ChangeKind::Roster | ChangeKind::Unknown => return true,
             ChangeKind::Events => self.observe_input_events(&change.service_id),
-            ChangeKind::Heartbeat => {}
+            ChangeKind::Hooks | ChangeKind::Tasks | ChangeKind::Heartbeat => {}
         }
         false
     }
@@ -595,6 +624,14 @@ impl App {
             self.exit();
             return;
         }
+        if self.signal_picker {
+            self.handle_key_press_signal_picker(key);
+            return;
+        }
+        if self.task_picker {
+            self.handle_key_press_task_picker(key);
+            return;
+        }
         if key.modifiers != KeyModifiers::NONE && key.modifiers != KeyModifiers::SHIFT {
             return;
         }
@@ -617,6 +654,19 @@ impl App {
 
             KeyCode::Char('s') => self.stop_current_dynamic_service(),
 
+            // Pick a signal to send to the current service
+            KeyCode::Char('x') => {
+                self.signal_picker = self.state.current_service().is_some();
+            }
+
+            // Pick an on-demand task to run
+            KeyCode::Char('T') => {
+                self.task_picker = !self.source.task_names().is_empty();
+            }
+
+            // Pause or resume current service
+            KeyCode::Char('p') => self.toggle_pause_current_service(),
+
             // Restart service
             KeyCode::Char('r') => self.restart_current_service(),
 
@@ -648,6 +698,12 @@ impl App {
 
             // Toggle automatic tailing for log viewer
             KeyCode::Char('t') => self.toggle_tail(),
+
+            // Cycle the log viewer through all output, stdout only, and stderr only
+            KeyCode::Char('o') => self.cycle_stream_filter(),
+
+            // Toggle whether restart and disable/enable act on the whole replica group
+            KeyCode::Char('u') => self.group_scope = !self.group_scope,
             _ => {}
         }
     }
@@ -670,6 +726,46 @@ impl App {
         }
     }
 
+    /// Any key closes the picker; a digit also sends the matching entry of
+    /// [`micromux::ServiceSignal::ALL`].
+    fn handle_key_press_signal_picker(&mut self, key: crossterm::event::KeyEvent) {
+        use crossterm::event::{KeyCode, KeyModifiers};
+
+        self.signal_picker = false;
+        let KeyCode::Char(digit) = key.code else {
+            return;
+        };
+        if key.modifiers != KeyModifiers::NONE {
+            return;
+        }
+        if let Some(signal) = ('1'..='9')
+            .position(|candidate| candidate == digit)
+            .and_then(|index| micromux::ServiceSignal::ALL.get(index))
+        {
+            self.signal_current_service(*signal);
+        }
+    }
+
+    /// Any key closes the picker; a digit also runs the matching task, in configuration order.
+    fn handle_key_press_task_picker(&mut self, key: crossterm::event::KeyEvent) {
+        use crossterm::event::{KeyCode, KeyModifiers};
+
+        self.task_picker = false;
+        let KeyCode::Char(digit) = key.code else {
+            return;
+        };
+        if key.modifiers != KeyModifiers::NONE {
+            return;
+        }
+        if let Some(task) = ('1'..='9')
+            .position(|candidate| candidate == digit)
+            .and_then(|index| self.source.task_names().into_iter().nth(index))
+        {
+            tracing::info!(task, "running task");
+            self.source.run_task(task);
+        }
+    }
+
     fn toggle_focus(&mut self) {
         self.focus = if self.show_healthcheck_pane {
             match self.focus {
@@ -741,6 +837,19 @@ impl App {
         self.healthcheck_view.wrap = wrap;
     }
 
+    fn cycle_stream_filter(&mut self) {
+        use micromux::OutputStream;
+
+        self.stream_filter = match self.stream_filter {
+            None => Some(OutputStream::Stdout),
+            Some(OutputStream::Stdout) => Some(OutputStream::Stderr),
+            Some(_) => None,
+        };
+        for service in &mut self.state.services {
+            service.text_dirty = true;
+        }
+    }
+
     fn toggle_tail(&mut self) {
         match self.focus {
             Focus::Logs | Focus::Services => {
@@ -816,15 +925,25 @@ impl App {
         self.running = false;
     }
 
+    /// The id lifecycle commands address: the selected service, or its replica group in group
+    /// scope.
+    fn command_target(&self, service: &state::Service) -> String {
+        match &service.snapshot.replica_group {
+            Some(group) if self.group_scope => group.clone(),
+            _ => service.snapshot.id.clone(),
+        }
+    }
+
     /// Disable service
     fn disable_current_service(&self) {
         let Some(service) = self.state.current_service() else {
             return;
         };
-        tracing::info!(service_id = service.snapshot.id, "disabling service");
+        let target = self.command_target(service);
+        tracing::info!(service_id = target, "disabling service");
         match service.snapshot.desired {
-            micromux::Desired::Disabled => self.source.enable(service.snapshot.id.clone()),
-            micromux::Desired::Enabled => self.source.disable(service.snapshot.id.clone()),
+            micromux::Desired::Disabled => self.source.enable(target),
+            micromux::Desired::Enabled => self.source.disable(target),
             micromux::Desired::Unknown => {}
         }
     }
@@ -847,8 +966,35 @@ impl App {
         let Some(service) = self.state.current_service() else {
             return;
         };
-        tracing::info!(service_id = service.snapshot.id, "restarting service");
-        self.source.restart(service.snapshot.id.clone());
+        let target = self.command_target(service);
+        tracing::info!(service_id = target, "restarting service");
+        self.source.restart(target);
+    }
+
+    fn signal_current_service(&self, signal: micromux::ServiceSignal) {
+        let Some(service) = self.state.current_service() else {
+            return;
+        };
+        tracing::info!(service_id = service.snapshot.id, %signal, "signaling service");
+        self.source.signal(service.snapshot.id.clone(), signal);
+    }
+
+    /// Pause a running service, or resume a paused one.
+    fn toggle_pause_current_service(&self) {
+        let Some(service) = self.state.current_service() else {
+            return;
+        };
+        match service.snapshot.execution {
+            micromux::Execution::Running => {
+                tracing::info!(service_id = service.snapshot.id, "pausing service");
+                self.source.pause(service.snapshot.id.clone());
+            }
+            micromux::Execution::Paused => {
+                tracing::info!(service_id = service.snapshot.id, "resuming service");
+                self.source.resume(service.snapshot.id.clone());
+            }
+            _ => {}
+        }
     }
 
     /// Restart all services
@@ -1611,6 +1757,309 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn signal_picker_sends_the_chosen_signal_or_cancels() -> eyre::Result<()> {
+        let yaml = indoc! {r#"
+            version: 1
+            services:
+              svc:
+                command: ["sh", "-c", "true"]
+        "#};
+        let mut diagnostics: Vec<Diagnostic<usize>> = vec![];
+        let parsed = micromux::from_str(yaml, Path::new("."), 0usize, None, &mut diagnostics)
+            .map_err(|err| eyre::eyre!(err.to_string()))?;
+        let mux = std::sync::Arc::new(
+            micromux::Micromux::new(&parsed).map_err(|err| eyre::eyre!(err.to_string()))?,
+        );
+        let shutdown = micromux::CancellationToken::new();
+        let (_runner, handles) = mux.start(shutdown.clone());
+        let (commands_tx, mut commands_rx) = mpsc::channel(4);
+        let mut app = App::new(
+            SessionSource::Local(LocalSource::new(
+                handles.reader.clone(),
+                commands_tx.clone(),
+            )),
+            Some(handles.terminal),
+            shutdown,
+            true,
+        );
+        let key = |code| KeyEvent {
+            code,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        };
+
+        app.handle_key_press(key(KeyCode::Char('x')));
+        assert!(app.signal_picker);
+        app.handle_key_press(key(KeyCode::Esc));
+        assert!(!app.signal_picker);
+        // With the picker closed, digits are not commands.
+        app.handle_key_press(key(KeyCode::Char('2')));
+        assert_matches!(
+            commands_rx.try_recv(),
+            Err(mpsc::error::TryRecvError::Empty)
+        );
+
+        app.handle_key_press(key(KeyCode::Char('x')));
+        app.handle_key_press(key(KeyCode::Char('2')));
+        assert!(!app.signal_picker);
+        let command = tokio::time::timeout(Duration::from_secs(1), commands_rx.recv())
+            .await?
+            .ok_or_else(|| eyre::eyre!("lifecycle relay stopped"))?;
+        match command {
+            micromux::Command::Signal {
+                service,
+                signal,
+                ack,
+            } => {
+                assert_eq!(service, "svc");
+                assert_eq!(signal, micromux::ServiceSignal::Int);
+                assert!(ack.is_none());
+            }
+            other => {
+                eyre::bail!("expected signal command, got {other:?}");
+            }
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn task_picker_runs_the_chosen_task_or_cancels() -> eyre::Result<()> {
+        let yaml = indoc! {r#"
+            version: 1
+            services:
+              svc:
+                command: ["sh", "-c", "true"]
+            tasks:
+              seed:
+                command: ["true"]
+              reset-cache:
+                command: ["true"]
+        "#};
+        let mut diagnostics: Vec<Diagnostic<usize>> = vec![];
+        let parsed = micromux::from_str(yaml, Path::new("."), 0usize, None, &mut diagnostics)
+            .map_err(|err| eyre::eyre!(err.to_string()))?;
+        let mux = std::sync::Arc::new(
+            micromux::Micromux::new(&parsed).map_err(|err| eyre::eyre!(err.to_string()))?,
+        );
+        let shutdown = micromux::CancellationToken::new();
+        let (_runner, handles) = mux.start(shutdown.clone());
+        let (commands_tx, mut commands_rx) = mpsc::channel(4);
+        let mut app = App::new(
+            SessionSource::Local(LocalSource::new(
+                handles.reader.clone(),
+                commands_tx.clone(),
+            )),
+            Some(handles.terminal),
+            shutdown,
+            true,
+        );
+        let key = |code| KeyEvent {
+            code,
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        };
+
+        app.handle_key_press(key(KeyCode::Char('T')));
+        assert!(app.task_picker);
+        app.handle_key_press(key(KeyCode::Esc));
+        assert!(!app.task_picker);
+        // A digit beyond the configured tasks closes the picker without running anything.
+        app.handle_key_press(key(KeyCode::Char('T')));
+        app.handle_key_press(key(KeyCode::Char('3')));
+        assert!(!app.task_picker);
+        assert_matches!(
+            commands_rx.try_recv(),
+            Err(mpsc::error::TryRecvError::Empty)
+        );
+
+        app.handle_key_press(key(KeyCode::Char('T')));
+        app.handle_key_press(key(KeyCode::Char('2')));
+        let command = tokio::time::timeout(Duration::from_secs(1), commands_rx.recv())
+            .await?
+            .ok_or_else(|| eyre::eyre!("lifecycle relay stopped"))?;
+        match command {
+            micromux::Command::RunTask { task, ack } => {
+                assert_eq!(task, "reset-cache");
+                assert!(ack.is_none());
+            }
+            other => {
+                eyre::bail!("expected run task command, got {other:?}");
+            }
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn pause_key_toggles_by_execution_state() -> eyre::Result<()> {
+        let yaml = indoc! {r#"
+            version: 1
+            services:
+              svc:
+                command: ["sh", "-c", "true"]
+        "#};
+        let mut diagnostics: Vec<Diagnostic<usize>> = vec![];
+        let parsed = micromux::from_str(yaml, Path::new("."), 0usize, None, &mut diagnostics)
+            .map_err(|err| eyre::eyre!(err.to_string()))?;
+        let mux = std::sync::Arc::new(
+            micromux::Micromux::new(&parsed).map_err(|err| eyre::eyre!(err.to_string()))?,
+        );
+        let shutdown = micromux::CancellationToken::new();
+        let (_runner, handles) = mux.start(shutdown.clone());
+        let (commands_tx, mut commands_rx) = mpsc::channel(4);
+        let mut app = App::new(
+            SessionSource::Local(LocalSource::new(
+                handles.reader.clone(),
+                commands_tx.clone(),
+            )),
+            Some(handles.terminal),
+            shutdown,
+            true,
+        );
+        let pause_key = KeyEvent {
+            code: KeyCode::Char('p'),
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        };
+
+        if let Some(service) = app.state.current_service_mut() {
+            service.snapshot.execution = micromux::Execution::Exited;
+        }
+        app.handle_key_press(pause_key);
+        assert_matches!(
+            commands_rx.try_recv(),
+            Err(mpsc::error::TryRecvError::Empty)
+        );
+
+        if let Some(service) = app.state.current_service_mut() {
+            service.snapshot.execution = micromux::Execution::Running;
+        }
+        app.handle_key_press(pause_key);
+        let command = tokio::time::timeout(Duration::from_secs(1), commands_rx.recv())
+            .await?
+            .ok_or_else(|| eyre::eyre!("lifecycle relay stopped"))?;
+        assert_matches!(
+            command,
+            micromux::Command::Pause { service, ack: None } if service == "svc"
+        );
+
+        if let Some(service) = app.state.current_service_mut() {
+            service.snapshot.execution = micromux::Execution::Paused;
+        }
+        app.handle_key_press(pause_key);
+        let command = tokio::time::timeout(Duration::from_secs(1), commands_rx.recv())
+            .await?
+            .ok_or_else(|| eyre::eyre!("lifecycle relay stopped"))?;
+        assert_matches!(
+            command,
+            micromux::Command::Resume { service, ack: None } if service == "svc"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn stream_key_cycles_the_log_filter_and_redraws() -> eyre::Result<()> {
+        let yaml = indoc! {r#"
+            version: 1
+            services:
+              svc:
+                command: ["sh", "-c", "true"]
+                tty: false
+        "#};
+        let mut diagnostics: Vec<Diagnostic<usize>> = vec![];
+        let parsed = micromux::from_str(yaml, Path::new("."), 0usize, None, &mut diagnostics)
+            .map_err(|err| eyre::eyre!(err.to_string()))?;
+        let mux = std::sync::Arc::new(
+            micromux::Micromux::new(&parsed).map_err(|err| eyre::eyre!(err.to_string()))?,
+        );
+        let shutdown = micromux::CancellationToken::new();
+        let (_runner, handles) = mux.start(shutdown.clone());
+        let (commands_tx, _commands_rx) = mpsc::channel(4);
+        let mut app = App::new(
+            SessionSource::Local(LocalSource::new(handles.reader.clone(), commands_tx)),
+            None,
+            shutdown,
+            true,
+        );
+        let stream_key = KeyEvent {
+            code: KeyCode::Char('o'),
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        };
+
+        for expected in [
+            Some(micromux::OutputStream::Stdout),
+            Some(micromux::OutputStream::Stderr),
+            None,
+        ] {
+            if let Some(service) = app.state.current_service_mut() {
+                service.text_dirty = false;
+            }
+            app.handle_key_press(stream_key);
+            assert_eq!(app.stream_filter, expected);
+            assert!(
+                app.state
+                    .current_service()
+                    .is_some_and(|service| service.text_dirty)
+            );
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn group_scope_addresses_the_replica_group() -> eyre::Result<()> {
+        let yaml = indoc! {r#"
+            version: 1
+            services:
+              worker:
+                command: ["sh", "-c", "true"]
+                replicas: 2
+        "#};
+        let mut diagnostics: Vec<Diagnostic<usize>> = vec![];
+        let parsed = micromux::from_str(yaml, Path::new("."), 0usize, None, &mut diagnostics)
+            .map_err(|err| eyre::eyre!(err.to_string()))?;
+        let mux = std::sync::Arc::new(
+            micromux::Micromux::new(&parsed).map_err(|err| eyre::eyre!(err.to_string()))?,
+        );
+        let shutdown = micromux::CancellationToken::new();
+        let (_runner, handles) = mux.start(shutdown.clone());
+        let (commands_tx, mut commands_rx) = mpsc::channel(4);
+        let mut app = App::new(
+            SessionSource::Local(LocalSource::new(handles.reader.clone(), commands_tx)),
+            None,
+            shutdown,
+            true,
+        );
+        let key = |c| KeyEvent {
+            code: KeyCode::Char(c),
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        };
+
+        for (scope_key, expected) in [(None, "worker-1"), (Some('u'), "worker")] {
+            if let Some(scope_key) = scope_key {
+                app.handle_key_press(key(scope_key));
+            }
+            app.handle_key_press(key('r'));
+            let command = tokio::time::timeout(Duration::from_secs(1), commands_rx.recv())
+                .await?
+                .ok_or_else(|| eyre::eyre!("lifecycle relay stopped"))?;
+            assert_matches!(
+                command,
+                micromux::Command::Restart { service, ack: None } if service == expected
+            );
+        }
+        Ok(())
+    }
+
     #[tokio::test]
     async fn stop_key_only_sends_for_a_live_dynamic_service() -> eyre::Result<()> {
         let yaml = indoc! {r#"
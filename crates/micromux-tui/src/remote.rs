@@ -36,6 +36,8 @@ pub struct RemoteSource {
 struct MirrorStore {
     session: SessionInfo,
     services: IndexMap<ServiceID, MirrorEntry>,
+    /// Names of the session's on-demand tasks, in configuration order.
+    tasks: Vec<String>,
     connected: bool,
     notice: Option<String>,
 }
@@ -56,13 +58,18 @@ enum RemoteCommand {
     RestartAll,
     Enable(ServiceID),
     Disable(ServiceID),
+    Signal(ServiceID, micromux::ServiceSignal),
+    Pause(ServiceID),
+    Resume(ServiceID),
     StopDynamic(ServiceID),
+    RunTask(String),
 }
 
 enum ExpectedCommandResponse {
     Accepted,
     RestartAccepted,
     Dynamic,
+    TaskStarted,
 }
 
 enum ConnectedLoopExit {
@@ -134,6 +141,7 @@ impl RemoteSource {
         let store = Arc::new(RwLock::new(MirrorStore {
             session,
             services: IndexMap::new(),
+            tasks: Vec::new(),
             connected: false,
             notice: None,
         }));
@@ -258,11 +266,37 @@ impl RemoteSource {
         let _ = self.commands.send(RemoteCommand::Disable(id));
     }
 
+    /// Queue a signal for a remote service's running process.
+    pub fn signal(&self, id: ServiceID, signal: micromux::ServiceSignal) {
+        let _ = self.commands.send(RemoteCommand::Signal(id, signal));
+    }
+
+    /// Queue a remote service pause.
+    pub fn pause(&self, id: ServiceID) {
+        let _ = self.commands.send(RemoteCommand::Pause(id));
+    }
+
+    /// Queue a remote service resume.
+    pub fn resume(&self, id: ServiceID) {
+        let _ = self.commands.send(RemoteCommand::Resume(id));
+    }
+
     /// Queue retirement of a remote dynamic service.
     pub fn stop_dynamic(&self, id: ServiceID) {
         let _ = self.commands.send(RemoteCommand::StopDynamic(id));
     }
 
+    /// Names of the remote session's on-demand tasks.
+    #[must_use]
+    pub fn task_names(&self) -> Vec<String> {
+        self.store.read().tasks.clone()
+    }
+
+    /// Queue a run of a remote on-demand task.
+    pub fn run_task(&self, task: String) {
+        let _ = self.commands.send(RemoteCommand::RunTask(task));
+    }
+
     /// Stop the mirror task without affecting the remote session.
     pub fn cancel(&self) {
         self.shutdown.cancel();
@@ -413,6 +447,7 @@ fn update_session(store: &Arc<RwLock<MirrorStore>>, session: SessionInfo) {
     let mut guard = store.write();
     if !guard.session.is_same_instance(&session) {
         guard.services.clear();
+        guard.tasks.clear();
         guard.notice = None;
     }
     guard.session = session;
@@ -438,6 +473,7 @@ async fn full_sync<C: RequestConnection>(
 ) -> Result<Vec<ServiceID>, ControlError> {
     let snapshots = request_services(request).await?;
     reconcile_services(store, snapshots);
+    refresh_tasks(request, store).await?;
     let service_ids = store.read().services.keys().cloned().collect::<Vec<_>>();
     for service_id in &service_ids {
         refresh_logs(request, store, service_id).await?;
@@ -484,6 +520,8 @@ async fn refresh_and_forward_batch<C: RequestConnection>(
             | ChangeKind::Roster
             | ChangeKind::Unknown
             | ChangeKind::Events
+            | ChangeKind::Hooks
+            | ChangeKind::Tasks
             | ChangeKind::Heartbeat => {}
         }
     }
@@ -510,6 +548,24 @@ async fn refresh_roster<C: RequestConnection>(
     Ok(())
 }
 
+/// Tasks are fixed for the life of a session, so their names are only fetched on a full sync.
+async fn refresh_tasks<C: RequestConnection>(
+    request: &mut C,
+    store: &Arc<RwLock<MirrorStore>>,
+) -> Result<(), ControlError> {
+    // Sessions before protocol 3.21 have no on-demand tasks.
+    if store.read().session.protocol_version.minor() < 21 {
+        return Ok(());
+    }
+    match request.request(Request::ListTasks).await? {
+        Response::Tasks(tasks) => {
+            store.write().tasks = tasks.into_iter().map(|task| task.name).collect();
+            Ok(())
+        }
+        other => Err(unexpected_response(&other)),
+    }
+}
+
 async fn request_services<C: RequestConnection>(
     request: &mut C,
 ) -> Result<Vec<ServiceSnapshot>, ControlError> {
@@ -728,10 +784,26 @@ async fn execute_command(
             Request::Disable { service },
             ExpectedCommandResponse::Accepted,
         ),
+        RemoteCommand::Signal(service, signal) => (
+            Request::Signal { service, signal },
+            ExpectedCommandResponse::Accepted,
+        ),
+        RemoteCommand::Pause(service) => (
+            Request::Pause { service },
+            ExpectedCommandResponse::Accepted,
+        ),
+        RemoteCommand::Resume(service) => (
+            Request::Resume { service },
+            ExpectedCommandResponse::Accepted,
+        ),
         RemoteCommand::StopDynamic(service) => (
             Request::StopDynamicService { service },
             ExpectedCommandResponse::Dynamic,
         ),
+        RemoteCommand::RunTask(task) => (
+            Request::RunTask { task },
+            ExpectedCommandResponse::TaskStarted,
+        ),
     };
     let response = request.request(request_message).await?;
     let notice = match (response, expected_response) {
@@ -740,7 +812,8 @@ async fn execute_command(
             None
         }
         (Response::Accepted { .. }, ExpectedCommandResponse::Accepted)
-        | (Response::DynamicService(_), ExpectedCommandResponse::Dynamic) => None,
+        | (Response::DynamicService(_), ExpectedCommandResponse::Dynamic)
+        | (Response::TaskStarted(_), ExpectedCommandResponse::TaskStarted) => None,
         (Response::Error { message, .. }, _) => Some(message),
         (other, _) => Some(format!("unexpected control response: {other:?}")),
     };
@@ -883,6 +956,7 @@ mod tests {
             services: Vec::new(),
             services_truncated: false,
             micromux_version: "test".to_string(),
+            profiles: Vec::new(),
             capabilities: None,
         }
     }
@@ -906,6 +980,7 @@ mod tests {
             seq,
             run_generation: 1,
             timestamp_unix_ms: seq,
+            stream: None,
             line: text.to_string(),
         }
     }
@@ -975,6 +1050,7 @@ mod tests {
         Arc::new(RwLock::new(MirrorStore {
             session: session(),
             services: [(entry.snapshot.id.clone(), entry)].into_iter().collect(),
+            tasks: Vec::new(),
             connected: true,
             notice: None,
         }))
@@ -13,7 +13,7 @@ use ratatui::{
 #[cfg(test)]
 mod tests {
     use super::{
-        lease_phrase,
+        countdown, lease_phrase,
         log_view::{LogView, RenderedLineIndex, window_text},
         service_detail_line, shell_join, state_name,
     };
@@ -100,6 +100,56 @@ mod tests {
         assert_eq!(state_name(&snapshot), "RETIRED");
     }
 
+    #[test]
+    fn parked_crash_loop_is_named_distinctly_from_an_exit() {
+        let mut snapshot = micromux::ServiceSnapshot::initial(
+            "flaky".to_string(),
+            "flaky".to_string(),
+            Vec::new(),
+            None,
+            micromux::RestartPolicy::Always,
+            Vec::new(),
+            None,
+        );
+        snapshot.execution = micromux::Execution::Exited;
+        assert_eq!(state_name(&snapshot), "EXITED");
+
+        snapshot.restart_state = Some(micromux::RestartState {
+            phase: micromux::RestartPhase::CrashLoop,
+            backoff_delay: std::time::Duration::ZERO,
+            restarts_remaining: None,
+        });
+        assert_eq!(state_name(&snapshot), "CRASHLOOP");
+    }
+
+    #[test]
+    fn held_ports_are_named_in_the_state_and_detail_line() {
+        let mut snapshot = micromux::ServiceSnapshot::initial(
+            "api".to_string(),
+            "api".to_string(),
+            vec![8080],
+            None,
+            micromux::RestartPolicy::Never,
+            Vec::new(),
+            None,
+        );
+        snapshot.execution = micromux::Execution::Blocked;
+        snapshot.port_conflict = Some(micromux::PortConflict {
+            port: 8080,
+            pid: Some(4242),
+            command: Some("node server.js".to_string()),
+            waiting: true,
+        });
+        assert_eq!(state_name(&snapshot), "PORT WAIT");
+        let detail = service_detail_line(&snapshot, 0)
+            .map(|line| line.to_string())
+            .unwrap_or_default();
+        assert!(detail.contains("port 8080 is held by pid 4242 (node server.js)"));
+
+        snapshot.execution = micromux::Execution::Exited;
+        assert_eq!(state_name(&snapshot), "PORT BUSY");
+    }
+
     #[test]
     fn shell_join_quotes_only_arguments_a_shell_would_split() {
         let argv = vec![
@@ -186,6 +236,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn scheduled_services_show_the_countdown_and_last_exit() {
+        assert_eq!(countdown(43_000, 1_000), "42s");
+        assert_eq!(countdown(253_000, 1_000), "4m12s");
+        assert_eq!(countdown(11_101_000, 1_000), "3h05m");
+        assert_eq!(countdown(500, 1_000), "0s");
+
+        let mut snapshot = micromux::ServiceSnapshot::initial(
+            "warmer".to_string(),
+            "warmer".to_string(),
+            Vec::new(),
+            None,
+            micromux::RestartPolicy::Never,
+            vec!["./warm".to_string()],
+            None,
+        );
+        snapshot.run_generation = 2;
+        snapshot.schedule = Some("*/5 * * * *".to_string());
+        snapshot.next_run_at_unix_ms = Some(91_000);
+        snapshot.last_exit_code = Some(1);
+        let detail = service_detail_line(&snapshot, 1_000)
+            .map(|line| line.to_string())
+            .unwrap_or_default();
+        assert_eq!(
+            detail,
+            " $ ./warm  gen 2  schedule */5 * * * * · next run in 1m30s · last exit 1 "
+        );
+    }
+
     #[test]
     fn wrapped_text_height_uses_word_boundaries() {
         let text: ratatui::text::Text = "aaaaaa aaaaaa aaaaaa".into();
@@ -453,6 +532,9 @@ mod tests {
                 success: true,
                 exit_code: 0,
                 cancelled: false,
+                latency_ms: None,
+                status_code: None,
+                failed_expectation: None,
             }),
         };
         assert_eq!(
@@ -481,12 +563,38 @@ mod tests {
                 success: false,
                 exit_code: -1,
                 cancelled: true,
+                latency_ms: None,
+                status_code: None,
+                failed_expectation: None,
             }),
         };
         assert_eq!(
             build_healthcheck_text(true, std::slice::from_ref(&cancelled)),
             "\x1b[90m[healthcheck cancelled]\x1b[0m probe\n\n"
         );
+
+        let http = HealthAttempt {
+            run_generation: 1,
+            attempt: 4,
+            command: "GET http://localhost/health".to_string(),
+            output: vec![HealthLine {
+                stream: OutputStream::Stderr,
+                line: "expected status 200-399, got 503".to_string(),
+            }],
+            result: Some(HealthResult {
+                success: false,
+                exit_code: -1,
+                cancelled: false,
+                latency_ms: Some(4),
+                status_code: Some(503),
+                failed_expectation: Some(micromux::ProbeExpectation::Status),
+            }),
+        };
+        assert_eq!(
+            build_healthcheck_text(true, std::slice::from_ref(&http)),
+            "\x1b[31m[healthcheck failed status=503 4ms]\x1b[0m GET http://localhost/health\n\n\
+             [stderr] expected status 200-399, got 503"
+        );
     }
 }
 
@@ -514,12 +622,12 @@ fn build_healthcheck_text(configured: bool, attempts: &[micromux::HealthAttempt]
                 "\x1b[90m[healthcheck cancelled]\x1b[0m".to_string()
             }
             Some(result) if result.success => {
-                let code = result.exit_code;
-                format!("\x1b[32m[healthcheck ok exit_code={code}]\x1b[0m")
+                let outcome = healthcheck_outcome(result);
+                format!("\x1b[32m[healthcheck ok {outcome}]\x1b[0m")
             }
             Some(result) => {
-                let code = result.exit_code;
-                format!("\x1b[31m[healthcheck failed exit_code={code}]\x1b[0m")
+                let outcome = healthcheck_outcome(result);
+                format!("\x1b[31m[healthcheck failed {outcome}]\x1b[0m")
             }
             None => "\x1b[33m[healthcheck running]\x1b[0m".to_string(),
         };
@@ -550,6 +658,18 @@ fn build_healthcheck_text(configured: bool, attempts: &[micromux::HealthAttempt]
     out
 }
 
+/// The status code (HTTP probes) or exit code of a finished attempt, plus its latency.
+fn healthcheck_outcome(result: micromux::HealthResult) -> String {
+    let code = result.status_code.map_or_else(
+        || format!("exit_code={}", result.exit_code),
+        |status| format!("status={status}"),
+    );
+    match result.latency_ms {
+        Some(latency) => format!("{code} {latency}ms"),
+        None => code,
+    }
+}
+
 fn state_name(snapshot: &micromux::ServiceSnapshot) -> &'static str {
     if snapshot.retired.is_some() {
         return "RETIRED";
@@ -560,6 +680,7 @@ fn state_name(snapshot: &micromux::ServiceSnapshot) -> &'static str {
 
     match snapshot.execution {
         micromux::Execution::Pending => "PENDING",
+        micromux::Execution::Blocked if snapshot.port_conflict.is_some() => "PORT WAIT",
         micromux::Execution::Blocked => "BLOCKED",
         micromux::Execution::Starting => "STARTING",
         micromux::Execution::Running => match snapshot.health {
@@ -569,6 +690,9 @@ fn state_name(snapshot: &micromux::ServiceSnapshot) -> &'static str {
             None => "RUNNING",
         },
         micromux::Execution::Stopping => "KILLED",
+        micromux::Execution::Paused => "PAUSED",
+        micromux::Execution::Exited if crate::style::crash_looping(snapshot) => "CRASHLOOP",
+        micromux::Execution::Exited if snapshot.port_conflict.is_some() => "PORT BUSY",
         micromux::Execution::Exited => "EXITED",
         micromux::Execution::Unknown => "UNKNOWN",
     }
@@ -632,9 +756,28 @@ fn lease_phrase(expires_at_unix_ms: Option<u64>, now_unix_ms: u64) -> String {
     }
 }
 
+/// Compact time until a scheduled service's next run, for the narrow service list.
+fn countdown(next_run_at_unix_ms: u64, now_unix_ms: u64) -> String {
+    let secs = next_run_at_unix_ms
+        .saturating_sub(now_unix_ms)
+        .div_ceil(1000);
+    if secs < 60 {
+        format!("{secs}s")
+    } else if secs < 3600 {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    } else if secs < 86_400 {
+        format!("{}h{:02}m", secs / 3600, secs % 3600 / 60)
+    } else {
+        format!("{}d{:02}h", secs / 86_400, secs % 86_400 / 3600)
+    }
+}
+
 /// One-line identity of the selected service for the logs pane frame: the resolved command it
 /// runs, its run generation, and for dynamic services the definition revision plus the lease and
-/// ownership facts an operator needs at a glance.
+/// ownership facts an operator needs at a glance. A held port is named with its holder, and a
+/// startup timeout of the service or of a dependency it waits on is called out, since the service's
+/// own log has nothing to say about either. A scheduled service shows its schedule, when it runs
+/// next, and how its last run ended.
 fn service_detail_line(
     snapshot: &micromux::ServiceSnapshot,
     now_unix_ms: u64,
@@ -661,9 +804,58 @@ fn service_detail_line(
         }
         spans.push(format!(" {} ", facts.join(" · ")).fg(tailwind::YELLOW.c500));
     }
+    if let Some(schedule) = &snapshot.schedule {
+        let mut facts = vec![format!("schedule {schedule}")];
+        if let Some(next_run_at_unix_ms) = snapshot.next_run_at_unix_ms {
+            facts.push(format!(
+                "next run in {}",
+                countdown(next_run_at_unix_ms, now_unix_ms)
+            ));
+        }
+        if let Some(exit_code) = snapshot.last_exit_code {
+            facts.push(format!("last exit {exit_code}"));
+        }
+        let color = if snapshot.last_exit_code.is_some_and(|code| code != 0) {
+            tailwind::RED.c400
+        } else {
+            tailwind::CYAN.c400
+        };
+        spans.push(format!(" {} ", facts.join(" · ")).fg(color));
+    }
+    if let Some(conflict) = &snapshot.port_conflict {
+        spans.push(format!(" {} ", conflict.describe()).fg(tailwind::RED.c400));
+    }
+    if snapshot.startup_timed_out {
+        spans.push(" startup timed out ".fg(tailwind::RED.c400));
+    }
+    if !snapshot.timed_out_dependencies.is_empty() {
+        spans.push(
+            format!(
+                " {} timed out starting ",
+                snapshot.timed_out_dependencies.join(", ")
+            )
+            .fg(tailwind::RED.c400),
+        );
+    }
     (!spans.is_empty()).then(|| Line::from(spans))
 }
 
+/// A footer entry: the keys to press and what they do.
+#[derive(Debug)]
+struct Keys<'a> {
+    keys: &'a str,
+    description: String,
+}
+
+impl<'a> Keys<'a> {
+    fn new(keys: &'a str, description: impl Into<String>) -> Self {
+        Self {
+            keys,
+            description: description.into(),
+        }
+    }
+}
+
 impl Widget for &mut App {
     fn render(self, area: Rect, buf: &mut Buffer) {
         let [header_area, main_area, footer_area] = Layout::default()
@@ -760,6 +952,7 @@ impl App {
     }
 
     fn render_services(&self, area: Rect, buf: &mut Buffer) {
+        let now_unix_ms = now_unix_ms();
         let items: Vec<ListItem> = self
             .state
             .services
@@ -777,6 +970,7 @@ impl App {
 
                 let origin = match service.snapshot.origin {
                     micromux::OriginKind::Dynamic => "+",
+                    micromux::OriginKind::External => "~",
                     micromux::OriginKind::Configured | micromux::OriginKind::Unknown => " ",
                 };
                 let line = [
@@ -786,6 +980,9 @@ impl App {
                     service.snapshot.id.as_str().into(),
                 ]
                 .into_iter()
+                .chain(service.snapshot.next_run_at_unix_ms.map(|next_run| {
+                    format!(" ⏱ {}", countdown(next_run, now_unix_ms)).fg(tailwind::CYAN.c400)
+                }))
                 .chain(if ports.len() > 0 {
                     [" [".into()]
                         .into_iter()
@@ -831,7 +1028,7 @@ impl App {
                 .state
                 .current_service()
                 .and_then(|service| service.cached_lines.back())
-                .map_or(0, |(seq, _)| seq.saturating_sub(1));
+                .map_or(0, |(seq, _, _)| seq.saturating_sub(1));
             let (first_retained, new_lines) = self.source.logs_since(&current_id, after);
             if let Some(service) = self.state.current_service_mut() {
                 match first_retained {
@@ -840,7 +1037,7 @@ impl App {
                         while service
                             .cached_lines
                             .front()
-                            .is_some_and(|(seq, _)| *seq < first)
+                            .is_some_and(|(seq, _, _)| *seq < first)
                         {
                             service.cached_lines.pop_front();
                         }
@@ -849,8 +1046,10 @@ impl App {
                 for line in new_lines {
                     let formatted = crate::json_log::format_line(&line.line, self.pretty_json_logs);
                     match service.cached_lines.back_mut() {
-                        Some((seq, cached)) if *seq == line.seq => *cached = formatted,
-                        _ => service.cached_lines.push_back((line.seq, formatted)),
+                        Some((seq, _, cached)) if *seq == line.seq => *cached = formatted,
+                        _ => service
+                            .cached_lines
+                            .push_back((line.seq, line.stream, formatted)),
                     }
                 }
                 service.text_dirty = true;
@@ -860,13 +1059,15 @@ impl App {
 
         let wrap = self.log_view.wrap;
         let wrap_width = area.width.saturating_sub(3);
+        let stream_filter = self.stream_filter;
         if let Some(service) = self.state.current_service_mut()
             && (service.text_dirty || service.cached_wrap != Some((wrap, wrap_width)))
         {
             let joined = service
                 .cached_lines
                 .iter()
-                .map(|(_, line)| line.as_str())
+                .filter(|(_, stream, _)| stream_filter.is_none_or(|filter| *stream == Some(filter)))
+                .map(|(_, _, line)| line.as_str())
                 .collect::<Vec<_>>()
                 .join("\n");
             service.cached_text = joined.as_str().into_text().unwrap_or_else(|err| {
@@ -968,20 +1169,8 @@ impl App {
     }
 
     fn render_footer(&self, area: Rect, buf: &mut Buffer) {
-        #[derive(Debug)]
-        struct Keys<'a> {
-            keys: &'a str,
-            description: String,
-        }
-
-        impl<'a> Keys<'a> {
-            fn new(keys: &'a str, description: impl Into<String>) -> Self {
-                Self {
-                    keys,
-                    description: description.into(),
-                }
-            }
-        }
+        const PICKER_KEYS: [&str; micromux::ServiceSignal::ALL.len()] =
+            ["1", "2", "3", "4", "5", "6", "7", "8", "9"];
 
         let tail = if self.log_view.follow_tail {
             "ON"
@@ -990,40 +1179,71 @@ impl App {
         };
         let wrap = if self.log_view.wrap { "ON" } else { "OFF" };
         let pty_input = if self.pty_input_mode { "ON" } else { "OFF" };
+        let stream = match self.stream_filter {
+            None => "ALL",
+            Some(micromux::OutputStream::Stdout) => "OUT",
+            Some(_) => "ERR",
+        };
+        let scope = if self.group_scope { "GROUP" } else { "SERVICE" };
         let focus = match self.focus {
             crate::Focus::Services => "SERVICES",
             crate::Focus::Logs => "LOGS",
             crate::Focus::Healthcheck => "HEALTH",
         };
 
-        let mut footer_text = vec![
-            Keys::new("↑/↓", "Navigate"),
-            Keys::new("←/→", "Resize"),
-            Keys::new("Tab", format!("Focus:{focus}")),
-        ];
-        if self.input.is_some() {
+        let task_names = self.source.task_names();
+        let footer_text = if self.signal_picker {
+            PICKER_KEYS
+                .into_iter()
+                .zip(micromux::ServiceSignal::ALL)
+                .map(|(keys, signal)| Keys::new(keys, signal.name()))
+                .chain([Keys::new("Esc", "Cancel")])
+                .collect::<Vec<_>>()
+        } else if self.task_picker {
+            PICKER_KEYS
+                .into_iter()
+                .zip(task_names)
+                .map(|(keys, task)| Keys::new(keys, task))
+                .chain([Keys::new("Esc", "Cancel")])
+                .collect::<Vec<_>>()
+        } else {
+            let mut footer_text = vec![
+                Keys::new("↑/↓", "Navigate"),
+                Keys::new("←/→", "Resize"),
+                Keys::new("Tab", format!("Focus:{focus}")),
+            ];
+            if self.input.is_some() {
+                footer_text.extend([
+                    Keys::new("a", format!("PTY Input:{pty_input}")),
+                    Keys::new("Alt+Esc", "Exit input"),
+                ]);
+            }
             footer_text.extend([
-                Keys::new("a", format!("PTY Input:{pty_input}")),
-                Keys::new("Alt+Esc", "Exit input"),
+                Keys::new("H", "Health"),
+                Keys::new("w", format!("Wrap:{wrap}")),
+                Keys::new("t", format!("Tail:{tail}")),
+                Keys::new("o", format!("Stream:{stream}")),
+                Keys::new("u", format!("Scope:{scope}")),
+                Keys::new("r", "Restart"),
+                Keys::new("R", "Restart All"),
+                Keys::new("d", "Disable/Enable"),
+                Keys::new("s", "Stop dynamic"),
+                Keys::new("x", "Signal"),
+                Keys::new("p", "Pause/Resume"),
             ]);
-        }
-        footer_text.extend([
-            Keys::new("H", "Health"),
-            Keys::new("w", format!("Wrap:{wrap}")),
-            Keys::new("t", format!("Tail:{tail}")),
-            Keys::new("r", "Restart"),
-            Keys::new("R", "Restart All"),
-            Keys::new("d", "Disable/Enable"),
-            Keys::new("s", "Stop dynamic"),
-            Keys::new(
+            if !task_names.is_empty() {
+                footer_text.push(Keys::new("T", "Tasks"));
+            }
+            footer_text.push(Keys::new(
                 "q",
                 if self.source.attachment_status().is_some() {
                     "Detach"
                 } else {
                     "Quit"
                 },
-            ),
-        ]);
+            ));
+            footer_text
+        };
 
         let widget = Paragraph::new(
             Line::from(
@@ -173,6 +173,33 @@ impl SessionSource {
         }
     }
 
+    pub(crate) fn signal(&self, id: ServiceID, signal: micromux::ServiceSignal) {
+        match self {
+            Self::Local(source) => {
+                let _ = source.lifecycle.send(Command::signal(id, signal));
+            }
+            Self::Remote(source) => source.signal(id, signal),
+        }
+    }
+
+    pub(crate) fn pause(&self, id: ServiceID) {
+        match self {
+            Self::Local(source) => {
+                let _ = source.lifecycle.send(Command::pause(id));
+            }
+            Self::Remote(source) => source.pause(id),
+        }
+    }
+
+    pub(crate) fn resume(&self, id: ServiceID) {
+        match self {
+            Self::Local(source) => {
+                let _ = source.lifecycle.send(Command::resume(id));
+            }
+            Self::Remote(source) => source.resume(id),
+        }
+    }
+
     pub(crate) fn stop_dynamic(&self, id: ServiceID) {
         match self {
             Self::Local(source) => {
@@ -182,6 +209,28 @@ impl SessionSource {
         }
     }
 
+    /// Names of the session's on-demand tasks, in configuration order.
+    pub(crate) fn task_names(&self) -> Vec<String> {
+        match self {
+            Self::Local(source) => source
+                .reader
+                .tasks()
+                .into_iter()
+                .map(|task| task.name)
+                .collect(),
+            Self::Remote(source) => source.task_names(),
+        }
+    }
+
+    pub(crate) fn run_task(&self, task: String) {
+        match self {
+            Self::Local(source) => {
+                let _ = source.lifecycle.send(Command::run_task(task));
+            }
+            Self::Remote(source) => source.run_task(task),
+        }
+    }
+
     pub(crate) fn cancel(&self) {
         if let Self::Remote(source) = self {
             source.cancel();
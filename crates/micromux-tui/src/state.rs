@@ -3,7 +3,7 @@
 #[derive(Debug)]
 pub struct Service {
     pub snapshot: micromux::ServiceSnapshot,
-    pub cached_lines: std::collections::VecDeque<(u64, String)>,
+    pub cached_lines: std::collections::VecDeque<(u64, Option<micromux::OutputStream>, String)>,
     pub cached_text: ratatui::text::Text<'static>,
     pub text_dirty: bool,
     pub cached_line_index: crate::render::log_view::RenderedLineIndex,
@@ -1,4 +1,4 @@
-use ratatui::style::{Style, palette::tailwind};
+use ratatui::style::{Modifier, Style, palette::tailwind};
 
 pub const INITIAL_SIDEBAR_WIDTH: u16 = 40;
 pub const MIN_SIDEBAR_WIDTH: u16 = 20;
@@ -32,9 +32,23 @@ pub fn service_style(snapshot: &micromux::ServiceSnapshot) -> Style {
             health_style(snapshot.health)
         }
         // Distinct from the green "running" styling so a stopped service is obvious at a glance.
-        micromux::Execution::Stopping | micromux::Execution::Unknown => {
-            Style::default().fg(tailwind::AMBER.c500)
-        }
+        micromux::Execution::Stopping
+        | micromux::Execution::Paused
+        | micromux::Execution::Unknown => Style::default().fg(tailwind::AMBER.c500),
+        // A parked crash loop will not come back on its own, so it reads louder than an exit that
+        // is merely waiting out its backoff.
+        micromux::Execution::Exited if crash_looping(snapshot) => Style::default()
+            .fg(tailwind::RED.c500)
+            .add_modifier(Modifier::BOLD),
         micromux::Execution::Exited => Style::default().fg(tailwind::RED.c400),
     }
 }
+
+/// Whether the crash-loop breaker has parked the service.
+#[must_use]
+pub fn crash_looping(snapshot: &micromux::ServiceSnapshot) -> bool {
+    snapshot
+        .restart_state
+        .as_ref()
+        .is_some_and(|restart| restart.phase == micromux::RestartPhase::CrashLoop)
+}
@@ -0,0 +1,608 @@
+//! Config composition: `include:` files and the local override file.
+//!
+//! Every file is parsed on its own and the resulting YAML trees are merged before the usual
+//! parser runs, so composition follows one set of rules for every key:
+//!
+//! - mappings (`services`, a service, `environment` given as a map, `healthcheck`, ...) merge key
+//!   by key,
+//! - `null` removes the key it would otherwise inherit,
+//! - any other value, including every list (`command`, `ports`, `depends_on`, ...), replaces the
+//!   inherited value as a whole.
+//!
+//! A file's `include:` entries are merged first, in order, and the file itself on top. The
+//! override file next to the main config (see [`override_file_name`]) is merged last.
+//!
+//! `yaml_spanned` reports offsets relative to the text it parsed, so spans of different files
+//! would collide once their trees are merged. Each file is therefore parsed behind enough blank
+//! lines to land in its own window of one shared offset space, and [`SourceMap`] maps diagnostics
+//! and errors back to the file and offset they came from.
+
+use super::{ConfigError, ConfigFile, MAX_CONFIG_FILE_BYTES};
+use crate::diagnostics::{FileId, SourceDatabase, Span, ToDiagnostics};
+use codespan_reporting::diagnostic::Diagnostic;
+use std::path::{Path, PathBuf};
+use yaml_spanned::{Mapping, Spanned, Value, value::Kind};
+
+/// Maximum number of files, counting the main config, one configuration is composed from.
+const MAX_CONFIG_SOURCES: usize = 32;
+
+/// Errors from loading a config file and the files it includes.
+#[derive(thiserror::Error, Debug)]
+pub enum LoadError {
+    /// The main config or its override file could not be read.
+    #[error("failed to read {}: {source}", path.display())]
+    Io {
+        /// File that could not be read.
+        path: PathBuf,
+        /// Underlying I/O error.
+        #[source]
+        source: std::io::Error,
+    },
+    /// One of the composed files is invalid.
+    #[error("{source}")]
+    Config {
+        /// File the error points into.
+        file_id: FileId,
+        /// Parse error, with its span relative to that file.
+        #[source]
+        source: ConfigError,
+    },
+}
+
+impl LoadError {
+    /// Convert this error into diagnostics against the file it occurred in.
+    #[must_use]
+    pub fn to_diagnostics(&self) -> Vec<Diagnostic<FileId>> {
+        match self {
+            Self::Io { .. } => vec![Diagnostic::error().with_message(self.to_string())],
+            Self::Config { file_id, source } => source.to_diagnostics(*file_id),
+        }
+    }
+}
+
+/// The override file merged on top of `config_path`: `micromux.yaml` becomes
+/// `micromux.override.yaml`, `.micromux.yml` becomes `.micromux.override.yml`.
+///
+/// Returns `None` for a path without an extension or one that already names an override file.
+#[must_use]
+pub fn override_file_name(config_path: &Path) -> Option<PathBuf> {
+    let file_name = config_path.file_name()?.to_str()?;
+    let (stem, extension) = file_name.rsplit_once('.')?;
+    if stem.is_empty() || stem.trim_start_matches('.').is_empty() || stem.ends_with(".override") {
+        return None;
+    }
+    Some(config_path.with_file_name(format!("{stem}.override.{extension}")))
+}
+
+/// Load the config at `config_path` together with its `include:` files and its override file.
+///
+/// Each file is registered with `sources`, and diagnostics (including those of
+/// [`LoadError::Config`]) point into the file they concern. Relative paths inside any of the files,
+/// such as `working_dir` or `env_file`, resolve against the main config's directory.
+///
+/// # Errors
+///
+/// Returns [`LoadError::Io`] when the main config or its override file cannot be read, and
+/// [`LoadError::Config`] when any composed file is invalid, including unreadable or cyclic
+/// includes.
+pub fn load_config_file<D: SourceDatabase>(
+    config_path: &Path,
+    strict_override: Option<bool>,
+    sources: &mut D,
+    diagnostics: &mut Vec<Diagnostic<FileId>>,
+) -> Result<ConfigFile<FileId>, LoadError> {
+    let config_path = config_path.canonicalize().map_err(|source| LoadError::Io {
+        path: config_path.to_path_buf(),
+        source,
+    })?;
+    let config_dir = config_path.parent().unwrap_or(&config_path).to_path_buf();
+
+    let mut loader = Loader {
+        sources,
+        map: SourceMap::default(),
+        active: Vec::new(),
+    };
+    let mut composed = loader.load(&config_path, None)?;
+    if let Some(override_path) = override_file_name(&config_path).filter(|path| path.is_file()) {
+        let overrides = loader.load(&override_path, None)?;
+        composed = merge(&composed, &overrides);
+    }
+
+    let root = loader.map.root();
+    let mut composed_diagnostics = Vec::new();
+    let parsed = super::parse_document(
+        &composed,
+        &config_dir,
+        root,
+        strict_override,
+        &mut composed_diagnostics,
+    );
+    diagnostics.extend(
+        composed_diagnostics
+            .into_iter()
+            .map(|diagnostic| loader.map.relocate_diagnostic(diagnostic)),
+    );
+    let mut config = parsed.map_err(|err| loader.map.relocate(err))?;
+    config.config_path = Some(config_path);
+    Ok(config)
+}
+
+/// One registered file and the offset window its spans occupy.
+#[derive(Debug, Clone, Copy)]
+struct Source {
+    file_id: FileId,
+    base: usize,
+    len: usize,
+}
+
+#[derive(Debug, Default)]
+struct SourceMap {
+    sources: Vec<Source>,
+    next_base: usize,
+}
+
+impl SourceMap {
+    fn root(&self) -> FileId {
+        self.sources
+            .first()
+            .map_or_else(FileId::default, |source| source.file_id)
+    }
+
+    fn push(&mut self, file_id: FileId, len: usize) {
+        self.sources.push(Source {
+            file_id,
+            base: self.next_base,
+            len,
+        });
+        // Leave a gap so a span at the very end of one file cannot be mistaken for the next.
+        self.next_base = self.next_base.saturating_add(len).saturating_add(1);
+    }
+
+    /// The file a composed span points into, and the span relative to that file.
+    fn locate(&self, span: &Span) -> Option<(FileId, Span)> {
+        self.sources
+            .iter()
+            .find(|source| {
+                span.start >= source.base && span.start <= source.base.saturating_add(source.len)
+            })
+            .map(|source| {
+                let start = span.start.saturating_sub(source.base);
+                let end = span
+                    .end
+                    .saturating_sub(source.base)
+                    .clamp(start, source.len);
+                (source.file_id, start..end)
+            })
+    }
+
+    fn relocate(&self, mut error: ConfigError) -> LoadError {
+        let located = error.span_mut().and_then(|span| {
+            let (file_id, local) = self.locate(span)?;
+            *span = local;
+            Some(file_id)
+        });
+        LoadError::Config {
+            file_id: located.unwrap_or_else(|| self.root()),
+            source: error,
+        }
+    }
+
+    fn relocate_diagnostic(&self, mut diagnostic: Diagnostic<FileId>) -> Diagnostic<FileId> {
+        for label in &mut diagnostic.labels {
+            if let Some((file_id, range)) = self.locate(&label.range) {
+                label.file_id = file_id;
+                label.range = range;
+            }
+        }
+        diagnostic
+    }
+}
+
+struct Loader<'a, D> {
+    sources: &'a mut D,
+    map: SourceMap,
+    /// Canonical paths of the files whose includes are being resolved, to detect cycles.
+    active: Vec<PathBuf>,
+}
+
+impl<D: SourceDatabase> Loader<'_, D> {
+    /// Read and register one file, then merge it on top of its includes.
+    ///
+    /// `entry` is the composed span of the `include:` entry naming the file; errors about the file
+    /// as a whole are reported there. It is `None` for the main config and its override file.
+    fn load(&mut self, path: &Path, entry: Option<&Span>) -> Result<Spanned<Value>, LoadError> {
+        let path = path
+            .canonicalize()
+            .map_err(|source| self.read_error(path, entry, source))?;
+        if self.active.contains(&path) {
+            return Err(self.entry_error(
+                &path,
+                entry,
+                format!("include cycle: {} includes itself", path.display()),
+            ));
+        }
+        if self.map.sources.len() >= MAX_CONFIG_SOURCES {
+            return Err(self.entry_error(
+                &path,
+                entry,
+                format!("a config may be composed from at most {MAX_CONFIG_SOURCES} files"),
+            ));
+        }
+        let raw = super::read_config_file(&path)
+            .map_err(|source| self.read_error(&path, entry, source))?;
+        if self.map.next_base.saturating_add(raw.len()) > MAX_CONFIG_FILE_BYTES {
+            return Err(self.entry_error(
+                &path,
+                entry,
+                format!(
+                    "the composed configuration exceeds the {MAX_CONFIG_FILE_BYTES} byte limit"
+                ),
+            ));
+        }
+
+        let file_id = self.sources.add_source(&path, raw.clone());
+        // Parse the plain text first so syntax errors keep exact positions within the file.
+        super::parse_single_document(&raw)
+            .map_err(|source| LoadError::Config { file_id, source })?;
+        let base = self.map.next_base;
+        let padded = format!("{}{raw}", "\n".repeat(base));
+        let value = super::parse_single_document(&padded)
+            .map_err(|source| LoadError::Config { file_id, source })?;
+        self.map.push(file_id, raw.len());
+
+        let includes = include_entries(&value).map_err(|err| self.map.relocate(err))?;
+        let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        self.active.push(path);
+        let mut composed: Option<Spanned<Value>> = None;
+        for (span, include) in includes {
+            let layer = self.load(&dir.join(include), Some(&span))?;
+            composed = Some(match composed {
+                Some(base) => merge(&base, &layer),
+                None => layer,
+            });
+        }
+        self.active.pop();
+
+        let own = without_include(&value);
+        Ok(match composed {
+            Some(base) => merge(&base, &own),
+            None => own,
+        })
+    }
+
+    fn read_error(&self, path: &Path, entry: Option<&Span>, source: std::io::Error) -> LoadError {
+        match entry {
+            Some(span) => self.map.relocate(ConfigError::InvalidValue {
+                message: format!("cannot read included file {}: {source}", path.display()),
+                span: span.clone(),
+            }),
+            None => LoadError::Io {
+                path: path.to_path_buf(),
+                source,
+            },
+        }
+    }
+
+    fn entry_error(&self, path: &Path, entry: Option<&Span>, message: String) -> LoadError {
+        match entry {
+            Some(span) => self.map.relocate(ConfigError::InvalidValue {
+                message,
+                span: span.clone(),
+            }),
+            None => LoadError::Io {
+                path: path.to_path_buf(),
+                source: std::io::Error::new(std::io::ErrorKind::InvalidData, message),
+            },
+        }
+    }
+}
+
+/// The paths listed under a document's top-level `include:` key, with their spans.
+fn include_entries(value: &Spanned<Value>) -> Result<Vec<(Span, String)>, ConfigError> {
+    let Some(include) = value.get("include") else {
+        return Ok(vec![]);
+    };
+    let items = match &include.inner {
+        Value::Sequence(seq) => seq.iter().collect::<Vec<_>>(),
+        _ => vec![include],
+    };
+    items
+        .into_iter()
+        .map(|item| match &item.inner {
+            Value::String(path) => Ok((item.span().into(), path.clone())),
+            other => Err(ConfigError::UnexpectedType {
+                message: "include entries must be file paths".to_string(),
+                expected: vec![Kind::String],
+                found: other.kind(),
+                span: item.span().into(),
+            }),
+        })
+        .collect()
+}
+
+fn without_include(value: &Spanned<Value>) -> Spanned<Value> {
+    let Value::Mapping(mapping) = &value.inner else {
+        return value.clone();
+    };
+    let mut own = Mapping::new();
+    for (key, item) in mapping {
+        if key.as_str() != Some("include") {
+            own.insert(key.clone(), item.clone());
+        }
+    }
+    Spanned::new(value.span, Value::Mapping(own))
+}
+
+/// Merge `overlay` on top of `base` following the rules in the module docs.
+fn merge(base: &Spanned<Value>, overlay: &Spanned<Value>) -> Spanned<Value> {
+    let (Value::Mapping(base_mapping), Value::Mapping(overlay_mapping)) =
+        (&base.inner, &overlay.inner)
+    else {
+        return overlay.clone();
+    };
+    let mut merged = Mapping::new();
+    for (key, item) in base_mapping {
+        match key.as_str().and_then(|name| overlay_mapping.get(name)) {
+            Some(Spanned {
+                inner: Value::Null, ..
+            }) => {}
+            Some(replacement) => {
+                merged.insert(key.clone(), merge(item, replacement));
+            }
+            None => {
+                merged.insert(key.clone(), item.clone());
+            }
+        }
+    }
+    for (key, item) in overlay_mapping {
+        let inherited = key
+            .as_str()
+            .is_some_and(|name| base_mapping.get(name).is_some());
+        if !inherited && !matches!(item.inner, Value::Null) {
+            merged.insert(key.clone(), item.clone());
+        }
+    }
+    Spanned::new(base.span, Value::Mapping(merged))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostics::DiagnosticExt;
+    use codespan_reporting::files::{Files, SimpleFiles};
+    use color_eyre::eyre;
+    use indoc::indoc;
+    use similar_asserts::assert_eq;
+
+    fn write(dir: &Path, name: &str, contents: &str) -> eyre::Result<PathBuf> {
+        let path = dir.join(name);
+        std::fs::write(&path, contents)?;
+        Ok(path)
+    }
+
+    fn service<'a>(
+        config: &'a ConfigFile<FileId>,
+        name: &str,
+    ) -> eyre::Result<&'a super::super::Service> {
+        config
+            .config
+            .services
+            .iter()
+            .find(|(id, _)| id.as_ref() == name)
+            .map(|(_, service)| service)
+            .ok_or_else(|| eyre::eyre!("missing service {name}"))
+    }
+
+    fn environment(service: &super::super::Service) -> Vec<(String, String)> {
+        service
+            .environment
+            .iter()
+            .map(|(key, value)| (key.as_ref().clone(), value.as_ref().clone()))
+            .collect()
+    }
+
+    #[test]
+    fn override_file_name_inserts_override_before_the_extension() {
+        assert_eq!(
+            override_file_name(Path::new("/p/micromux.yaml")),
+            Some(PathBuf::from("/p/micromux.override.yaml"))
+        );
+        assert_eq!(
+            override_file_name(Path::new("/p/.micromux.yml")),
+            Some(PathBuf::from("/p/.micromux.override.yml"))
+        );
+        assert_eq!(
+            override_file_name(Path::new("/p/micromux.override.yaml")),
+            None
+        );
+        assert_eq!(override_file_name(Path::new("/p/micromux")), None);
+    }
+
+    #[test]
+    fn includes_and_override_merge_per_key() -> eyre::Result<()> {
+        let dir = tempfile::tempdir()?;
+        write(
+            dir.path(),
+            "shared.yaml",
+            indoc! {r#"
+                restart: always
+                services:
+                  db:
+                    command: ["postgres"]
+                    ports: [5432]
+            "#},
+        )?;
+        let config_path = write(
+            dir.path(),
+            "micromux.yaml",
+            indoc! {r#"
+                version: 1
+                include: [shared.yaml]
+                services:
+                  api:
+                    command: ["./api"]
+                    ports: [8080, 8081]
+                    environment:
+                      PORT: "8080"
+                      MODE: dev
+                    healthcheck:
+                      test: ["CMD", "true"]
+            "#},
+        )?;
+        write(
+            dir.path(),
+            "micromux.override.yaml",
+            indoc! {r#"
+                services:
+                  api:
+                    ports: [9090]
+                    environment:
+                      PORT: "9090"
+                    healthcheck: null
+                  db:
+                    command: ["postgres", "-c", "fsync=off"]
+            "#},
+        )?;
+
+        let mut files = SimpleFiles::new();
+        let mut diagnostics = Vec::new();
+        let config = load_config_file(&config_path, None, &mut files, &mut diagnostics)?;
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+
+        let api = service(&config, "api")?;
+        assert_eq!(
+            api.ports
+                .iter()
+                .map(|port| port.port.as_ref().clone())
+                .collect::<Vec<_>>(),
+            vec!["9090"]
+        );
+        assert_eq!(
+            environment(api),
+            vec![
+                ("PORT".to_string(), "9090".to_string()),
+                ("MODE".to_string(), "dev".to_string()),
+            ]
+        );
+        assert!(api.healthcheck.is_none());
+
+        let db = service(&config, "db")?;
+        assert_eq!(
+            db.command
+                .iter()
+                .flat_map(|(_, args)| args)
+                .map(|arg| arg.as_ref().as_str())
+                .collect::<Vec<_>>(),
+            vec!["-c", "fsync=off"]
+        );
+        assert_eq!(
+            config.config.restart_policy,
+            crate::service::RestartPolicy::Always
+        );
+        assert_eq!(config.config_path, Some(config_path.canonicalize()?));
+        Ok(())
+    }
+
+    #[test]
+    fn diagnostics_and_errors_point_into_the_file_they_concern() -> eyre::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let config_path = write(
+            dir.path(),
+            "micromux.yaml",
+            indoc! {r#"
+                version: 1
+                include: extra.yaml
+                services:
+                  api:
+                    command: ["./api"]
+            "#},
+        )?;
+        let extra_path = write(
+            dir.path(),
+            "extra.yaml",
+            indoc! {r#"
+                services:
+                  worker:
+                    command: ["./worker"]
+                    future_option: true
+            "#},
+        )?;
+
+        let mut files = SimpleFiles::new();
+        let mut diagnostics = Vec::new();
+        load_config_file(&config_path, None, &mut files, &mut diagnostics)?;
+        let label = diagnostics
+            .iter()
+            .find(|diagnostic| diagnostic.is_warning())
+            .and_then(|diagnostic| diagnostic.labels.first())
+            .ok_or_else(|| eyre::eyre!("missing unknown-field warning"))?;
+        assert_eq!(
+            files.name(label.file_id)?,
+            extra_path.canonicalize()?.display().to_string()
+        );
+        assert_eq!(
+            files.source(label.file_id)?.get(label.range.clone()),
+            Some("future_option")
+        );
+
+        write(
+            dir.path(),
+            "extra.yaml",
+            indoc! {r#"
+                services:
+                  worker:
+                    command: ["./worker"]
+                    stop_grace_period: soon
+            "#},
+        )?;
+        let mut files = SimpleFiles::new();
+        let Err(LoadError::Config {
+            file_id,
+            mut source,
+        }) = load_config_file(&config_path, None, &mut files, &mut Vec::new())
+        else {
+            eyre::bail!("an invalid duration in an include should fail to load");
+        };
+        let span = source
+            .span_mut()
+            .cloned()
+            .ok_or_else(|| eyre::eyre!("error without span"))?;
+        assert_eq!(
+            files.name(file_id)?,
+            extra_path.canonicalize()?.display().to_string()
+        );
+        assert_eq!(files.source(file_id)?.get(span), Some("soon"));
+        Ok(())
+    }
+
+    #[test]
+    fn include_cycles_are_reported_at_the_include_entry() -> eyre::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let config_path = write(
+            dir.path(),
+            "micromux.yaml",
+            "version: 1\ninclude: [a.yaml]\nservices: {}\n",
+        )?;
+        let a_path = write(dir.path(), "a.yaml", "include: micromux.yaml\n")?;
+
+        let mut files = SimpleFiles::new();
+        let Err(LoadError::Config {
+            file_id,
+            mut source,
+        }) = load_config_file(&config_path, None, &mut files, &mut Vec::new())
+        else {
+            eyre::bail!("an include cycle should fail to load");
+        };
+        assert!(source.to_string().contains("include cycle"));
+        assert_eq!(
+            files.name(file_id)?,
+            a_path.canonicalize()?.display().to_string()
+        );
+        let span = source
+            .span_mut()
+            .cloned()
+            .ok_or_else(|| eyre::eyre!("error without span"))?;
+        assert_eq!(files.source(file_id)?.get(span), Some("micromux.yaml"));
+        Ok(())
+    }
+}
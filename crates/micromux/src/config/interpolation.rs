@@ -0,0 +1,130 @@
+//! Load-time checks for `${...}` interpolation in service definitions.
+//!
+//! Values are only expanded when a service is materialized, which would turn an unset required
+//! variable into a start-up failure without a location. This pass expands the same values in the
+//! same order and reports the first failure at the span of the value that caused it.
+
+use super::{ConfigError, EnvFile, Service};
+use crate::env::{self, EnvLayer, LayeredEnv, Unresolved};
+use indexmap::IndexMap;
+use std::collections::HashMap;
+use std::path::Path;
+use yaml_spanned::Spanned;
+
+/// Check `working_dir`, the inherited and own `env_file` paths and values, `environment`,
+/// `command`, the `pre_start` and `post_stop` hooks, and `ports` of `service` against the current
+/// process environment. A replica is checked with its replica variables in scope, and every
+/// service with `MICROMUX_PORT_OFFSET` when the `worktree:` block is enabled.
+///
+/// Env files that cannot be read or parsed are skipped here; materializing the service reports
+/// them.
+pub(crate) fn check_service(service: &Service, config_dir: &Path) -> Result<(), ConfigError> {
+    if let Some(working_dir) = &service.working_dir {
+        env::resolve_path(config_dir, working_dir.as_ref())
+            .map_err(|err| invalid(working_dir, err.to_string()))?;
+    }
+
+    let mut layered = LayeredEnv::new(std::env::vars().collect());
+    if let Some(offset) = service.port_offset {
+        layered
+            .push(
+                EnvLayer::Worktree,
+                super::worktree::PORT_OFFSET_ENV,
+                &offset.to_string(),
+                &mut Vec::new(),
+            )
+            .map_err(|err| invalid(&service.name, err.to_string()))?;
+    }
+    if let Some(replica) = &service.replica {
+        for (key, value) in replica.environment() {
+            layered
+                .push(EnvLayer::Replica, key, &value, &mut Vec::new())
+                .map_err(|err| invalid(&service.name, err.to_string()))?;
+        }
+    }
+    check_env_files(
+        &mut layered,
+        EnvLayer::SessionEnvFile,
+        &service.inherited_env_file,
+        config_dir,
+    )?;
+    check_environment(
+        &mut layered,
+        EnvLayer::SessionEnvironment,
+        &service.inherited_environment,
+    )?;
+    check_env_files(
+        &mut layered,
+        EnvLayer::ServiceEnvFile,
+        &service.env_file,
+        config_dir,
+    )?;
+    check_environment(
+        &mut layered,
+        EnvLayer::ServiceEnvironment,
+        &service.environment,
+    )?;
+
+    let commands = service
+        .command
+        .iter()
+        .chain(&service.pre_start)
+        .chain(&service.post_stop);
+    for (program, args) in commands {
+        for arg in std::iter::once(program).chain(args) {
+            expand(arg, layered.scope(), Unresolved::Keep)?;
+        }
+    }
+    for port in service.ports.iter().filter(|port| !port.is_auto()) {
+        expand(&port.port, layered.scope(), Unresolved::Empty)?;
+    }
+    Ok(())
+}
+
+fn check_env_files(
+    layered: &mut LayeredEnv,
+    layer: EnvLayer,
+    env_files: &[EnvFile],
+    config_dir: &Path,
+) -> Result<(), ConfigError> {
+    for env_file in env_files {
+        let path = env::resolve_path(config_dir, env_file.path.as_ref())
+            .map_err(|err| invalid(&env_file.path, err.to_string()))?;
+        let Ok(entries) = env::load_env_files_sync(std::slice::from_ref(&path)) else {
+            continue;
+        };
+        layered
+            .push_file(layer, &path, &entries, &mut Vec::new())
+            .map_err(|err| invalid(&env_file.path, format!("{}: {err}", path.display())))?;
+    }
+    Ok(())
+}
+
+fn check_environment(
+    layered: &mut LayeredEnv,
+    layer: EnvLayer,
+    environment: &IndexMap<Spanned<String>, Spanned<String>>,
+) -> Result<(), ConfigError> {
+    for (key, value) in environment {
+        layered
+            .push(layer, key.as_ref(), value.as_ref(), &mut Vec::new())
+            .map_err(|err| invalid(value, err.to_string()))?;
+    }
+    Ok(())
+}
+
+fn expand(
+    value: &Spanned<String>,
+    env: &HashMap<String, String>,
+    unresolved: Unresolved,
+) -> Result<String, ConfigError> {
+    env::interpolate(value.as_ref(), env, unresolved, &mut Vec::new())
+        .map_err(|err| invalid(value, err.to_string()))
+}
+
+fn invalid(value: &Spanned<String>, message: String) -> ConfigError {
+    ConfigError::InvalidValue {
+        message,
+        span: value.span.into(),
+    }
+}
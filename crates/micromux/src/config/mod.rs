@@ -4,21 +4,32 @@
 //!
 //! - [`from_str`]: parse a YAML configuration into a typed [`ConfigFile`].
 //! - [`find_config_file`]: locate a config file in a directory.
+//! - [`load_config_file`]: load a config file with its `include:` files and local override file.
 //! - A set of configuration types (e.g. [`Service`], [`HealthCheck`]) and diagnostics-friendly
 //!   errors ([`ConfigError`]).
 
+mod compose;
+mod interpolation;
+pub(crate) mod replicas;
 pub mod v1;
+pub mod worktree;
 
 use crate::diagnostics::{DiagnosticExt, Span, ToDiagnostics};
 use crate::model::LogRetention;
 use crate::service::{RestartPolicy, StartupMode};
+use crate::spec::PortConflictPolicy;
 use codespan_reporting::diagnostic::{Diagnostic, Label};
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 use yaml_spanned::{Spanned, Value};
 
+pub use compose::{LoadError, load_config_file, override_file_name};
+pub use replicas::{MAX_REPLICAS, Replica};
+pub use worktree::Worktree;
+
 /// Maximum buffered size of one configuration file, bounding validation and reload memory.
 pub const MAX_CONFIG_FILE_BYTES: usize = 4 * 1024 * 1024;
 
@@ -167,6 +178,9 @@ pub async fn find_config_file(dir: &Path) -> std::io::Result<Option<PathBuf>> {
     Ok(None)
 }
 
+/// A command and its arguments as written, such as a hook entry.
+pub type CommandLine = (Spanned<String>, Vec<Spanned<String>>);
+
 /// Configuration file format version.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
 pub enum Version {
@@ -245,8 +259,10 @@ impl Default for UiConfig {
 )]
 pub struct Config {
     /// Optional session name, surfaced as the session identity to agents. Falls back to
-    /// `basename(working_dir)` when unset.
+    /// `basename(working_dir)` when unset. Suffixed with `@<worktree>` in a linked worktree.
     pub name: Option<String>,
+    /// Checkout the session runs in, when the `worktree:` block is enabled.
+    pub worktree: Option<Worktree>,
     /// Configuration for the UI.
     pub ui_config: UiConfig,
     /// Agent-control and dynamic-service policy.
@@ -255,10 +271,26 @@ pub struct Config {
     pub log_retention: LogRetention,
     /// Default restart policy inherited by services that do not set `restart`.
     pub restart_policy: RestartPolicy,
+    /// Default port-conflict policy inherited by services that do not set `port_conflict`.
+    pub port_conflict: PortConflictPolicy,
+    /// Order in which services are stopped on shutdown and `restart_all`.
+    pub shutdown_order: ShutdownOrder,
     /// Default healthcheck timing inherited by services that configure a healthcheck test.
     pub healthcheck_defaults: HealthCheckDefaults,
+    /// Environment files loaded beneath every service's own environment.
+    pub env_file: Vec<EnvFile>,
+    /// Environment variables set beneath every service's own environment.
+    pub environment: IndexMap<Spanned<String>, Spanned<String>>,
+    /// Commands run in order before any service starts; the first failure aborts the session.
+    pub before: Vec<CommandLine>,
+    /// Commands run in order once every service has stopped.
+    pub after: Vec<CommandLine>,
+    /// Time each `after` and `post_stop` command may run before it is killed.
+    pub hook_timeout: Option<Spanned<std::time::Duration>>,
     /// Service definitions keyed by service name.
     pub services: IndexMap<Spanned<String>, Service>,
+    /// On-demand task definitions keyed by task name.
+    pub tasks: IndexMap<Spanned<String>, Task>,
 }
 
 /// A parsed config file together with its origin metadata.
@@ -272,6 +304,12 @@ pub struct ConfigFile<F> {
     pub config_dir: PathBuf,
     /// Strict-mode override supplied by the caller (for example CLI `--strict`).
     pub strict_override: Option<bool>,
+    /// Profiles activated by the caller (for example CLI `--profile`). Services assigned to
+    /// profiles start disabled unless one of theirs is listed here.
+    pub profiles: Vec<String>,
+    /// Replica counts set for this session (for example by scaling at runtime), replacing the
+    /// configured `replicas` of the groups they name.
+    pub replicas: BTreeMap<String, usize>,
     /// Parsed config contents.
     pub config: Config,
 }
@@ -308,6 +346,33 @@ pub enum DependencyCondition {
     CompletedSuccessfully,
 }
 
+/// What a running dependent does when one of its dependencies starts a new run.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, schemars::JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum OnDependencyRestart {
+    /// Keep running.
+    #[default]
+    Ignore,
+    /// Restart once the dependency's new run satisfies the dependency's condition again.
+    Restart,
+}
+
+/// Order in which session shutdown and `restart_all` stop running services.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, schemars::JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ShutdownOrder {
+    /// Stop dependents before their dependencies, one tier at a time, giving each tier up to its
+    /// longest stop grace period to exit.
+    #[default]
+    Dependencies,
+    /// Stop every service at once.
+    Parallel,
+}
+
 /// A dependency on another service.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Dependency {
@@ -315,6 +380,8 @@ pub struct Dependency {
     pub name: Spanned<String>,
     /// Optional condition that must be met.
     pub condition: Option<Spanned<DependencyCondition>>,
+    /// Optional reaction to the dependency restarting.
+    pub on_dependency_restart: Option<Spanned<OnDependencyRestart>>,
 }
 
 /// A `.env` file reference.
@@ -327,6 +394,37 @@ pub struct EnvFile {
     pub optional: bool,
 }
 
+/// One `ports` entry: a bare port, or a named mapping whose port may be `auto`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Port {
+    /// Name other services reference the port by; required for `auto`.
+    pub name: Option<Spanned<String>>,
+    /// Port number, possibly interpolated, or `auto`.
+    pub port: Spanned<String>,
+}
+
+impl Port {
+    /// Whether micromux picks the port number at spawn time.
+    #[must_use]
+    pub fn is_auto(&self) -> bool {
+        self.port.as_str() == AUTO_PORT
+    }
+}
+
+/// `ports` value that asks micromux to allocate a free loopback port.
+pub(crate) const AUTO_PORT: &str = "auto";
+
+/// File-watch configuration that restarts a service when its sources change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Watch {
+    /// Paths or glob patterns to watch, relative to the service working directory.
+    pub paths: Vec<Spanned<String>>,
+    /// Glob patterns for changes that never trigger a restart.
+    pub ignore: Vec<Spanned<String>>,
+    /// Quiet period that coalesces a burst of changes into one restart.
+    pub debounce: Option<Spanned<Duration>>,
+}
+
 /// Service configuration.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Service {
@@ -334,32 +432,97 @@ pub struct Service {
     pub name: Spanned<String>,
     /// Startup behavior for new sessions.
     pub startup_mode: StartupMode,
-    /// Command to execute and its arguments.
-    pub command: (Spanned<String>, Vec<Spanned<String>>),
+    /// Whether the service runs outside micromux, which only probes its healthcheck.
+    pub external: bool,
+    /// Command to execute and its arguments; `None` for an external service.
+    pub command: Option<(Spanned<String>, Vec<Spanned<String>>)>,
+    /// Commands run in order before each run spawns; the first failure fails the start.
+    pub pre_start: Vec<CommandLine>,
+    /// Commands run in order after each run exits.
+    pub post_stop: Vec<CommandLine>,
     /// Optional working directory.
     pub working_dir: Option<Spanned<String>>,
     /// Environment files to load.
     pub env_file: Vec<EnvFile>,
     /// Explicit environment variables.
     pub environment: IndexMap<Spanned<String>, Spanned<String>>,
+    /// Top-level `env_file` entries inherited from the config, layered beneath the service's own.
+    pub inherited_env_file: Vec<EnvFile>,
+    /// Top-level `environment` inherited from the config, layered beneath the service's own.
+    pub inherited_environment: IndexMap<Spanned<String>, Spanned<String>>,
     /// Dependencies on other services.
     pub depends_on: Vec<Dependency>,
     /// Optional healthcheck configuration.
     pub healthcheck: Option<HealthCheck>,
     /// Port mappings / port specs.
-    pub ports: Vec<Spanned<String>>,
+    pub ports: Vec<Port>,
+    /// Effective port-conflict policy after applying the global default.
+    pub port_conflict: PortConflictPolicy,
     /// Raw restart policy configured directly on this service, if any.
     pub restart: Option<RestartPolicy>,
     /// Effective restart policy after applying the global default.
     pub restart_policy: RestartPolicy,
     /// Time allowed for graceful termination before forced termination.
     pub stop_grace_period: Spanned<Duration>,
+    /// Signal that requests graceful termination; `SIGTERM` when unset.
+    pub stop_signal: Option<Spanned<crate::ServiceSignal>>,
+    /// Time a run has to become healthy before it is stopped and handled like a crash.
+    pub startup_timeout: Option<Spanned<Duration>>,
+    /// Wall-clock age at which a run is stopped and restarted.
+    pub max_runtime: Option<Spanned<Duration>>,
+    /// When the service starts its runs, from `schedule` or `every`; unset for a long-running
+    /// service.
+    pub schedule: Option<Spanned<crate::RunSchedule>>,
+    /// Spacing of automatic restarts and the crash-loop breaker, when configured.
+    pub restart_backoff: Option<crate::RestartBackoffSpec>,
+    /// Files whose changes restart this service.
+    pub watch: Option<Watch>,
+    /// Profiles this service belongs to; empty means it runs whatever profiles are active.
+    pub profiles: Vec<Spanned<String>>,
     /// Whether this service should be rendered in color.
     pub color: Option<Spanned<bool>>,
+    /// Whether this service runs on a pseudo-terminal; `true` when unset.
+    pub tty: Option<Spanned<bool>>,
+    /// Number of instances this definition runs as, named `<name>-1` onwards. Unset runs it once
+    /// under its own name.
+    pub replicas: Option<Spanned<usize>>,
+    /// Set on an instance expanded from a replicated definition.
+    pub replica: Option<Replica>,
+    /// Worktree port offset added to declared ports, when the `worktree:` block is enabled.
+    pub port_offset: Option<u16>,
     /// Effective log retention after applying global defaults and this service's overrides.
     pub log_retention: LogRetention,
 }
 
+impl Service {
+    /// Whether the service runs under `active` profiles: it belongs to none, or to one of them.
+    #[must_use]
+    pub fn in_profiles(&self, active: &[String]) -> bool {
+        self.profiles.is_empty()
+            || self
+                .profiles
+                .iter()
+                .any(|profile| active.contains(profile.as_ref()))
+    }
+}
+
+/// On-demand task configuration: a command run to completion when asked, never supervised.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Task {
+    /// Task name.
+    pub name: Spanned<String>,
+    /// Optional one-line description shown in task listings.
+    pub description: Option<Spanned<String>>,
+    /// Command to execute and its arguments.
+    pub command: (Spanned<String>, Vec<Spanned<String>>),
+    /// Optional working directory.
+    pub working_dir: Option<Spanned<String>>,
+    /// Environment files to load, layered above the top-level `env_file` entries.
+    pub env_file: Vec<EnvFile>,
+    /// Explicit environment variables, layered above the top-level `environment`.
+    pub environment: IndexMap<Spanned<String>, Spanned<String>>,
+}
+
 /// Healthcheck timing defaults shared by services that define a healthcheck test.
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct HealthCheckDefaults {
@@ -376,10 +539,8 @@ pub struct HealthCheckDefaults {
 /// Healthcheck configuration for a service.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct HealthCheck {
-    /// The healthcheck test.
-    ///
-    /// For example, `( "pg_isready", ["-U", "postgres"] )`.
-    pub test: (Spanned<String>, Vec<Spanned<String>>),
+    /// How the service is probed.
+    pub probe: HealthProbe,
     /// Optional delay before the first healthcheck.
     pub start_delay: Option<Spanned<Duration>>,
     /// Healthcheck interval (e.g. `"30s"`).
@@ -388,6 +549,66 @@ pub struct HealthCheck {
     pub timeout: Option<Spanned<Duration>>,
     /// Number of retries before marking unhealthy.
     pub retries: Option<Spanned<usize>>,
+    /// What happens once the run turns unhealthy.
+    pub on_unhealthy: Option<Spanned<OnUnhealthy>>,
+}
+
+/// What a healthcheck does once a run turns unhealthy.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, schemars::JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum OnUnhealthy {
+    /// Mark the service unhealthy and keep probing so it can recover.
+    #[default]
+    #[serde(rename = "none", alias = "ignore")]
+    Ignore,
+    /// Stop the run and restart it through the restart backoff.
+    Restart,
+}
+
+/// The probe a healthcheck runs on every attempt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HealthProbe {
+    /// Run a command (`test`), for example `( "pg_isready", ["-U", "postgres"] )`.
+    Command((Spanned<String>, Vec<Spanned<String>>)),
+    /// Send an HTTP request (`http`).
+    Http(Box<HttpProbe>),
+    /// Open a TCP connection (`tcp`).
+    Tcp(TcpProbe),
+    /// Wait for the service's own output to match a pattern (`log_pattern`).
+    LogPattern(LogPatternProbe),
+}
+
+/// An in-process HTTP healthcheck probe.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpProbe {
+    /// Plain `http://` URL to request.
+    pub url: Spanned<String>,
+    /// Accepted status codes (e.g. `200`, `"2xx"`, or `"200-299"`).
+    pub status: Option<Spanned<crate::spec::StatusRange>>,
+    /// Regular expression the response body must match.
+    pub body: Option<Spanned<String>>,
+    /// Extra request headers.
+    pub headers: IndexMap<Spanned<String>, Spanned<String>>,
+}
+
+/// An in-process TCP connect healthcheck probe.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TcpProbe {
+    /// Host to connect to; `localhost` unless the probe names one.
+    pub host: Spanned<String>,
+    /// Port to connect to.
+    pub port: Spanned<u16>,
+}
+
+/// A readiness check that watches the service's log output instead of probing it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogPatternProbe {
+    /// Regular expression one output line must match.
+    pub pattern: Spanned<String>,
+    /// How long to wait for the first match before the service becomes unhealthy.
+    pub timeout: Option<Spanned<Duration>>,
 }
 
 /// Reason why a command is invalid.
@@ -502,6 +723,19 @@ impl ToDiagnostics for ConfigError {
 }
 
 impl ConfigError {
+    /// The span the error points at, when it has one.
+    pub(crate) fn span_mut(&mut self) -> Option<&mut Span> {
+        match self {
+            Self::InvalidCommand { span, .. }
+            | Self::InvalidDuration { span, .. }
+            | Self::MissingKey { span, .. }
+            | Self::UnexpectedType { span, .. }
+            | Self::InvalidValue { span, .. }
+            | Self::Serde { span, .. } => Some(span),
+            Self::Yaml(_) => None,
+        }
+    }
+
     fn invalid_command_diagnostics<F: Copy + PartialEq>(
         file_id: F,
         command: &str,
@@ -690,19 +924,8 @@ fn document_separator_span(raw_config: &str, second_document_start: usize) -> Sp
     })
 }
 
-/// Parse a micromux configuration from a YAML string.
-///
-/// # Errors
-///
-/// Returns an error if the YAML cannot be parsed or if the resulting value does not match the
-/// expected schema.
-pub fn from_str<F: Copy + PartialEq>(
-    raw_config: &str,
-    config_dir: &Path,
-    file_id: F,
-    strict_override: Option<bool>,
-    diagnostics: &mut Vec<Diagnostic<F>>,
-) -> Result<ConfigFile<F>, ConfigError> {
+/// Parse a YAML string that must hold at most one document, as a config file does.
+pub(crate) fn parse_single_document(raw_config: &str) -> Result<Spanned<Value>, ConfigError> {
     let mut documents = yaml_spanned::from_str_all(raw_config).map_err(ConfigError::Yaml)?;
     if documents.len() > 1 {
         let second_document_start = documents.get(1).map_or(0, |document| {
@@ -716,14 +939,24 @@ pub fn from_str<F: Copy + PartialEq>(
             span: document_separator_span(raw_config, second_document_start),
         });
     }
-    let value = documents
+    Ok(documents
         .pop()
-        .unwrap_or_else(|| Spanned::new(yaml_spanned::spanned::Span::default(), Value::Null));
-    let effective_strict = strict_override.or(parse_strict(&value)?);
-    let version = parse_version(&value, file_id, effective_strict, diagnostics)?;
+        .unwrap_or_else(|| Spanned::new(yaml_spanned::spanned::Span::default(), Value::Null)))
+}
+
+/// Parse an already-composed config document.
+pub(crate) fn parse_document<F: Copy + PartialEq>(
+    value: &Spanned<Value>,
+    config_dir: &Path,
+    file_id: F,
+    strict_override: Option<bool>,
+    diagnostics: &mut Vec<Diagnostic<F>>,
+) -> Result<ConfigFile<F>, ConfigError> {
+    let effective_strict = strict_override.or(parse_strict(value)?);
+    let version = parse_version(value, file_id, effective_strict, diagnostics)?;
     let config = match version {
         Version::Latest | Version::V1 => {
-            v1::parse_config(&value, config_dir, file_id, strict_override, diagnostics)?
+            v1::parse_config(value, config_dir, file_id, strict_override, diagnostics)?
         }
     };
 
@@ -732,10 +965,42 @@ pub fn from_str<F: Copy + PartialEq>(
         config_path: None,
         config_dir: config_dir.to_path_buf(),
         strict_override,
+        profiles: Vec::new(),
+        replicas: BTreeMap::new(),
         config,
     })
 }
 
+/// Parse a micromux configuration from a YAML string.
+///
+/// `include:` entries and override files need the filesystem and a file database for their
+/// diagnostics, so they are only resolved by [`load_config_file`]; here `include:` is reported and
+/// otherwise ignored.
+///
+/// # Errors
+///
+/// Returns an error if the YAML cannot be parsed or if the resulting value does not match the
+/// expected schema.
+pub fn from_str<F: Copy + PartialEq>(
+    raw_config: &str,
+    config_dir: &Path,
+    file_id: F,
+    strict_override: Option<bool>,
+    diagnostics: &mut Vec<Diagnostic<F>>,
+) -> Result<ConfigFile<F>, ConfigError> {
+    let value = parse_single_document(raw_config)?;
+    if let Some(include) = value.get("include") {
+        diagnostics.push(
+            Diagnostic::warning()
+                .with_message("`include` is ignored when parsing a config from a string")
+                .with_labels(vec![Label::primary(file_id, include.span).with_message(
+                    "only resolved when loading the config file from disk",
+                )]),
+        );
+    }
+    parse_document(&value, config_dir, file_id, strict_override, diagnostics)
+}
+
 #[cfg(test)]
 mod tests {
     use color_eyre::eyre;
@@ -813,8 +1078,11 @@ mod tests {
             .ok_or_else(|| eyre::eyre!("missing service 'db'"))?;
 
         // command parsing: string form is split
-        assert_eq!(app.command.0.as_ref(), "./start.sh");
-        assert!(app.command.1.iter().any(|v| v.as_ref() == "--flag"));
+        let Some((program, args)) = &app.command else {
+            return Err(eyre::eyre!("missing command"));
+        };
+        assert_eq!(program.as_ref(), "./start.sh");
+        assert!(args.iter().any(|v| v.as_ref() == "--flag"));
 
         // env_file parsing: string + mapping forms
         assert_eq!(app.env_file.len(), 1);
@@ -1000,6 +1268,10 @@ mod tests {
                   - 9090
                 restart: on-failure:5
                 color: false
+                watch:
+                  paths: ["src", "config/*.toml"]
+                  ignore: "**/*.tmp"
+                  debounce: 250ms
                 healthcheck:
                   test: "CMD-SHELL curl -f http://localhost:8080/health || exit 1"
                   interval: "30s"
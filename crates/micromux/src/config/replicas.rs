@@ -0,0 +1,201 @@
+//! Replica groups: one service definition run as several numbered instances.
+//!
+//! A service with `replicas: N` stands for the instances `<name>-1` .. `<name>-N`. Each instance
+//! is the definition with its own name, the replica variables in its environment, and
+//! `${replica}` in its ports replaced by its number. `depends_on` entries that name the group wait
+//! for every instance.
+//!
+//! A session may override a group's count at runtime. Overrides are applied here too, so reloads
+//! and validation see the same instances the session runs.
+
+use super::{Dependency, Service};
+use indexmap::IndexMap;
+use std::collections::{BTreeMap, HashMap};
+use yaml_spanned::Spanned;
+
+/// Largest number of instances a replica group may be configured or scaled to.
+pub const MAX_REPLICAS: usize = 64;
+
+/// Environment variable holding an instance's 1-based replica number.
+pub const REPLICA_ENV: &str = "MICROMUX_REPLICA";
+
+/// Environment variable holding an instance's 0-based replica index.
+pub const REPLICA_INDEX_ENV: &str = "MICROMUX_REPLICA_INDEX";
+
+/// Replaced by the instance's replica number in `ports` entries.
+const PORT_PLACEHOLDER: &str = "${replica}";
+
+/// Marks a service as one instance of a replica group.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Replica {
+    /// Name of the replicated service definition.
+    pub group: String,
+    /// 1-based instance number.
+    pub number: usize,
+}
+
+impl Replica {
+    /// The replica variables exported to the instance, lowest precedence of its environment.
+    #[must_use]
+    pub fn environment(&self) -> [(&'static str, String); 2] {
+        [
+            (REPLICA_ENV, self.number.to_string()),
+            (REPLICA_INDEX_ENV, self.number.saturating_sub(1).to_string()),
+        ]
+    }
+}
+
+/// Service id of instance `number` of `group`.
+#[must_use]
+pub fn instance_id(group: &str, number: usize) -> String {
+    format!("{group}-{number}")
+}
+
+/// Why a replica group cannot be expanded to a given count.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Conflict<'a> {
+    /// The count is zero or above [`MAX_REPLICAS`].
+    Count,
+    /// An instance id would not be a valid service id.
+    InvalidId(String),
+    /// An instance id is already the name of another service.
+    Taken {
+        /// Instance id.
+        id: String,
+        /// Name of the service that already uses it.
+        by: &'a Spanned<String>,
+    },
+}
+
+impl std::fmt::Display for Conflict<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Count => write!(f, "replicas must be between 1 and {MAX_REPLICAS}"),
+            Self::InvalidId(id) => write!(
+                f,
+                "replica id `{id}` must match [A-Za-z0-9._-]{{1,64}}; shorten the service name"
+            ),
+            Self::Taken { id, .. } => write!(f, "replica id `{id}` is already a service name"),
+        }
+    }
+}
+
+/// Check that `group` can be expanded to `count` instances alongside the other `services`.
+///
+/// # Errors
+///
+/// Returns the first [`Conflict`] found.
+pub fn check<'a>(
+    services: &'a IndexMap<Spanned<String>, Service>,
+    group: &str,
+    count: usize,
+) -> Result<(), Conflict<'a>> {
+    if !(1..=MAX_REPLICAS).contains(&count) {
+        return Err(Conflict::Count);
+    }
+    for number in 1..=count {
+        let id = instance_id(group, number);
+        if !crate::spec::service_id_is_valid(&id) {
+            return Err(Conflict::InvalidId(id));
+        }
+        if let Some(by) = services.keys().find(|name| name.as_str() == id) {
+            return Err(Conflict::Taken { id, by });
+        }
+    }
+    Ok(())
+}
+
+/// The count `group` runs with: its override if it has one, else its configured `replicas`.
+///
+/// Returns `None` for a service that is not replicated and for unknown names.
+#[must_use]
+pub fn count(
+    services: &IndexMap<Spanned<String>, Service>,
+    overrides: &BTreeMap<String, usize>,
+    group: &str,
+) -> Option<usize> {
+    let configured = services
+        .iter()
+        .find(|(name, _)| name.as_str() == group)
+        .and_then(|(_, service)| service.replicas.as_ref())?;
+    Some(overrides.get(group).copied().unwrap_or(configured.inner))
+}
+
+/// Expand every replicated service into its instances, keeping definition order. Services without
+/// `replicas` pass through unchanged apart from `depends_on` entries that name a group.
+///
+/// `overrides` maps group names to counts that replace their configured `replicas`; entries for
+/// other names are ignored.
+#[must_use]
+pub fn expand(
+    services: &IndexMap<Spanned<String>, Service>,
+    overrides: &BTreeMap<String, usize>,
+) -> IndexMap<Spanned<String>, Service> {
+    let counts = services
+        .keys()
+        .filter_map(|name| Some((name.as_str(), count(services, overrides, name)?)))
+        .collect::<HashMap<_, _>>();
+    let mut expanded = IndexMap::new();
+    for (name, service) in services {
+        let mut service = service.clone();
+        service.depends_on = expand_dependencies(&service.depends_on, &counts);
+        let Some(&count) = counts.get(name.as_str()) else {
+            expanded.insert(name.clone(), service);
+            continue;
+        };
+        for number in 1..=count {
+            let id = Spanned {
+                span: name.span,
+                inner: instance_id(name, number),
+            };
+            expanded.insert(id, instance(name, &service, number));
+        }
+    }
+    expanded
+}
+
+fn instance(group: &str, template: &Service, number: usize) -> Service {
+    let mut service = template.clone();
+    service.name = Spanned {
+        span: template.name.span,
+        inner: instance_id(&template.name, number),
+    };
+    for port in &mut service.ports {
+        port.port.inner = port
+            .port
+            .inner
+            .replace(PORT_PLACEHOLDER, &number.to_string());
+    }
+    service.replica = Some(Replica {
+        group: group.to_string(),
+        number,
+    });
+    service
+}
+
+fn expand_dependencies(
+    depends_on: &[Dependency],
+    counts: &HashMap<&str, usize>,
+) -> Vec<Dependency> {
+    depends_on
+        .iter()
+        .flat_map(|dependency| {
+            let instances = counts.get(dependency.name.as_str()).map_or_else(
+                || vec![dependency.name.inner.clone()],
+                |&count| {
+                    (1..=count)
+                        .map(|number| instance_id(&dependency.name, number))
+                        .collect()
+                },
+            );
+            instances.into_iter().map(|inner| Dependency {
+                name: Spanned {
+                    span: dependency.name.span,
+                    inner,
+                },
+                condition: dependency.condition.clone(),
+                on_dependency_restart: dependency.on_dependency_restart.clone(),
+            })
+        })
+        .collect()
+}
@@ -1,16 +1,18 @@
 use super::{
     Config, ConfigError, ControlConfig, DynamicServicesPolicy, HealthCheckDefaults, Service,
-    UiConfig, parse, parse_duration, parse_optional,
+    ShutdownOrder, Task, UiConfig, parse, parse_duration, parse_optional,
 };
 use crate::diagnostics::DiagnosticExt;
 use crate::{
     DiskLogRetention, LogLimit, LogRetention,
     config::InvalidCommandReason,
     service::{RestartPolicy, StartupMode},
+    spec::PortConflictPolicy,
 };
 use codespan_reporting::diagnostic::{Diagnostic, Label};
 use indexmap::IndexMap;
 use itertools::Itertools;
+use std::collections::BTreeMap;
 use std::path::Path;
 use yaml_spanned::{Mapping, Sequence, Spanned, Value, value::Kind};
 
@@ -19,7 +21,10 @@ use yaml_spanned::{Mapping, Sequence, Spanned, Value, value::Kind};
 const KNOWN_SERVICE_KEYS: &[&str] = &[
     "name",
     "disabled",
+    "external",
     "command",
+    "pre_start",
+    "post_stop",
     "working_dir",
     "cwd",
     "directory",
@@ -28,12 +33,34 @@ const KNOWN_SERVICE_KEYS: &[&str] = &[
     "depends_on",
     "healthcheck",
     "ports",
+    "port_conflict",
     "restart",
+    "restart_backoff",
     "stop_grace_period",
+    "startup_timeout",
+    "max_runtime",
+    "schedule",
+    "every",
+    "stop_signal",
+    "watch",
+    "profiles",
     "color",
+    "tty",
+    "replicas",
     "logs",
 ];
 
+/// Known keys for a task definition (including accepted aliases).
+const KNOWN_TASK_KEYS: &[&str] = &[
+    "description",
+    "command",
+    "working_dir",
+    "cwd",
+    "directory",
+    "env_file",
+    "environment",
+];
+
 const KNOWN_HEALTHCHECK_TIMING_KEYS: &[&str] = &[
     "start_delay",
     "startup_delay",
@@ -45,24 +72,61 @@ const KNOWN_HEALTHCHECK_TIMING_KEYS: &[&str] = &[
 
 const KNOWN_HEALTHCHECK_KEYS: &[&str] = &[
     "test",
+    "http",
+    "tcp",
+    "log_pattern",
     "start_delay",
     "startup_delay",
     "initial_delay",
     "interval",
     "timeout",
     "retries",
+    "on_unhealthy",
 ];
 
+const KNOWN_HTTP_PROBE_KEYS: &[&str] = &["url", "status", "body", "headers"];
+
+const KNOWN_TCP_PROBE_KEYS: &[&str] = &["host", "port"];
+
+const KNOWN_LOG_PATTERN_KEYS: &[&str] = &["pattern", "timeout"];
+
+const KNOWN_PORT_KEYS: &[&str] = &["name", "port"];
+
+const KNOWN_WATCH_KEYS: &[&str] = &["paths", "ignore", "debounce"];
+
+const KNOWN_RESTART_BACKOFF_KEYS: &[&str] = &[
+    "initial",
+    "max",
+    "multiplier",
+    "jitter",
+    "reset_after",
+    "crash_loop",
+];
+
+const KNOWN_CRASH_LOOP_KEYS: &[&str] = &["crashes", "window"];
+
+const KNOWN_WORKTREE_KEYS: &[&str] = &["enabled", "port_step", "max_port_offset"];
+
 const KNOWN_CONFIG_KEYS: &[&str] = &[
     "version",
+    "include",
     "name",
     "strict",
     "ui",
     "control",
+    "worktree",
     "restart",
+    "port_conflict",
+    "shutdown_order",
     "healthcheck",
     "logs",
+    "env_file",
+    "environment",
+    "before",
+    "after",
+    "hook_timeout",
     "services",
+    "tasks",
 ];
 
 /// Warn (or, in strict mode, error) about mapping keys that the parser does not recognize.
@@ -240,6 +304,7 @@ fn parse_depends_on(
                         inner: name.clone(),
                     },
                     condition: None,
+                    on_dependency_restart: None,
                 });
             }
             Spanned {
@@ -255,7 +320,13 @@ fn parse_depends_on(
                 };
                 let name = parse::<String>(name_value)?;
                 let condition = parse_optional::<super::DependencyCondition>(m.get("condition"))?;
-                deps.push(super::Dependency { name, condition });
+                let on_dependency_restart =
+                    parse_optional::<super::OnDependencyRestart>(m.get("on_dependency_restart"))?;
+                deps.push(super::Dependency {
+                    name,
+                    condition,
+                    on_dependency_restart,
+                });
             }
             _ => {
                 return Err(ConfigError::UnexpectedType {
@@ -270,22 +341,116 @@ fn parse_depends_on(
     Ok(deps)
 }
 
-fn parse_ports(mapping: &yaml_spanned::Mapping) -> Result<Vec<Spanned<String>>, ConfigError> {
+fn parse_ports<F: Copy>(
+    mapping: &yaml_spanned::Mapping,
+    file_id: F,
+    strict: bool,
+    diagnostics: &mut Vec<Diagnostic<F>>,
+) -> Result<Vec<super::Port>, ConfigError> {
     let Some(value) = mapping.get("ports") else {
         return Ok(vec![]);
     };
     let seq = expect_sequence(value, "ports must be a sequence".into())?;
-    let mut ports = vec![];
+    let mut ports: Vec<super::Port> = vec![];
     for item in seq {
-        let raw = parse_string_value(item, "ports entries must be a scalar")?;
-        ports.push(Spanned {
-            span: item.span,
-            inner: raw,
-        });
+        let port = if let Value::Mapping(m) = &item.inner {
+            warn_unknown_keys(m, KNOWN_PORT_KEYS, "port", file_id, strict, diagnostics);
+            let missing = |key: &str| ConfigError::MissingKey {
+                key: key.to_string(),
+                message: format!("ports entries must have a '{key}'"),
+                span: item.span.into(),
+            };
+            let name_value = m.get("name").ok_or_else(|| missing("name"))?;
+            let port_value = m.get("port").ok_or_else(|| missing("port"))?;
+            let name = parse::<String>(name_value)?;
+            if !crate::spec::port_name_is_valid(&name) {
+                return Err(ConfigError::InvalidValue {
+                    message: format!(
+                        "invalid port name `{}`: use a letter followed by letters, digits, `_`, or `-`",
+                        name.inner
+                    ),
+                    span: name.span.into(),
+                });
+            }
+            if ports
+                .iter()
+                .filter_map(|port| port.name.as_ref())
+                .any(|existing| existing.inner == name.inner)
+            {
+                return Err(ConfigError::InvalidValue {
+                    message: format!("duplicate port name `{}`", name.inner),
+                    span: name.span.into(),
+                });
+            }
+            super::Port {
+                name: Some(name),
+                port: Spanned {
+                    span: port_value.span,
+                    inner: parse_string_value(port_value, "port must be a scalar")?,
+                },
+            }
+        } else {
+            let port = super::Port {
+                name: None,
+                port: Spanned {
+                    span: item.span,
+                    inner: parse_string_value(item, "ports entries must be a scalar or mapping")?,
+                },
+            };
+            if port.is_auto() {
+                return Err(ConfigError::InvalidValue {
+                    message: "`auto` ports need a name: use `{ name: <name>, port: auto }`"
+                        .to_string(),
+                    span: item.span.into(),
+                });
+            }
+            port
+        };
+        ports.push(port);
     }
     Ok(ports)
 }
 
+/// Whether `name` is usable as a profile: an alphanumeric start followed by alphanumerics, `_`,
+/// `.`, or `-`. Commas and whitespace are excluded so a profile list round-trips through
+/// `--profile a,b` and `MICROMUX_PROFILES`.
+fn is_valid_profile_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphanumeric())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+}
+
+fn parse_profiles(mapping: &yaml_spanned::Mapping) -> Result<Vec<Spanned<String>>, ConfigError> {
+    let Some(value) = mapping.get("profiles") else {
+        return Ok(vec![]);
+    };
+    let items = match &value.inner {
+        Value::Sequence(seq) => seq.iter().collect::<Vec<_>>(),
+        _ => vec![value],
+    };
+    items
+        .into_iter()
+        .map(|item| {
+            let (span, name) = expect_string(item, Some("profiles entries must be strings"))?;
+            if !is_valid_profile_name(name) {
+                return Err(ConfigError::InvalidValue {
+                    message: format!(
+                        "invalid profile `{name}`: use letters, digits, `_`, `.`, or `-`, starting \
+                         with a letter or digit"
+                    ),
+                    span: span.into(),
+                });
+            }
+            Ok(Spanned {
+                span: *span,
+                inner: name.clone(),
+            })
+        })
+        .collect()
+}
+
 fn parse_restart_value(value: &yaml_spanned::Spanned<Value>) -> Result<RestartPolicy, ConfigError> {
     let raw = parse_string_value(value, "restart must be a string")?;
     let normalized = raw.trim().to_ascii_lowercase();
@@ -446,6 +611,80 @@ pub fn parse_control<F: Copy>(
     })
 }
 
+/// Parse the optional top-level `worktree:` block and detect the checkout in `config_dir`.
+fn parse_worktree<F: Copy>(
+    value: &yaml_spanned::Spanned<Value>,
+    config_dir: &Path,
+    file_id: F,
+    strict: bool,
+    diagnostics: &mut Vec<Diagnostic<F>>,
+) -> Result<Option<super::Worktree>, ConfigError> {
+    use super::worktree::{DEFAULT_MAX_PORT_OFFSET, DEFAULT_PORT_STEP};
+
+    let Some(value) = value.get("worktree") else {
+        return Ok(None);
+    };
+    let (enabled, port_step, max_port_offset) = match &value.inner {
+        Value::Bool(enabled) => (*enabled, None, None),
+        Value::Mapping(mapping) => {
+            warn_unknown_keys(
+                mapping,
+                KNOWN_WORKTREE_KEYS,
+                "worktree",
+                file_id,
+                strict,
+                diagnostics,
+            );
+            let enabled = parse_optional::<bool>(mapping.get("enabled"))?
+                .map(Spanned::into_inner)
+                .unwrap_or(true);
+            (
+                enabled,
+                parse_optional::<u16>(mapping.get("port_step"))?,
+                parse_optional::<u16>(mapping.get("max_port_offset"))?,
+            )
+        }
+        other => {
+            return Err(ConfigError::UnexpectedType {
+                message: "worktree must be a boolean or a mapping".to_string(),
+                expected: vec![Kind::Bool, Kind::Mapping],
+                found: other.kind(),
+                span: value.span().into(),
+            });
+        }
+    };
+    if !enabled {
+        return Ok(None);
+    }
+    let step = port_step
+        .as_ref()
+        .map_or(DEFAULT_PORT_STEP, |step| step.inner);
+    if step == 0 {
+        return Err(ConfigError::InvalidValue {
+            message: "port_step must be greater than zero".to_string(),
+            span: port_step.map_or(value.span, |step| step.span).into(),
+        });
+    }
+    let max = max_port_offset
+        .as_ref()
+        .map_or(DEFAULT_MAX_PORT_OFFSET, |max| max.inner);
+    if max < step {
+        return Err(ConfigError::InvalidValue {
+            message: format!("max_port_offset must be at least port_step ({step})"),
+            span: max_port_offset.map_or(value.span, |max| max.span).into(),
+        });
+    }
+    super::worktree::detect(config_dir, step, max)
+        .map(Some)
+        .map_err(|err| ConfigError::InvalidValue {
+            message: format!(
+                "failed to inspect `{}`: {err}",
+                config_dir.join(".git").display()
+            ),
+            span: value.span().into(),
+        })
+}
+
 fn parse_dynamic_services<F: Copy>(
     dynamic: Option<&yaml_spanned::Spanned<Value>>,
     default_span: &yaml_spanned::spanned::Span,
@@ -866,6 +1105,32 @@ pub fn parse_command(
     }
 }
 
+/// Parse a hook list: a single command, or a sequence whose entries are each a command string or
+/// an argv sequence.
+fn parse_hooks(
+    value: Option<&yaml_spanned::Spanned<Value>>,
+) -> Result<Vec<super::CommandLine>, ConfigError> {
+    match value {
+        None => Ok(Vec::new()),
+        Some(
+            value @ Spanned {
+                inner: Value::String(_),
+                ..
+            },
+        ) => Ok(vec![parse_command(value)?]),
+        Some(Spanned {
+            inner: Value::Sequence(commands),
+            ..
+        }) => commands.iter().map(parse_command).collect(),
+        Some(other) => Err(ConfigError::UnexpectedType {
+            message: "hooks must be a command or a sequence of commands".to_string(),
+            expected: vec![Kind::String, Kind::Sequence],
+            found: other.kind(),
+            span: other.span().into(),
+        }),
+    }
+}
+
 fn parse_health_check<F: Copy>(
     mapping: &yaml_spanned::Mapping,
     defaults: &HealthCheckDefaults,
@@ -892,14 +1157,8 @@ fn parse_health_check<F: Copy>(
                 strict,
                 diagnostics,
             );
-            let test = match healthcheck.get("test") {
-                None => Err(ConfigError::MissingKey {
-                    key: "test".to_string(),
-                    message: "missing healthcheck test command".to_string(),
-                    span: value.span().into(),
-                }),
-                Some(value) => parse_command(value),
-            }?;
+            let probe =
+                parse_health_probe(healthcheck, value.span(), file_id, strict, diagnostics)?;
             let start_delay = parse_duration(
                 healthcheck
                     .get("start_delay")
@@ -917,17 +1176,328 @@ fn parse_health_check<F: Copy>(
                 parse_duration(healthcheck.get("timeout"))?.or_else(|| defaults.timeout.clone()),
                 "healthcheck timeout",
             )?;
+            let on_unhealthy =
+                parse_optional::<super::OnUnhealthy>(healthcheck.get("on_unhealthy"))?;
             Ok(super::HealthCheck {
-                test,
+                probe,
                 start_delay,
                 interval,
                 timeout,
                 retries,
+                on_unhealthy,
             })
         })
         .transpose()
 }
 
+/// Parse the one probe a healthcheck runs: a `test` command, an `http` request, a `tcp`
+/// connect, or a `log_pattern` to wait for.
+fn parse_health_probe<F: Copy>(
+    healthcheck: &Mapping,
+    span: &yaml_spanned::spanned::Span,
+    file_id: F,
+    strict: bool,
+    diagnostics: &mut Vec<Diagnostic<F>>,
+) -> Result<super::HealthProbe, ConfigError> {
+    let mut probes = ["test", "http", "tcp", "log_pattern"]
+        .into_iter()
+        .filter_map(|key| healthcheck.get(key).map(|value| (key, value)));
+    let Some((key, value)) = probes.next() else {
+        return Err(ConfigError::MissingKey {
+            key: "test".to_string(),
+            message: "missing healthcheck probe: set `test`, `http`, `tcp`, or `log_pattern`"
+                .to_string(),
+            span: span.into(),
+        });
+    };
+    if let Some((other, extra)) = probes.next() {
+        return Err(ConfigError::InvalidValue {
+            message: format!("healthcheck `{key}` and `{other}` are mutually exclusive"),
+            span: extra.span().into(),
+        });
+    }
+    match key {
+        "http" => parse_http_probe(value, file_id, strict, diagnostics)
+            .map(|probe| super::HealthProbe::Http(Box::new(probe))),
+        "tcp" => parse_tcp_probe(value, file_id, strict, diagnostics).map(super::HealthProbe::Tcp),
+        "log_pattern" => parse_log_pattern_probe(value, file_id, strict, diagnostics)
+            .map(super::HealthProbe::LogPattern),
+        _ => parse_command(value).map(super::HealthProbe::Command),
+    }
+}
+
+fn parse_http_probe<F: Copy>(
+    value: &yaml_spanned::Spanned<Value>,
+    file_id: F,
+    strict: bool,
+    diagnostics: &mut Vec<Diagnostic<F>>,
+) -> Result<super::HttpProbe, ConfigError> {
+    if value.as_string().is_some() {
+        return Ok(super::HttpProbe {
+            url: parse_http_probe_url(value)?,
+            status: None,
+            body: None,
+            headers: IndexMap::new(),
+        });
+    }
+    let (span, probe) = expect_mapping(value, "http probe must be a URL or a mapping".into())?;
+    warn_unknown_keys(
+        probe,
+        KNOWN_HTTP_PROBE_KEYS,
+        "http probe",
+        file_id,
+        strict,
+        diagnostics,
+    );
+    let url = match probe.get("url") {
+        None => Err(ConfigError::MissingKey {
+            key: "url".to_string(),
+            message: "missing http probe url".to_string(),
+            span: span.into(),
+        }),
+        Some(url) => parse_http_probe_url(url),
+    }?;
+    let status = probe.get("status").map(parse_status_range).transpose()?;
+    let body = probe
+        .get("body")
+        .map(|body| {
+            let (span, pattern) =
+                expect_string(body, Some("http probe body must be a regular expression"))?;
+            regex::Regex::new(pattern).map_err(|err| ConfigError::InvalidValue {
+                message: format!("invalid http probe body pattern: {err}"),
+                span: span.into(),
+            })?;
+            Ok::<_, ConfigError>(Spanned {
+                span: *span,
+                inner: pattern.clone(),
+            })
+        })
+        .transpose()?;
+    let mut headers = IndexMap::new();
+    if let Some(value) = probe.get("headers") {
+        let (_span, mapping) =
+            expect_mapping(value, "http probe headers must be a mapping".into())?;
+        for (name, header) in mapping {
+            let name = parse::<String>(name)?;
+            let raw = parse_string_value(header, "http probe header values must be scalar")?;
+            if !crate::spec::http_header_is_valid(&name.inner, &raw) {
+                return Err(ConfigError::InvalidValue {
+                    message: format!("invalid http probe header `{}`", name.inner),
+                    span: name.span.into(),
+                });
+            }
+            headers.insert(
+                name,
+                Spanned {
+                    span: header.span,
+                    inner: raw,
+                },
+            );
+        }
+    }
+    Ok(super::HttpProbe {
+        url,
+        status,
+        body,
+        headers,
+    })
+}
+
+fn parse_http_probe_url(
+    value: &yaml_spanned::Spanned<Value>,
+) -> Result<Spanned<String>, ConfigError> {
+    let (span, url) = expect_string(value, Some("http probe url must be a string"))?;
+    crate::spec::parse_http_url(url).map_err(|message| ConfigError::InvalidValue {
+        message,
+        span: span.into(),
+    })?;
+    Ok(Spanned {
+        span: *span,
+        inner: url.clone(),
+    })
+}
+
+/// Parse an accepted status: one code (`204`), a class (`2xx`), or an inclusive range
+/// (`200-299`).
+fn parse_status_range(
+    value: &yaml_spanned::Spanned<Value>,
+) -> Result<Spanned<crate::spec::StatusRange>, ConfigError> {
+    use crate::spec::StatusRange;
+
+    let raw = parse_string_value(value, "http probe status must be a code or a range")?;
+    let trimmed = raw.trim();
+    let range = if let Some(class) = trimmed
+        .strip_suffix("xx")
+        .or_else(|| trimmed.strip_suffix("XX"))
+    {
+        class
+            .parse::<u16>()
+            .ok()
+            .and_then(|class| class.checked_mul(100))
+            .map(|min| StatusRange {
+                min,
+                max: min.saturating_add(99),
+            })
+    } else if let Some((min, max)) = trimmed.split_once('-') {
+        min.trim()
+            .parse()
+            .ok()
+            .zip(max.trim().parse().ok())
+            .map(|(min, max)| StatusRange { min, max })
+    } else {
+        trimmed.parse().ok().map(|code| StatusRange {
+            min: code,
+            max: code,
+        })
+    };
+    range
+        .filter(|range| range.is_valid())
+        .map(|inner| Spanned {
+            span: value.span,
+            inner,
+        })
+        .ok_or_else(|| ConfigError::InvalidValue {
+            message: format!(
+                "invalid http probe status `{raw}`; expected a code (200), a class (2xx), or a range (200-299)"
+            ),
+            span: value.span().into(),
+        })
+}
+
+fn parse_tcp_probe<F: Copy>(
+    value: &yaml_spanned::Spanned<Value>,
+    file_id: F,
+    strict: bool,
+    diagnostics: &mut Vec<Diagnostic<F>>,
+) -> Result<super::TcpProbe, ConfigError> {
+    let localhost = || Spanned {
+        span: value.span,
+        inner: "localhost".to_string(),
+    };
+    match &value.inner {
+        Value::Number(_) => Ok(super::TcpProbe {
+            host: localhost(),
+            port: parse_tcp_port(value)?,
+        }),
+        Value::String(address) => {
+            let invalid = || ConfigError::InvalidValue {
+                message: format!("invalid tcp probe address `{address}`; expected host:port"),
+                span: value.span().into(),
+            };
+            let (host, port) = address.rsplit_once(':').ok_or_else(invalid)?;
+            let host = host
+                .strip_prefix('[')
+                .and_then(|host| host.strip_suffix(']'))
+                .unwrap_or(host);
+            let port = port
+                .parse::<u16>()
+                .ok()
+                .filter(|port| *port != 0)
+                .ok_or_else(invalid)?;
+            if host.is_empty() {
+                return Err(invalid());
+            }
+            Ok(super::TcpProbe {
+                host: Spanned {
+                    span: value.span,
+                    inner: host.to_string(),
+                },
+                port: Spanned {
+                    span: value.span,
+                    inner: port,
+                },
+            })
+        }
+        Value::Mapping(probe) => {
+            warn_unknown_keys(
+                probe,
+                KNOWN_TCP_PROBE_KEYS,
+                "tcp probe",
+                file_id,
+                strict,
+                diagnostics,
+            );
+            let host = parse_optional::<String>(probe.get("host"))?.unwrap_or_else(localhost);
+            let port = match probe.get("port") {
+                None => Err(ConfigError::MissingKey {
+                    key: "port".to_string(),
+                    message: "missing tcp probe port".to_string(),
+                    span: value.span().into(),
+                }),
+                Some(port) => parse_tcp_port(port),
+            }?;
+            Ok(super::TcpProbe { host, port })
+        }
+        other => Err(ConfigError::UnexpectedType {
+            message: "tcp probe must be a port, a host:port string, or a mapping".to_string(),
+            expected: vec![Kind::Number, Kind::String, Kind::Mapping],
+            found: other.kind(),
+            span: value.span().into(),
+        }),
+    }
+}
+
+fn parse_tcp_port(value: &yaml_spanned::Spanned<Value>) -> Result<Spanned<u16>, ConfigError> {
+    let port = parse::<u16>(value)?;
+    if *port == 0 {
+        return Err(ConfigError::InvalidValue {
+            message: "tcp probe port must be greater than zero".to_string(),
+            span: port.span.into(),
+        });
+    }
+    Ok(port)
+}
+
+/// Parse a `log_pattern`: a bare regular expression, or a mapping that also sets a `timeout`.
+fn parse_log_pattern_probe<F: Copy>(
+    value: &yaml_spanned::Spanned<Value>,
+    file_id: F,
+    strict: bool,
+    diagnostics: &mut Vec<Diagnostic<F>>,
+) -> Result<super::LogPatternProbe, ConfigError> {
+    if value.as_string().is_some() {
+        return Ok(super::LogPatternProbe {
+            pattern: parse_log_pattern(value)?,
+            timeout: None,
+        });
+    }
+    let (span, probe) = expect_mapping(
+        value,
+        "log_pattern must be a regular expression or a mapping".into(),
+    )?;
+    warn_unknown_keys(
+        probe,
+        KNOWN_LOG_PATTERN_KEYS,
+        "log_pattern",
+        file_id,
+        strict,
+        diagnostics,
+    );
+    let pattern = match probe.get("pattern") {
+        None => Err(ConfigError::MissingKey {
+            key: "pattern".to_string(),
+            message: "missing log_pattern pattern".to_string(),
+            span: span.into(),
+        }),
+        Some(pattern) => parse_log_pattern(pattern),
+    }?;
+    let timeout =
+        parse_positive_duration(parse_duration(probe.get("timeout"))?, "log_pattern timeout")?;
+    Ok(super::LogPatternProbe { pattern, timeout })
+}
+
+fn parse_log_pattern(value: &yaml_spanned::Spanned<Value>) -> Result<Spanned<String>, ConfigError> {
+    let (span, pattern) = expect_string(value, Some("log_pattern must be a regular expression"))?;
+    regex::Regex::new(pattern).map_err(|err| ConfigError::InvalidValue {
+        message: format!("invalid log_pattern: {err}"),
+        span: span.into(),
+    })?;
+    Ok(Spanned {
+        span: *span,
+        inner: pattern.clone(),
+    })
+}
+
 fn parse_positive_duration(
     duration: Option<Spanned<std::time::Duration>>,
     name: &str,
@@ -958,6 +1528,52 @@ fn parse_stop_grace_period(
     Ok(duration)
 }
 
+fn parse_stop_signal(
+    value: &yaml_spanned::Spanned<Value>,
+) -> Result<Spanned<crate::ServiceSignal>, ConfigError> {
+    let (span, raw) = expect_string(value, Some("stop_signal must be a signal name"))?;
+    let signal = raw
+        .parse()
+        .map_err(|err: crate::UnknownSignal| ConfigError::InvalidValue {
+            message: err.to_string(),
+            span: span.into(),
+        })?;
+    Ok(Spanned {
+        span: *span,
+        inner: signal,
+    })
+}
+
+/// Parse `schedule` (a cron expression) or `every` (an interval); a service sets at most one.
+fn parse_schedule(
+    mapping: &yaml_spanned::Mapping,
+) -> Result<Option<Spanned<crate::RunSchedule>>, ConfigError> {
+    let every = parse_positive_duration(parse_duration(mapping.get("every"))?, "every")?;
+    let Some(value) = mapping.get("schedule") else {
+        return Ok(every.map(|every| Spanned {
+            span: every.span,
+            inner: crate::RunSchedule::Every(every.inner),
+        }));
+    };
+    let (span, raw) = expect_string(value, Some("schedule must be a cron expression"))?;
+    if every.is_some() {
+        return Err(ConfigError::InvalidValue {
+            message: "set either schedule or every, not both".to_string(),
+            span: span.into(),
+        });
+    }
+    let cron = raw
+        .parse()
+        .map_err(|err: crate::CronError| ConfigError::InvalidValue {
+            message: err.to_string(),
+            span: span.into(),
+        })?;
+    Ok(Some(Spanned {
+        span: *span,
+        inner: crate::RunSchedule::Cron(cron),
+    }))
+}
+
 fn parse_healthcheck_defaults<F: Copy>(
     value: &yaml_spanned::Spanned<Value>,
     file_id: F,
@@ -995,60 +1611,451 @@ fn parse_healthcheck_defaults<F: Copy>(
     })
 }
 
-#[derive(Clone, Copy)]
-struct ServiceDefaults<'a> {
-    log_retention: LogRetention,
-    restart_policy: &'a RestartPolicy,
-    healthcheck: &'a HealthCheckDefaults,
-}
+fn parse_glob_list(
+    value: &yaml_spanned::Spanned<Value>,
+    field: &str,
+) -> Result<Vec<Spanned<String>>, ConfigError> {
+    let items = match &value.inner {
+        Value::Sequence(seq) => seq.iter().collect::<Vec<_>>(),
+        _ => vec![value],
+    };
+    let message = format!("{field} entries must be strings");
+    items
+        .into_iter()
+        .map(|item| {
+            let (span, pattern) = expect_string(item, Some(&message))?;
+            globset::Glob::new(pattern).map_err(|err| ConfigError::InvalidValue {
+                message: format!("invalid {field} pattern `{pattern}`: {}", err.kind()),
+                span: span.into(),
+            })?;
+            Ok(Spanned {
+                span: *span,
+                inner: pattern.clone(),
+            })
+        })
+        .collect()
+}
 
-fn parse_service<F: Copy>(
+/// Parse a service `watch` block.
+///
+/// `false` disables watching, `true` watches the working directory, and a path, glob, or list of
+/// them watches exactly those. The mapping form adds `ignore` patterns and a `debounce` window.
+fn parse_watch<F: Copy>(
+    mapping: &yaml_spanned::Mapping,
+    file_id: F,
+    strict: bool,
+    diagnostics: &mut Vec<Diagnostic<F>>,
+) -> Result<Option<super::Watch>, ConfigError> {
+    let Some(value) = mapping.get("watch") else {
+        return Ok(None);
+    };
+    let current_dir = || Spanned {
+        span: value.span,
+        inner: ".".to_string(),
+    };
+    match &value.inner {
+        Value::Bool(false) => Ok(None),
+        Value::Bool(true) => Ok(Some(super::Watch {
+            paths: vec![current_dir()],
+            ignore: Vec::new(),
+            debounce: None,
+        })),
+        Value::String(_) | Value::Sequence(_) => Ok(Some(super::Watch {
+            paths: parse_glob_list(value, "watch")?,
+            ignore: Vec::new(),
+            debounce: None,
+        })),
+        Value::Mapping(watch) => {
+            warn_unknown_keys(
+                watch,
+                KNOWN_WATCH_KEYS,
+                "watch",
+                file_id,
+                strict,
+                diagnostics,
+            );
+            let mut paths = watch
+                .get("paths")
+                .map(|paths| parse_glob_list(paths, "watch.paths"))
+                .transpose()?
+                .unwrap_or_default();
+            if paths.is_empty() {
+                paths.push(current_dir());
+            }
+            let ignore = watch
+                .get("ignore")
+                .map(|ignore| parse_glob_list(ignore, "watch.ignore"))
+                .transpose()?
+                .unwrap_or_default();
+            let debounce =
+                parse_positive_duration(parse_duration(watch.get("debounce"))?, "watch debounce")?;
+            Ok(Some(super::Watch {
+                paths,
+                ignore,
+                debounce,
+            }))
+        }
+        other => Err(ConfigError::UnexpectedType {
+            message: "watch must be a boolean, path, sequence, or mapping".to_string(),
+            expected: vec![Kind::Bool, Kind::String, Kind::Sequence, Kind::Mapping],
+            found: other.kind(),
+            span: value.span().into(),
+        }),
+    }
+}
+
+fn parse_backoff_factor(
+    value: Option<&yaml_spanned::Spanned<Value>>,
+    field: &str,
+    valid: impl Fn(f64) -> bool,
+    requirement: &str,
+) -> Result<Option<f64>, ConfigError> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+    let factor = parse::<f64>(value)?.into_inner();
+    if !factor.is_finite() || !valid(factor) {
+        return Err(ConfigError::InvalidValue {
+            message: format!("restart_backoff {field} must be {requirement}"),
+            span: value.span().into(),
+        });
+    }
+    Ok(Some(factor))
+}
+
+fn parse_crash_loop<F: Copy>(
     value: &yaml_spanned::Spanned<Value>,
-    name: &yaml_spanned::Spanned<String>,
-    defaults: ServiceDefaults<'_>,
     file_id: F,
     strict: bool,
     diagnostics: &mut Vec<Diagnostic<F>>,
-) -> Result<Service, ConfigError> {
-    let (span, mapping) = expect_mapping(value, "service config must be a mapping".into())?;
+) -> Result<crate::CrashLoopSpec, ConfigError> {
+    let (span, crash_loop) =
+        expect_mapping(value, "restart_backoff.crash_loop must be a mapping".into())?;
     warn_unknown_keys(
-        mapping,
-        KNOWN_SERVICE_KEYS,
-        "service",
+        crash_loop,
+        KNOWN_CRASH_LOOP_KEYS,
+        "restart_backoff.crash_loop",
         file_id,
         strict,
         diagnostics,
     );
-    let name = parse_optional::<String>(mapping.get("name"))?.unwrap_or_else(|| name.clone());
+    let crashes = match crash_loop.get("crashes") {
+        Some(crashes) => parse_positive_usize(crashes, "restart_backoff.crash_loop.crashes")?,
+        None => {
+            return Err(ConfigError::MissingKey {
+                key: "crashes".to_string(),
+                message: "crash_loop needs the number of crashes that stop restarts".to_string(),
+                span: span.into(),
+            });
+        }
+    };
+    let window = parse_positive_duration(
+        parse_duration(crash_loop.get("window"))?,
+        "restart_backoff.crash_loop.window",
+    )?
+    .ok_or_else(|| ConfigError::MissingKey {
+        key: "window".to_string(),
+        message: "crash_loop needs the window crashes are counted over".to_string(),
+        span: span.into(),
+    })?;
+    Ok(crate::CrashLoopSpec {
+        crashes,
+        window: window.into_inner(),
+    })
+}
+
+/// Parse a service `restart_backoff` block. Omitted fields keep the built-in schedule.
+fn parse_restart_backoff<F: Copy>(
+    mapping: &yaml_spanned::Mapping,
+    file_id: F,
+    strict: bool,
+    diagnostics: &mut Vec<Diagnostic<F>>,
+) -> Result<Option<crate::RestartBackoffSpec>, ConfigError> {
+    let Some(value) = mapping.get("restart_backoff") else {
+        return Ok(None);
+    };
+    let (span, backoff) = expect_mapping(value, "restart_backoff must be a mapping".into())?;
+    warn_unknown_keys(
+        backoff,
+        KNOWN_RESTART_BACKOFF_KEYS,
+        "restart_backoff",
+        file_id,
+        strict,
+        diagnostics,
+    );
+    let defaults = crate::RestartBackoffSpec::default();
+    let initial = parse_positive_duration(
+        parse_duration(backoff.get("initial"))?,
+        "restart_backoff initial",
+    )?;
+    let max = parse_positive_duration(parse_duration(backoff.get("max"))?, "restart_backoff max")?;
+    let reset_after = parse_positive_duration(
+        parse_duration(backoff.get("reset_after"))?,
+        "restart_backoff reset_after",
+    )?;
+    let multiplier = parse_backoff_factor(
+        backoff.get("multiplier"),
+        "multiplier",
+        |factor| factor >= 1.0,
+        "a number of at least 1",
+    )?;
+    let jitter = parse_backoff_factor(
+        backoff.get("jitter"),
+        "jitter",
+        |factor| (0.0..=1.0).contains(&factor),
+        "a number between 0 and 1",
+    )?;
+    let crash_loop = backoff
+        .get("crash_loop")
+        .map(|crash_loop| parse_crash_loop(crash_loop, file_id, strict, diagnostics))
+        .transpose()?;
+    let spec = crate::RestartBackoffSpec {
+        initial: initial.map_or(defaults.initial, Spanned::into_inner),
+        max: max.map_or(defaults.max, Spanned::into_inner),
+        multiplier: multiplier.unwrap_or(defaults.multiplier),
+        jitter: jitter.unwrap_or(defaults.jitter),
+        reset_after: reset_after.map(Spanned::into_inner),
+        crash_loop,
+    };
+    spec.validate()
+        .map_err(|message| ConfigError::InvalidValue {
+            message: format!("restart_backoff {message}"),
+            span: span.into(),
+        })?;
+    Ok(Some(spec))
+}
+
+#[derive(Clone, Copy)]
+struct ServiceDefaults<'a> {
+    log_retention: LogRetention,
+    restart_policy: &'a RestartPolicy,
+    port_conflict: PortConflictPolicy,
+    healthcheck: &'a HealthCheckDefaults,
+    env_file: &'a [super::EnvFile],
+    environment: &'a IndexMap<Spanned<String>, Spanned<String>>,
+    port_offset: Option<u16>,
+}
+
+/// Keys that configure the process micromux spawns, which an external service does not have.
+const PROCESS_SERVICE_KEYS: &[&str] = &[
+    "command",
+    "pre_start",
+    "post_stop",
+    "depends_on",
+    "restart",
+    "restart_backoff",
+    "stop_grace_period",
+    "stop_signal",
+    "startup_timeout",
+    "max_runtime",
+    "schedule",
+    "every",
+    "watch",
+    "tty",
+    "replicas",
+];
+
+fn reject_process_keys(mapping: &yaml_spanned::Mapping) -> Result<(), ConfigError> {
+    let Some((key, value)) = PROCESS_SERVICE_KEYS
+        .iter()
+        .find_map(|key| mapping.get(*key).map(|value| (key, value)))
+    else {
+        return Ok(());
+    };
+    Err(ConfigError::InvalidValue {
+        message: format!(
+            "`{key}` does not apply to an external service: micromux only probes it and never \
+             runs a process for it"
+        ),
+        span: value.span.into(),
+    })
+}
+
+/// An external service is known only through its probe, so it needs one that does not read the
+/// output of a process micromux runs, and a failing probe cannot restart it.
+fn check_external_healthcheck(
+    mapping: &yaml_spanned::Mapping,
+    healthcheck: Option<&super::HealthCheck>,
+    span: &yaml_spanned::spanned::Span,
+) -> Result<(), ConfigError> {
+    let Some(healthcheck) = healthcheck else {
+        return Err(ConfigError::MissingKey {
+            key: "healthcheck".to_string(),
+            message: "an external service needs a healthcheck for micromux to probe".to_string(),
+            span: span.into(),
+        });
+    };
+    let healthcheck_span = mapping
+        .get("healthcheck")
+        .map_or(*span, |healthcheck| healthcheck.span);
+    if matches!(healthcheck.probe, super::HealthProbe::LogPattern(_)) {
+        return Err(ConfigError::InvalidValue {
+            message: "an external service has no output to match; probe it with `test`, `http`, \
+                      or `tcp`"
+                .to_string(),
+            span: healthcheck_span.into(),
+        });
+    }
+    if let Some(on_unhealthy) = &healthcheck.on_unhealthy
+        && on_unhealthy.inner == super::OnUnhealthy::Restart
+    {
+        return Err(ConfigError::InvalidValue {
+            message: "on_unhealthy: restart does not apply to an external service, which micromux \
+                      cannot restart"
+                .to_string(),
+            span: on_unhealthy.span.into(),
+        });
+    }
+    Ok(())
+}
+
+/// `working_dir`, or its aliases `cwd` and `directory`.
+fn parse_working_dir(
+    mapping: &yaml_spanned::Mapping,
+) -> Result<Option<Spanned<String>>, ConfigError> {
+    let working_dir = mapping
+        .get("working_dir")
+        .or_else(|| mapping.get("cwd"))
+        .or_else(|| mapping.get("directory"));
+    parse_optional::<String>(working_dir)
+}
+
+fn parse_startup_mode(mapping: &yaml_spanned::Mapping) -> Result<StartupMode, ConfigError> {
     let disabled = parse_optional::<bool>(mapping.get("disabled"))?
         .map(Spanned::into_inner)
         .unwrap_or(false);
-    let startup_mode = if disabled {
+    Ok(if disabled {
         StartupMode::Disabled
     } else {
         StartupMode::Enabled
-    };
-    let color = parse_optional::<bool>(mapping.get("color"))?;
-    let working_dir = mapping
-        .get("working_dir")
-        .or_else(|| mapping.get("cwd"))
-        .or_else(|| mapping.get("directory"));
-    let working_dir = parse_optional::<String>(working_dir)?;
-    let command = match mapping.get("command") {
+    })
+}
+
+fn parse_replicas(mapping: &yaml_spanned::Mapping) -> Result<Option<Spanned<usize>>, ConfigError> {
+    let replicas = parse_optional::<usize>(mapping.get("replicas"))?;
+    if let Some(replicas) = replicas
+        .as_ref()
+        .filter(|replicas| !(1..=super::MAX_REPLICAS).contains(&replicas.inner))
+    {
+        return Err(ConfigError::InvalidValue {
+            message: format!("replicas must be between 1 and {}", super::MAX_REPLICAS),
+            span: replicas.span.into(),
+        });
+    }
+    Ok(replicas)
+}
+
+/// The service's `command`, required unless the service is external, which takes none of the
+/// process keys.
+fn parse_service_command(
+    mapping: &yaml_spanned::Mapping,
+    span: &yaml_spanned::spanned::Span,
+    external: bool,
+) -> Result<Option<super::CommandLine>, ConfigError> {
+    if external {
+        reject_process_keys(mapping)?;
+    }
+    match mapping.get("command") {
+        None if external => Ok(None),
         None => Err(ConfigError::MissingKey {
             key: "command".to_string(),
             message: "missing command".to_string(),
             span: span.into(),
         }),
-        Some(value) => parse_command(value),
-    }?;
+        Some(value) => parse_command(value).map(Some),
+    }
+}
+
+fn parse_startup_timeout<F: Copy>(
+    mapping: &yaml_spanned::Mapping,
+    has_healthcheck: bool,
+    file_id: F,
+    strict: bool,
+    diagnostics: &mut Vec<Diagnostic<F>>,
+) -> Result<Option<Spanned<std::time::Duration>>, ConfigError> {
+    let startup_timeout = parse_positive_duration(
+        parse_duration(mapping.get("startup_timeout"))?,
+        "startup timeout",
+    )?;
+    if let Some(timeout) = &startup_timeout
+        && !has_healthcheck
+    {
+        diagnostics.push(
+            Diagnostic::warning_or_error(strict)
+                .with_message("startup_timeout has no effect without a healthcheck")
+                .with_labels(vec![
+                    Label::primary(file_id, timeout.span).with_message("needs a healthcheck"),
+                ]),
+        );
+    }
+    Ok(startup_timeout)
+}
+
+/// The service's `schedule` or `every`, warning that a `restart` next to it is ignored.
+fn parse_service_schedule<F: Copy>(
+    mapping: &yaml_spanned::Mapping,
+    file_id: F,
+    strict: bool,
+    diagnostics: &mut Vec<Diagnostic<F>>,
+) -> Result<Option<Spanned<crate::RunSchedule>>, ConfigError> {
+    let schedule = parse_schedule(mapping)?;
+    if let Some(schedule) = &schedule
+        && let Some(restart) = mapping.get("restart")
+    {
+        diagnostics.push(
+            Diagnostic::warning_or_error(strict)
+                .with_message("restart has no effect on a scheduled service")
+                .with_labels(vec![
+                    Label::primary(file_id, restart.span)
+                        .with_message("ignored: each tick starts the next run"),
+                    Label::secondary(file_id, schedule.span).with_message("scheduled here"),
+                ]),
+        );
+    }
+    Ok(schedule)
+}
+
+fn parse_service<F: Copy>(
+    value: &yaml_spanned::Spanned<Value>,
+    name: &yaml_spanned::Spanned<String>,
+    defaults: &ServiceDefaults<'_>,
+    file_id: F,
+    strict: bool,
+    diagnostics: &mut Vec<Diagnostic<F>>,
+) -> Result<Service, ConfigError> {
+    let (span, mapping) = expect_mapping(value, "service config must be a mapping".into())?;
+    warn_unknown_keys(
+        mapping,
+        KNOWN_SERVICE_KEYS,
+        "service",
+        file_id,
+        strict,
+        diagnostics,
+    );
+    let name = parse_optional::<String>(mapping.get("name"))?.unwrap_or_else(|| name.clone());
+    let startup_mode = parse_startup_mode(mapping)?;
+    let color = parse_optional::<bool>(mapping.get("color"))?;
+    let tty = parse_optional::<bool>(mapping.get("tty"))?;
+    let replicas = parse_replicas(mapping)?;
+    let working_dir = parse_working_dir(mapping)?;
+    let external = parse_optional::<bool>(mapping.get("external"))?
+        .map(Spanned::into_inner)
+        .unwrap_or(false);
+    let command = parse_service_command(mapping, span, external)?;
+    let pre_start = parse_hooks(mapping.get("pre_start"))?;
+    let post_stop = parse_hooks(mapping.get("post_stop"))?;
     let healthcheck =
         parse_health_check(mapping, defaults.healthcheck, file_id, strict, diagnostics)?;
+    if external {
+        check_external_healthcheck(mapping, healthcheck.as_ref(), span)?;
+    }
 
     let env_file = parse_env_file(mapping)?;
     let environment = parse_environment(mapping)?;
     let depends_on = parse_depends_on(mapping)?;
-    let ports = parse_ports(mapping)?;
+    let ports = parse_ports(mapping, file_id, strict, diagnostics)?;
+    let port_conflict = parse_optional::<PortConflictPolicy>(mapping.get("port_conflict"))?
+        .map_or(defaults.port_conflict, Spanned::into_inner);
     let restart = parse_restart(mapping)?;
     let restart_policy = restart
         .clone()
@@ -1060,6 +2067,18 @@ fn parse_service<F: Copy>(
         inner: crate::spec::DEFAULT_STOP_GRACE_PERIOD,
         span: *span,
     });
+    let stop_signal = mapping
+        .get("stop_signal")
+        .map(parse_stop_signal)
+        .transpose()?;
+    let startup_timeout =
+        parse_startup_timeout(mapping, healthcheck.is_some(), file_id, strict, diagnostics)?;
+    let max_runtime =
+        parse_positive_duration(parse_duration(mapping.get("max_runtime"))?, "max runtime")?;
+    let schedule = parse_service_schedule(mapping, file_id, strict, diagnostics)?;
+    let restart_backoff = parse_restart_backoff(mapping, file_id, strict, diagnostics)?;
+    let watch = parse_watch(mapping, file_id, strict, diagnostics)?;
+    let profiles = parse_profiles(mapping)?;
     let log_retention = parse_log_retention(
         mapping.get("logs"),
         defaults.log_retention,
@@ -1071,24 +2090,41 @@ fn parse_service<F: Copy>(
     Ok(Service {
         name,
         startup_mode,
+        external,
         command,
+        pre_start,
+        post_stop,
         working_dir,
         env_file,
         environment,
+        inherited_env_file: defaults.env_file.to_vec(),
+        inherited_environment: defaults.environment.clone(),
         depends_on,
         healthcheck,
         ports,
+        port_conflict,
         restart,
         restart_policy,
         stop_grace_period,
+        stop_signal,
+        startup_timeout,
+        max_runtime,
+        schedule,
+        restart_backoff,
+        watch,
+        profiles,
         color,
+        tty,
+        replicas,
+        replica: None,
+        port_offset: defaults.port_offset,
         log_retention,
     })
 }
 
 fn parse_services<F: Copy>(
     value: &yaml_spanned::Spanned<Value>,
-    defaults: ServiceDefaults<'_>,
+    defaults: &ServiceDefaults<'_>,
     file_id: F,
     strict: bool,
     diagnostics: &mut Vec<Diagnostic<F>>,
@@ -1140,6 +2176,28 @@ fn parse_services<F: Copy>(
                     parse_service(service, &name, defaults, file_id, strict, diagnostics)?;
                 parsed.insert(name, service);
             }
+            for (name, service) in &parsed {
+                let Some(replicas) = &service.replicas else {
+                    continue;
+                };
+                if let Err(conflict) = super::replicas::check(&parsed, name, replicas.inner) {
+                    let mut labels = vec![
+                        Label::primary(file_id, replicas.span)
+                            .with_message("replicas are named after the service"),
+                    ];
+                    if let super::replicas::Conflict::Taken { by, .. } = &conflict {
+                        labels.push(
+                            Label::secondary(file_id, by.span)
+                                .with_message("service with the same name"),
+                        );
+                    }
+                    diagnostics.push(
+                        Diagnostic::error()
+                            .with_message(format!("service `{name}`: {conflict}"))
+                            .with_labels(labels),
+                    );
+                }
+            }
             for (service_id, service) in &parsed {
                 for dependency in &service.depends_on {
                     let Some(invalid_span) = invalid.get(dependency.name.as_str()) else {
@@ -1165,6 +2223,81 @@ fn parse_services<F: Copy>(
     }
 }
 
+fn parse_task<F: Copy>(
+    value: &yaml_spanned::Spanned<Value>,
+    name: Spanned<String>,
+    file_id: F,
+    strict: bool,
+    diagnostics: &mut Vec<Diagnostic<F>>,
+) -> Result<Task, ConfigError> {
+    let (span, mapping) = expect_mapping(value, "task config must be a mapping".into())?;
+    warn_unknown_keys(
+        mapping,
+        KNOWN_TASK_KEYS,
+        "task",
+        file_id,
+        strict,
+        diagnostics,
+    );
+    let description = parse_optional::<String>(mapping.get("description"))?;
+    let working_dir = parse_working_dir(mapping)?;
+    let command = match mapping.get("command") {
+        None => Err(ConfigError::MissingKey {
+            key: "command".to_string(),
+            message: "missing command".to_string(),
+            span: span.into(),
+        }),
+        Some(value) => parse_command(value),
+    }?;
+    Ok(Task {
+        name,
+        description,
+        command,
+        working_dir,
+        env_file: parse_env_file(mapping)?,
+        environment: parse_environment(mapping)?,
+    })
+}
+
+fn parse_tasks<F: Copy>(
+    value: &yaml_spanned::Spanned<Value>,
+    file_id: F,
+    strict: bool,
+    diagnostics: &mut Vec<Diagnostic<F>>,
+) -> Result<IndexMap<Spanned<String>, Task>, ConfigError> {
+    let Some(value) = value.get("tasks") else {
+        return Ok(IndexMap::new());
+    };
+    let tasks = value
+        .as_mapping()
+        .ok_or_else(|| ConfigError::UnexpectedType {
+            message: "tasks must be a mapping".to_string(),
+            found: value.kind(),
+            expected: vec![Kind::Mapping],
+            span: value.span().into(),
+        })?;
+    let mut parsed = IndexMap::new();
+    for (name, task) in tasks {
+        let name = parse::<String>(name)?;
+        if !crate::spec::service_id_is_valid(&name) {
+            diagnostics.push(
+                Diagnostic::warning_or_error(strict)
+                    .with_message(format!(
+                        "task name `{name}` must match [A-Za-z0-9._-]{{1,64}}; the task cannot be \
+                         run"
+                    ))
+                    .with_labels(vec![
+                        Label::primary(file_id, name.span).with_message("invalid task name"),
+                    ]),
+            );
+            continue;
+        }
+        let task = parse_task(task, name.clone(), file_id, strict, diagnostics)?;
+        parsed.insert(name, task);
+    }
+    Ok(parsed)
+}
+
 pub fn parse_config<F: Copy + PartialEq>(
     value: &yaml_spanned::Spanned<Value>,
     config_dir: &Path,
@@ -1184,7 +2317,13 @@ pub fn parse_config<F: Copy + PartialEq>(
             diagnostics,
         );
     }
-    let name = parse_optional::<String>(value.get("name"))?.map(Spanned::into_inner);
+    let worktree = parse_worktree(value, config_dir, file_id, strict, diagnostics)?;
+    let name = parse_optional::<String>(value.get("name"))?
+        .map(Spanned::into_inner)
+        .map(|name| match &worktree {
+            Some(worktree) => worktree.session_name(name),
+            None => name,
+        });
     let ui_config = parse_ui_config(value, file_id, strict, diagnostics)?;
     let control = parse_control(value, config_dir, file_id, strict, diagnostics)?;
     let restart_policy = value
@@ -1192,6 +2331,12 @@ pub fn parse_config<F: Copy + PartialEq>(
         .map(parse_restart_value)
         .transpose()?
         .unwrap_or_default();
+    let port_conflict = parse_optional::<PortConflictPolicy>(value.get("port_conflict"))?
+        .map(Spanned::into_inner)
+        .unwrap_or_default();
+    let shutdown_order = parse_optional::<ShutdownOrder>(value.get("shutdown_order"))?
+        .map(Spanned::into_inner)
+        .unwrap_or_default();
     let healthcheck_defaults = parse_healthcheck_defaults(value, file_id, strict, diagnostics)?;
     let log_retention = parse_log_retention(
         value.get("logs"),
@@ -1200,25 +2345,50 @@ pub fn parse_config<F: Copy + PartialEq>(
         strict,
         diagnostics,
     )?;
+    let (env_file, environment) = match value.as_mapping() {
+        Some(mapping) => (parse_env_file(mapping)?, parse_environment(mapping)?),
+        None => (Vec::new(), IndexMap::new()),
+    };
+    let before = parse_hooks(value.get("before"))?;
+    let after = parse_hooks(value.get("after"))?;
+    let hook_timeout =
+        parse_positive_duration(parse_duration(value.get("hook_timeout"))?, "hook timeout")?;
     let services = parse_services(
         value,
-        ServiceDefaults {
+        &ServiceDefaults {
             log_retention,
             restart_policy: &restart_policy,
+            port_conflict,
             healthcheck: &healthcheck_defaults,
+            env_file: &env_file,
+            environment: &environment,
+            port_offset: worktree.as_ref().map(|worktree| worktree.port_offset),
         },
         file_id,
         strict,
         diagnostics,
     )?;
+    for service in super::replicas::expand(&services, &BTreeMap::new()).values() {
+        super::interpolation::check_service(service, config_dir)?;
+    }
+    let tasks = parse_tasks(value, file_id, strict, diagnostics)?;
     Ok(Config {
         name,
+        worktree,
         ui_config,
         control,
         log_retention,
         restart_policy,
+        port_conflict,
+        shutdown_order,
         healthcheck_defaults,
-        services,
+        env_file,
+        environment,
+        before,
+        after,
+        hook_timeout,
+        services,
+        tasks,
     })
 }
 
@@ -1285,57 +2455,627 @@ mod tests {
             .copied()
             .collect::<std::collections::BTreeSet<_>>();
 
-        assert_eq!(schema_keys, parser_keys);
+        assert_eq!(schema_keys, parser_keys);
+        Ok(())
+    }
+
+    #[test]
+    fn recurring_healthcheck_durations_must_be_positive() {
+        for (field, yaml) in [
+            (
+                "healthcheck interval",
+                "version: 1\nservices:\n  app:\n    command: \"true\"\n    healthcheck:\n      test: \"true\"\n      interval: 0s\n",
+            ),
+            (
+                "healthcheck timeout",
+                "version: 1\nhealthcheck:\n  timeout: 0s\nservices:\n  app:\n    command: \"true\"\n    healthcheck:\n      test: \"true\"\n",
+            ),
+        ] {
+            let mut diagnostics: Vec<Diagnostic<usize>> = Vec::new();
+            let error = config::from_str(yaml, Path::new("."), 0, None, &mut diagnostics)
+                .expect_err("zero duration should be rejected");
+            assert!(
+                matches!(error, config::ConfigError::InvalidValue { message, .. } if message.contains(field))
+            );
+        }
+    }
+
+    #[test]
+    fn stop_grace_period_must_be_positive() {
+        let yaml =
+            "version: 1\nservices:\n  app:\n    command: \"true\"\n    stop_grace_period: 0s\n";
+        let mut diagnostics: Vec<Diagnostic<usize>> = Vec::new();
+        let error = config::from_str(yaml, Path::new("."), 0, None, &mut diagnostics)
+            .expect_err("zero stop grace should be rejected");
+
+        assert!(
+            matches!(error, config::ConfigError::InvalidValue { message, .. } if message.contains("stop grace period"))
+        );
+    }
+
+    #[test]
+    fn stop_signal_accepts_signal_names_and_rejects_unknown_ones() -> eyre::Result<()> {
+        let yaml = "version: 1\nservices:\n  app:\n    command: \"true\"\n    stop_signal: int\n";
+        let mut diagnostics: Vec<Diagnostic<usize>> = Vec::new();
+        let parsed = config::from_str(yaml, Path::new("."), 0, None, &mut diagnostics)?;
+        assert_eq!(
+            get_service(&parsed.config, "app")?
+                .stop_signal
+                .as_deref()
+                .copied(),
+            Some(crate::ServiceSignal::Int)
+        );
+
+        let yaml =
+            "version: 1\nservices:\n  app:\n    command: \"true\"\n    stop_signal: SIGSTOP\n";
+        let error = config::from_str(yaml, Path::new("."), 0, None, &mut diagnostics)
+            .expect_err("unknown stop signal should be rejected");
+        assert!(
+            matches!(error, config::ConfigError::InvalidValue { message, .. } if message.contains("unknown signal `SIGSTOP`"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn stop_grace_period_must_not_exceed_five_minutes() {
+        let yaml =
+            "version: 1\nservices:\n  app:\n    command: \"true\"\n    stop_grace_period: 301s\n";
+        let mut diagnostics: Vec<Diagnostic<usize>> = Vec::new();
+        let error = config::from_str(yaml, Path::new("."), 0, None, &mut diagnostics)
+            .expect_err("excessive stop grace should be rejected");
+
+        assert!(
+            matches!(error, config::ConfigError::InvalidValue { message, .. } if message.contains("must not exceed 5m"))
+        );
+    }
+
+    #[test]
+    fn restart_backoff_fills_omitted_fields_from_defaults() -> eyre::Result<()> {
+        let yaml = indoc! {r#"
+            version: 1
+            services:
+              app:
+                command: "true"
+                restart_backoff:
+                  initial: 1s
+                  multiplier: 3
+                  jitter: 0.25
+                  crash_loop:
+                    crashes: 5
+                    window: 2m
+        "#};
+
+        let mut diagnostics: Vec<Diagnostic<usize>> = vec![];
+        let parsed = config::from_str(yaml, Path::new("."), 0, None, &mut diagnostics)?;
+        assert_eq!(
+            get_service(&parsed.config, "app")?.restart_backoff,
+            Some(crate::RestartBackoffSpec {
+                initial: std::time::Duration::from_secs(1),
+                multiplier: 3.0,
+                jitter: 0.25,
+                crash_loop: Some(crate::CrashLoopSpec {
+                    crashes: 5,
+                    window: std::time::Duration::from_mins(2),
+                }),
+                ..crate::RestartBackoffSpec::default()
+            })
+        );
+        assert!(diagnostics.is_empty());
+
+        let yaml = indoc! {r#"
+            version: 1
+            services:
+              app:
+                command: "true"
+                restart_backoff:
+                  initial: 30s
+        "#};
+        let error = config::from_str(yaml, Path::new("."), 0, None, &mut diagnostics)
+            .expect_err("an initial delay above the default max should be rejected");
+        assert!(
+            matches!(error, config::ConfigError::InvalidValue { message, .. } if message.contains("max must not be shorter than initial"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn profiles_accept_a_name_or_a_list() -> eyre::Result<()> {
+        let yaml = indoc! {r#"
+            version: 1
+            services:
+              api:
+                command: "true"
+              worker:
+                command: "true"
+                profiles: jobs
+              debug:
+                command: "true"
+                profiles: [debug, jobs]
+        "#};
+
+        let mut diagnostics: Vec<Diagnostic<usize>> = vec![];
+        let parsed = config::from_str(yaml, Path::new("."), 0, None, &mut diagnostics)?;
+        let profiles = |name: &str| -> eyre::Result<Vec<String>> {
+            Ok(get_service(&parsed.config, name)?
+                .profiles
+                .iter()
+                .map(|profile| profile.as_ref().clone())
+                .collect())
+        };
+        assert_eq!(profiles("api")?, Vec::<String>::new());
+        assert_eq!(profiles("worker")?, vec!["jobs"]);
+        assert_eq!(profiles("debug")?, vec!["debug", "jobs"]);
+        assert!(diagnostics.is_empty());
+
+        let active = vec!["debug".to_string()];
+        assert!(get_service(&parsed.config, "api")?.in_profiles(&active));
+        assert!(!get_service(&parsed.config, "worker")?.in_profiles(&active));
+        assert!(get_service(&parsed.config, "debug")?.in_profiles(&active));
+
+        let yaml = indoc! {r#"
+            version: 1
+            services:
+              api:
+                command: "true"
+                profiles: ["back end"]
+        "#};
+        let error = config::from_str(yaml, Path::new("."), 0, None, &mut diagnostics)
+            .expect_err("a profile name with whitespace should be rejected");
+        assert!(
+            matches!(error, config::ConfigError::InvalidValue { message, .. } if message.contains("invalid profile `back end`"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn ports_accept_named_and_auto_entries() -> eyre::Result<()> {
+        let yaml = indoc! {r#"
+            version: 1
+            services:
+              api:
+                command: "true"
+                ports:
+                  - 8080
+                  - name: http
+                    port: auto
+                  - { name: admin, port: 9000 }
+        "#};
+
+        let mut diagnostics: Vec<Diagnostic<usize>> = vec![];
+        let parsed = config::from_str(yaml, Path::new("."), 0, None, &mut diagnostics)?;
+        let ports = get_service(&parsed.config, "api")?
+            .ports
+            .iter()
+            .map(|port| {
+                (
+                    port.name.as_ref().map(|name| name.as_ref().clone()),
+                    port.port.as_ref().clone(),
+                    port.is_auto(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ports,
+            vec![
+                (None, "8080".to_string(), false),
+                (Some("http".to_string()), "auto".to_string(), true),
+                (Some("admin".to_string()), "9000".to_string(), false),
+            ]
+        );
+        assert!(diagnostics.is_empty());
+
+        for (ports, expected) in [
+            ("[auto]", "`auto` ports need a name"),
+            ("[{ name: 1http, port: auto }]", "invalid port name `1http`"),
+            (
+                "[{ name: http, port: auto }, { name: http, port: 80 }]",
+                "duplicate port name `http`",
+            ),
+        ] {
+            let yaml = format!(
+                "version: 1\nservices:\n  api:\n    command: \"true\"\n    ports: {ports}\n"
+            );
+            let error = config::from_str(&yaml, Path::new("."), 0, None, &mut diagnostics)
+                .expect_err("invalid ports entries should be rejected");
+            assert!(
+                matches!(&error, config::ConfigError::InvalidValue { message, .. } if message.contains(expected)),
+                "{ports}: {error:?}"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn replicas_expand_into_numbered_instances() -> eyre::Result<()> {
+        let yaml = indoc! {r#"
+            version: 1
+            services:
+              worker:
+                command: ["./worker"]
+                replicas: 3
+                ports: ["90${replica}"]
+              api:
+                command: ["./api"]
+                depends_on:
+                  - name: worker
+                    condition: service_healthy
+        "#};
+
+        let mut diagnostics: Vec<Diagnostic<usize>> = vec![];
+        let parsed = config::from_str(yaml, Path::new("."), 0, None, &mut diagnostics)?;
+        assert!(diagnostics.is_empty());
+        assert_eq!(
+            get_service(&parsed.config, "worker")?
+                .replicas
+                .as_ref()
+                .map(|replicas| replicas.inner),
+            Some(3)
+        );
+
+        let mut overrides = std::collections::BTreeMap::new();
+        overrides.insert("worker".to_string(), 2);
+        let expanded = config::replicas::expand(&parsed.config.services, &overrides);
+        let ids = expanded
+            .keys()
+            .map(|id| id.as_ref().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["worker-1", "worker-2", "api"]);
+
+        let second = expanded
+            .iter()
+            .find(|(id, _)| id.as_ref() == "worker-2")
+            .map(|(_, service)| service)
+            .ok_or_else(|| eyre::eyre!("missing worker-2"))?;
+        assert_eq!(
+            second.replica,
+            Some(config::Replica {
+                group: "worker".to_string(),
+                number: 2,
+            })
+        );
+        assert_eq!(
+            second.ports.first().map(|port| port.port.as_str()),
+            Some("902")
+        );
+
+        let api = expanded
+            .iter()
+            .find(|(id, _)| id.as_ref() == "api")
+            .map(|(_, service)| service)
+            .ok_or_else(|| eyre::eyre!("missing api"))?;
+        let dependencies = api
+            .depends_on
+            .iter()
+            .map(|dependency| dependency.name.as_ref().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(dependencies, vec!["worker-1", "worker-2"]);
+        assert!(api.depends_on.iter().all(|dependency| {
+            dependency.condition.as_deref() == Some(&config::DependencyCondition::Healthy)
+        }));
+        Ok(())
+    }
+
+    #[test]
+    fn replica_ids_must_not_collide_with_services() -> eyre::Result<()> {
+        let yaml = indoc! {r#"
+            version: 1
+            services:
+              worker:
+                command: ["./worker"]
+                replicas: 2
+              worker-2:
+                command: ["./other"]
+        "#};
+
+        let mut diagnostics: Vec<Diagnostic<usize>> = vec![];
+        config::from_str(yaml, Path::new("."), 0, None, &mut diagnostics)?;
+        assert!(diagnostics.iter().any(|diagnostic| {
+            diagnostic.severity == codespan_reporting::diagnostic::Severity::Error
+                && diagnostic
+                    .message
+                    .contains("replica id `worker-2` is already a service name")
+        }));
+
+        let yaml = indoc! {r#"
+            version: 1
+            services:
+              worker:
+                command: ["./worker"]
+                replicas: 0
+        "#};
+        let error = config::from_str(yaml, Path::new("."), 0, None, &mut diagnostics)
+            .expect_err("zero replicas should be rejected");
+        assert!(
+            matches!(error, config::ConfigError::InvalidValue { message, .. } if message.contains("replicas must be between 1 and 64"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn interpolation_failures_are_reported_at_the_value() -> eyre::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let variable = format!("MICROMUX_UNSET_REQUIRED_{}", std::process::id());
+        std::fs::write(
+            dir.path().join(".env"),
+            "DATABASE_URL=postgres://localhost/app\n",
+        )?;
+
+        let yaml = format!(
+            "version: 1\nservices:\n  api:\n    command: [\"serve\", \"$1\", \"${{HOST:-localhost}}\"]\n    \
+             env_file: .env\n    environment:\n      URL: \"${{DATABASE_URL:?}}\"\n      \
+             LEVEL: \"${{{variable}:-info}}\"\n"
+        );
+        let mut diagnostics: Vec<Diagnostic<usize>> = vec![];
+        config::from_str(&yaml, dir.path(), 0, None, &mut diagnostics)?;
+
+        let yaml = format!(
+            "version: 1\nservices:\n  api:\n    command: \"true\"\n    environment:\n      \
+             TOKEN: \"${{{variable}:?create a token first}}\"\n"
+        );
+        let error = config::from_str(&yaml, dir.path(), 0, None, &mut diagnostics)
+            .expect_err("an unset required variable should be rejected");
+        let config::ConfigError::InvalidValue { message, span } = error else {
+            return Err(eyre::eyre!("unexpected error {error:?}"));
+        };
+        assert_eq!(
+            message,
+            format!("required variable `{variable}` is missing a value: create a token first")
+        );
+        assert!(
+            yaml.get(span)
+                .is_some_and(|value| value.contains(&variable))
+        );
+
+        let yaml = "version: 1\nservices:\n  api:\n    command: [\"echo\", \"${HOME\"]\n";
+        assert!(config::from_str(yaml, dir.path(), 0, None, &mut diagnostics).is_ok());
+        let yaml =
+            "version: 1\nservices:\n  api:\n    command: \"true\"\n    working_dir: \"${HOME\"\n";
+        let error = config::from_str(yaml, dir.path(), 0, None, &mut diagnostics)
+            .expect_err("a malformed path interpolation should be rejected");
+        assert!(
+            matches!(error, config::ConfigError::InvalidValue { message, .. } if message.contains("invalid interpolation `${HOME`"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn watch_accepts_boolean_list_and_mapping_forms() -> eyre::Result<()> {
+        let yaml = indoc! {r#"
+            version: 1
+            services:
+              off:
+                command: "true"
+                watch: false
+              cwd:
+                command: "true"
+                watch: true
+              list:
+                command: "true"
+                watch: ["src/**/*.rs", "Cargo.toml"]
+              full:
+                command: "true"
+                watch:
+                  ignore: "**/*.tmp"
+                  debounce: 2s
+        "#};
+
+        let mut diagnostics: Vec<Diagnostic<usize>> = vec![];
+        let parsed = config::from_str(yaml, Path::new("."), 0, None, &mut diagnostics)?;
+        let paths = |name: &str| -> eyre::Result<Option<Vec<String>>> {
+            Ok(get_service(&parsed.config, name)?
+                .watch
+                .as_ref()
+                .map(|watch| {
+                    watch
+                        .paths
+                        .iter()
+                        .map(|path| path.as_ref().clone())
+                        .collect()
+                }))
+        };
+
+        assert_eq!(paths("off")?, None);
+        assert_eq!(paths("cwd")?, Some(vec![".".to_string()]));
+        assert_eq!(
+            paths("list")?,
+            Some(vec!["src/**/*.rs".to_string(), "Cargo.toml".to_string()])
+        );
+        assert_eq!(paths("full")?, Some(vec![".".to_string()]));
+        let full = get_service(&parsed.config, "full")?
+            .watch
+            .as_ref()
+            .ok_or_else(|| eyre::eyre!("missing watch block"))?;
+        assert_eq!(
+            full.ignore
+                .iter()
+                .map(|pattern| pattern.as_ref().as_str())
+                .collect::<Vec<_>>(),
+            vec!["**/*.tmp"]
+        );
+        assert_eq!(
+            full.debounce.as_ref().map(|debounce| *debounce.as_ref()),
+            Some(std::time::Duration::from_secs(2))
+        );
+        assert!(diagnostics.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn watch_rejects_invalid_glob_patterns() {
+        let yaml = "version: 1\nservices:\n  app:\n    command: \"true\"\n    watch: [\"src/[\"]\n";
+        let mut diagnostics: Vec<Diagnostic<usize>> = Vec::new();
+        let error = config::from_str(yaml, Path::new("."), 0, None, &mut diagnostics)
+            .expect_err("an unclosed character class should be rejected");
+
+        assert!(
+            matches!(error, config::ConfigError::InvalidValue { message, .. } if message.contains("invalid watch pattern `src/[`"))
+        );
+    }
+
+    #[test]
+    fn healthcheck_accepts_http_and_tcp_probes() -> eyre::Result<()> {
+        let yaml = indoc! {r#"
+            version: 1
+            services:
+              api:
+                command: "true"
+                healthcheck:
+                  http:
+                    url: http://localhost:8080/health
+                    status: 2xx
+                    body: '"ok":\s*true'
+                    headers:
+                      Authorization: Bearer dev
+              web:
+                command: "true"
+                healthcheck:
+                  http: http://[::1]:3000
+              db:
+                command: "true"
+                healthcheck:
+                  tcp: db.internal:5432
+              cache:
+                command: "true"
+                healthcheck:
+                  tcp: 6379
+        "#};
+
+        let mut diagnostics: Vec<Diagnostic<usize>> = vec![];
+        let parsed = config::from_str(yaml, Path::new("."), 0, None, &mut diagnostics)?;
+        let spec = |name: &str| -> eyre::Result<crate::HealthcheckSpec> {
+            get_service(&parsed.config, name)?
+                .healthcheck
+                .clone()
+                .map(crate::HealthcheckSpec::from)
+                .ok_or_else(|| eyre::eyre!("missing healthcheck for {name}"))
+        };
+
+        assert_eq!(
+            spec("api")?.http,
+            Some(crate::HttpProbeSpec {
+                url: "http://localhost:8080/health".to_string(),
+                status: crate::StatusRange { min: 200, max: 299 },
+                body: Some(r#""ok":\s*true"#.to_string()),
+                headers: indexmap::IndexMap::from([(
+                    "Authorization".to_string(),
+                    "Bearer dev".to_string()
+                )]),
+            })
+        );
+        let web = spec("web")?;
+        assert!(web.test.is_empty());
+        assert_eq!(
+            web.http.map(|http| http.status),
+            Some(crate::StatusRange::default())
+        );
+        assert_eq!(
+            spec("db")?.tcp,
+            Some(crate::TcpProbeSpec {
+                host: "db.internal".to_string(),
+                port: 5432,
+            })
+        );
+        assert_eq!(
+            spec("cache")?.tcp,
+            Some(crate::TcpProbeSpec {
+                host: "localhost".to_string(),
+                port: 6379,
+            })
+        );
+        assert!(diagnostics.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn healthcheck_accepts_log_pattern_readiness() -> eyre::Result<()> {
+        let yaml = indoc! {r#"
+            version: 1
+            services:
+              api:
+                command: "true"
+                healthcheck:
+                  log_pattern: 'Listening on :\d+'
+              worker:
+                command: "true"
+                healthcheck:
+                  log_pattern:
+                    pattern: ready
+                    timeout: 45s
+        "#};
+
+        let mut diagnostics: Vec<Diagnostic<usize>> = vec![];
+        let parsed = config::from_str(yaml, Path::new("."), 0, None, &mut diagnostics)?;
+        let log_pattern = |name: &str| -> eyre::Result<Option<crate::LogPatternSpec>> {
+            Ok(get_service(&parsed.config, name)?
+                .healthcheck
+                .clone()
+                .map(crate::HealthcheckSpec::from)
+                .ok_or_else(|| eyre::eyre!("missing healthcheck for {name}"))?
+                .log_pattern)
+        };
+
+        assert_eq!(
+            log_pattern("api")?,
+            Some(crate::LogPatternSpec {
+                pattern: r"Listening on :\d+".to_string(),
+                timeout: None,
+            })
+        );
+        assert_eq!(
+            log_pattern("worker")?,
+            Some(crate::LogPatternSpec {
+                pattern: "ready".to_string(),
+                timeout: Some(std::time::Duration::from_secs(45)),
+            })
+        );
+        assert!(diagnostics.is_empty());
         Ok(())
     }
 
     #[test]
-    fn recurring_healthcheck_durations_must_be_positive() {
-        for (field, yaml) in [
+    fn healthcheck_rejects_conflicting_and_invalid_probes() {
+        let cases = [
             (
-                "healthcheck interval",
-                "version: 1\nservices:\n  app:\n    command: \"true\"\n    healthcheck:\n      test: \"true\"\n      interval: 0s\n",
+                "test: \"true\"\n      tcp: 5432",
+                "healthcheck `test` and `tcp` are mutually exclusive",
             ),
             (
-                "healthcheck timeout",
-                "version: 1\nhealthcheck:\n  timeout: 0s\nservices:\n  app:\n    command: \"true\"\n    healthcheck:\n      test: \"true\"\n",
+                "http: https://localhost/health",
+                "unsupported URL scheme `https`",
             ),
-        ] {
+            (
+                "http:\n        url: http://localhost/\n        status: 700",
+                "invalid http probe status `700`",
+            ),
+            (
+                "http:\n        url: http://localhost/\n        body: \"(\"",
+                "invalid http probe body pattern",
+            ),
+            ("tcp: localhost", "invalid tcp probe address `localhost`"),
+            (
+                "log_pattern: ready\n      tcp: 5432",
+                "healthcheck `tcp` and `log_pattern` are mutually exclusive",
+            ),
+            ("log_pattern: \"(ready\"", "invalid log_pattern"),
+            (
+                "log_pattern:\n        pattern: ready\n        timeout: 0s",
+                "log_pattern timeout must be greater than zero",
+            ),
+        ];
+        for (probe, expected) in cases {
+            let yaml = format!(
+                "version: 1\nservices:\n  app:\n    command: \"true\"\n    healthcheck:\n      {probe}\n"
+            );
             let mut diagnostics: Vec<Diagnostic<usize>> = Vec::new();
-            let error = config::from_str(yaml, Path::new("."), 0, None, &mut diagnostics)
-                .expect_err("zero duration should be rejected");
+            let error = config::from_str(&yaml, Path::new("."), 0, None, &mut diagnostics)
+                .expect_err("invalid healthcheck probe should be rejected");
             assert!(
-                matches!(error, config::ConfigError::InvalidValue { message, .. } if message.contains(field))
+                matches!(&error, config::ConfigError::InvalidValue { message, .. } if message.contains(expected)),
+                "{probe}: {error:?}"
             );
         }
     }
 
-    #[test]
-    fn stop_grace_period_must_be_positive() {
-        let yaml =
-            "version: 1\nservices:\n  app:\n    command: \"true\"\n    stop_grace_period: 0s\n";
-        let mut diagnostics: Vec<Diagnostic<usize>> = Vec::new();
-        let error = config::from_str(yaml, Path::new("."), 0, None, &mut diagnostics)
-            .expect_err("zero stop grace should be rejected");
-
-        assert!(
-            matches!(error, config::ConfigError::InvalidValue { message, .. } if message.contains("stop grace period"))
-        );
-    }
-
-    #[test]
-    fn stop_grace_period_must_not_exceed_five_minutes() {
-        let yaml =
-            "version: 1\nservices:\n  app:\n    command: \"true\"\n    stop_grace_period: 301s\n";
-        let mut diagnostics: Vec<Diagnostic<usize>> = Vec::new();
-        let error = config::from_str(yaml, Path::new("."), 0, None, &mut diagnostics)
-            .expect_err("excessive stop grace should be rejected");
-
-        assert!(
-            matches!(error, config::ConfigError::InvalidValue { message, .. } if message.contains("must not exceed 5m"))
-        );
-    }
-
     #[test]
     fn env_file_accepts_string_mapping_and_sequence_forms() -> eyre::Result<()> {
         let yaml = indoc! {r#"
@@ -1444,25 +3184,26 @@ mod tests {
         let mut diagnostics: Vec<Diagnostic<usize>> = vec![];
         let parsed = config::from_str(yaml, Path::new("."), 0, None, &mut diagnostics)?;
         let svc = get_service(&parsed.config, "app")?;
-        let Some(hc) = svc.healthcheck.as_ref() else {
-            return Err(eyre::eyre!("missing healthcheck"));
+        let Some(config::HealthProbe::Command(test)) = svc.healthcheck.as_ref().map(|hc| &hc.probe)
+        else {
+            return Err(eyre::eyre!("missing healthcheck command"));
         };
         #[cfg(unix)]
         {
-            assert_eq!(hc.test.0.as_ref(), "sh");
-            assert_eq!(hc.test.1.first().map(|v| v.as_ref().as_str()), Some("-c"));
+            assert_eq!(test.0.as_ref(), "sh");
+            assert_eq!(test.1.first().map(|v| v.as_ref().as_str()), Some("-c"));
             assert_eq!(
-                hc.test.1.get(1).map(|v| v.as_ref().as_str()),
+                test.1.get(1).map(|v| v.as_ref().as_str()),
                 Some("echo \"a b\"")
             );
         }
         #[cfg(windows)]
         {
-            assert_eq!(hc.test.0.as_ref(), "cmd.exe");
-            assert_eq!(hc.test.1.first().map(|v| v.as_ref().as_str()), Some("/S"));
-            assert_eq!(hc.test.1.get(1).map(|v| v.as_ref().as_str()), Some("/C"));
+            assert_eq!(test.0.as_ref(), "cmd.exe");
+            assert_eq!(test.1.first().map(|v| v.as_ref().as_str()), Some("/S"));
+            assert_eq!(test.1.get(1).map(|v| v.as_ref().as_str()), Some("/C"));
             assert_eq!(
-                hc.test.1.get(2).map(|v| v.as_ref().as_str()),
+                test.1.get(2).map(|v| v.as_ref().as_str()),
                 Some("echo \"a b\"")
             );
         }
@@ -1700,6 +3441,377 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn worktree_block_offsets_ports_and_suffixes_the_session_name() -> eyre::Result<()> {
+        let yaml = indoc! {r#"
+            version: 1
+            name: my-project
+            worktree:
+              port_step: 100
+              max_port_offset: 100
+            services:
+              app:
+                command: ["serve", "--port=${MICROMUX_PORT_OFFSET}"]
+                ports:
+                  - 3000
+                  - name: admin
+                    port: 3001
+                healthcheck:
+                  tcp: 3000
+        "#};
+        let dir = tempfile::tempdir()?;
+        std::fs::write(
+            dir.path().join(".git"),
+            "gitdir: /src/my-project/.git/worktrees/feature-x\n",
+        )?;
+        let mut diagnostics: Vec<Diagnostic<usize>> = vec![];
+        let parsed = config::from_str(yaml, dir.path(), 0, None, &mut diagnostics)?;
+        assert_eq!(parsed.config.name.as_deref(), Some("my-project@feature-x"));
+        assert_eq!(
+            parsed.config.worktree,
+            Some(config::Worktree {
+                linked: Some("feature-x".to_string()),
+                port_offset: 100,
+            })
+        );
+        let app = crate::service::Service::new(
+            "app",
+            dir.path(),
+            get_service(&parsed.config, "app")?.clone(),
+        )?;
+        assert_eq!(app.spec.ports, vec![3100, 3101]);
+        assert_eq!(
+            app.spec.named_ports.get("admin"),
+            Some(&crate::PortBinding::Fixed(3101))
+        );
+        assert_eq!(app.spec.command, vec!["serve", "--port=100"]);
+        assert_eq!(
+            app.spec.environment.get("MICROMUX_PORT_OFFSET"),
+            Some(&"100".to_string())
+        );
+        assert_eq!(
+            app.spec
+                .healthcheck
+                .as_ref()
+                .and_then(|healthcheck| healthcheck.tcp.as_ref())
+                .map(|tcp| tcp.port),
+            Some(3100)
+        );
+
+        // The main checkout keeps its declared ports and name.
+        let main = tempfile::tempdir()?;
+        std::fs::create_dir(main.path().join(".git"))?;
+        let parsed = config::from_str(yaml, main.path(), 0, None, &mut diagnostics)?;
+        assert_eq!(parsed.config.name.as_deref(), Some("my-project"));
+        let app = crate::service::Service::new(
+            "app",
+            main.path(),
+            get_service(&parsed.config, "app")?.clone(),
+        )?;
+        assert_eq!(app.spec.ports, vec![3000, 3001]);
+        assert_eq!(
+            app.spec.environment.get("MICROMUX_PORT_OFFSET"),
+            Some(&"0".to_string())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn startup_timeout_parses_and_warns_without_a_healthcheck() -> eyre::Result<()> {
+        let yaml = indoc! {r#"
+            version: 1
+            services:
+              db:
+                command: ["sh", "-c", "true"]
+                startup_timeout: 30s
+                healthcheck:
+                  test: ["true"]
+              app:
+                command: ["sh", "-c", "true"]
+                startup_timeout: 5s
+        "#};
+
+        let mut diagnostics: Vec<Diagnostic<usize>> = vec![];
+        let parsed = config::from_str(yaml, Path::new("."), 0, None, &mut diagnostics)?;
+        let timeout = |name: &str| -> eyre::Result<_> {
+            Ok(get_service(&parsed.config, name)?
+                .startup_timeout
+                .as_ref()
+                .map(|timeout| timeout.inner))
+        };
+        assert_eq!(timeout("db")?, Some(std::time::Duration::from_secs(30)));
+        assert_eq!(timeout("app")?, Some(std::time::Duration::from_secs(5)));
+        assert_eq!(
+            diagnostics
+                .iter()
+                .filter(|diagnostic| diagnostic.message.contains("startup_timeout"))
+                .count(),
+            1
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn hooks_accept_a_command_or_a_list_of_commands() -> eyre::Result<()> {
+        type Hooks = [(
+            yaml_spanned::Spanned<String>,
+            Vec<yaml_spanned::Spanned<String>>,
+        )];
+        let yaml = indoc! {r#"
+            version: 1
+            before:
+              - "docker compose up -d db"
+              - ["npm", "install"]
+            after: "docker compose down"
+            services:
+              api:
+                command: "./run-api"
+                pre_start: ./migrate
+                post_stop: [["rm", "-f", "api.sock"]]
+        "#};
+
+        let mut diagnostics: Vec<Diagnostic<usize>> = vec![];
+        let parsed = config::from_str(yaml, Path::new("."), 0, None, &mut diagnostics)?;
+        let argv = |hooks: &Hooks| -> Vec<Vec<String>> {
+            hooks
+                .iter()
+                .map(|(prog, args)| {
+                    std::iter::once(prog)
+                        .chain(args)
+                        .map(|arg| arg.as_ref().clone())
+                        .collect()
+                })
+                .collect()
+        };
+        assert_eq!(
+            argv(&parsed.config.before),
+            vec![
+                vec!["docker", "compose", "up", "-d", "db"],
+                vec!["npm", "install"],
+            ]
+        );
+        assert_eq!(
+            argv(&parsed.config.after),
+            vec![vec!["docker", "compose", "down"]]
+        );
+        let api = get_service(&parsed.config, "api")?;
+        assert_eq!(argv(&api.pre_start), vec![vec!["./migrate"]]);
+        assert_eq!(argv(&api.post_stop), vec![vec!["rm", "-f", "api.sock"]]);
+        assert!(diagnostics.is_empty());
+
+        let yaml = indoc! {r#"
+            version: 1
+            services:
+              api:
+                command: "./run-api"
+                pre_start: {run: ./migrate}
+        "#};
+        let error = config::from_str(yaml, Path::new("."), 0, None, &mut diagnostics)
+            .expect_err("a mapping is not a hook list");
+        assert!(matches!(error, config::ConfigError::UnexpectedType { .. }));
+        Ok(())
+    }
+
+    #[test]
+    fn tasks_parse_command_environment_and_working_dir() -> eyre::Result<()> {
+        let yaml = indoc! {r#"
+            version: 1
+            services:
+              api:
+                command: "./run-api"
+            tasks:
+              seed-db:
+                description: Load fixtures
+                command: "./seed --reset"
+                cwd: db
+                environment:
+                  DATABASE_URL: postgres://localhost/dev
+              "bad name":
+                command: "true"
+        "#};
+
+        let mut diagnostics: Vec<Diagnostic<usize>> = vec![];
+        let parsed = config::from_str(yaml, Path::new("."), 0, None, &mut diagnostics)?;
+        let tasks = parsed
+            .config
+            .tasks
+            .iter()
+            .map(|(name, task)| (name.as_ref().clone(), task))
+            .collect::<Vec<_>>();
+        let [(name, task)] = tasks.as_slice() else {
+            eyre::bail!("expected exactly one valid task, got {}", tasks.len());
+        };
+        assert_eq!(name, "seed-db");
+        assert_eq!(
+            task.description.as_ref().map(|text| text.as_ref().as_str()),
+            Some("Load fixtures")
+        );
+        assert_eq!(task.command.0.as_ref().as_str(), "./seed");
+        assert_eq!(
+            task.working_dir.as_ref().map(|dir| dir.as_ref().as_str()),
+            Some("db")
+        );
+        assert_eq!(task.environment.len(), 1);
+        assert_eq!(diagnostics.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn task_keys_match_schema() -> eyre::Result<()> {
+        let schema: serde_json::Value =
+            serde_json::from_str(include_str!("../../../../micromux.schema.json"))?;
+        let schema_keys = schema
+            .pointer("/definitions/task/properties")
+            .and_then(serde_json::Value::as_object)
+            .ok_or_else(|| eyre::eyre!("schema is missing task properties"))?
+            .keys()
+            .map(String::as_str)
+            .collect::<std::collections::BTreeSet<_>>();
+        let parser_keys = super::KNOWN_TASK_KEYS
+            .iter()
+            .copied()
+            .collect::<std::collections::BTreeSet<_>>();
+
+        assert_eq!(schema_keys, parser_keys);
+        Ok(())
+    }
+
+    #[test]
+    fn healthcheck_on_unhealthy_accepts_restart_and_none() -> eyre::Result<()> {
+        let yaml = indoc! {r#"
+            version: 1
+            services:
+              worker:
+                command: ["sh", "-c", "true"]
+                healthcheck:
+                  test: ["true"]
+                  on_unhealthy: restart
+              api:
+                command: ["sh", "-c", "true"]
+                healthcheck:
+                  test: ["true"]
+                  on_unhealthy: none
+        "#};
+
+        let mut diagnostics: Vec<Diagnostic<usize>> = vec![];
+        let parsed = config::from_str(yaml, Path::new("."), 0, None, &mut diagnostics)?;
+        let on_unhealthy = |name: &str| -> eyre::Result<_> {
+            Ok(get_service(&parsed.config, name)?
+                .healthcheck
+                .as_ref()
+                .and_then(|healthcheck| healthcheck.on_unhealthy.as_ref())
+                .map(|on_unhealthy| on_unhealthy.inner))
+        };
+        assert_eq!(on_unhealthy("worker")?, Some(config::OnUnhealthy::Restart));
+        assert_eq!(on_unhealthy("api")?, Some(config::OnUnhealthy::Ignore));
+        assert!(diagnostics.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn max_runtime_parses_and_rejects_zero() -> eyre::Result<()> {
+        let yaml = indoc! {r#"
+            version: 1
+            services:
+              webpack:
+                command: ["sh", "-c", "true"]
+                max_runtime: 4h
+        "#};
+
+        let mut diagnostics: Vec<Diagnostic<usize>> = vec![];
+        let parsed = config::from_str(yaml, Path::new("."), 0, None, &mut diagnostics)?;
+        assert_eq!(
+            get_service(&parsed.config, "webpack")?
+                .max_runtime
+                .as_ref()
+                .map(|max_runtime| max_runtime.inner),
+            Some(std::time::Duration::from_hours(4))
+        );
+
+        let zero = "version: 1\nservices:\n  app:\n    command: \"true\"\n    max_runtime: 0s\n";
+        let mut diagnostics: Vec<Diagnostic<usize>> = Vec::new();
+        assert!(config::from_str(zero, Path::new("."), 0, None, &mut diagnostics).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn external_service_parses_without_command_and_rejects_process_keys() -> eyre::Result<()> {
+        let yaml = indoc! {r"
+            version: 1
+            services:
+              postgres:
+                external: true
+                healthcheck:
+                  tcp: 5432
+        "};
+
+        let mut diagnostics: Vec<Diagnostic<usize>> = vec![];
+        let parsed = config::from_str(yaml, Path::new("."), 0, None, &mut diagnostics)?;
+        let postgres = get_service(&parsed.config, "postgres")?;
+        assert!(postgres.external);
+        assert!(postgres.command.is_none());
+
+        for invalid in [
+            "    command: \"true\"\n    healthcheck:\n      tcp: 5432\n",
+            "    restart: always\n    healthcheck:\n      tcp: 5432\n",
+            "    environment:\n      PGHOST: localhost\n",
+            "    healthcheck:\n      log_pattern: ready\n",
+            "    healthcheck:\n      tcp: 5432\n      on_unhealthy: restart\n",
+        ] {
+            let yaml = format!("version: 1\nservices:\n  db:\n    external: true\n{invalid}");
+            let mut diagnostics: Vec<Diagnostic<usize>> = Vec::new();
+            assert!(
+                config::from_str(&yaml, Path::new("."), 0, None, &mut diagnostics).is_err(),
+                "{invalid}"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn schedule_and_every_parse_and_exclude_each_other() -> eyre::Result<()> {
+        let yaml = indoc! {r#"
+            version: 1
+            services:
+              warmer:
+                command: ["sh", "-c", "true"]
+                schedule: "*/5 * * * *"
+                restart: always
+              webhook:
+                command: ["sh", "-c", "true"]
+                every: 90s
+        "#};
+
+        let mut diagnostics: Vec<Diagnostic<usize>> = vec![];
+        let parsed = config::from_str(yaml, Path::new("."), 0, None, &mut diagnostics)?;
+        let schedule = |name: &str| -> eyre::Result<_> {
+            Ok(get_service(&parsed.config, name)?
+                .schedule
+                .as_ref()
+                .map(|schedule| schedule.inner.to_string()))
+        };
+        assert_eq!(schedule("warmer")?.as_deref(), Some("*/5 * * * *"));
+        assert_eq!(schedule("webhook")?.as_deref(), Some("every 1m 30s"));
+        assert!(
+            diagnostics
+                .iter()
+                .any(|diagnostic| diagnostic.message.contains("scheduled service"))
+        );
+
+        for yaml in [
+            "version: 1\nservices:\n  app:\n    command: \"true\"\n    schedule: \"* * *\"\n",
+            "version: 1\nservices:\n  app:\n    command: \"true\"\n    every: 0s\n",
+            "version: 1\nservices:\n  app:\n    command: \"true\"\n    every: 5m\n    schedule: \"@hourly\"\n",
+        ] {
+            let mut diagnostics: Vec<Diagnostic<usize>> = Vec::new();
+            assert!(
+                config::from_str(yaml, Path::new("."), 0, None, &mut diagnostics).is_err(),
+                "{yaml}"
+            );
+        }
+        Ok(())
+    }
+
     #[test]
     fn warns_when_logs_memory_overrides_aliases() -> eyre::Result<()> {
         let yaml = indoc! {r#"
@@ -1753,27 +3865,22 @@ mod tests {
         let mut diagnostics: Vec<Diagnostic<usize>> = vec![];
         let parsed = config::from_str(yaml, Path::new("."), 0, None, &mut diagnostics)?;
         let svc = get_service(&parsed.config, "app")?;
-        let Some(hc) = svc.healthcheck.as_ref() else {
-            return Err(eyre::eyre!("missing healthcheck"));
+        let Some(config::HealthProbe::Command(test)) = svc.healthcheck.as_ref().map(|hc| &hc.probe)
+        else {
+            return Err(eyre::eyre!("missing healthcheck command"));
         };
         #[cfg(unix)]
         {
-            assert_eq!(hc.test.0.as_ref(), "sh");
-            assert_eq!(hc.test.1.first().map(|v| v.as_ref().as_str()), Some("-c"));
-            assert_eq!(
-                hc.test.1.get(1).map(|v| v.as_ref().as_str()),
-                Some("echo a b")
-            );
+            assert_eq!(test.0.as_ref(), "sh");
+            assert_eq!(test.1.first().map(|v| v.as_ref().as_str()), Some("-c"));
+            assert_eq!(test.1.get(1).map(|v| v.as_ref().as_str()), Some("echo a b"));
         }
         #[cfg(windows)]
         {
-            assert_eq!(hc.test.0.as_ref(), "cmd.exe");
-            assert_eq!(hc.test.1.first().map(|v| v.as_ref().as_str()), Some("/S"));
-            assert_eq!(hc.test.1.get(1).map(|v| v.as_ref().as_str()), Some("/C"));
-            assert_eq!(
-                hc.test.1.get(2).map(|v| v.as_ref().as_str()),
-                Some("echo a b")
-            );
+            assert_eq!(test.0.as_ref(), "cmd.exe");
+            assert_eq!(test.1.first().map(|v| v.as_ref().as_str()), Some("/S"));
+            assert_eq!(test.1.get(1).map(|v| v.as_ref().as_str()), Some("/C"));
+            assert_eq!(test.1.get(2).map(|v| v.as_ref().as_str()), Some("echo a b"));
         }
         Ok(())
     }
@@ -0,0 +1,137 @@
+//! Git-worktree awareness: running several checkouts of one project side by side.
+//!
+//! With a `worktree:` block, micromux looks at `.git` in the config directory. A linked worktree
+//! has a `.git` file pointing at `<repo>/.git/worktrees/<name>`; the main checkout has a `.git`
+//! directory. Each linked worktree gets a port offset derived from its name, so it keeps the same
+//! ports across sessions while another checkout uses the declared ones. The main checkout keeps
+//! offset zero.
+
+use std::path::Path;
+
+/// Environment variable holding the session's port offset.
+pub const PORT_OFFSET_ENV: &str = "MICROMUX_PORT_OFFSET";
+
+/// Spacing of per-worktree offsets when `port_step` is unset.
+pub const DEFAULT_PORT_STEP: u16 = 10;
+
+/// Largest per-worktree offset when `max_port_offset` is unset.
+pub const DEFAULT_MAX_PORT_OFFSET: u16 = 1000;
+
+/// The checkout a session runs in, resolved from an enabled `worktree:` block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Worktree {
+    /// Name of the linked worktree, or `None` in the main checkout.
+    pub linked: Option<String>,
+    /// Added to every declared port and exported as [`PORT_OFFSET_ENV`].
+    pub port_offset: u16,
+}
+
+impl Worktree {
+    /// `name` with the linked worktree appended, so sessions of different checkouts differ.
+    #[must_use]
+    pub fn session_name(&self, name: String) -> String {
+        match &self.linked {
+            Some(linked) => format!("{name}@{linked}"),
+            None => name,
+        }
+    }
+}
+
+/// Resolve the checkout in `dir`, spacing linked worktree offsets `step` apart up to `max`.
+///
+/// # Errors
+///
+/// Returns an I/O error when `.git` in `dir` cannot be read or is a file without a `gitdir:` line.
+pub fn detect(dir: &Path, step: u16, max: u16) -> std::io::Result<Worktree> {
+    let linked = linked_worktree(dir)?;
+    let port_offset = linked
+        .as_deref()
+        .map_or(0, |linked| port_offset(linked, step, max));
+    Ok(Worktree {
+        linked,
+        port_offset,
+    })
+}
+
+/// Name of the linked worktree checked out in `dir`, or `None` for a main checkout, a submodule,
+/// or a directory outside git.
+fn linked_worktree(dir: &Path) -> std::io::Result<Option<String>> {
+    let dot_git = dir.join(".git");
+    let contents = match std::fs::read_to_string(&dot_git) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound || dot_git.is_dir() => {
+            return Ok(None);
+        }
+        Err(err) => return Err(err),
+    };
+    let Some(git_dir) = contents.trim().strip_prefix("gitdir:") else {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "expected a `gitdir:` line",
+        ));
+    };
+    let git_dir = Path::new(git_dir.trim());
+    // Submodules also use a `.git` file, pointing into `.git/modules/` instead.
+    let in_worktrees = git_dir
+        .parent()
+        .and_then(Path::file_name)
+        .is_some_and(|parent| parent == "worktrees");
+    if !in_worktrees {
+        return Ok(None);
+    }
+    Ok(git_dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned()))
+}
+
+/// A multiple of `step` in `step..=max`, chosen by a hash of the worktree name so it is the same
+/// every time the worktree is started.
+fn port_offset(linked: &str, step: u16, max: u16) -> u16 {
+    // FNV-1a: `std`'s hashers are not guaranteed stable across releases.
+    let hash = linked
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+    let slots = u64::from(max / step);
+    let slot = u16::try_from(hash % slots).unwrap_or(0);
+    (slot + 1) * step
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use color_eyre::eyre;
+    use similar_asserts::assert_eq;
+
+    #[test]
+    fn linked_worktrees_get_a_stable_offset_and_main_checkouts_none() -> eyre::Result<()> {
+        let root = tempfile::tempdir()?;
+        let main = root.path().join("main");
+        std::fs::create_dir_all(main.join(".git"))?;
+        assert_eq!(linked_worktree(&main)?, None);
+
+        let linked = root.path().join("feature");
+        std::fs::create_dir_all(&linked)?;
+        std::fs::write(
+            linked.join(".git"),
+            format!(
+                "gitdir: {}\n",
+                main.join(".git/worktrees/feature-x").display()
+            ),
+        )?;
+        assert_eq!(linked_worktree(&linked)?.as_deref(), Some("feature-x"));
+
+        let submodule = root.path().join("vendored");
+        std::fs::create_dir_all(&submodule)?;
+        std::fs::write(submodule.join(".git"), "gitdir: ../.git/modules/vendored\n")?;
+        assert_eq!(linked_worktree(&submodule)?, None);
+
+        let offset = port_offset("feature-x", 10, 1000);
+        assert_eq!(offset, port_offset("feature-x", 10, 1000));
+        assert_eq!(offset % 10, 0);
+        assert!((10..=1000).contains(&offset));
+        assert_eq!(port_offset("feature-x", 100, 100), 100);
+        Ok(())
+    }
+}
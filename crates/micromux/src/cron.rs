@@ -0,0 +1,282 @@
+//! Five-field cron expressions for scheduled services.
+//!
+//! The fields are minute, hour, day of month, month and day of week. Each is `*`, a value, a range
+//! `a-b`, a step `*/n` or `a-b/n`, or a comma-separated list of those; months and weekdays also
+//! accept three-letter names. The `@hourly`, `@daily`, `@weekly`, `@monthly` and `@yearly` macros
+//! are accepted too. Expressions are evaluated in UTC.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const MINUTES_PER_DAY: u64 = 24 * 60;
+/// How far ahead a tick is searched for. Eight years cover every 29 February, so only an
+/// expression that can never fire (such as `0 0 31 2 *`) exhausts it.
+const MAX_SEARCH_DAYS: u64 = 8 * 366;
+const MONTH_NAMES: &[&str] = &[
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAY_NAMES: &[&str] = &["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// A cron expression that does not parse.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid cron expression: {0}")]
+pub struct CronError(String);
+
+/// A parsed five-field cron expression, evaluated in UTC.
+#[derive(Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[schemars(with = "String")]
+pub struct CronSchedule {
+    source: String,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    /// Whether day of month and day of week were restricted. When both are, a day matching either
+    /// one fires, as in classic cron.
+    day_of_month_restricted: bool,
+    day_of_week_restricted: bool,
+}
+
+impl CronSchedule {
+    /// The expression as it was written.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// The first tick strictly after `unix_ms`, in Unix milliseconds, or `None` when the
+    /// expression never fires.
+    #[must_use]
+    pub fn next_after(&self, unix_ms: u64) -> Option<u64> {
+        let start = unix_ms / 60_000 + 1;
+        let first_day = start / MINUTES_PER_DAY;
+        let mut from_minute = start % MINUTES_PER_DAY;
+        for day in first_day..=first_day.saturating_add(MAX_SEARCH_DAYS) {
+            if self.matches_day(day)
+                && let Some(minute) = (from_minute..MINUTES_PER_DAY)
+                    .find(|minute| has(self.hours, minute / 60) && has(self.minutes, minute % 60))
+            {
+                return day
+                    .checked_mul(MINUTES_PER_DAY)?
+                    .checked_add(minute)?
+                    .checked_mul(60_000);
+            }
+            from_minute = 0;
+        }
+        None
+    }
+
+    fn matches_day(&self, days_since_epoch: u64) -> bool {
+        let (month, day) = month_and_day(days_since_epoch);
+        // 1970-01-01 was a Thursday.
+        let weekday = (days_since_epoch + 4) % 7;
+        if !has(self.months, month) {
+            return false;
+        }
+        let day_of_month = has(self.days_of_month, day);
+        let day_of_week = has(self.days_of_week, weekday);
+        if self.day_of_month_restricted && self.day_of_week_restricted {
+            day_of_month || day_of_week
+        } else {
+            day_of_month && day_of_week
+        }
+    }
+}
+
+impl std::str::FromStr for CronSchedule {
+    type Err = CronError;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        let expanded = match raw.trim() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            other => other,
+        };
+        let fields = expanded.split_whitespace().collect::<Vec<_>>();
+        let [minute, hour, day_of_month, month, day_of_week] = fields.as_slice() else {
+            return Err(CronError(format!(
+                "expected 5 fields (minute hour day-of-month month day-of-week), found {}",
+                fields.len()
+            )));
+        };
+        let mut weekdays = parse_field(day_of_week, 0, 7, WEEKDAY_NAMES, "day of week")?;
+        // Both 0 and 7 are Sunday.
+        if has(weekdays, 7) {
+            weekdays = (weekdays & !(1 << 7)) | 1;
+        }
+        Ok(Self {
+            source: raw.trim().to_string(),
+            minutes: parse_field(minute, 0, 59, &[], "minute")?,
+            hours: parse_field(hour, 0, 23, &[], "hour")?,
+            days_of_month: parse_field(day_of_month, 1, 31, &[], "day of month")?,
+            months: parse_field(month, 1, 12, MONTH_NAMES, "month")?,
+            days_of_week: weekdays,
+            day_of_month_restricted: !day_of_month.starts_with('*'),
+            day_of_week_restricted: !day_of_week.starts_with('*'),
+        })
+    }
+}
+
+impl Serialize for CronSchedule {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.source)
+    }
+}
+
+impl<'de> Deserialize<'de> for CronSchedule {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+fn has(set: u64, value: u64) -> bool {
+    value < 64 && set & (1 << value) != 0
+}
+
+/// Parse one field into a bit set of the values it matches.
+fn parse_field(
+    field: &str,
+    min: u64,
+    max: u64,
+    names: &[&str],
+    what: &str,
+) -> Result<u64, CronError> {
+    let mut set = 0;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step = step
+                    .parse::<u64>()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .ok_or_else(|| CronError(format!("invalid {what} step `{step}`")))?;
+                (range, Some(step))
+            }
+            None => (part, None),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (
+                parse_value(start, min, max, names, what)?,
+                parse_value(end, min, max, names, what)?,
+            )
+        } else {
+            let value = parse_value(range, min, max, names, what)?;
+            // `5/15` steps from 5 to the end of the field.
+            (value, if step.is_some() { max } else { value })
+        };
+        if start > end {
+            return Err(CronError(format!("{what} range `{range}` is reversed")));
+        }
+        let step = usize::try_from(step.unwrap_or(1)).unwrap_or(usize::MAX);
+        for value in (start..=end).step_by(step) {
+            set |= 1 << value;
+        }
+    }
+    Ok(set)
+}
+
+fn parse_value(
+    raw: &str,
+    min: u64,
+    max: u64,
+    names: &[&str],
+    what: &str,
+) -> Result<u64, CronError> {
+    let by_name = names
+        .iter()
+        .position(|name| name.eq_ignore_ascii_case(raw))
+        .and_then(|index| u64::try_from(index).ok())
+        .map(|index| index + min);
+    by_name
+        .or_else(|| raw.parse().ok())
+        .filter(|value| (min..=max).contains(value))
+        .ok_or_else(|| {
+            CronError(format!(
+                "invalid {what} `{raw}`: expected a value from {min} to {max}"
+            ))
+        })
+}
+
+/// The month (1-12) and day of month (1-31) of a day counted from 1970-01-01.
+fn month_and_day(days_since_epoch: u64) -> (u64, u64) {
+    // Civil-from-days over 400-year eras, shifted so years start in March.
+    let days = days_since_epoch + 719_468;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    };
+    (month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use color_eyre::eyre;
+    use similar_asserts::assert_eq;
+
+    /// 2026-10-16T12:03:20Z, a Friday.
+    const NOW: u64 = 1_792_152_200_000;
+    const MINUTE: u64 = 60_000;
+
+    #[test]
+    fn next_after_follows_steps_lists_and_weekdays() -> eyre::Result<()> {
+        let every_five: CronSchedule = "*/5 * * * *".parse()?;
+        assert_eq!(every_five.next_after(NOW), Some(NOW - 200_000 + 5 * MINUTE));
+
+        let listed: CronSchedule = "10,40 12 * * *".parse()?;
+        assert_eq!(listed.next_after(NOW), Some(NOW - 200_000 + 10 * MINUTE));
+
+        // Friday noon has passed, so the next Friday is a week out.
+        let fridays: CronSchedule = "0 12 * * fri".parse()?;
+        assert_eq!(
+            fridays.next_after(NOW),
+            Some(NOW - 200_000 + 7 * 24 * 60 * MINUTE)
+        );
+
+        let new_year: CronSchedule = "@yearly".parse()?;
+        // 2027-01-01T00:00:00Z.
+        assert_eq!(new_year.next_after(NOW), Some(1_798_761_600_000));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_malformed_expressions() {
+        for raw in [
+            "* * * *",
+            "60 * * * *",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "* * * foo *",
+        ] {
+            assert!(raw.parse::<CronSchedule>().is_err(), "{raw}");
+        }
+        assert_eq!(
+            "0 0 31 2 *"
+                .parse::<CronSchedule>()
+                .ok()
+                .and_then(|never| never.next_after(NOW)),
+            None
+        );
+    }
+}
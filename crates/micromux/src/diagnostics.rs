@@ -94,6 +94,25 @@ impl Printer {
 
 /// A file identifier returned by [`Printer::add_source_file`].
 pub type FileId = usize;
+
+/// A file database that config loading registers each source file with, so diagnostics can point
+/// into included and override files as well as the main config.
+pub trait SourceDatabase {
+    /// Register `source`, read from `path`, and return its file id.
+    fn add_source(&mut self, path: &Path, source: String) -> FileId;
+}
+
+impl SourceDatabase for files::SimpleFiles<String, String> {
+    fn add_source(&mut self, path: &Path, source: String) -> FileId {
+        self.add(path.to_source_name(), source)
+    }
+}
+
+impl SourceDatabase for Printer {
+    fn add_source(&mut self, path: &Path, source: String) -> FileId {
+        self.add_source_file(path, source)
+    }
+}
 /// A half-open character span.
 pub type Span = std::ops::Range<usize>;
 
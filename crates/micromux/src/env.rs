@@ -1,4 +1,6 @@
 use indexmap::IndexMap;
+use schemars::JsonSchema;
+use serde::Serialize;
 use std::collections::HashMap;
 use std::io::Read as _;
 use std::path::{Path, PathBuf};
@@ -47,6 +49,22 @@ pub enum Error {
         /// Path of the oversized file.
         path: PathBuf,
     },
+    /// A `${VAR:?message}` or `${VAR?message}` reference names a variable without a value.
+    #[error("required variable `{variable}` is missing a value{}", required_detail(.message))]
+    RequiredVariable {
+        /// Referenced variable name.
+        variable: String,
+        /// Message given after `?`, possibly empty.
+        message: String,
+    },
+    /// A `${...}` expression could not be parsed.
+    #[error("invalid interpolation `{expression}`: {reason}")]
+    InvalidInterpolation {
+        /// The offending expression as written.
+        expression: String,
+        /// What is wrong with it.
+        reason: &'static str,
+    },
     /// A configured path references environment variables that are not set.
     #[error("path `{path}` references unset environment variables: {variables:?}")]
     UnsetPathVariables {
@@ -62,18 +80,36 @@ pub struct EnvMap {
     inner: IndexMap<String, String>,
 }
 
+fn required_detail(message: &str) -> String {
+    if message.is_empty() {
+        String::new()
+    } else {
+        format!(": {message}")
+    }
+}
+
+/// How [`interpolate`] treats references it cannot resolve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unresolved {
+    /// Unset variables expand to nothing and malformed `${...}` expressions are errors.
+    Empty,
+    /// Unset variables and `${...}` forms micromux does not support are kept as written, so a
+    /// shell running the command can still expand them.
+    Keep,
+}
+
 #[cfg(test)]
-pub fn interpolate_str(input: &str, env: &HashMap<String, String>) -> String {
+pub fn interpolate_str(input: &str, env: &HashMap<String, String>) -> Result<String, Error> {
     let mut missing = Vec::new();
-    interpolate_tracking(input, env, &mut missing)
+    interpolate(input, env, Unresolved::Empty, &mut missing)
 }
 
 pub fn interpolate_str_tracking(
     input: &str,
     env: &HashMap<String, String>,
     missing: &mut Vec<String>,
-) -> String {
-    interpolate_tracking(input, env, missing)
+) -> Result<String, Error> {
+    interpolate(input, env, Unresolved::Empty, missing)
 }
 
 impl Default for EnvMap {
@@ -263,32 +299,145 @@ pub fn load_env_files_sync(paths: &[PathBuf]) -> Result<EnvMap, Error> {
 }
 
 #[cfg(test)]
-pub fn expand_env_values(env: &EnvMap, base: &HashMap<String, String>) -> EnvMap {
-    let mut missing = Vec::new();
-    expand_env_values_tracking(env, base, &mut missing)
+pub fn expand_env_values(env: &EnvMap, base: &HashMap<String, String>) -> Result<EnvMap, Error> {
+    let mut layered = LayeredEnv::new(base.clone());
+    layered.push_file(
+        EnvLayer::ServiceEnvFile,
+        Path::new(".env"),
+        env,
+        &mut Vec::new(),
+    )?;
+    let (inner, _) = layered.into_parts();
+    Ok(EnvMap { inner })
 }
 
-pub fn expand_env_values_tracking(
-    env: &EnvMap,
-    base: &HashMap<String, String>,
-    missing: &mut Vec<String>,
-) -> EnvMap {
-    let mut current: HashMap<String, String> = base.clone();
-    let mut out = EnvMap::new();
+/// A config layer that contributes to a service's environment, lowest precedence first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EnvLayer {
+    /// The `MICROMUX_PORT_OFFSET` of a session with the `worktree:` block enabled.
+    Worktree,
+    /// The `MICROMUX_REPLICA` variables of a replica-group instance.
+    Replica,
+    /// The top-level `env_file`.
+    SessionEnvFile,
+    /// The top-level `environment`.
+    SessionEnvironment,
+    /// The service's own `env_file`.
+    ServiceEnvFile,
+    /// The service's own `environment`.
+    ServiceEnvironment,
+}
 
-    for (k, v) in env.iter() {
-        let expanded = interpolate_tracking(v, &current, missing);
-        out.insert(k.clone(), expanded.clone());
-        current.insert(k.clone(), expanded);
+/// Where the effective value of one service environment variable was set. Never holds the value.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct EnvOrigin {
+    /// Layer that set the effective value.
+    pub layer: EnvLayer,
+    /// Env file that set it, for the env-file layers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// Lower layers that also set the variable and were overridden.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub overrides: Vec<EnvLayer>,
+}
+
+/// A service environment assembled layer by layer, remembering which layer set each variable.
+///
+/// Every value is interpolated against the process environment and everything added before it.
+#[derive(Debug, Clone)]
+pub struct LayeredEnv {
+    scope: HashMap<String, String>,
+    values: IndexMap<String, String>,
+    origins: IndexMap<String, EnvOrigin>,
+}
+
+impl LayeredEnv {
+    /// Start from the process environment, which is visible to interpolation but not recorded.
+    pub fn new(process: HashMap<String, String>) -> Self {
+        Self {
+            scope: process,
+            values: IndexMap::new(),
+            origins: IndexMap::new(),
+        }
     }
 
-    out
+    /// The variables later values and command arguments interpolate against.
+    pub fn scope(&self) -> &HashMap<String, String> {
+        &self.scope
+    }
+
+    /// Add the entries parsed from the env file at `path`.
+    pub fn push_file(
+        &mut self,
+        layer: EnvLayer,
+        path: &Path,
+        entries: &EnvMap,
+        missing: &mut Vec<String>,
+    ) -> Result<(), Error> {
+        for (key, value) in entries.iter() {
+            self.set(layer, Some(path), key, value, missing)?;
+        }
+        Ok(())
+    }
+
+    /// Add one inline `environment` entry.
+    pub fn push(
+        &mut self,
+        layer: EnvLayer,
+        key: &str,
+        value: &str,
+        missing: &mut Vec<String>,
+    ) -> Result<(), Error> {
+        self.set(layer, None, key, value, missing)
+    }
+
+    fn set(
+        &mut self,
+        layer: EnvLayer,
+        file: Option<&Path>,
+        key: &str,
+        value: &str,
+        missing: &mut Vec<String>,
+    ) -> Result<(), Error> {
+        let expanded = interpolate(value, &self.scope, Unresolved::Empty, missing)?;
+        let overrides = match self.origins.get(key) {
+            Some(previous) if previous.layer != layer => {
+                let mut overrides = previous.overrides.clone();
+                overrides.push(previous.layer);
+                overrides
+            }
+            Some(previous) => previous.overrides.clone(),
+            None => Vec::new(),
+        };
+        self.origins.insert(
+            key.to_string(),
+            EnvOrigin {
+                layer,
+                file: file.map(|path| path.display().to_string()),
+                overrides,
+            },
+        );
+        self.scope.insert(key.to_string(), expanded.clone());
+        self.values.insert(key.to_string(), expanded);
+        Ok(())
+    }
+
+    /// The recorded variables with their expanded values, and where each was set.
+    pub fn into_parts(self) -> (IndexMap<String, String>, IndexMap<String, EnvOrigin>) {
+        (self.values, self.origins)
+    }
 }
 
 pub fn resolve_path(config_dir: &Path, raw: &str) -> Result<PathBuf, Error> {
     let base: HashMap<String, String> = std::env::vars().collect();
     let mut missing = Vec::new();
-    let expanded = interpolate_tracking(&shellexpand::tilde(raw), &base, &mut missing);
+    let expanded = interpolate(
+        &shellexpand::tilde(raw),
+        &base,
+        Unresolved::Empty,
+        &mut missing,
+    )?;
     if !missing.is_empty() {
         missing.sort_unstable();
         missing.dedup();
@@ -305,66 +454,147 @@ pub fn resolve_path(config_dir: &Path, raw: &str) -> Result<PathBuf, Error> {
     }
 }
 
-fn interpolate_tracking(
+/// A `${services.<service>.ports.<name>}` reference to a named port of a service.
+///
+/// Auto ports only get a number when a run spawns, so references are kept verbatim through load-time
+/// interpolation and substituted by the scheduler.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortReference {
+    /// Referenced service id.
+    pub service: String,
+    /// Referenced port name.
+    pub port: String,
+}
+
+impl PortReference {
+    /// Parse the body of a `${...}` expression. Service ids may contain dots, so the port name is
+    /// whatever follows the last `.ports.`.
+    fn parse(body: &str) -> Option<Self> {
+        let (service, port) = body.strip_prefix("services.")?.rsplit_once(".ports.")?;
+        (!service.is_empty() && crate::spec::port_name_is_valid(port)).then(|| Self {
+            service: service.to_string(),
+            port: port.to_string(),
+        })
+    }
+}
+
+impl std::fmt::Display for PortReference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "${{services.{}.ports.{}}}", self.service, self.port)
+    }
+}
+
+/// Every port reference in `input`, in order of appearance.
+pub fn port_references(input: &str) -> Vec<PortReference> {
+    let mut references = Vec::new();
+    let _ = substitute_port_references(input, |reference| {
+        references.push(reference.clone());
+        Ok::<_, std::convert::Infallible>(0)
+    });
+    references
+}
+
+/// Replace every port reference in `input` with the number `resolve` returns for it.
+///
+/// # Errors
+///
+/// Returns the first error `resolve` reports.
+pub fn substitute_port_references<E>(
+    input: &str,
+    mut resolve: impl FnMut(&PortReference) -> Result<u16, E>,
+) -> Result<String, E> {
+    let mut out = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(start) = rest.find("${") {
+        let (before, expression) = rest.split_at(start);
+        out.push_str(before);
+        let reference = expression
+            .find('}')
+            .and_then(|end| Some((end, PortReference::parse(expression.get(2..end)?)?)));
+        if let Some((end, reference)) = reference {
+            out.push_str(&resolve(&reference)?.to_string());
+            rest = expression.get(end + 1..).unwrap_or_default();
+        } else {
+            out.push_str("${");
+            rest = expression.get(2..).unwrap_or_default();
+        }
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Expand `$VAR`, `${VAR}` and the Compose operators `${VAR:-default}`, `${VAR-default}`,
+/// `${VAR:?message}`, `${VAR?message}`, `${VAR:+alternative}` and `${VAR+alternative}`; `$$` is a
+/// literal `$`. Under [`Unresolved::Keep`] only `$${` and `$$NAME` collapse; any other `$$` is
+/// kept for the shell.
+///
+/// The forms with a colon treat an empty variable like an unset one. Defaults, alternatives and
+/// messages are themselves interpolated. Unset variables expanded to nothing are recorded in
+/// `missing`. [`PortReference`]s are kept as written.
+pub fn interpolate(
     input: &str,
     env: &HashMap<String, String>,
+    unresolved: Unresolved,
     missing: &mut Vec<String>,
-) -> String {
+) -> Result<String, Error> {
     let mut out = String::with_capacity(input.len());
-    let mut chars = input.chars().peekable();
+    let mut chars = input.char_indices().peekable();
 
-    while let Some(ch) = chars.next() {
+    while let Some((start, ch)) = chars.next() {
         if ch != '$' {
             out.push(ch);
             continue;
         }
 
-        let Some(next) = chars.peek().copied() else {
+        let Some(&(_, next)) = chars.peek() else {
             out.push('$');
             break;
         };
 
         if next == '$' {
             let _ = chars.next();
+            // A command keeps a bare `$$` for the shell, which expands it to its PID; only an
+            // escaped reference collapses to `$`.
+            let escapes = chars
+                .peek()
+                .is_some_and(|&(_, c)| c == '{' || is_var_start(c));
+            if unresolved == Unresolved::Keep && !escapes {
+                out.push('$');
+            }
             out.push('$');
             continue;
         }
 
         if next == '{' {
-            let _ = chars.next();
-            let mut key = String::new();
-            for c in chars.by_ref() {
-                if c == '}' {
+            let body_start = start + 2;
+            let Some(end) = closing_brace(input, body_start) else {
+                let expression = input.get(start..).unwrap_or_default();
+                if unresolved == Unresolved::Keep {
+                    out.push_str(expression);
                     break;
                 }
-                key.push(c);
-            }
-            if let Some(value) = env.get(&key) {
-                out.push_str(value);
-            } else if !key.is_empty() {
-                missing.push(key);
-            }
+                return Err(Error::InvalidInterpolation {
+                    expression: expression.to_string(),
+                    reason: "missing closing `}`",
+                });
+            };
+            let body = input.get(body_start..end).unwrap_or_default();
+            let expression = input.get(start..=end).unwrap_or_default();
+            out.push_str(&expand_braced(body, expression, env, unresolved, missing)?);
+            while chars.next_if(|&(index, _)| index <= end).is_some() {}
             continue;
         }
 
         if is_var_start(next) {
             let mut key = String::new();
-            let Some(first) = chars.next() else {
-                out.push('$');
-                continue;
-            };
-            key.push(first);
-            while let Some(c) = chars.peek().copied() {
-                if !is_var_continue(c) {
-                    break;
-                }
-                let Some(next) = chars.next() else {
-                    break;
-                };
-                key.push(next);
+            while let Some((_, c)) = chars.next_if(|&(_, c)| is_var_continue(c)) {
+                key.push(c);
             }
             if let Some(value) = env.get(&key) {
                 out.push_str(value);
+            } else if unresolved == Unresolved::Keep {
+                out.push('$');
+                out.push_str(&key);
             } else {
                 missing.push(key);
             }
@@ -374,7 +604,91 @@ fn interpolate_tracking(
         out.push('$');
     }
 
-    out
+    Ok(out)
+}
+
+/// Byte index of the `}` closing the expression whose body starts at `body_start`, skipping
+/// nested `${...}` expressions.
+fn closing_brace(input: &str, body_start: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut chars = input.get(body_start..)?.char_indices().peekable();
+    while let Some((index, ch)) = chars.next() {
+        match ch {
+            // `$$` is an escaped dollar, so only `${` opens a nested expression.
+            '$' => {
+                if let Some((_, '{')) = chars.next_if(|&(_, c)| c == '{' || c == '$') {
+                    depth += 1;
+                }
+            }
+            '}' if depth == 0 => return Some(body_start + index),
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Expand the body of one `${...}` expression.
+fn expand_braced(
+    body: &str,
+    expression: &str,
+    env: &HashMap<String, String>,
+    unresolved: Unresolved,
+    missing: &mut Vec<String>,
+) -> Result<String, Error> {
+    let invalid = |reason: &'static str| match unresolved {
+        Unresolved::Keep => Ok(expression.to_string()),
+        Unresolved::Empty => Err(Error::InvalidInterpolation {
+            expression: expression.to_string(),
+            reason,
+        }),
+    };
+
+    if PortReference::parse(body).is_some() {
+        return Ok(expression.to_string());
+    }
+
+    let name_len = body
+        .find(|c: char| !is_var_continue(c))
+        .unwrap_or(body.len());
+    let (name, operation) = body.split_at(name_len);
+    if !name.starts_with(is_var_start) {
+        return invalid("expected a variable name");
+    }
+    let value = env.get(name);
+
+    if operation.is_empty() {
+        return Ok(match value {
+            Some(value) => value.clone(),
+            None if unresolved == Unresolved::Keep => expression.to_string(),
+            None => {
+                missing.push(name.to_string());
+                String::new()
+            }
+        });
+    }
+
+    let (treat_empty_as_unset, operation) = match operation.strip_prefix(':') {
+        Some(rest) => (true, rest),
+        None => (false, operation),
+    };
+    let mut operation = operation.chars();
+    let operator = operation.next();
+    let word = operation.as_str();
+    let set = value.is_some_and(|value| !(treat_empty_as_unset && value.is_empty()));
+
+    match (operator, value) {
+        (Some('-'), Some(value)) if set => Ok(value.clone()),
+        (Some('-'), _) => interpolate(word, env, unresolved, missing),
+        (Some('?'), Some(value)) if set => Ok(value.clone()),
+        (Some('?'), _) => Err(Error::RequiredVariable {
+            variable: name.to_string(),
+            message: interpolate(word, env, unresolved, missing)?,
+        }),
+        (Some('+'), _) if set => interpolate(word, env, unresolved, missing),
+        (Some('+'), _) => Ok(String::new()),
+        _ => invalid("expected one of `:-`, `-`, `:?`, `?`, `:+` or `+` after the variable name"),
+    }
 }
 
 fn is_var_start(c: char) -> bool {
@@ -400,30 +714,135 @@ mod tests {
     }
 
     #[test]
-    fn interpolate_vars() {
+    fn interpolate_vars() -> eyre::Result<()> {
         let mut m = HashMap::new();
         m.insert("A".to_string(), "x".to_string());
         m.insert("B".to_string(), "y".to_string());
-        assert_eq!(interpolate_str("$A-$B", &m), "x-y");
-        assert_eq!(interpolate_str("${A}${B}", &m), "xy");
-        assert_eq!(interpolate_str("$$A", &m), "$A");
+        assert_eq!(interpolate_str("$A-$B", &m)?, "x-y");
+        assert_eq!(interpolate_str("${A}${B}", &m)?, "xy");
+        assert_eq!(interpolate_str("$$A", &m)?, "$A");
+        Ok(())
     }
 
     #[test]
-    fn interpolation_tracking_records_missing_variables() {
+    fn interpolation_tracking_records_missing_variables() -> eyre::Result<()> {
         let mut m = HashMap::new();
         m.insert("A".to_string(), "x".to_string());
         let mut missing = Vec::new();
 
         assert_eq!(
-            interpolate_str_tracking("$A-${MISSING}-$OTHER-$$OK", &m, &mut missing),
+            interpolate_str_tracking("$A-${MISSING}-$OTHER-$$OK", &m, &mut missing)?,
             "x---$OK"
         );
         assert_eq!(missing, vec!["MISSING", "OTHER"]);
+        Ok(())
+    }
+
+    #[test]
+    fn interpolation_supports_compose_operators() -> eyre::Result<()> {
+        let mut m = HashMap::new();
+        m.insert("SET".to_string(), "x".to_string());
+        m.insert("EMPTY".to_string(), String::new());
+
+        assert_eq!(
+            interpolate_str("${SET:-d}|${EMPTY:-d}|${UNSET:-d}", &m)?,
+            "x|d|d"
+        );
+        assert_eq!(
+            interpolate_str("${SET-d}|${EMPTY-d}|${UNSET-d}", &m)?,
+            "x||d"
+        );
+        assert_eq!(
+            interpolate_str("${SET:+a}|${EMPTY:+a}|${UNSET:+a}", &m)?,
+            "a||"
+        );
+        assert_eq!(
+            interpolate_str("${SET+a}|${EMPTY+a}|${UNSET+a}", &m)?,
+            "a|a|"
+        );
+        assert_eq!(interpolate_str("${UNSET:-${SET}-$$}", &m)?, "x-$");
+        assert_eq!(interpolate_str("${EMPTY?unused}${SET:?unused}", &m)?, "x");
+        Ok(())
+    }
+
+    #[test]
+    fn port_references_survive_interpolation_and_substitute_later() -> eyre::Result<()> {
+        let mut m = HashMap::new();
+        m.insert("HOST".to_string(), "localhost".to_string());
+        let expanded = interpolate_str("http://$HOST:${services.api.v2.ports.http}/", &m)?;
+        assert_eq!(expanded, "http://localhost:${services.api.v2.ports.http}/");
+
+        assert_eq!(
+            port_references(&expanded),
+            vec![PortReference {
+                service: "api.v2".to_string(),
+                port: "http".to_string(),
+            }]
+        );
+        let substituted = substitute_port_references(&expanded, |reference| {
+            assert_eq!(reference.to_string(), "${services.api.v2.ports.http}");
+            Ok::<_, std::convert::Infallible>(41234)
+        })?;
+        assert_eq!(substituted, "http://localhost:41234/");
+        assert_eq!(
+            substitute_port_references("${services.api}", |_| Err(()))
+                .map_err(|()| eyre::eyre!("not a port reference"))?,
+            "${services.api}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn required_variables_and_malformed_expressions_are_errors() {
+        let mut m = HashMap::new();
+        m.insert("EMPTY".to_string(), String::new());
+
+        assert!(matches!(
+            interpolate_str("${UNSET:?set UNSET to the database url}", &m),
+            Err(Error::RequiredVariable { variable, message })
+                if variable == "UNSET" && message == "set UNSET to the database url"
+        ));
+        assert!(matches!(
+            interpolate_str("${EMPTY:?}", &m),
+            Err(Error::RequiredVariable { variable, message })
+                if variable == "EMPTY" && message.is_empty()
+        ));
+        assert!(matches!(
+            interpolate_str("${UNSET", &m),
+            Err(Error::InvalidInterpolation { expression, .. }) if expression == "${UNSET"
+        ));
+        assert!(matches!(
+            interpolate_str("${file%.txt}", &m),
+            Err(Error::InvalidInterpolation { expression, .. }) if expression == "${file%.txt}"
+        ));
     }
 
     #[test]
-    fn expand_env_values_is_single_pass_and_ordered() {
+    fn keep_mode_leaves_unresolved_references_for_the_shell() -> eyre::Result<()> {
+        let mut m = HashMap::new();
+        m.insert("PORT".to_string(), "8080".to_string());
+        let mut missing = Vec::new();
+
+        let out = interpolate(
+            "serve $PORT; for f in *; do echo $f ${f%.txt} ${HOST:-localhost}; done",
+            &m,
+            Unresolved::Keep,
+            &mut missing,
+        )?;
+        assert_eq!(
+            out,
+            "serve 8080; for f in *; do echo $f ${f%.txt} localhost; done"
+        );
+        assert!(missing.is_empty());
+        assert!(matches!(
+            interpolate("${TOKEN:?}", &m, Unresolved::Keep, &mut missing),
+            Err(Error::RequiredVariable { variable, .. }) if variable == "TOKEN"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn expand_env_values_is_single_pass_and_ordered() -> eyre::Result<()> {
         let mut base = HashMap::new();
         base.insert("X".to_string(), "base".to_string());
 
@@ -431,22 +850,83 @@ mod tests {
         env.insert("A", "${X}-a");
         env.insert("B", "${A}-b");
 
-        let out = expand_env_values(&env, &base);
+        let out = expand_env_values(&env, &base)?;
         assert_eq!(out.inner.get("A").map(String::as_str), Some("base-a"));
         assert_eq!(out.inner.get("B").map(String::as_str), Some("base-a-b"));
+        Ok(())
     }
 
     #[test]
-    fn expand_env_values_does_not_expand_forward_references() {
+    fn expand_env_values_does_not_expand_forward_references() -> eyre::Result<()> {
         let base = HashMap::new();
 
         let mut env = EnvMap::new();
         env.insert("B", "${A}-b");
         env.insert("A", "a");
 
-        let out = expand_env_values(&env, &base);
+        let out = expand_env_values(&env, &base)?;
         assert_eq!(out.inner.get("B").map(String::as_str), Some("-b"));
         assert_eq!(out.inner.get("A").map(String::as_str), Some("a"));
+        Ok(())
+    }
+
+    #[test]
+    fn layered_env_records_the_layer_that_set_each_key() -> eyre::Result<()> {
+        let mut base = HashMap::new();
+        base.insert("HOME".to_string(), "/home/dev".to_string());
+        let mut session_file = EnvMap::new();
+        session_file.insert("RUST_LOG", "info");
+        session_file.insert("DATA", "${HOME}/data");
+        let mut missing = Vec::new();
+
+        let mut layered = LayeredEnv::new(base);
+        layered.push_file(
+            EnvLayer::SessionEnvFile,
+            Path::new("/project/.env"),
+            &session_file,
+            &mut missing,
+        )?;
+        layered.push(
+            EnvLayer::SessionEnvironment,
+            "RUST_LOG",
+            "debug",
+            &mut missing,
+        )?;
+        layered.push(
+            EnvLayer::ServiceEnvironment,
+            "RUST_LOG",
+            "${RUST_LOG},api=trace",
+            &mut missing,
+        )?;
+        let (values, origins) = layered.into_parts();
+
+        assert_eq!(
+            values.get("RUST_LOG").map(String::as_str),
+            Some("debug,api=trace")
+        );
+        assert_eq!(
+            values.get("DATA").map(String::as_str),
+            Some("/home/dev/data")
+        );
+        assert!(!values.contains_key("HOME"));
+        assert_eq!(
+            origins.get("RUST_LOG"),
+            Some(&EnvOrigin {
+                layer: EnvLayer::ServiceEnvironment,
+                file: None,
+                overrides: vec![EnvLayer::SessionEnvFile, EnvLayer::SessionEnvironment],
+            })
+        );
+        assert_eq!(
+            origins.get("DATA"),
+            Some(&EnvOrigin {
+                layer: EnvLayer::SessionEnvFile,
+                file: Some("/project/.env".to_string()),
+                overrides: Vec::new(),
+            })
+        );
+        assert!(missing.is_empty());
+        Ok(())
     }
 
     #[test]
@@ -475,7 +955,7 @@ mod tests {
     #[test]
     fn single_quoted_values_are_literal_after_expansion() -> eyre::Result<()> {
         let env = parse_dotenv("PASS='s3cr$t!'\nLIT='${X}'\nDOLLARS='$$'\n")?;
-        let out = expand_env_values(&env, &HashMap::new());
+        let out = expand_env_values(&env, &HashMap::new())?;
         assert_eq!(out.inner.get("PASS").map(String::as_str), Some("s3cr$t!"));
         assert_eq!(out.inner.get("LIT").map(String::as_str), Some("${X}"));
         assert_eq!(out.inner.get("DOLLARS").map(String::as_str), Some("$$"));
@@ -487,7 +967,7 @@ mod tests {
         let mut base = HashMap::new();
         base.insert("X".to_string(), "world".to_string());
         let env = parse_dotenv("GREETING=\"hello ${X}\"\n")?;
-        let out = expand_env_values(&env, &base);
+        let out = expand_env_values(&env, &base)?;
         assert_eq!(
             out.inner.get("GREETING").map(String::as_str),
             Some("hello world")
@@ -1,5 +1,8 @@
 use crate::ServiceMap;
+use crate::service::StartupMode;
+use petgraph::Direction;
 use petgraph::graphmap::DiGraphMap;
+use std::collections::HashMap;
 
 /// Errors from validating service dependencies.
 #[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
@@ -20,13 +23,38 @@ pub enum Error {
     },
 }
 
+/// An enabled service depending on one that the active profiles left out.
+///
+/// Not an error: the dependency stays in the graph, disabled, so the dependent simply waits until
+/// someone enables it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExcludedDependency {
+    /// Service declaring the dependency.
+    pub service: String,
+    /// Dependency disabled by profile selection.
+    pub dependency: String,
+}
+
+impl std::fmt::Display for ExcludedDependency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "service `{}` depends on `{}`, which no active profile includes; it will wait until `{}` is enabled",
+            self.service, self.dependency, self.dependency
+        )
+    }
+}
+
 #[derive(Debug)]
-pub struct ServiceGraph;
+pub struct ServiceGraph {
+    warnings: Vec<ExcludedDependency>,
+}
 
 impl ServiceGraph {
     pub fn new(services: &ServiceMap) -> Result<ServiceGraph, Error> {
         // Build an empty directed graph keyed by service name
         let mut graph: DiGraphMap<&str, ()> = DiGraphMap::new();
+        let mut warnings = Vec::new();
 
         // Add all nodes first so dependency validation is order-independent.
         for (name, _service) in services {
@@ -47,6 +75,17 @@ impl ServiceGraph {
                     });
                 }
                 graph.add_edge(dep_name, name, ());
+
+                if service.startup_mode == StartupMode::Enabled
+                    && services
+                        .get(dep_name)
+                        .is_some_and(|dependency| dependency.excluded_by_profile)
+                {
+                    warnings.push(ExcludedDependency {
+                        service: name.to_string(),
+                        dependency: dep_name.to_string(),
+                    });
+                }
             }
         }
 
@@ -62,10 +101,57 @@ impl ServiceGraph {
             }
         })?;
 
-        Ok(Self)
+        Ok(Self { warnings })
+    }
+
+    /// Dependencies that profile selection disabled underneath enabled services.
+    pub fn warnings(&self) -> &[ExcludedDependency] {
+        &self.warnings
     }
 }
 
+/// Services grouped into tiers for stopping: every service is in a later tier than all services
+/// depending on it, directly or through others, so dependents stop before their dependencies.
+/// Tiers keep roster order. A cycle, which validation rejects, degrades to one tier.
+pub(crate) fn stop_tiers(services: &ServiceMap) -> Vec<Vec<String>> {
+    let mut graph: DiGraphMap<&str, ()> = DiGraphMap::new();
+    for name in services.keys() {
+        graph.add_node(name.as_str());
+    }
+    for (name, service) in services {
+        for dep in &service.spec.depends_on {
+            if graph.contains_node(dep.service.as_str()) {
+                graph.add_edge(dep.service.as_str(), name.as_str(), ());
+            }
+        }
+    }
+    let Ok(order) = petgraph::algo::toposort(&graph, None) else {
+        return vec![services.keys().cloned().collect()];
+    };
+
+    // Walk dependents first, so each service's tier is one past its deepest dependent's.
+    let mut tiers_by_name: HashMap<&str, usize> = HashMap::new();
+    for name in order.into_iter().rev() {
+        let tier = graph
+            .neighbors_directed(name, Direction::Outgoing)
+            .filter_map(|dependent| tiers_by_name.get(dependent))
+            .map(|tier| tier + 1)
+            .max()
+            .unwrap_or(0);
+        tiers_by_name.insert(name, tier);
+    }
+    let mut tiers = vec![Vec::new(); tiers_by_name.values().max().map_or(0, |max| max + 1)];
+    for name in services.keys() {
+        if let Some(tier) = tiers_by_name
+            .get(name.as_str())
+            .and_then(|tier| tiers.get_mut(*tier))
+        {
+            tier.push(name.clone());
+        }
+    }
+    tiers
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -87,6 +173,7 @@ mod tests {
         let dep = config::Dependency {
             name: spanned_string("b"),
             condition: None,
+            on_dependency_restart: None,
         };
 
         // Insert "a" before "b" to ensure graph creation does not depend on iteration order.
@@ -110,6 +197,7 @@ mod tests {
         let dep = config::Dependency {
             name: spanned_string("missing"),
             condition: None,
+            on_dependency_restart: None,
         };
 
         let mut services: ServiceMap = ServiceMap::new();
@@ -120,4 +208,74 @@ mod tests {
         assert!(res.is_err());
         Ok(())
     }
+
+    #[test]
+    fn graph_warns_when_enabled_service_depends_on_excluded_profile() -> eyre::Result<()> {
+        let config_dir = Path::new(".");
+        let dep = config::Dependency {
+            name: spanned_string("db"),
+            condition: None,
+            on_dependency_restart: None,
+        };
+
+        let mut services: ServiceMap = ServiceMap::new();
+        services.insert(
+            "api".to_string(),
+            Service::new("api", config_dir, service_config("api", vec![dep]))?,
+        );
+        let mut db = Service::new("db", config_dir, service_config("db", vec![]))?;
+        db.startup_mode = StartupMode::Disabled;
+        db.excluded_by_profile = true;
+        services.insert("db".to_string(), db);
+
+        let graph = ServiceGraph::new(&services)?;
+        similar_asserts::assert_eq!(
+            graph.warnings(),
+            [ExcludedDependency {
+                service: "api".to_string(),
+                dependency: "db".to_string(),
+            }]
+        );
+
+        // A dependent that is itself disabled has nothing to wait for.
+        if let Some(api) = services.get_mut("api") {
+            api.startup_mode = StartupMode::Disabled;
+        }
+        assert!(ServiceGraph::new(&services)?.warnings().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn stop_tiers_put_dependents_before_their_dependencies() -> eyre::Result<()> {
+        let config_dir = Path::new(".");
+        let dep = |name: &str| config::Dependency {
+            name: spanned_string(name),
+            condition: None,
+            on_dependency_restart: None,
+        };
+
+        let mut services: ServiceMap = ServiceMap::new();
+        for (name, depends_on) in [
+            ("db", vec![]),
+            ("cache", vec![]),
+            ("api", vec![dep("db"), dep("cache")]),
+            ("web", vec![dep("api")]),
+            ("worker", vec![dep("db")]),
+        ] {
+            services.insert(
+                name.to_string(),
+                Service::new(name, config_dir, service_config(name, depends_on))?,
+            );
+        }
+
+        similar_asserts::assert_eq!(
+            stop_tiers(&services),
+            vec![
+                vec!["web".to_string(), "worker".to_string()],
+                vec!["api".to_string()],
+                vec!["db".to_string(), "cache".to_string()],
+            ]
+        );
+        Ok(())
+    }
 }
@@ -1,12 +1,12 @@
 use crate::{
-    model::RunSink,
+    model::{HealthResult, ProbeExpectation, RunSink},
     scheduler::{OutputStream, ProcessEvent, RunId, ServiceID},
 };
 use itertools::Itertools;
 use std::process::Stdio;
 use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
 use tokio::process::Command;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, oneshot, watch};
 use tokio_util::sync::CancellationToken;
 
 #[derive(
@@ -34,8 +34,11 @@ pub enum Health {
     Unknown,
 }
 
+#[path = "health_check/net.rs"]
+mod net;
+
 /// How long to wait for stdout/stderr readers to flush after the probe process exits.
-const OUTPUT_DRAIN_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(250);
+pub(crate) const OUTPUT_DRAIN_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(250);
 
 #[cfg(all(test, unix))]
 mod tests {
@@ -81,7 +84,7 @@ mod tests {
         let pid_path = dir.0.join("pid");
 
         let hc: crate::HealthcheckSpec = crate::config::HealthCheck {
-            test: (
+            probe: crate::config::HealthProbe::Command((
                 spanned_string("sh"),
                 vec![
                     spanned_string("-c"),
@@ -90,7 +93,7 @@ mod tests {
                         pid_path.to_string_lossy()
                     )),
                 ],
-            ),
+            )),
             start_delay: None,
             interval: None,
             timeout: Some(Spanned {
@@ -101,6 +104,7 @@ mod tests {
                 span: yaml_spanned::spanned::Span::default(),
                 inner: 1,
             }),
+            on_unhealthy: None,
         }
         .into();
 
@@ -155,10 +159,10 @@ mod tests {
     #[tokio::test]
     async fn terminated_probe_emits_cancelled_finished() -> eyre::Result<()> {
         let hc: crate::HealthcheckSpec = crate::config::HealthCheck {
-            test: (
+            probe: crate::config::HealthProbe::Command((
                 spanned_string("sh"),
                 vec![spanned_string("-c"), spanned_string("sleep 5")],
-            ),
+            )),
             start_delay: None,
             interval: None,
             timeout: Some(Spanned {
@@ -169,6 +173,7 @@ mod tests {
                 span: yaml_spanned::spanned::Span::default(),
                 inner: 1,
             }),
+            on_unhealthy: None,
         }
         .into();
 
@@ -227,7 +232,7 @@ mod tests {
         let pid_path = dir.0.join("pid");
 
         let hc: crate::HealthcheckSpec = crate::config::HealthCheck {
-            test: (
+            probe: crate::config::HealthProbe::Command((
                 spanned_string("sh"),
                 vec![
                     spanned_string("-c"),
@@ -236,7 +241,7 @@ mod tests {
                         pid_path.to_string_lossy()
                     )),
                 ],
-            ),
+            )),
             start_delay: None,
             interval: None,
             timeout: Some(Spanned {
@@ -247,6 +252,7 @@ mod tests {
                 span: yaml_spanned::spanned::Span::default(),
                 inner: 1,
             }),
+            on_unhealthy: None,
         }
         .into();
 
@@ -345,10 +351,10 @@ mod tests {
 
     async fn started_attempts_before_unhealthy(retries: usize) -> eyre::Result<usize> {
         let hc: crate::HealthcheckSpec = crate::config::HealthCheck {
-            test: (
+            probe: crate::config::HealthProbe::Command((
                 spanned_string("sh"),
                 vec![spanned_string("-c"), spanned_string("exit 1")],
-            ),
+            )),
             start_delay: None,
             interval: Some(Spanned {
                 span: yaml_spanned::spanned::Span::default(),
@@ -362,6 +368,7 @@ mod tests {
                 span: yaml_spanned::spanned::Span::default(),
                 inner: retries,
             }),
+            on_unhealthy: None,
         }
         .into();
 
@@ -371,6 +378,7 @@ mod tests {
         let service_id: ServiceID = "svc".to_string();
         let run_id = RunId::new(1);
         let (reader, sink) = run_sink(&service_id, run_id.get());
+        let (_pause, paused) = watch::channel(false);
 
         let handle = tokio::spawn({
             let terminate = terminate.clone();
@@ -386,6 +394,8 @@ mod tests {
                         events_tx,
                         shutdown,
                         terminate,
+                        log_match: None,
+                        paused,
                     },
                 )
                 .await;
@@ -406,6 +416,191 @@ mod tests {
         Ok(reader.healthchecks("svc").len())
     }
 
+    #[tokio::test]
+    async fn http_probe_records_structured_result() -> eyre::Result<()> {
+        use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("http://{}/health", listener.local_addr()?);
+        tokio::spawn(async move {
+            if let Ok((mut stream, _)) = listener.accept().await {
+                let mut request = [0; 1024];
+                let _ = stream.read(&mut request).await;
+                let _ = stream
+                    .write_all(b"HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\n\r\n")
+                    .await;
+            }
+        });
+        let hc = crate::HealthcheckSpec {
+            http: Some(crate::HttpProbeSpec {
+                url: url.clone(),
+                status: crate::StatusRange::default(),
+                body: None,
+                headers: indexmap::IndexMap::new(),
+            }),
+            timeout: std::time::Duration::from_secs(5),
+            ..crate::HealthcheckSpec::default()
+        };
+
+        let (reader, sink) = run_sink("svc", 1);
+        let env = std::collections::HashMap::new();
+        let res = super::run(
+            &hc,
+            1,
+            RunParams {
+                working_dir: None,
+                environment: &env,
+                sink,
+                shutdown: CancellationToken::new(),
+                terminate: CancellationToken::new(),
+            },
+        )
+        .await;
+        assert!(matches!(
+            res,
+            Err(Error {
+                source: ErrorReason::Expectation {
+                    expectation: ProbeExpectation::Status,
+                    ..
+                },
+                ..
+            })
+        ));
+
+        // No running snapshot was written, so read the attempt from the full history.
+        let attempt = reader
+            .healthchecks("svc")
+            .pop()
+            .ok_or_else(|| eyre::eyre!("missing http attempt"))?;
+        assert_eq!(attempt.command, format!("GET {url}"));
+        let result = attempt
+            .result
+            .ok_or_else(|| eyre::eyre!("http attempt did not finish"))?;
+        assert!(!result.success);
+        assert_eq!(result.status_code, Some(503));
+        assert_eq!(result.failed_expectation, Some(ProbeExpectation::Status));
+        assert!(result.latency_ms.is_some());
+        assert_eq!(
+            attempt
+                .output
+                .iter()
+                .map(|line| line.line.as_str())
+                .collect::<Vec<_>>(),
+            vec!["expected status 200-399, got 503"]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn log_pattern_times_out_then_recovers_on_a_late_match() -> eyre::Result<()> {
+        use crate::scheduler::LogUpdateKind;
+
+        async fn next_event(
+            events_rx: &mut mpsc::Receiver<ProcessEvent>,
+        ) -> eyre::Result<ProcessEvent> {
+            tokio::time::timeout(std::time::Duration::from_secs(2), events_rx.recv())
+                .await?
+                .ok_or_else(|| eyre::eyre!("healthcheck event channel closed"))
+        }
+
+        let hc = crate::HealthcheckSpec {
+            log_pattern: Some(crate::LogPatternSpec {
+                pattern: r"listening on :\d+".to_string(),
+                timeout: Some(std::time::Duration::from_millis(50)),
+            }),
+            ..crate::HealthcheckSpec::default()
+        };
+        let (reader, mut sink) = run_sink("svc", 1);
+        let log_match = watch_log_pattern(&hc, &mut sink);
+        let (events_tx, mut events_rx) = mpsc::channel(8);
+        let (_pause, paused) = watch::channel(false);
+        let handle = tokio::spawn(run_loop(
+            hc,
+            RunLoopParams {
+                service_id: "svc".to_string(),
+                run_id: RunId::new(1),
+                sink: sink.clone(),
+                working_dir: None,
+                environment: std::collections::HashMap::new(),
+                events_tx,
+                shutdown: CancellationToken::new(),
+                terminate: CancellationToken::new(),
+                log_match,
+                paused,
+            },
+        ));
+
+        assert!(matches!(
+            next_event(&mut events_rx).await?,
+            ProcessEvent::Unhealthy { .. }
+        ));
+        sink.append_log(
+            OutputStream::Stdout,
+            LogUpdateKind::Append,
+            "booting".to_string(),
+        );
+        sink.append_log(
+            OutputStream::Stdout,
+            LogUpdateKind::Append,
+            "listening on :8080".to_string(),
+        );
+        assert!(matches!(
+            next_event(&mut events_rx).await?,
+            ProcessEvent::Healthy { .. }
+        ));
+        handle.await?;
+
+        let attempts = reader.healthchecks("svc");
+        let results = attempts
+            .iter()
+            .map(|attempt| {
+                attempt
+                    .result
+                    .as_ref()
+                    .map(|result| (result.success, result.failed_expectation))
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            results,
+            vec![
+                Some((false, Some(ProbeExpectation::Timeout))),
+                Some((true, None)),
+            ]
+        );
+        assert_eq!(
+            attempts
+                .last()
+                .and_then(|attempt| attempt.output.first())
+                .map(|line| line.line.as_str()),
+            Some("listening on :8080")
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn log_pattern_matches_colored_output_without_its_escapes() -> eyre::Result<()> {
+        use crate::scheduler::LogUpdateKind;
+
+        let hc = crate::HealthcheckSpec {
+            log_pattern: Some(crate::LogPatternSpec {
+                pattern: r"^ready on :\d+$".to_string(),
+                timeout: None,
+            }),
+            ..crate::HealthcheckSpec::default()
+        };
+        let (_reader, mut sink) = run_sink("svc", 1);
+        let log_match = watch_log_pattern(&hc, &mut sink)
+            .ok_or_else(|| eyre::eyre!("log pattern watch was not armed"))?;
+        sink.append_log(
+            OutputStream::Stdout,
+            LogUpdateKind::Append,
+            "\x1b[1;32mready\x1b[0m on :\x1b[33m8080\x1b[0m".to_string(),
+        );
+
+        assert_eq!(log_match.await?, "ready on :8080");
+        Ok(())
+    }
+
     #[tokio::test]
     async fn retries_is_number_of_failures_before_unhealthy() -> eyre::Result<()> {
         assert_eq!(started_attempts_before_unhealthy(1).await?, 1);
@@ -417,6 +612,50 @@ mod tests {
         assert_eq!(started_attempts_before_unhealthy(0).await?, 1);
         Ok(())
     }
+
+    #[tokio::test]
+    async fn paused_runs_are_not_probed_until_resumed() -> eyre::Result<()> {
+        let hc = crate::HealthcheckSpec {
+            test: vec!["sh".to_string(), "-c".to_string(), "exit 1".to_string()],
+            interval: std::time::Duration::from_millis(10),
+            retries: 1,
+            ..crate::HealthcheckSpec::default()
+        };
+        let (reader, sink) = run_sink("svc", 1);
+        let (events_tx, mut events_rx) = mpsc::channel(8);
+        let (pause, paused) = watch::channel(true);
+        let terminate = CancellationToken::new();
+        let handle = tokio::spawn(run_loop(
+            hc,
+            RunLoopParams {
+                service_id: "svc".to_string(),
+                run_id: RunId::new(1),
+                sink,
+                working_dir: None,
+                environment: std::collections::HashMap::new(),
+                events_tx,
+                shutdown: CancellationToken::new(),
+                terminate: terminate.clone(),
+                log_match: None,
+                paused,
+            },
+        ));
+
+        let idle =
+            tokio::time::timeout(std::time::Duration::from_millis(200), events_rx.recv()).await;
+        assert!(idle.is_err(), "a paused run must not report health");
+        assert_eq!(reader.healthchecks("svc").len(), 0);
+
+        pause.send_replace(false);
+        let event = tokio::time::timeout(std::time::Duration::from_secs(2), events_rx.recv())
+            .await?
+            .ok_or_else(|| eyre::eyre!("healthcheck event channel closed"))?;
+        assert!(matches!(event, ProcessEvent::Unhealthy { .. }));
+
+        terminate.cancel();
+        handle.await?;
+        Ok(())
+    }
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -427,6 +666,11 @@ pub enum ErrorReason {
     Failed { exit_code: i32 },
     #[error("failed to spawn")]
     Spawn(#[from] std::io::Error),
+    #[error("{expectation} expectation failed: {message}")]
+    Expectation {
+        expectation: ProbeExpectation,
+        message: String,
+    },
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -454,6 +698,11 @@ pub(crate) struct RunLoopParams {
     pub events_tx: mpsc::Sender<ProcessEvent>,
     pub shutdown: CancellationToken,
     pub terminate: CancellationToken,
+    /// The first matching output line, for a `log_pattern` healthcheck.
+    pub log_match: Option<oneshot::Receiver<String>>,
+    /// Whether the run is paused. Probes and readiness timeouts are suspended while it is, so a
+    /// stopped process is never reported unhealthy.
+    pub paused: watch::Receiver<bool>,
 }
 
 enum Completion {
@@ -462,6 +711,14 @@ enum Completion {
     Status(Result<std::process::ExitStatus, std::io::Error>),
 }
 
+/// How a `log_pattern` wait ended.
+enum LogWait {
+    Cancelled,
+    Matched(String),
+    Paused,
+    TimedOut,
+}
+
 /// The result of a single successful (or cancelled) probe run.
 enum Outcome {
     /// The probe completed successfully.
@@ -503,6 +760,19 @@ fn log_probe_error(
                 "health check timed out",
             );
         }
+        ErrorReason::Expectation {
+            expectation,
+            message,
+        } => {
+            tracing::warn!(
+                service_id,
+                %expectation,
+                detail = message,
+                attempt,
+                max_attempts = max_retries,
+                "health check failed",
+            );
+        }
     }
 }
 
@@ -537,6 +807,23 @@ async fn record_probe_failure(
     true
 }
 
+/// Wait until the run is not paused. Returns `false` if the run ends first.
+async fn wait_until_resumed(params: &mut RunLoopParams) -> bool {
+    tokio::select! {
+        biased;
+        () = params.shutdown.cancelled() => false,
+        () = params.terminate.cancelled() => false,
+        resumed = params.paused.wait_for(|paused| !paused) => resumed.is_ok(),
+    }
+}
+
+/// Resolve once the run is paused, or never if the pause state is gone.
+async fn next_pause(paused: &mut watch::Receiver<bool>) {
+    if paused.wait_for(|paused| *paused).await.is_err() {
+        std::future::pending::<()>().await;
+    }
+}
+
 async fn send_event(params: &RunLoopParams, event: ProcessEvent) -> bool {
     tokio::select! {
         biased;
@@ -546,7 +833,166 @@ async fn send_event(params: &RunLoopParams, event: ProcessEvent) -> bool {
     }
 }
 
-pub async fn run_loop(health_check: crate::HealthcheckSpec, params: RunLoopParams) {
+/// Arm the output watch a `log_pattern` healthcheck waits on.
+///
+/// Call this before the sink is cloned for the PTY reader so the run's first lines are seen.
+pub(crate) fn watch_log_pattern(
+    health_check: &crate::HealthcheckSpec,
+    sink: &mut RunSink,
+) -> Option<oneshot::Receiver<String>> {
+    let log_pattern = health_check.log_pattern.as_ref()?;
+    match regex::Regex::new(&log_pattern.pattern) {
+        Ok(pattern) => Some(sink.watch_output(pattern)),
+        Err(err) => {
+            // Normalization rejects invalid patterns, so this only guards hand-built specs.
+            tracing::warn!(?err, "invalid healthcheck log pattern");
+            None
+        }
+    }
+}
+
+/// Wait for the run's output to match a `log_pattern` instead of running probes.
+///
+/// The wait is recorded as one health attempt. If the optional timeout passes first, that attempt
+/// fails and the service turns unhealthy, but a new attempt keeps waiting so a late match still
+/// marks the run healthy. Readiness is latched: once matched, the run stays healthy.
+async fn run_log_pattern(log_pattern: &crate::LogPatternSpec, mut params: RunLoopParams) {
+    let command = format!("log pattern /{}/", log_pattern.pattern);
+    let mut matched = params.log_match.take();
+    let started = tokio::time::Instant::now();
+    let mut deadline = log_pattern
+        .timeout
+        .and_then(|timeout| started.checked_add(timeout));
+    let mut attempt: u64 = 1;
+    params.sink.start_health_attempt(attempt, command.clone());
+
+    loop {
+        match next_log_wait(&mut params, &mut matched, deadline).await {
+            LogWait::Cancelled => {
+                params
+                    .sink
+                    .finish_health_attempt(attempt, HealthResult::cancelled());
+                return;
+            }
+            LogWait::Matched(line) => {
+                params
+                    .sink
+                    .append_health_line(attempt, OutputStream::Stdout, line);
+                params.sink.finish_health_attempt(
+                    attempt,
+                    HealthResult::exited(true, 0).with_latency(started.elapsed()),
+                );
+                let _ = send_event(
+                    &params,
+                    ProcessEvent::Healthy {
+                        service_id: params.service_id.clone(),
+                        run_id: params.run_id,
+                    },
+                )
+                .await;
+                return;
+            }
+            LogWait::Paused => {
+                // A stopped process cannot print, so the timeout only counts running time.
+                let remaining = deadline.map(|deadline| {
+                    deadline.saturating_duration_since(tokio::time::Instant::now())
+                });
+                if !wait_until_resumed(&mut params).await {
+                    params
+                        .sink
+                        .finish_health_attempt(attempt, HealthResult::cancelled());
+                    return;
+                }
+                let now = tokio::time::Instant::now();
+                deadline = remaining.and_then(|remaining| now.checked_add(remaining));
+            }
+            LogWait::TimedOut => {
+                deadline = None;
+                let waited = started.elapsed();
+                tracing::warn!(
+                    service_id = params.service_id,
+                    pattern = log_pattern.pattern,
+                    ?waited,
+                    "no output matched the healthcheck log pattern",
+                );
+                params.sink.append_health_line(
+                    attempt,
+                    OutputStream::Stderr,
+                    format!(
+                        "no output matched within {}",
+                        humantime::format_duration(log_pattern.timeout.unwrap_or(waited))
+                    ),
+                );
+                params.sink.finish_health_attempt(
+                    attempt,
+                    HealthResult {
+                        failed_expectation: Some(ProbeExpectation::Timeout),
+                        ..HealthResult::exited(false, -1).with_latency(waited)
+                    },
+                );
+                if !send_event(
+                    &params,
+                    ProcessEvent::Unhealthy {
+                        service_id: params.service_id.clone(),
+                        run_id: params.run_id,
+                    },
+                )
+                .await
+                {
+                    return;
+                }
+                attempt = attempt.wrapping_add(1);
+                params.sink.start_health_attempt(attempt, command.clone());
+            }
+        }
+    }
+}
+
+/// Wait for whatever ends the current `log_pattern` wait first. Pausing only interrupts a wait
+/// with a deadline, which must not run down while the process is stopped.
+async fn next_log_wait(
+    params: &mut RunLoopParams,
+    matched: &mut Option<oneshot::Receiver<String>>,
+    deadline: Option<tokio::time::Instant>,
+) -> LogWait {
+    tokio::select! {
+        biased;
+        () = params.shutdown.cancelled() => LogWait::Cancelled,
+        () = params.terminate.cancelled() => LogWait::Cancelled,
+        line = next_log_match(matched) => LogWait::Matched(line),
+        () = next_pause(&mut params.paused), if deadline.is_some() => LogWait::Paused,
+        () = async {
+            match deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline).await,
+                None => std::future::pending::<()>().await,
+            }
+        } => LogWait::TimedOut,
+    }
+}
+
+/// Resolve with the matched line, or never if the watch is gone.
+async fn next_log_match(matched: &mut Option<oneshot::Receiver<String>>) -> String {
+    if let Some(rx) = matched.as_mut()
+        && let Ok(line) = rx.await
+    {
+        return line;
+    }
+    // A finished receiver must not be polled again.
+    *matched = None;
+    std::future::pending().await
+}
+
+pub async fn run_loop(health_check: crate::HealthcheckSpec, mut params: RunLoopParams) {
+    if let Some(log_pattern) = &health_check.log_pattern {
+        tracing::info!(
+            service_id = params.service_id,
+            pattern = log_pattern.pattern,
+            timeout = ?log_pattern.timeout,
+            "waiting for healthcheck log pattern"
+        );
+        run_log_pattern(log_pattern, params).await;
+        return;
+    }
     let max_retries = health_check.retries;
     let start_delay = health_check.start_delay.unwrap_or_default();
     let interval = health_check.interval;
@@ -572,6 +1018,9 @@ pub async fn run_loop(health_check: crate::HealthcheckSpec, params: RunLoopParam
     // Run one probe at a time so a slow command cannot accumulate overlapping descendants.
     // `HealthcheckSpec::retries` documents the resulting worst-case transition latency.
     loop {
+        if !wait_until_resumed(&mut params).await {
+            return;
+        }
         attempt_id = attempt_id.wrapping_add(1);
 
         let res = run(
@@ -589,6 +1038,11 @@ pub async fn run_loop(health_check: crate::HealthcheckSpec, params: RunLoopParam
             },
         )
         .await;
+        // A probe that overlapped a pause says nothing about the service; drop its verdict and
+        // probe again once the service runs.
+        if params.paused.has_changed().unwrap_or(true) {
+            continue;
+        }
         match res {
             Ok(Outcome::Cancelled) => return,
             Ok(Outcome::Healthy) => {
@@ -635,7 +1089,7 @@ fn command_string(health_check: &crate::HealthcheckSpec) -> String {
 
 fn emit_spawn_failed(sink: &RunSink, attempt: u64, source: &std::io::Error) {
     sink.append_health_line(attempt, OutputStream::Stderr, source.to_string());
-    sink.finish_health_attempt(attempt, false, -1, false);
+    sink.finish_health_attempt(attempt, HealthResult::exited(false, -1));
 }
 
 fn spawn_output_task(
@@ -661,7 +1115,7 @@ fn spawn_output_task(
     })
 }
 
-async fn read_bounded_line<R: AsyncRead + Unpin>(
+pub(crate) async fn read_bounded_line<R: AsyncRead + Unpin>(
     reader: &mut BufReader<R>,
     line: &mut Vec<u8>,
 ) -> std::io::Result<Option<String>> {
@@ -774,6 +1228,7 @@ impl Drop for OutputReaders {
 struct Running {
     sink: RunSink,
     attempt: u64,
+    started: tokio::time::Instant,
     command: String,
     output_readers: OutputReaders,
     process: tokio::process::Child,
@@ -888,13 +1343,14 @@ async fn finish_with_exit(running: &mut Running, success: bool, exit_code: i32)
         running.output_readers.abort_and_join().await;
     }
 
-    running
-        .sink
-        .finish_health_attempt(running.attempt, success, exit_code, false);
+    running.sink.finish_health_attempt(
+        running.attempt,
+        HealthResult::exited(success, exit_code).with_latency(running.started.elapsed()),
+    );
 }
 
 #[cfg(unix)]
-fn kill_process_group(pid: i32) {
+pub(crate) fn kill_process_group(pid: i32) {
     let pid = nix::unistd::Pid::from_raw(pid);
     let _ = nix::sys::signal::killpg(pid, nix::sys::signal::Signal::SIGKILL);
     let _ = nix::sys::signal::kill(pid, nix::sys::signal::Signal::SIGKILL);
@@ -909,6 +1365,9 @@ async fn run(
     attempt: u64,
     params: RunParams<'_>,
 ) -> Result<Outcome, Error> {
+    if let Some(probe) = net::Probe::of(health_check) {
+        return run_network(probe, health_check.timeout, attempt, params).await;
+    }
     let command = command_string(health_check);
 
     let Some((prog, args)) = health_check.test.split_first() else {
@@ -939,6 +1398,7 @@ async fn run(
         cmd.current_dir(dir);
     }
 
+    let started = tokio::time::Instant::now();
     let mut process = cmd.spawn().map_err(|source| {
         emit_spawn_failed(&params.sink, attempt, &source);
         Error {
@@ -996,6 +1456,7 @@ async fn run(
     let mut running = Running {
         sink: params.sink,
         attempt,
+        started,
         command,
         output_readers,
         process,
@@ -1008,14 +1469,21 @@ async fn run(
             // The service is being stopped/restarted: this is a cancellation, not a probe
             // result. Tear down the probe but do NOT report it as healthy or unhealthy.
             cleanup_after_cancel(&mut running).await;
-            running.sink.finish_health_attempt(attempt, false, -1, true);
+            running
+                .sink
+                .finish_health_attempt(attempt, HealthResult::cancelled());
             Ok(Outcome::Cancelled)
         }
         Completion::Timeout => {
+            let latency = running.started.elapsed();
             cleanup_after_cancel(&mut running).await;
-            running
-                .sink
-                .finish_health_attempt(attempt, false, -1, false);
+            running.sink.finish_health_attempt(
+                attempt,
+                HealthResult {
+                    failed_expectation: Some(ProbeExpectation::Timeout),
+                    ..HealthResult::exited(false, -1).with_latency(latency)
+                },
+            );
             let command = std::mem::take(&mut running.command);
             Err(Error {
                 command,
@@ -1045,3 +1513,66 @@ async fn run(
         }
     }
 }
+
+/// Run one in-process `http` or `tcp` probe, bounded by `timeout` like a command probe.
+async fn run_network(
+    probe: net::Probe<'_>,
+    timeout: std::time::Duration,
+    attempt: u64,
+    params: RunParams<'_>,
+) -> Result<Outcome, Error> {
+    let command = probe.describe();
+    params.sink.start_health_attempt(attempt, command.clone());
+    let started = tokio::time::Instant::now();
+    let report = tokio::select! {
+        biased;
+        () = params.shutdown.cancelled() => None,
+        () = params.terminate.cancelled() => None,
+        report = tokio::time::timeout(timeout, probe.run()) => Some(report),
+    };
+    let Some(report) = report else {
+        params
+            .sink
+            .finish_health_attempt(attempt, HealthResult::cancelled());
+        return Ok(Outcome::Cancelled);
+    };
+    let latency = started.elapsed();
+    let report = report.unwrap_or_else(|_elapsed| {
+        net::Report::failed(
+            ProbeExpectation::Timeout,
+            None,
+            format!("no answer within {}", humantime::format_duration(timeout)),
+        )
+    });
+
+    let (stream, exit_code) = if report.failed.is_some() {
+        (OutputStream::Stderr, -1)
+    } else {
+        (OutputStream::Stdout, 0)
+    };
+    params
+        .sink
+        .append_health_line(attempt, stream, report.detail.clone());
+    params.sink.finish_health_attempt(
+        attempt,
+        HealthResult {
+            status_code: report.status_code,
+            failed_expectation: report.failed,
+            ..HealthResult::exited(report.failed.is_none(), exit_code).with_latency(latency)
+        },
+    );
+    match report.failed {
+        None => Ok(Outcome::Healthy),
+        Some(ProbeExpectation::Timeout) => Err(Error {
+            command,
+            source: ErrorReason::Timeout,
+        }),
+        Some(expectation) => Err(Error {
+            command,
+            source: ErrorReason::Expectation {
+                expectation,
+                message: report.detail,
+            },
+        }),
+    }
+}
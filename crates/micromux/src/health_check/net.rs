@@ -0,0 +1,370 @@
+//! In-process `http` and `tcp` probes.
+//!
+//! These never spawn a process: a probe is one connect (and for `http`, one `GET` with
+//! `Connection: close`) whose outcome is reported as a [`Report`] instead of an exit code.
+
+use crate::model::ProbeExpectation;
+use crate::spec::{HealthcheckSpec, HttpProbeSpec, HttpTarget, TcpProbeSpec};
+use std::fmt::Write as _;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+
+/// Largest response head (status line and headers) accepted from an `http` probe.
+const MAX_HEAD_BYTES: u64 = 16 * 1024;
+/// Largest prefix of a response body matched against an `http` probe's `body` pattern.
+const MAX_BODY_BYTES: u64 = 64 * 1024;
+/// Largest chunk-size line accepted in a chunked response body.
+const MAX_CHUNK_LINE_BYTES: u64 = 1024;
+
+/// A network probe borrowed from its healthcheck definition.
+#[derive(Debug, Clone, Copy)]
+pub(super) enum Probe<'a> {
+    Http(&'a HttpProbeSpec),
+    Tcp(&'a TcpProbeSpec),
+}
+
+impl<'a> Probe<'a> {
+    /// The network probe of `health_check`, or `None` for a command probe.
+    pub(super) fn of(health_check: &'a HealthcheckSpec) -> Option<Self> {
+        health_check
+            .http
+            .as_ref()
+            .map(Self::Http)
+            .or_else(|| health_check.tcp.as_ref().map(Self::Tcp))
+    }
+
+    /// What the probe does, recorded in place of a command line.
+    pub(super) fn describe(self) -> String {
+        match self {
+            Self::Http(probe) => format!("GET {}", probe.url),
+            Self::Tcp(probe) => format!("connect {}", tcp_address(probe)),
+        }
+    }
+
+    pub(super) async fn run(self) -> Report {
+        match self {
+            Self::Http(probe) => http(probe).await,
+            Self::Tcp(probe) => tcp(probe).await,
+        }
+    }
+}
+
+/// What one network probe attempt observed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Report {
+    /// HTTP status code, when a response head was received.
+    pub status_code: Option<u16>,
+    /// The expectation that was not met, or `None` when the probe passed.
+    pub failed: Option<ProbeExpectation>,
+    /// One line summarizing the attempt, recorded as probe output.
+    pub detail: String,
+}
+
+impl Report {
+    fn passed(status_code: Option<u16>, detail: String) -> Self {
+        Self {
+            status_code,
+            failed: None,
+            detail,
+        }
+    }
+
+    pub(super) fn failed(
+        expectation: ProbeExpectation,
+        status_code: Option<u16>,
+        detail: String,
+    ) -> Self {
+        Self {
+            status_code,
+            failed: Some(expectation),
+            detail,
+        }
+    }
+}
+
+fn tcp_address(probe: &TcpProbeSpec) -> String {
+    if probe.host.contains(':') {
+        format!("[{}]:{}", probe.host, probe.port)
+    } else {
+        format!("{}:{}", probe.host, probe.port)
+    }
+}
+
+async fn tcp(probe: &TcpProbeSpec) -> Report {
+    let address = tcp_address(probe);
+    match TcpStream::connect((probe.host.as_str(), probe.port)).await {
+        Ok(_stream) => Report::passed(None, format!("connected to {address}")),
+        Err(err) => Report::failed(
+            ProbeExpectation::Connect,
+            None,
+            format!("cannot connect to {address}: {err}"),
+        ),
+    }
+}
+
+async fn http(probe: &HttpProbeSpec) -> Report {
+    // Both were validated when the spec was normalized; failing here keeps the probe total.
+    let target = match probe.target() {
+        Ok(target) => target,
+        Err(message) => return Report::failed(ProbeExpectation::Connect, None, message),
+    };
+    let pattern = match probe.body.as_deref().map(regex::Regex::new).transpose() {
+        Ok(pattern) => pattern,
+        Err(err) => return Report::failed(ProbeExpectation::Body, None, err.to_string()),
+    };
+    let stream = match TcpStream::connect((target.host.as_str(), target.port)).await {
+        Ok(stream) => stream,
+        Err(err) => {
+            return Report::failed(
+                ProbeExpectation::Connect,
+                None,
+                format!("cannot connect to {}: {err}", target.authority),
+            );
+        }
+    };
+    let (status, body) = match exchange(stream, &target, probe, pattern.is_some()).await {
+        Ok(response) => response,
+        Err(err) => {
+            return Report::failed(
+                ProbeExpectation::Response,
+                None,
+                format!("GET {} failed: {err}", probe.url),
+            );
+        }
+    };
+    if !probe.status.contains(status) {
+        return Report::failed(
+            ProbeExpectation::Status,
+            Some(status),
+            format!("expected status {}, got {status}", probe.status),
+        );
+    }
+    if let Some(pattern) = pattern
+        && !pattern.is_match(&body)
+    {
+        return Report::failed(
+            ProbeExpectation::Body,
+            Some(status),
+            format!(
+                "HTTP {status}, but the body does not match `{}`",
+                pattern.as_str()
+            ),
+        );
+    }
+    Report::passed(Some(status), format!("HTTP {status}"))
+}
+
+/// Send one `GET` and read the status code, plus the (bounded) body when `read_body` is set.
+async fn exchange(
+    mut stream: TcpStream,
+    target: &HttpTarget,
+    probe: &HttpProbeSpec,
+    read_body: bool,
+) -> std::io::Result<(u16, String)> {
+    let mut request = format!("GET {} HTTP/1.1\r\n", target.path);
+    let has = |name: &str| {
+        probe
+            .headers
+            .keys()
+            .any(|header| header.eq_ignore_ascii_case(name))
+    };
+    if !has("host") {
+        let _ = write!(request, "Host: {}\r\n", target.authority);
+    }
+    if !has("user-agent") {
+        request.push_str("User-Agent: micromux\r\n");
+    }
+    for (name, value) in &probe.headers {
+        let _ = write!(request, "{name}: {value}\r\n");
+    }
+    request.push_str("Connection: close\r\n\r\n");
+    stream.write_all(request.as_bytes()).await?;
+
+    let mut reader = BufReader::new(stream);
+    let (status, framing) = read_head(&mut reader).await?;
+    if !read_body {
+        return Ok((status, String::new()));
+    }
+    let mut body = Vec::new();
+    match framing {
+        Framing::Chunked => read_chunked(&mut reader, &mut body).await?,
+        Framing::Length(length) => {
+            (&mut reader)
+                .take(length.min(MAX_BODY_BYTES))
+                .read_to_end(&mut body)
+                .await?;
+        }
+        Framing::UntilClose => {
+            (&mut reader)
+                .take(MAX_BODY_BYTES)
+                .read_to_end(&mut body)
+                .await?;
+        }
+    }
+    Ok((status, String::from_utf8_lossy(&body).into_owned()))
+}
+
+/// How the response body is delimited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Framing {
+    Chunked,
+    Length(u64),
+    UntilClose,
+}
+
+fn invalid(message: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+async fn read_head<R: AsyncRead + Unpin>(
+    reader: &mut BufReader<R>,
+) -> std::io::Result<(u16, Framing)> {
+    let mut head = reader.take(MAX_HEAD_BYTES);
+    let mut line = Vec::new();
+    head.read_until(b'\n', &mut line).await?;
+    let status_line = String::from_utf8_lossy(&line);
+    let mut parts = status_line.split_whitespace();
+    let status = match (parts.next(), parts.next()) {
+        (Some(version), Some(code)) if version.starts_with("HTTP/") => code
+            .parse::<u16>()
+            .map_err(|_| invalid("malformed status code"))?,
+        _ => return Err(invalid("missing HTTP status line")),
+    };
+
+    let mut framing = Framing::UntilClose;
+    loop {
+        line.clear();
+        if head.read_until(b'\n', &mut line).await? == 0 {
+            return Err(invalid("response head is truncated or too large"));
+        }
+        let header = String::from_utf8_lossy(&line);
+        let header = header.trim_end_matches(['\r', '\n']);
+        if header.is_empty() {
+            return Ok((status, framing));
+        }
+        let Some((name, value)) = header.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("transfer-encoding")
+            && value.to_ascii_lowercase().contains("chunked")
+        {
+            framing = Framing::Chunked;
+        } else if name.eq_ignore_ascii_case("content-length") && framing != Framing::Chunked {
+            let length = value
+                .parse()
+                .map_err(|_| invalid("malformed content-length"))?;
+            framing = Framing::Length(length);
+        }
+    }
+}
+
+/// Decode a chunked body, keeping at most [`MAX_BODY_BYTES`] of it.
+async fn read_chunked<R: AsyncRead + Unpin>(
+    reader: &mut BufReader<R>,
+    body: &mut Vec<u8>,
+) -> std::io::Result<()> {
+    let mut remaining = MAX_BODY_BYTES;
+    let mut line = Vec::new();
+    while remaining > 0 {
+        line.clear();
+        (&mut *reader)
+            .take(MAX_CHUNK_LINE_BYTES)
+            .read_until(b'\n', &mut line)
+            .await?;
+        let size_line = String::from_utf8_lossy(&line);
+        let size = size_line.split(';').next().unwrap_or_default().trim();
+        let size = u64::from_str_radix(size, 16).map_err(|_| invalid("malformed chunk size"))?;
+        if size == 0 {
+            break;
+        }
+        let wanted = size.min(remaining);
+        (&mut *reader).take(wanted).read_to_end(body).await?;
+        remaining = remaining.saturating_sub(wanted);
+        if wanted < size {
+            // The rest of the body is never matched, so stop reading instead of draining it.
+            break;
+        }
+        line.clear();
+        (&mut *reader).take(2).read_until(b'\n', &mut line).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use color_eyre::eyre;
+    use similar_asserts::assert_eq;
+    use tokio::net::TcpListener;
+
+    /// Serve `response` to the first connection and return the probe URL for `path`.
+    async fn serve_once(response: &'static str, path: &str) -> eyre::Result<String> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let address = listener.local_addr()?;
+        tokio::spawn(async move {
+            let Ok((mut stream, _)) = listener.accept().await else {
+                return;
+            };
+            let mut request = Vec::new();
+            let mut buffer = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                match stream.read(&mut buffer).await {
+                    Ok(0) | Err(_) => return,
+                    Ok(read) => request.extend_from_slice(buffer.get(..read).unwrap_or_default()),
+                }
+            }
+            let _ = stream.write_all(response.as_bytes()).await;
+        });
+        Ok(format!("http://{address}{path}"))
+    }
+
+    fn http_probe(url: String, body: Option<&str>) -> HttpProbeSpec {
+        HttpProbeSpec {
+            url,
+            status: crate::spec::StatusRange::default(),
+            body: body.map(ToString::to_string),
+            headers: indexmap::IndexMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn http_probe_reports_status_and_body_expectations() -> eyre::Result<()> {
+        let url = serve_once(
+            "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\n\r\n",
+            "/health",
+        )
+        .await?;
+        let report = http(&http_probe(url, None)).await;
+        assert_eq!(report.status_code, Some(503));
+        assert_eq!(report.failed, Some(ProbeExpectation::Status));
+        assert_eq!(report.detail, "expected status 200-399, got 503");
+
+        let chunked = "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n\
+                       7\r\n{\"ok\": \r\n4\r\ntrue\r\n1\r\n}\r\n0\r\n\r\n";
+        let url = serve_once(chunked, "/ready?verbose=1").await?;
+        let report = http(&http_probe(url, Some(r#""ok": true"#))).await;
+        assert_eq!(report, Report::passed(Some(200), "HTTP 200".to_string()));
+
+        let url = serve_once("HTTP/1.1 200 OK\r\n\r\n{\"ok\": false}", "/").await?;
+        let report = http(&http_probe(url, Some(r#""ok": true"#))).await;
+        assert_eq!(report.status_code, Some(200));
+        assert_eq!(report.failed, Some(ProbeExpectation::Body));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn tcp_probe_distinguishes_listening_and_closed_ports() -> eyre::Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let port = listener.local_addr()?.port();
+        let probe = TcpProbeSpec {
+            host: "127.0.0.1".to_string(),
+            port,
+        };
+
+        assert_eq!(tcp(&probe).await.failed, None);
+        drop(listener);
+        assert_eq!(tcp(&probe).await.failed, Some(ProbeExpectation::Connect));
+        Ok(())
+    }
+}
@@ -0,0 +1,364 @@
+//! Session `before`/`after` hooks and per-service `pre_start`/`post_stop` hooks.
+//!
+//! Hooks are plain commands run in order; their output is recorded in the model through a
+//! [`HookSink`] rather than in the service logs. Cleanup hooks (`after` and `post_stop`) run with
+//! a per-command timeout so a hung command cannot hold the session open.
+
+use crate::health_check::{OUTPUT_DRAIN_TIMEOUT, read_bounded_line};
+use crate::model::{HealthResult, HookKind, HookSink};
+use crate::scheduler::OutputStream;
+use indexmap::IndexMap;
+use itertools::Itertools;
+use std::path::Path;
+use std::process::{ExitStatus, Stdio};
+use std::time::Duration;
+use tokio::io::{AsyncRead, BufReader};
+use tokio::process::Command;
+use tokio_util::sync::CancellationToken;
+
+/// How many trailing output lines a failed hook reports in its error.
+const FAILURE_OUTPUT_LINES: usize = 20;
+
+/// Default time each `after` or `post_stop` command may run before it is killed.
+pub(crate) const DEFAULT_HOOK_TIMEOUT: Duration = Duration::from_mins(1);
+
+/// How cleanup hooks (`after` and `post_stop`) are bounded once services stop.
+#[derive(Debug, Clone)]
+pub(crate) struct Cleanup {
+    /// Time each cleanup command may run before it is killed.
+    pub(crate) timeout: Duration,
+    /// Kills every cleanup command still running, for example on a second interrupt.
+    pub(crate) abort: CancellationToken,
+}
+
+impl Default for Cleanup {
+    fn default() -> Self {
+        Self {
+            timeout: DEFAULT_HOOK_TIMEOUT,
+            abort: CancellationToken::new(),
+        }
+    }
+}
+
+/// Session-level hooks, materialized from the config with the session environment.
+#[derive(Debug, Clone)]
+pub(crate) struct SessionHooks {
+    pub(crate) before: Vec<Vec<String>>,
+    pub(crate) after: Vec<Vec<String>>,
+    pub(crate) environment: IndexMap<String, String>,
+    /// Time each `after` command may run before it is killed.
+    pub(crate) timeout: Duration,
+}
+
+impl Default for SessionHooks {
+    fn default() -> Self {
+        Self {
+            before: Vec::new(),
+            after: Vec::new(),
+            environment: IndexMap::new(),
+            timeout: DEFAULT_HOOK_TIMEOUT,
+        }
+    }
+}
+
+/// A hook command that did not complete successfully.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    /// The hook ran and exited unsuccessfully.
+    #[error(
+        "{kind} hook `{command}` exited with code {exit_code}{}",
+        output_tail(output)
+    )]
+    Failed {
+        /// Which hook list the command belongs to.
+        kind: HookKind,
+        /// The command that failed.
+        command: String,
+        /// Its exit code, or `-1` when it was killed by a signal.
+        exit_code: i32,
+        /// Its retained output, with stderr lines marked.
+        output: Vec<String>,
+    },
+    /// The hook command could not be spawned.
+    #[error("{kind} hook `{command}` could not be started")]
+    Spawn {
+        /// Which hook list the command belongs to.
+        kind: HookKind,
+        /// The command that could not be spawned.
+        command: String,
+        /// Why spawning failed.
+        #[source]
+        source: std::io::Error,
+    },
+    /// The hook ran past its timeout and was killed.
+    #[error(
+        "{kind} hook `{command}` timed out after {}",
+        humantime::format_duration(*timeout)
+    )]
+    TimedOut {
+        /// Which hook list the command belongs to.
+        kind: HookKind,
+        /// The command that timed out.
+        command: String,
+        /// How long it was allowed to run.
+        timeout: Duration,
+    },
+    /// The hook was stopped before it finished.
+    #[error("{kind} hook `{command}` was cancelled")]
+    Cancelled {
+        /// Which hook list the command belongs to.
+        kind: HookKind,
+        /// The command that was cancelled.
+        command: String,
+    },
+}
+
+impl Error {
+    /// The failure without its captured output, for one-line lifecycle events.
+    pub(crate) fn summary(&self) -> String {
+        match self {
+            Self::Failed {
+                kind,
+                command,
+                exit_code,
+                ..
+            } => format!("{kind} hook `{command}` exited with code {exit_code}"),
+            Self::Spawn { source, .. } => format!("{self}: {source}"),
+            Self::TimedOut { .. } | Self::Cancelled { .. } => self.to_string(),
+        }
+    }
+}
+
+fn output_tail(output: &[String]) -> String {
+    use std::fmt::Write as _;
+
+    let skip = output.len().saturating_sub(FAILURE_OUTPUT_LINES);
+    output
+        .iter()
+        .skip(skip)
+        .fold(String::new(), |mut tail, line| {
+            let _ = write!(tail, "\n  {line}");
+            tail
+        })
+}
+
+/// Run `commands` in order, stopping at the first one that fails.
+///
+/// Each command is recorded in `sink` with its output. Cancelling `cancel` kills the running
+/// command and fails with [`Error::Cancelled`]; a command still running after `timeout` is killed
+/// the same way and fails with [`Error::TimedOut`].
+pub(crate) async fn run(
+    kind: HookKind,
+    commands: &[Vec<String>],
+    environment: &IndexMap<String, String>,
+    working_dir: Option<&Path>,
+    sink: &HookSink,
+    cancel: &CancellationToken,
+    timeout: Option<Duration>,
+) -> Result<(), Error> {
+    for command in commands {
+        run_one(
+            kind,
+            command,
+            environment,
+            working_dir,
+            sink,
+            cancel,
+            timeout,
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+async fn run_one(
+    kind: HookKind,
+    command: &[String],
+    environment: &IndexMap<String, String>,
+    working_dir: Option<&Path>,
+    sink: &HookSink,
+    cancel: &CancellationToken,
+    timeout: Option<Duration>,
+) -> Result<(), Error> {
+    let command_string = command.iter().join(" ");
+    let seq = sink.start(kind, command_string.clone());
+    let spawn_failed = |source: std::io::Error| {
+        sink.append_line(seq, OutputStream::Stderr, source.to_string());
+        sink.finish(seq, HealthResult::exited(false, -1));
+        Error::Spawn {
+            kind,
+            command: command_string.clone(),
+            source,
+        }
+    };
+
+    let on_line = {
+        let sink = sink.clone();
+        move |stream, line| sink.append_line(seq, stream, line)
+    };
+    // The timeout cancels a child token, so the command is killed through the same path.
+    let bounded = cancel.child_token();
+    let run = run_command(command, environment, working_dir, &bounded, on_line);
+    tokio::pin!(run);
+    let (exit, timed_out) = tokio::select! {
+        exit = &mut run => (exit, false),
+        () = async {
+            match timeout {
+                Some(timeout) => tokio::time::sleep(timeout).await,
+                None => std::future::pending::<()>().await,
+            }
+        } => {
+            bounded.cancel();
+            (run.await, true)
+        }
+    };
+    let exit = exit.map_err(spawn_failed)?;
+    let CommandExit::Exited { status, elapsed } = exit else {
+        if let Some(timeout) = timeout
+            && timed_out
+            && !cancel.is_cancelled()
+        {
+            let message = format!("timed out after {}", humantime::format_duration(timeout));
+            sink.append_line(seq, OutputStream::Stderr, message);
+            sink.finish(seq, HealthResult::exited(false, -1).with_latency(timeout));
+            return Err(Error::TimedOut {
+                kind,
+                command: command_string,
+                timeout,
+            });
+        }
+        sink.finish(seq, HealthResult::cancelled());
+        return Err(Error::Cancelled {
+            kind,
+            command: command_string,
+        });
+    };
+    let exit_code = status.code().unwrap_or(-1);
+    sink.finish(
+        seq,
+        HealthResult::exited(status.success(), exit_code).with_latency(elapsed),
+    );
+    if status.success() {
+        return Ok(());
+    }
+    Err(Error::Failed {
+        kind,
+        command: command_string,
+        exit_code,
+        output: sink.output(seq),
+    })
+}
+
+/// How a command run by [`run_command`] ended.
+pub(crate) enum CommandExit {
+    /// The command exited on its own.
+    Exited {
+        /// Its exit status.
+        status: ExitStatus,
+        /// Time from spawn until it exited.
+        elapsed: Duration,
+    },
+    /// `cancel` fired first and the command's process group was killed.
+    Cancelled,
+}
+
+/// Run one plain command to completion, passing each bounded output line to `on_line`.
+///
+/// The command runs in its own process group with stdin closed, so cancelling `cancel` kills it
+/// together with its children. Hooks and on-demand tasks both run through here.
+pub(crate) async fn run_command(
+    command: &[String],
+    environment: &IndexMap<String, String>,
+    working_dir: Option<&Path>,
+    cancel: &CancellationToken,
+    on_line: impl Fn(OutputStream, String) + Clone + Send + 'static,
+) -> std::io::Result<CommandExit> {
+    let Some((prog, args)) = command.split_first() else {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "command is empty",
+        ));
+    };
+    let mut cmd = Command::new(prog);
+    cmd.args(args)
+        .envs(environment.iter())
+        .kill_on_drop(true)
+        .stdin(Stdio::null())
+        .stderr(Stdio::piped())
+        .stdout(Stdio::piped());
+    #[cfg(unix)]
+    {
+        cmd.process_group(0);
+    }
+    if let Some(dir) = working_dir {
+        cmd.current_dir(dir);
+    }
+
+    let started = tokio::time::Instant::now();
+    let mut process = cmd.spawn()?;
+    let readers = [
+        process
+            .stdout
+            .take()
+            .map(|stdout| spawn_output_task(stdout, OutputStream::Stdout, on_line.clone())),
+        process
+            .stderr
+            .take()
+            .map(|stderr| spawn_output_task(stderr, OutputStream::Stderr, on_line.clone())),
+    ];
+
+    let status = tokio::select! {
+        status = process.wait() => Some(status),
+        () = cancel.cancelled() => None,
+    };
+    let Some(status) = status else {
+        #[cfg(unix)]
+        if let Some(pid) = process.id().and_then(|pid| i32::try_from(pid).ok()) {
+            // The child has not been waited yet, so its process-group id cannot be recycled.
+            crate::health_check::kill_process_group(pid);
+        }
+        let _ = process.start_kill();
+        let _ = process.wait().await;
+        drain(readers).await;
+        return Ok(CommandExit::Cancelled);
+    };
+    // A backgrounded descendant may keep an output pipe open; do not wait on it past the drain.
+    drain(readers).await;
+
+    Ok(CommandExit::Exited {
+        status: status?,
+        elapsed: started.elapsed(),
+    })
+}
+
+async fn drain(readers: [Option<tokio::task::JoinHandle<()>>; 2]) {
+    for mut reader in readers.into_iter().flatten() {
+        if tokio::time::timeout(OUTPUT_DRAIN_TIMEOUT, &mut reader)
+            .await
+            .is_err()
+        {
+            reader.abort();
+        }
+    }
+}
+
+fn spawn_output_task(
+    reader: impl AsyncRead + Unpin + Send + 'static,
+    stream: OutputStream,
+    on_line: impl Fn(OutputStream, String) + Send + 'static,
+) -> tokio::task::JoinHandle<()> {
+    tokio::task::spawn(async move {
+        let mut reader = BufReader::new(reader);
+        let mut line = Vec::new();
+        loop {
+            match read_bounded_line(&mut reader, &mut line).await {
+                Ok(Some(line)) => on_line(stream, line),
+                Ok(None) => break,
+                Err(err) => {
+                    tracing::error!(?err, "command: failed to read line");
+                    break;
+                }
+            }
+        }
+    })
+}
@@ -20,17 +20,23 @@
 //! ```
 
 mod config;
+mod cron;
 mod diagnostics;
 mod env;
 mod graph;
 mod health_check;
+mod hooks;
 mod model;
+mod portowner;
 mod scheduler;
 mod service;
+mod signal;
 mod spec;
 pub mod structured_log;
+mod task;
 #[cfg(test)]
 pub(crate) mod test_util;
+mod watch;
 #[cfg(windows)]
 mod windows_job;
 
@@ -45,31 +51,39 @@ use tokio::sync::mpsc;
 pub use tokio_util::sync::CancellationToken;
 
 pub use config::{
-    ConfigError, ConfigFile, ControlConfig, DynamicServicesPolicy, config_file_names,
-    find_config_file, from_str, read_config_file, read_config_file_async,
+    ConfigError, ConfigFile, ControlConfig, DynamicServicesPolicy, LoadError, OnDependencyRestart,
+    OnUnhealthy, ShutdownOrder, config_file_names, find_config_file, from_str, load_config_file,
+    override_file_name, read_config_file, read_config_file_async,
 };
-pub use diagnostics::{Printer, ToDiagnostics, render_to_string};
-pub use env::Error as EnvironmentError;
+pub use cron::{CronError, CronSchedule};
+pub use diagnostics::{Printer, SourceDatabase, ToDiagnostics, render_to_string};
+pub use env::{EnvLayer, EnvOrigin, Error as EnvironmentError};
 pub use graph::Error as GraphError;
 pub use health_check::Health;
+pub use hooks::Error as HookError;
 pub use model::{
-    ChangeKind, Desired, DiskLogRetention, DynamicServiceInfo, EVENT_HISTORY, Execution,
-    HealthAttempt, HealthLine, HealthResult, HealthcheckConfig, LogLimit, LogLine, LogRetention,
-    LogRun, LogRunReadError, LogRunSummary, MemoryLogRetention, OriginKind, RestartState,
-    RetiredReason, ServiceEvent, ServiceEventKind, ServiceSnapshot, SessionChange,
-    SessionModelReader, trim_to_last_bytes,
+    ChangeKind, Desired, DiskLogRetention, DynamicServiceInfo, EVENT_HISTORY, EventCause,
+    Execution, HealthAttempt, HealthLine, HealthResult, HealthcheckConfig, HookKind, HookRun,
+    LogLimit, LogLine, LogRetention, LogRun, LogRunReadError, LogRunSummary, MemoryLogRetention,
+    OriginKind, PortConflict, ProbeExpectation, RestartPhase, RestartState, RetiredReason,
+    ServiceEvent, ServiceEventKind, ServiceSnapshot, SessionChange, SessionModelReader, TaskRun,
+    TaskSnapshot, trim_to_last_bytes,
 };
+pub use portowner::{PortOwner, listening_owner};
 pub use scheduler::{
     Command, CommandRejection, DynamicServiceAck, DynamicServiceResult, MAX_PTY_INPUT_BATCH_BYTES,
     MAX_PTY_PASTE_BYTES, OutputStream, PreparedPtyInput, PtyInputKind, PtyInputPrepareError,
     PtyInputSendError, ReconcileAction, ReconcileActionKind, ReconcileReceipt, ReconcileResult,
     SchedulerStopped, ServiceCommandAck, ServiceCommandResult, ServiceControl, ServiceID,
-    TerminalControl,
+    TaskRunAck, TaskRunResult, TerminalControl,
 };
 pub use service::{Error as ServiceError, RestartPolicy};
+pub use signal::{ServiceSignal, UnknownSignal};
 pub use spec::{
-    DependencySpec, DynamicOrigin, DynamicServiceParams, HealthcheckSpec, Lease,
-    PartialServiceSpec, ServiceOrigin, ServiceSpec, SpecError, SpecField,
+    CrashLoopSpec, DependencySpec, DynamicOrigin, DynamicServiceParams, HealthcheckSpec,
+    HttpProbeSpec, Lease, LogPatternSpec, PartialServiceSpec, PortBinding, PortConflictPolicy,
+    RestartBackoffSpec, RunSchedule, ServiceOrigin, ServiceSpec, SpecError, SpecField, StatusRange,
+    TcpProbeSpec, WatchSpec,
 };
 pub use structured_log::{
     FIELDS_KEY, MESSAGE_KEYS, StructuredLogLevel, find_fields_object, find_key,
@@ -100,29 +114,47 @@ pub enum Error {
     /// Service dependencies are invalid.
     #[error(transparent)]
     Graph(#[from] GraphError),
+    /// A session `before` hook failed, so no service was started.
+    #[error(transparent)]
+    Hook(#[from] HookError),
 }
 
 #[derive(Debug, Clone)]
 pub(crate) struct ReloadConfig {
     pub(crate) config_path: PathBuf,
     pub(crate) strict_override: Option<bool>,
+    pub(crate) profiles: Vec<String>,
+    // Replica counts scaled at runtime, with the configured count each scale was made against.
+    pub(crate) replicas: std::collections::BTreeMap<String, ScaledReplicas>,
+}
+
+/// A runtime replica count. It applies only while the config still sets the group's `replicas` to
+/// `configured`; editing the count in the file takes precedence over scaling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ScaledReplicas {
+    pub(crate) configured: usize,
+    pub(crate) replicas: usize,
 }
 
 pub(crate) fn service_map_from_config<F>(
     config_file: &config::ConfigFile<F>,
 ) -> Result<ServiceMap, ServiceError> {
     let config_dir = config_file.config_dir.clone();
-    config_file
-        .config
-        .services
+    let services = config::replicas::expand(&config_file.config.services, &config_file.replicas)
         .iter()
         .map(|(name, service_config)| {
             let service_id = name.as_ref().clone();
-            let service =
+            let mut service =
                 service::Service::new(name.as_ref().clone(), &config_dir, service_config.clone())?;
+            if !service_config.in_profiles(&config_file.profiles) {
+                service.startup_mode = service::StartupMode::Disabled;
+                service.excluded_by_profile = true;
+            }
             Ok::<_, ServiceError>((service_id, service))
         })
-        .collect::<Result<ServiceMap, _>>()
+        .collect::<Result<ServiceMap, _>>()?;
+    service::validate_port_references(&services)?;
+    Ok(services)
 }
 
 /// Severity of one config-validation diagnostic.
@@ -153,6 +185,10 @@ pub struct ValidationReport {
     pub config_path: String,
     /// Normalized service ids in config order.
     pub services: Vec<ServiceID>,
+    /// For each service, the config layer that set each of its environment variables. Values are
+    /// never included.
+    #[serde(skip_serializing_if = "indexmap::IndexMap::is_empty")]
+    pub environment: indexmap::IndexMap<ServiceID, indexmap::IndexMap<String, EnvOrigin>>,
     /// Compact errors and warnings.
     pub diagnostics: Vec<ConfigDiagnostic>,
     /// ANSI-free codespan rendering, capped to 16 KiB.
@@ -162,10 +198,12 @@ pub struct ValidationReport {
 
 /// Validate a config without constructing or starting a session.
 ///
-/// This runs parsing, service normalization (including environment-file loading and
-/// interpolation), and dependency-graph validation. `strict_override` overrides the config's own
-/// `strict:` key like the CLI `--strict` flag; pass `None` to let the config decide, so
-/// validation agrees with what a session startup would enforce.
+/// This runs parsing (including `include:` files and the local override file), service
+/// normalization (including environment-file loading and interpolation), and dependency-graph
+/// validation. `strict_override` overrides the config's own `strict:` key like the CLI `--strict`
+/// flag; pass `None` to let the config decide, so validation agrees with what a session startup
+/// would enforce. No profiles are active, so enabled services depending on profile-only services
+/// are reported as warnings.
 ///
 /// # Errors
 ///
@@ -177,33 +215,29 @@ pub fn validate_config_file(
     const MAX_RENDERED: usize = 16 * 1024;
 
     let config_path = path.canonicalize()?;
-    let config_dir = config_path
-        .parent()
-        .ok_or_else(|| Error::MissingConfigParent {
+    if config_path.parent().is_none() {
+        return Err(Error::MissingConfigParent {
             path: config_path.clone(),
-        })?;
-    let raw = config::read_config_file(&config_path)?;
+        });
+    }
     let mut files = SimpleFiles::new();
-    let file_id = files.add(config_path.display().to_string(), raw.clone());
     let mut source_diagnostics = Vec::new();
-    let mut parsed = match config::from_str(
-        &raw,
-        config_dir,
-        file_id,
+    let parsed = match config::load_config_file(
+        &config_path,
         strict_override,
+        &mut files,
         &mut source_diagnostics,
     ) {
         Ok(config) => Some(config),
+        Err(config::LoadError::Io { source, .. }) => return Err(source.into()),
         Err(err) => {
-            source_diagnostics.extend(err.to_diagnostics(file_id));
+            source_diagnostics.extend(err.to_diagnostics());
             None
         }
     };
-    if let Some(config) = &mut parsed {
-        config.config_path = Some(config_path.clone());
-    }
 
     let mut services = Vec::new();
+    let mut environment = indexmap::IndexMap::new();
     if !source_diagnostics
         .iter()
         .any(|diagnostic| diagnostic.severity == Severity::Error)
@@ -212,17 +246,33 @@ pub fn validate_config_file(
         match service_map_from_config(config) {
             Ok(service_map) => {
                 services.extend(service_map.keys().cloned());
-                if let Err(err) = graph::ServiceGraph::new(&service_map) {
-                    source_diagnostics.push(
+                environment.extend(
+                    service_map
+                        .iter()
+                        .map(|(id, service)| (id.clone(), service.environment_origins.clone())),
+                );
+                match graph::ServiceGraph::new(&service_map) {
+                    Ok(graph) => {
+                        source_diagnostics.extend(graph.warnings().iter().map(|warning| {
+                            codespan_reporting::diagnostic::Diagnostic::warning()
+                                .with_message(warning.to_string())
+                        }));
+                    }
+                    Err(err) => source_diagnostics.push(
                         codespan_reporting::diagnostic::Diagnostic::error()
                             .with_message(err.to_string()),
-                    );
+                    ),
                 }
             }
             Err(err) => source_diagnostics.push(
                 codespan_reporting::diagnostic::Diagnostic::error().with_message(err.to_string()),
             ),
         }
+        if let Err(err) = service::tasks(&config.config_dir, &config.config) {
+            source_diagnostics.push(
+                codespan_reporting::diagnostic::Diagnostic::error().with_message(err.to_string()),
+            );
+        }
     }
 
     let valid = parsed.is_some()
@@ -253,6 +303,7 @@ pub fn validate_config_file(
         valid,
         config_path: config_path.display().to_string(),
         services,
+        environment,
         diagnostics,
         rendered: (!rendered.is_empty()).then_some(rendered),
     })
@@ -276,6 +327,12 @@ pub(crate) fn initial_model_entries(services: &ServiceMap) -> Vec<(ServiceSnapsh
                 service.working_dir_display(),
             );
             snapshot.stop_grace_period = service.spec.stop_grace_period;
+            snapshot.stop_signal = service.spec.stop_signal;
+            snapshot.replica_group.clone_from(&service.replica_group);
+            snapshot.schedule = service.spec.schedule.as_ref().map(ToString::to_string);
+            if service.external {
+                snapshot.origin = OriginKind::External;
+            }
             snapshot.desired = match service.startup_mode {
                 service::StartupMode::Enabled => Desired::Enabled,
                 service::StartupMode::Disabled => Desired::Disabled,
@@ -292,6 +349,10 @@ pub struct Micromux {
     config_dir: PathBuf,
     dynamic_policy: DynamicServicesPolicy,
     default_log_retention: LogRetention,
+    shutdown_order: ShutdownOrder,
+    hooks: hooks::SessionHooks,
+    tasks: task::TaskMap,
+    cleanup_abort: CancellationToken,
 }
 
 /// Capability handles returned by [`Micromux::start`].
@@ -340,10 +401,12 @@ impl Micromux {
     ///
     /// # Errors
     ///
-    /// Returns an error if a service definition in the configuration cannot be normalized
-    /// (e.g. invalid environment interpolation, invalid port parsing, etc.).
+    /// Returns an error if a service definition, session hook or task in the configuration cannot
+    /// be normalized (e.g. invalid environment interpolation, invalid port parsing, etc.).
     pub fn new(config_file: &config::ConfigFile<diagnostics::FileId>) -> Result<Self, Error> {
         let services = service_map_from_config(config_file)?;
+        let hooks = service::session_hooks(&config_file.config_dir, &config_file.config)?;
+        let tasks = service::tasks(&config_file.config_dir, &config_file.config)?;
         let unbounded_services = services
             .values()
             .filter(|service| memory_retention_is_unbounded(service.log_retention))
@@ -363,9 +426,18 @@ impl Micromux {
             .map(|config_path| ReloadConfig {
                 config_path,
                 strict_override: config_file.strict_override,
+                profiles: config_file.profiles.clone(),
+                replicas: std::collections::BTreeMap::new(),
             });
 
-        graph::ServiceGraph::new(&services)?;
+        let graph = graph::ServiceGraph::new(&services)?;
+        for warning in graph.warnings() {
+            tracing::warn!(
+                service = %warning.service,
+                dependency = %warning.dependency,
+                "dependency excluded by profile selection"
+            );
+        }
 
         let mut dynamic_policy = config_file.config.control.dynamic_services.clone();
         for root in &mut dynamic_policy.allowed_working_roots {
@@ -380,11 +452,30 @@ impl Micromux {
             config_dir: config_file.config_dir.clone(),
             dynamic_policy,
             default_log_retention: config_file.config.log_retention,
+            shutdown_order: config_file.config.shutdown_order,
+            hooks,
+            tasks,
+            cleanup_abort: CancellationToken::new(),
         })
     }
 
+    /// Kill cleanup hooks still running once `abort` is cancelled.
+    ///
+    /// Session `after` and per-service `post_stop` hooks outlive the shutdown token so cleanup can
+    /// finish; cancelling `abort` (for example on a second interrupt) kills them instead of waiting
+    /// out their `hook_timeout`.
+    #[must_use]
+    pub fn with_cleanup_abort(mut self, abort: CancellationToken) -> Self {
+        self.cleanup_abort = abort;
+        self
+    }
+
     /// Start the scheduler, returning the runner future and the capability [`Handles`].
     ///
+    /// The runner first runs the session `before` hooks; if one fails, no service is started and
+    /// the runner resolves to [`Error::Hook`]. The `after` hooks run once the scheduler has stopped
+    /// every service, or after a failed `before` hook.
+    ///
     /// The model (`Inner` + `Writer`) and the command channel are built internally; the writer is
     /// moved into the runner future and never leaves the core, so adapters can only read the model
     /// or send commands. `Arc<Self>` makes the future `'static`, so the caller can `tokio::spawn` it
@@ -394,6 +485,12 @@ impl Micromux {
         shutdown: CancellationToken,
     ) -> (impl Future<Output = Result<(), Error>> + 'static, Handles) {
         let (reader, writer) = model::new(initial_model_entries(&self.services));
+        for task in self.tasks.values() {
+            writer.insert_task(
+                task.snapshot(),
+                self.default_log_retention.disk.retained_runs,
+            );
+        }
         let (commands_tx, commands_rx) = mpsc::channel(1024);
         let (terminal, pty_input_rx) = TerminalControl::channel(commands_tx.clone());
         let handles = Handles {
@@ -406,8 +503,25 @@ impl Micromux {
         let runner = async move {
             tracing::info!("starting");
             let (events_tx, events_rx) = mpsc::channel(1024);
+            let hook_sink = writer.hook_sink(None, 0);
+
+            if let Err(err) = hooks::run(
+                model::HookKind::Before,
+                &self.hooks.before,
+                &self.hooks.environment,
+                None,
+                &hook_sink,
+                &shutdown,
+                None,
+            )
+            .await
+            {
+                tracing::error!(%err, "session before hook failed");
+                run_after_hooks(&self.hooks, &hook_sink, &self.cleanup_abort).await;
+                return Err(err.into());
+            }
 
-            scheduler::scheduler(scheduler::SchedulerInput {
+            let result = scheduler::scheduler(scheduler::SchedulerInput {
                 services: self.services.clone(),
                 reload_config: self.reload_config.clone(),
                 commands_rx,
@@ -421,8 +535,16 @@ impl Micromux {
                 config_dir: self.config_dir.clone(),
                 dynamic_policy: self.dynamic_policy.clone(),
                 default_log_retention: self.default_log_retention,
+                shutdown_order: self.shutdown_order,
+                tasks: self.tasks.clone(),
+                cleanup: hooks::Cleanup {
+                    timeout: self.hooks.timeout,
+                    abort: self.cleanup_abort.clone(),
+                },
             })
-            .await?;
+            .await;
+            run_after_hooks(&self.hooks, &hook_sink, &self.cleanup_abort).await;
+            result?;
             tracing::info!("exiting");
             Ok::<(), Error>(())
         };
@@ -431,6 +553,28 @@ impl Micromux {
     }
 }
 
+/// Run the session `after` hooks, each bounded by the hook timeout and killed early once `abort`
+/// is cancelled; a failure is logged but does not fail the session.
+async fn run_after_hooks(
+    session_hooks: &hooks::SessionHooks,
+    sink: &model::HookSink,
+    abort: &CancellationToken,
+) {
+    if let Err(err) = hooks::run(
+        model::HookKind::After,
+        &session_hooks.after,
+        &session_hooks.environment,
+        None,
+        sink,
+        abort,
+        Some(session_hooks.timeout),
+    )
+    .await
+    {
+        tracing::error!(%err, "session after hook failed");
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -464,6 +608,370 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn inactive_profiles_seed_disabled_model_state() -> eyre::Result<()> {
+        let raw = r#"
+            version: 1
+            services:
+              api:
+                command: ["true"]
+              worker:
+                command: ["true"]
+                profiles: [jobs]
+              debug:
+                command: ["true"]
+                profiles: [debug, jobs]
+        "#;
+        let mut diagnostics = Vec::new();
+        let mut config = from_str(raw, Path::new("."), 0usize, None, &mut diagnostics)?;
+        config.profiles = vec!["debug".to_string()];
+        let mux = Arc::new(Micromux::new(&config)?);
+        let (_runner, handles) = mux.start(CancellationToken::new());
+        let desired = |id: &str| {
+            handles
+                .reader
+                .service(id)
+                .map(|snapshot| snapshot.desired)
+                .ok_or_else(|| eyre::eyre!("missing {id} snapshot"))
+        };
+
+        assert!(diagnostics.is_empty());
+        assert_eq!(desired("api")?, Desired::Enabled);
+        assert_eq!(desired("worker")?, Desired::Disabled);
+        assert_eq!(desired("debug")?, Desired::Enabled);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn failing_before_hook_aborts_the_session_and_still_runs_after_hooks() -> eyre::Result<()>
+    {
+        let raw = r#"
+            version: 1
+            environment:
+              STAGE: setup
+            before:
+              - "echo preparing"
+              - ["sh", "-c", "echo $STAGE failed >&2; exit 4"]
+              - "echo unreachable"
+            after: "echo tearing down"
+            services:
+              api:
+                command: ["sh", "-c", "sleep 60"]
+        "#;
+        let mut diagnostics = Vec::new();
+        let config = from_str(raw, Path::new("."), 0usize, None, &mut diagnostics)?;
+        let mux = Arc::new(Micromux::new(&config)?);
+        let (runner, handles) = mux.start(CancellationToken::new());
+
+        let err = tokio::time::timeout(std::time::Duration::from_secs(5), runner)
+            .await?
+            .expect_err("a failing before hook should abort the session");
+        assert_eq!(
+            err.to_string(),
+            "before hook `sh -c echo setup failed >&2; exit 4` exited with code 4\n  \
+             [stderr] setup failed"
+        );
+        let hooks = handles
+            .reader
+            .session_hooks()
+            .into_iter()
+            .map(|run| (run.kind, run.command))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            hooks,
+            vec![
+                (HookKind::Before, "echo preparing".to_string()),
+                (
+                    HookKind::Before,
+                    "sh -c echo setup failed >&2; exit 4".to_string()
+                ),
+                (HookKind::After, "echo tearing down".to_string()),
+            ]
+        );
+        let snapshot = handles
+            .reader
+            .service("api")
+            .ok_or_else(|| eyre::eyre!("missing api snapshot"))?;
+        assert_eq!(snapshot.run_generation, 0);
+        Ok(())
+    }
+
+    /// Start `mux`, wait for `api` to run, and cancel the session, returning the reader and the
+    /// still-draining runner.
+    async fn start_and_shut_down(
+        mux: Micromux,
+    ) -> eyre::Result<(
+        SessionModelReader,
+        impl Future<Output = Result<(), Error>> + 'static,
+    )> {
+        let shutdown = CancellationToken::new();
+        let (runner, handles) = Arc::new(mux).start(shutdown.clone());
+        let runner = tokio::spawn(runner);
+        let running = async {
+            while handles
+                .reader
+                .service("api")
+                .is_none_or(|snapshot| snapshot.execution != Execution::Running)
+            {
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+        };
+        tokio::time::timeout(std::time::Duration::from_secs(5), running).await?;
+        shutdown.cancel();
+        Ok((handles.reader, async move {
+            runner.await.expect("runner panicked")
+        }))
+    }
+
+    fn last_hook(hooks: Vec<model::HookRun>, kind: HookKind) -> eyre::Result<model::HookRun> {
+        hooks
+            .into_iter()
+            .rfind(|run| run.kind == kind)
+            .ok_or_else(|| eyre::eyre!("missing {kind} hook run"))
+    }
+
+    #[tokio::test]
+    async fn hung_cleanup_hooks_are_killed_at_the_hook_timeout() -> eyre::Result<()> {
+        let raw = r#"
+            version: 1
+            hook_timeout: 200ms
+            after: ["sleep 60", "echo unreachable"]
+            services:
+              api:
+                command: ["sh", "-c", "sleep 60"]
+                post_stop: "sleep 60"
+        "#;
+        let mut diagnostics = Vec::new();
+        let config = from_str(raw, Path::new("."), 0usize, None, &mut diagnostics)?;
+        let (reader, runner) = start_and_shut_down(Micromux::new(&config)?).await?;
+
+        tokio::time::timeout(std::time::Duration::from_secs(5), runner).await??;
+        for run in [
+            last_hook(reader.hooks("api"), HookKind::PostStop)?,
+            last_hook(reader.session_hooks(), HookKind::After)?,
+        ] {
+            let result = run
+                .result
+                .ok_or_else(|| eyre::eyre!("{} hook did not finish", run.kind))?;
+            assert!(!result.success);
+            assert!(!result.cancelled);
+            assert_eq!(
+                run.output.last().map(|line| line.line.as_str()),
+                Some("timed out after 200ms")
+            );
+        }
+        assert_eq!(reader.session_hooks().len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn cleanup_abort_kills_cleanup_hooks_before_their_timeout() -> eyre::Result<()> {
+        let raw = r#"
+            version: 1
+            after: "sleep 60"
+            services:
+              api:
+                command: ["sh", "-c", "sleep 60"]
+                post_stop: "sleep 60"
+        "#;
+        let mut diagnostics = Vec::new();
+        let config = from_str(raw, Path::new("."), 0usize, None, &mut diagnostics)?;
+        let abort = CancellationToken::new();
+        let mux = Micromux::new(&config)?.with_cleanup_abort(abort.clone());
+        let (reader, runner) = start_and_shut_down(mux).await?;
+
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        abort.cancel();
+        tokio::time::timeout(std::time::Duration::from_secs(5), runner).await??;
+        let post_stop = last_hook(reader.hooks("api"), HookKind::PostStop)?;
+        assert!(post_stop.result.is_some_and(|result| result.cancelled));
+        let after = last_hook(reader.session_hooks(), HookKind::After)?;
+        assert!(after.result.is_some_and(|result| result.cancelled));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn tasks_run_on_demand_and_retain_their_output() -> eyre::Result<()> {
+        let raw = r#"
+            version: 1
+            environment:
+              GREETING: hello
+            services:
+              api:
+                command: ["sh", "-c", "sleep 60"]
+            tasks:
+              seed:
+                description: Seed the database
+                command: ["sh", "-c", "echo $GREETING $TARGET; echo oops >&2; exit 3"]
+                environment:
+                  TARGET: db
+              wait:
+                command: ["sh", "-c", "sleep 60"]
+        "#;
+        let mut diagnostics = Vec::new();
+        let config = from_str(raw, Path::new("."), 0usize, None, &mut diagnostics)?;
+        let mux = Arc::new(Micromux::new(&config)?);
+        let shutdown = CancellationToken::new();
+        let (runner, handles) = mux.start(shutdown.clone());
+        let runner = tokio::spawn(runner);
+        let control = handles.service_control();
+
+        let ack = control.run_task("seed").await??;
+        assert_eq!(ack.run_generation, 1);
+        assert_eq!(
+            control.run_task("missing").await?,
+            Err(CommandRejection::UnknownTask)
+        );
+        control.run_task("wait").await??;
+        assert!(matches!(
+            control.run_task("wait").await?,
+            Err(CommandRejection::InvalidState(_))
+        ));
+
+        let finished = |name: &'static str| {
+            let reader = handles.reader.clone();
+            async move {
+                loop {
+                    if let Some(run) = reader
+                        .task(name)
+                        .and_then(|task| task.latest_run().cloned())
+                        .filter(|run| run.result.is_some())
+                    {
+                        return run;
+                    }
+                    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                }
+            }
+        };
+        let run = tokio::time::timeout(std::time::Duration::from_secs(5), finished("seed")).await?;
+        let result = run
+            .result
+            .ok_or_else(|| eyre::eyre!("seed has no result"))?;
+        assert!(!result.success);
+        assert_eq!(result.exit_code, 3);
+        let mut lines = handles
+            .reader
+            .try_task_run_log("seed", 1, None)?
+            .ok_or_else(|| eyre::eyre!("missing seed run log"))?
+            .lines
+            .into_iter()
+            .map(|line| line.line)
+            .collect::<Vec<_>>();
+        lines.sort();
+        assert_eq!(lines, vec!["[stderr] oops", "hello db"]);
+
+        shutdown.cancel();
+        tokio::time::timeout(std::time::Duration::from_secs(10), runner).await???;
+        let run = handles
+            .reader
+            .task("wait")
+            .and_then(|task| task.latest_run().cloned())
+            .ok_or_else(|| eyre::eyre!("wait never ran"))?;
+        assert!(run.result.is_some_and(|result| result.cancelled));
+        Ok(())
+    }
+
+    #[test]
+    fn port_references_must_name_an_existing_named_port() -> eyre::Result<()> {
+        let raw = r#"
+            version: 1
+            services:
+              api:
+                command: ["true"]
+                ports: [{ name: http, port: auto }]
+              web:
+                command: ["true"]
+                environment:
+                  API: ${services.api.ports.grpc}
+        "#;
+        let mut diagnostics = Vec::new();
+        let config = from_str(raw, Path::new("."), 0usize, None, &mut diagnostics)?;
+        assert!(matches!(
+            Micromux::new(&config),
+            Err(Error::Service(ServiceError::PortReference { service, reason, .. }))
+                if service == "web" && reason == "service `api` has no port named `grpc`"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn config_validation_warns_about_dependencies_outside_profiles() -> eyre::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let config_path = dir.path().join("micromux.yaml");
+        std::fs::write(
+            &config_path,
+            r#"version: 1
+services:
+  api:
+    command: ["true"]
+    depends_on: [db]
+  db:
+    command: ["true"]
+    profiles: [backend]
+"#,
+        )?;
+
+        let report = validate_config_file(&config_path, None)?;
+        assert!(report.valid);
+        assert!(report.diagnostics.iter().any(|diagnostic| {
+            diagnostic.severity == ConfigDiagnosticSeverity::Warning
+                && diagnostic.message.contains("no active profile includes")
+        }));
+        Ok(())
+    }
+
+    #[test]
+    fn config_validation_reports_environment_layers_without_values() -> eyre::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let config_path = dir.path().join("micromux.yaml");
+        std::fs::write(
+            dir.path().join(".env"),
+            "DATABASE_URL=postgres://secret@db/app\n",
+        )?;
+        std::fs::write(
+            &config_path,
+            r#"version: 1
+env_file: .env
+environment:
+  RUST_LOG: info
+services:
+  api:
+    command: ["true"]
+    environment:
+      RUST_LOG: debug
+  worker:
+    command: ["true"]
+"#,
+        )?;
+
+        let report = validate_config_file(&config_path, None)?;
+        assert!(report.valid);
+        let origin = |service: &str, key: &str| {
+            report
+                .environment
+                .get(service)
+                .and_then(|keys| keys.get(key))
+                .map(|origin| origin.layer)
+        };
+        assert_eq!(
+            origin("api", "RUST_LOG"),
+            Some(EnvLayer::ServiceEnvironment)
+        );
+        assert_eq!(
+            origin("worker", "RUST_LOG"),
+            Some(EnvLayer::SessionEnvironment)
+        );
+        assert_eq!(
+            origin("worker", "DATABASE_URL"),
+            Some(EnvLayer::SessionEnvFile)
+        );
+
+        let json = serde_json::to_string(&report)?;
+        assert!(json.contains("session_env_file"));
+        assert!(!json.contains("secret"));
+        Ok(())
+    }
+
     #[test]
     fn programmatic_dynamic_policy_defaults_resolve_from_the_config_directory() -> eyre::Result<()>
     {
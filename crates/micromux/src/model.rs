@@ -22,7 +22,7 @@ use indexmap::IndexMap;
 use parking_lot::RwLock;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, oneshot};
 
 use crate::health_check::Health;
 use crate::scheduler::{LogUpdateKind, OutputStream, ServiceID};
@@ -40,6 +40,12 @@ pub(crate) const MODEL_STRING_MAX_BYTES: usize = 16 * KIB;
 const HEALTH_HISTORY: usize = 8;
 /// Per-attempt healthcheck output retention.
 const HEALTH_OUTPUT_MAX_LINES: usize = 200;
+/// How many recent hook runs the model retains per service, and for the session.
+const HOOK_HISTORY: usize = 16;
+/// Per-hook-run output retention.
+const HOOK_OUTPUT_MAX_LINES: usize = 200;
+/// In-memory output retention per task run, for sessions without a log spool.
+const TASK_OUTPUT_MAX_LINES: usize = 200;
 /// Maximum retained lifecycle events per service.
 pub const EVENT_HISTORY: usize = 256;
 /// Capacity of the liveness-only change broadcast. A lagging subscriber loses only coalescible
@@ -74,6 +80,8 @@ pub enum Execution {
     Stopping,
     /// The last process has exited (crash, completion, or stop).
     Exited,
+    /// A process is live but its process group is suspended (`SIGSTOP`) until resumed.
+    Paused,
     /// A newer peer sent an execution state this binary does not know yet.
     #[serde(other)]
     Unknown,
@@ -87,6 +95,8 @@ pub enum OriginKind {
     Configured,
     /// Created through the control plane.
     Dynamic,
+    /// Loaded from the session configuration as `external`: micromux probes it but never runs it.
+    External,
     /// A newer peer sent an origin this binary does not know yet.
     #[serde(other)]
     Unknown,
@@ -154,10 +164,26 @@ impl From<&crate::spec::HealthcheckSpec> for HealthcheckConfig {
     }
 }
 
+/// Why an exited service is not restarting right now.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum RestartPhase {
+    /// Waiting out the backoff delay before the next automatic restart.
+    #[default]
+    Backoff,
+    /// The crash-loop breaker tripped; only an operator restart or enable starts it again.
+    CrashLoop,
+    /// A newer peer sent a restart phase this binary does not know yet.
+    #[serde(other)]
+    Unknown,
+}
+
 /// Active automatic-restart backoff for an exited service.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct RestartState {
-    /// Delay selected for the next automatic restart attempt.
+    /// Whether the service is backing off or parked by the crash-loop breaker.
+    #[serde(default)]
+    pub phase: RestartPhase,
+    /// Delay selected for the next automatic restart attempt; zero while parked.
     #[schemars(with = "DurationSchema")]
     pub backoff_delay: Duration,
     /// Remaining automatic restarts for a bounded `on-failure` policy; `None` means unbounded.
@@ -165,6 +191,37 @@ pub struct RestartState {
     pub restarts_remaining: Option<usize>,
 }
 
+/// A declared port found held by another process when the service was about to start.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct PortConflict {
+    /// The port that could not be bound.
+    pub port: u16,
+    /// Process listening on the port, when it could be identified.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pid: Option<u32>,
+    /// Command name of that process, when it could be identified.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// Whether the scheduler is waiting for the port (`port_conflict: wait`) rather than having
+    /// failed the start.
+    #[serde(default)]
+    pub waiting: bool,
+}
+
+impl PortConflict {
+    /// One-line description naming the holder when known, such as `port 8080 is held by pid 4242
+    /// (node)`.
+    #[must_use]
+    pub fn describe(&self) -> String {
+        let port = self.port;
+        match (self.pid, self.command.as_deref()) {
+            (Some(pid), Some(command)) => format!("port {port} is held by pid {pid} ({command})"),
+            (Some(pid), None) => format!("port {port} is held by pid {pid}"),
+            (None, _) => format!("port {port} is held by another process"),
+        }
+    }
+}
+
 /// A point-in-time, serializable view of one service. This is the wire payload reused directly by
 /// the control protocol (no DTO mirror).
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -221,8 +278,11 @@ pub struct ServiceSnapshot {
     #[serde(default = "crate::spec::default_stop_grace_period")]
     #[schemars(with = "DurationSchema")]
     pub stop_grace_period: Duration,
-    /// Whether the current command, working directory, ports, healthcheck timing, or stop grace
-    /// differs from the configuration captured for this run.
+    /// Graceful-stop signal captured for this run.
+    #[serde(default)]
+    pub stop_signal: crate::ServiceSignal,
+    /// Whether the current command, working directory, ports, healthcheck timing, stop grace, or
+    /// stop signal differs from the configuration captured for this run.
     #[serde(default)]
     pub config_stale: bool,
     /// Active automatic-restart backoff, if the scheduler is delaying the next attempt.
@@ -246,6 +306,27 @@ pub struct ServiceSnapshot {
     /// The configured restart policy.
     #[serde(default)]
     pub restart_policy: RestartPolicy,
+    /// Replica group this service is an instance of; commands naming the group address every
+    /// instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replica_group: Option<String>,
+    /// A declared port held by another process, found before the latest start attempt.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port_conflict: Option<PortConflict>,
+    /// Whether the latest run was stopped for not becoming healthy within its `startup_timeout`.
+    /// Cleared once a run becomes healthy.
+    #[serde(default)]
+    pub startup_timed_out: bool,
+    /// Dependencies this service is blocked on whose latest run hit its `startup_timeout`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub timed_out_dependencies: Vec<ServiceID>,
+    /// The `schedule` (cron expression) or `every` interval of a scheduled service.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<String>,
+    /// When the next scheduled tick starts a run, in Unix milliseconds. `None` for a service that
+    /// is not scheduled, is disabled, or has no future tick.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_run_at_unix_ms: Option<u64>,
 }
 
 impl ServiceSnapshot {
@@ -278,6 +359,7 @@ impl ServiceSnapshot {
             healthcheck_configured: healthcheck.is_some(),
             healthcheck,
             stop_grace_period: crate::spec::DEFAULT_STOP_GRACE_PERIOD,
+            stop_signal: crate::ServiceSignal::default(),
             config_stale: false,
             restart_state: None,
             last_exit_code: None,
@@ -285,6 +367,12 @@ impl ServiceSnapshot {
             working_dir,
             uptime: None,
             restart_policy,
+            replica_group: None,
+            port_conflict: None,
+            startup_timed_out: false,
+            timed_out_dependencies: Vec::new(),
+            schedule: None,
+            next_run_at_unix_ms: None,
         }
     }
 }
@@ -368,6 +456,10 @@ pub struct LogLine {
     /// Wall-clock time when micromux ingested this record, in Unix milliseconds.
     #[serde(default)]
     pub timestamp_unix_ms: u64,
+    /// The stream that produced the line. Absent for terminal output, where stdout and stderr
+    /// share one PTY, and for micromux's own lifecycle banners.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stream: Option<OutputStream>,
     /// The already-formatted line (stderr lines carry a `[stderr]` prefix, matching the TUI).
     pub line: String,
 }
@@ -423,11 +515,73 @@ pub struct HealthLine {
 pub struct HealthResult {
     /// Whether the probe exited successfully.
     pub success: bool,
-    /// Exit code of the probe process.
+    /// Exit code of the probe process; `0` or `-1` for in-process `http` and `tcp` probes.
     pub exit_code: i32,
     /// Whether the probe was cancelled because the service run was stopping.
     #[serde(default)]
     pub cancelled: bool,
+    /// Time from the start of the attempt until it finished, in milliseconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+    /// Status code returned to an `http` probe.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_code: Option<u16>,
+    /// The expectation an `http` or `tcp` probe did not meet.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failed_expectation: Option<ProbeExpectation>,
+}
+
+impl HealthResult {
+    /// The result of a probe that finished (or could not run) with `exit_code`.
+    pub(crate) fn exited(success: bool, exit_code: i32) -> Self {
+        Self {
+            success,
+            exit_code,
+            cancelled: false,
+            latency_ms: None,
+            status_code: None,
+            failed_expectation: None,
+        }
+    }
+
+    /// Record how long the attempt took.
+    #[must_use]
+    pub(crate) fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency_ms = Some(u64::try_from(latency.as_millis()).unwrap_or(u64::MAX));
+        self
+    }
+
+    /// The result of a probe torn down because its service run was stopping.
+    pub(crate) fn cancelled() -> Self {
+        Self {
+            success: false,
+            exit_code: -1,
+            cancelled: true,
+            latency_ms: None,
+            status_code: None,
+            failed_expectation: None,
+        }
+    }
+}
+
+/// Which part of an in-process probe failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::Display, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ProbeExpectation {
+    /// The TCP connection could not be established.
+    Connect,
+    /// The peer did not answer with a well-formed HTTP response.
+    Response,
+    /// The HTTP status code was outside the accepted range.
+    Status,
+    /// The HTTP response body did not match the configured pattern.
+    Body,
+    /// The probe did not finish within its timeout.
+    Timeout,
+    /// A newer peer reported an expectation this binary does not know yet.
+    #[serde(other)]
+    Unknown,
 }
 
 /// One healthcheck attempt and its (bounded) output.
@@ -445,6 +599,86 @@ pub struct HealthAttempt {
     pub result: Option<HealthResult>,
 }
 
+/// Which hook list a [`HookRun`] belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::Display, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum HookKind {
+    /// Session-level setup, run before any service starts.
+    Before,
+    /// Session-level teardown, run once every service has stopped.
+    After,
+    /// Run before each run of a service spawns.
+    PreStart,
+    /// Run after each run of a service exits.
+    PostStop,
+    /// A newer peer sent a hook kind this binary does not know yet.
+    #[serde(other)]
+    Unknown,
+}
+
+/// One hook command and its (bounded) output.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HookRun {
+    /// Monotonic sequence number within the service's, or the session's, hook history.
+    pub seq: u64,
+    /// Which hook list the command belongs to.
+    pub kind: HookKind,
+    /// Run generation the hook ran for; `0` for session-level hooks.
+    pub run_generation: u64,
+    /// The command that was executed.
+    pub command: String,
+    /// Captured output (bounded).
+    pub output: Vec<HealthLine>,
+    /// The final result, or `None` while the command is still running.
+    pub result: Option<HealthResult>,
+}
+
+/// An on-demand task from the `tasks:` config section and its retained runs.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TaskSnapshot {
+    /// Task name, as configured.
+    pub name: String,
+    /// One-line description from the config.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// The command the task runs.
+    pub command: String,
+    /// Working directory the task runs in, when configured.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<String>,
+    /// Retained runs, oldest first.
+    #[serde(default)]
+    pub runs: Vec<TaskRun>,
+}
+
+impl TaskSnapshot {
+    /// The most recent run, if the task has run this session.
+    #[must_use]
+    pub fn latest_run(&self) -> Option<&TaskRun> {
+        self.runs.last()
+    }
+
+    /// Whether a run of the task is still in progress.
+    #[must_use]
+    pub fn running(&self) -> bool {
+        self.latest_run().is_some_and(|run| run.result.is_none())
+    }
+}
+
+/// One run of an on-demand task.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TaskRun {
+    /// Run number within the task, starting at 1; addresses the run's retained log.
+    pub run_generation: u64,
+    /// Wall-clock time the run started, in Unix milliseconds.
+    pub started_at_unix_ms: u64,
+    /// The final result, or `None` while the task is still running.
+    pub result: Option<HealthResult>,
+    /// Summary of the run's retained log.
+    pub log: LogRunSummary,
+}
+
 /// One scheduler lifecycle fact retained for service diagnosis.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct ServiceEvent {
@@ -470,6 +704,28 @@ pub struct ServiceEvent {
     /// Dependencies preventing a start.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub blocked_on: Option<Vec<ServiceID>>,
+    /// Watched files whose changes triggered a restart.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub changed_paths: Option<Vec<String>>,
+    /// Signal an operator sent to the service.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signal: Option<crate::ServiceSignal>,
+    /// The run of another service that triggered this transition.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub caused_by: Option<EventCause>,
+    /// Output of the failing healthcheck attempt behind an unhealthy restart.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub probe_output: Option<Vec<String>>,
+}
+
+/// A run of another service that a [`ServiceEvent`] happened in response to. Its own events carry
+/// the same `run_generation`, so a cascade can be followed back to where it began.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct EventCause {
+    /// Service whose run caused the event.
+    pub service: ServiceID,
+    /// Generation of that run.
+    pub run_generation: u64,
 }
 
 impl ServiceEvent {
@@ -483,22 +739,35 @@ impl ServiceEvent {
     fn log_banner(&self) -> Option<String> {
         match self.kind {
             ServiceEventKind::RestartRequested
+            | ServiceEventKind::DependencyRestarted
+            | ServiceEventKind::MaxRuntimeExceeded
             | ServiceEventKind::EnableRequested
             | ServiceEventKind::DisableRequested
             | ServiceEventKind::SpawnFailed
+            | ServiceEventKind::HookFailed
             | ServiceEventKind::BackoffScheduled
+            | ServiceEventKind::CrashLoop
             | ServiceEventKind::DependencyBlocked
             | ServiceEventKind::DependencyReady
+            | ServiceEventKind::PortConflict
+            | ServiceEventKind::PortReleased
             | ServiceEventKind::InputDropped
             | ServiceEventKind::Replaced
-            | ServiceEventKind::Retired => Some(banner_line(&self.detail)),
+            | ServiceEventKind::Retired
+            | ServiceEventKind::FilesChanged
+            | ServiceEventKind::Paused
+            | ServiceEventKind::Resumed => Some(banner_line(&self.detail)),
             ServiceEventKind::ConfigReloaded
             | ServiceEventKind::Spawned
             | ServiceEventKind::Healthy
             | ServiceEventKind::Unhealthy
+            | ServiceEventKind::StartupTimedOut
+            | ServiceEventKind::UnhealthyRestart
+            | ServiceEventKind::ScheduledRunSkipped
             | ServiceEventKind::Exited
             | ServiceEventKind::Created
             | ServiceEventKind::LeaseRenewed
+            | ServiceEventKind::Signaled
             | ServiceEventKind::Unknown => None,
         }
     }
@@ -540,6 +809,8 @@ pub enum ServiceEventKind {
     SpawnFailed,
     /// A process was spawned.
     Spawned,
+    /// A `post_stop` hook failed after a run exited.
+    HookFailed,
     /// A running service became healthy.
     Healthy,
     /// A running service became unhealthy.
@@ -552,6 +823,20 @@ pub enum ServiceEventKind {
     DependencyBlocked,
     /// Previously blocking dependencies became ready.
     DependencyReady,
+    /// A dependency restarted, so the service is restarted after it.
+    DependencyRestarted,
+    /// A run did not become healthy within its `startup_timeout` and was stopped.
+    StartupTimedOut,
+    /// A run stayed unhealthy and was stopped so `on_unhealthy: restart` can restart it.
+    UnhealthyRestart,
+    /// A run outlived its `max_runtime` and is being restarted.
+    MaxRuntimeExceeded,
+    /// A scheduled tick found the previous run still alive, so no run was started for it.
+    ScheduledRunSkipped,
+    /// A declared port is held by another process.
+    PortConflict,
+    /// A port the service was waiting for became free.
+    PortReleased,
     /// Terminal input was discarded before reaching the service.
     InputDropped,
     /// A service was created or added to the live roster.
@@ -562,6 +847,16 @@ pub enum ServiceEventKind {
     LeaseRenewed,
     /// A service was retired after an explicit stop, lease expiry, or config removal.
     Retired,
+    /// Watched files changed and triggered a restart.
+    FilesChanged,
+    /// An operator sent a signal to the running process.
+    Signaled,
+    /// An operator suspended the running process group.
+    Paused,
+    /// An operator continued a suspended process group.
+    Resumed,
+    /// The crash-loop breaker stopped automatic restarts.
+    CrashLoop,
     /// A newer peer sent an event kind this binary does not know yet.
     #[serde(other)]
     Unknown,
@@ -581,6 +876,11 @@ pub enum ChangeKind {
     Roster,
     /// Lifecycle timeline history changed.
     Events,
+    /// Hook history changed, for the service or, with [`SessionChange::SESSION_WIDE`], for the
+    /// session.
+    Hooks,
+    /// An on-demand task started, produced output, or finished. Always session-wide.
+    Tasks,
     /// Subscription keepalive; no model content changed.
     Heartbeat,
     /// A newer peer sent a change kind this binary does not know yet.
@@ -601,15 +901,17 @@ pub struct SessionChange {
 impl SessionChange {
     /// `service_id` marker for a change not scoped to one service.
     ///
-    /// Only control-server notifications carry it; the model itself always publishes real service
-    /// ids. Consumers waiting on a specific service should treat session-wide content changes as
-    /// touching that service too.
+    /// Control-server notifications carry it, and the model publishes it only for session-level
+    /// [`ChangeKind::Hooks`] and for [`ChangeKind::Tasks`]; every other model change names a real
+    /// service. Consumers waiting on a specific service should treat session-wide changes other
+    /// than [`ChangeKind::Tasks`] as touching that service too.
     pub const SESSION_WIDE: &'static str = "*";
 }
 
 mod disk;
 mod memory;
 mod runlog;
+mod task;
 
 use self::disk::{DiskLogOp, DiskLogRecord, DiskLogWorker, DiskLogWriter, SharedDiskRunMetadata};
 use self::memory::MemoryLogBuffer;
@@ -620,6 +922,8 @@ use self::runlog::RUN_LOG_OFFSET_CACHE;
 use self::runlog::{
     RunLogEntry, RunLogReadIndex, create_spool_dir, read_run_log_file, unix_timestamp_ms,
 };
+pub(crate) use self::task::TaskSink;
+use self::task::{TaskEntry, TaskLogTarget};
 
 struct ServiceEntry {
     snapshot: ServiceSnapshot,
@@ -634,10 +938,40 @@ struct ServiceEntry {
     spool_dir: Option<PathBuf>,
     disk: Option<DiskLogWriter>,
     health: VecDeque<HealthAttempt>,
+    hooks: HookHistory,
     events: VecDeque<ServiceEvent>,
     next_event_seq: u64,
 }
 
+/// Retained hook runs of a service or of the session.
+#[derive(Default)]
+struct HookHistory {
+    runs: VecDeque<HookRun>,
+    next_seq: u64,
+}
+
+impl HookHistory {
+    fn start(&mut self, kind: HookKind, run_generation: u64, command: String) -> u64 {
+        self.next_seq = self.next_seq.saturating_add(1);
+        while self.runs.len() >= HOOK_HISTORY {
+            self.runs.pop_front();
+        }
+        self.runs.push_back(HookRun {
+            seq: self.next_seq,
+            kind,
+            run_generation,
+            command,
+            output: Vec::new(),
+            result: None,
+        });
+        self.next_seq
+    }
+
+    fn run_mut(&mut self, seq: u64) -> Option<&mut HookRun> {
+        self.runs.iter_mut().find(|run| run.seq == seq)
+    }
+}
+
 struct RunLogSource {
     path: PathBuf,
     index: Arc<parking_lot::Mutex<RunLogReadIndex>>,
@@ -683,6 +1017,7 @@ impl ServiceEntry {
             spool_dir: spool_dir.map(Path::to_path_buf),
             disk,
             health: VecDeque::new(),
+            hooks: HookHistory::default(),
             events: VecDeque::new(),
             next_event_seq: 1,
         }
@@ -752,6 +1087,7 @@ impl ServiceEntry {
     fn append_log(
         &mut self,
         run_generation: u64,
+        stream: Option<OutputStream>,
         update: LogUpdateKind,
         line: String,
         disk_line: Option<String>,
@@ -809,6 +1145,7 @@ impl ServiceEntry {
                 seq,
                 run_generation,
                 timestamp_unix_ms,
+                stream,
                 line,
             };
             let disk_write =
@@ -823,6 +1160,7 @@ impl ServiceEntry {
                             run_generation,
                             timestamp_unix_ms,
                             op,
+                            stream,
                             line,
                         },
                     });
@@ -849,6 +1187,7 @@ impl ServiceEntry {
     ) -> Option<PendingDiskWrite> {
         self.append_log(
             self.latest_begun_run,
+            None,
             LogUpdateKind::Append,
             line,
             disk_line,
@@ -964,6 +1303,8 @@ impl ServiceEntry {
 
 struct Inner {
     services: RwLock<IndexMap<ServiceID, Arc<RwLock<ServiceEntry>>>>,
+    session_hooks: RwLock<HookHistory>,
+    tasks: RwLock<IndexMap<String, TaskEntry>>,
     change_tx: broadcast::Sender<SessionChange>,
     spool_dir: Option<PathBuf>,
     _spool_lock: Option<File>,
@@ -1233,6 +1574,99 @@ impl SessionModelReader {
             .and_then(|entry| entry.read().latest_current_health())
     }
 
+    /// Retained `pre_start` and `post_stop` hook runs for a service, oldest first, across runs.
+    #[must_use]
+    pub fn hooks(&self, id: &str) -> Vec<HookRun> {
+        self.inner
+            .service_entry(id)
+            .map(|entry| entry.read().hooks.runs.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Retained session-level `before` and `after` hook runs, oldest first.
+    #[must_use]
+    pub fn session_hooks(&self) -> Vec<HookRun> {
+        self.inner
+            .session_hooks
+            .read()
+            .runs
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Every configured on-demand task with its retained runs, in configuration order.
+    #[must_use]
+    pub fn tasks(&self) -> Vec<TaskSnapshot> {
+        self.inner.flush_disk();
+        self.inner
+            .tasks
+            .read()
+            .values()
+            .map(TaskEntry::snapshot)
+            .collect()
+    }
+
+    /// One on-demand task with its retained runs.
+    #[must_use]
+    pub fn task(&self, name: &str) -> Option<TaskSnapshot> {
+        self.inner.flush_disk();
+        self.inner.tasks.read().get(name).map(TaskEntry::snapshot)
+    }
+
+    /// Tries to read the retained log of one task run.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LogRunReadError::Rotating`] when the disk segment changes too frequently to read a
+    /// stable page. Callers may retry.
+    pub fn try_task_run_log(
+        &self,
+        name: &str,
+        run_generation: u64,
+        tail: Option<usize>,
+    ) -> Result<Option<LogRun>, LogRunReadError> {
+        let target = {
+            let tasks = self.inner.tasks.read();
+            let Some(target) = tasks
+                .get(name)
+                .and_then(|task| task.log_target(run_generation, tail))
+            else {
+                return Ok(None);
+            };
+            target
+        };
+        let source = match target {
+            TaskLogTarget::Memory(run) => return Ok(Some(run)),
+            TaskLogTarget::Disk(source) => source,
+        };
+        self.inner.flush_disk();
+        let Some(lines) = ({
+            let mut index = source.index.lock();
+            read_run_log_file(
+                &source.path,
+                tail,
+                None,
+                None,
+                &mut index,
+                &source.disk_metadata,
+            )?
+        }) else {
+            return Ok(None);
+        };
+        let current = self
+            .inner
+            .tasks
+            .read()
+            .get(name)
+            .is_some_and(|task| task.is_current_run(run_generation));
+        Ok(Some(LogRun {
+            run_generation,
+            current,
+            lines,
+        }))
+    }
+
     /// Retained lifecycle events, either forward from `after` or as the newest chronological tail.
     ///
     /// The returned boolean is true when retention or `tail` omitted matching events.
@@ -1294,13 +1728,66 @@ pub(crate) struct RunSink {
     inner: Arc<Inner>,
     service_id: ServiceID,
     run_generation: u64,
+    output_watch: Option<Arc<OutputWatch>>,
+}
+
+/// Reports the first ingested output line of a run that matches a readiness pattern.
+///
+/// Lines are matched with ANSI escapes stripped, so colored output still satisfies anchored
+/// patterns such as `^ready`.
+struct OutputWatch {
+    pattern: regex::Regex,
+    matched: parking_lot::Mutex<Option<oneshot::Sender<String>>>,
+}
+
+impl OutputWatch {
+    fn observe(&self, line: &str) {
+        let mut matched = self.matched.lock();
+        if matched.is_none() {
+            return;
+        }
+        let line = strip_ansi_escapes::strip_str(line);
+        if !self.pattern.is_match(&line) {
+            return;
+        }
+        if let Some(tx) = matched.take() {
+            let _ = tx.send(line);
+        }
+    }
 }
 
 impl RunSink {
+    /// Watch this run's appended output for the first line matching `pattern`.
+    ///
+    /// The watch is shared by clones made *after* this call, so install it before the sink is
+    /// handed to the PTY reader; otherwise early output would be missed. Live terminal snapshots
+    /// are redrawn screens rather than lines, so only appended records are matched.
+    pub(crate) fn watch_output(&mut self, pattern: regex::Regex) -> oneshot::Receiver<String> {
+        let (tx, rx) = oneshot::channel();
+        self.output_watch = Some(Arc::new(OutputWatch {
+            pattern,
+            matched: parking_lot::Mutex::new(Some(tx)),
+        }));
+        rx
+    }
+
+    /// Append a line read from one of the run's output pipes.
     pub(crate) fn append_log(&self, stream: OutputStream, update: LogUpdateKind, line: String) {
+        self.append(Some(stream), update, line);
+    }
+
+    /// Append terminal output, which interleaves stdout and stderr on one PTY.
+    pub(crate) fn append_terminal_log(&self, update: LogUpdateKind, line: String) {
+        self.append(None, update, line);
+    }
+
+    fn append(&self, stream: Option<OutputStream>, update: LogUpdateKind, line: String) {
+        if let (Some(watch), LogUpdateKind::Append) = (&self.output_watch, update) {
+            watch.observe(&line);
+        }
         let line = match stream {
-            OutputStream::Stdout | OutputStream::Unknown => line,
-            OutputStream::Stderr => format!("[stderr] {line}"),
+            Some(OutputStream::Stderr) => format!("[stderr] {line}"),
+            Some(OutputStream::Stdout | OutputStream::Unknown) | None => line,
         };
         let line = match update {
             LogUpdateKind::Append => truncate_to_first_bytes(line, MODEL_STRING_MAX_BYTES),
@@ -1315,6 +1802,7 @@ impl RunSink {
             let timestamp_unix_ms = entry.next_ingest_timestamp();
             if let Some(disk_write) = entry.append_log(
                 self.run_generation,
+                stream,
                 update,
                 line,
                 disk_line,
@@ -1373,13 +1861,7 @@ impl RunSink {
         self.inner.publish(&self.service_id, ChangeKind::Health);
     }
 
-    pub(crate) fn finish_health_attempt(
-        &self,
-        attempt: u64,
-        success: bool,
-        exit_code: i32,
-        cancelled: bool,
-    ) {
+    pub(crate) fn finish_health_attempt(&self, attempt: u64, result: HealthResult) {
         {
             let Some(entry) = self.inner.service_entry(&self.service_id) else {
                 return;
@@ -1393,16 +1875,100 @@ impl RunSink {
             }) else {
                 return;
             };
-            attempt_entry.result = Some(HealthResult {
-                success,
-                exit_code,
-                cancelled,
-            });
+            attempt_entry.result = Some(result);
         }
         self.inner.publish(&self.service_id, ChangeKind::Health);
     }
 }
 
+/// Write capability for hook runs: the `pre_start` and `post_stop` commands of one service run, or
+/// the session-level `before` and `after` commands when it names no service.
+#[derive(Clone)]
+pub(crate) struct HookSink {
+    inner: Arc<Inner>,
+    service_id: Option<ServiceID>,
+    run_generation: u64,
+}
+
+impl HookSink {
+    fn update<R>(&self, update: impl FnOnce(&mut HookHistory) -> R) -> Option<R> {
+        let result = match &self.service_id {
+            Some(service_id) => {
+                let entry = self.inner.service_entry(service_id)?;
+                let mut entry = entry.write();
+                update(&mut entry.hooks)
+            }
+            None => update(&mut self.inner.session_hooks.write()),
+        };
+        let service_id = self
+            .service_id
+            .clone()
+            .unwrap_or_else(|| SessionChange::SESSION_WIDE.to_string());
+        self.inner.publish(&service_id, ChangeKind::Hooks);
+        Some(result)
+    }
+
+    /// Record a hook command starting; its output and result are reported under the returned
+    /// sequence number.
+    pub(crate) fn start(&self, kind: HookKind, command: String) -> u64 {
+        let command = truncate_to_first_bytes(command, MODEL_STRING_MAX_BYTES);
+        self.update(|hooks| hooks.start(kind, self.run_generation, command))
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn append_line(&self, seq: u64, stream: OutputStream, line: String) {
+        let line = truncate_to_first_bytes(line, MODEL_STRING_MAX_BYTES);
+        self.update(|hooks| {
+            let Some(run) = hooks.run_mut(seq) else {
+                return;
+            };
+            while run.output.len() >= HOOK_OUTPUT_MAX_LINES {
+                run.output.remove(0);
+            }
+            run.output.push(HealthLine { stream, line });
+        });
+    }
+
+    pub(crate) fn finish(&self, seq: u64, result: HealthResult) {
+        self.update(|hooks| {
+            if let Some(run) = hooks.run_mut(seq) {
+                run.result = Some(result);
+            }
+        });
+    }
+
+    /// Retained output of a hook run, with stderr lines marked as in logs.
+    pub(crate) fn output(&self, seq: u64) -> Vec<String> {
+        let output = |hooks: &HookHistory| {
+            hooks
+                .runs
+                .iter()
+                .find(|run| run.seq == seq)
+                .map(|run| marked_output(&run.output))
+                .unwrap_or_default()
+        };
+        match &self.service_id {
+            Some(service_id) => self
+                .inner
+                .service_entry(service_id)
+                .map(|entry| output(&entry.read().hooks))
+                .unwrap_or_default(),
+            None => output(&self.inner.session_hooks.read()),
+        }
+    }
+}
+
+/// Captured command output as log-style lines, with stderr lines marked as in logs.
+fn marked_output(output: &[HealthLine]) -> Vec<String> {
+    output
+        .iter()
+        .map(|output| match output.stream {
+            OutputStream::Stderr => format!("[stderr] {}", output.line),
+            OutputStream::Stdout | OutputStream::Unknown => output.line.clone(),
+        })
+        .collect()
+}
+
 impl SessionModelWriter {
     /// Add a service to the end of the roster.
     pub(crate) fn insert_service(&self, snapshot: ServiceSnapshot, retention: LogRetention) {
@@ -1426,6 +1992,23 @@ impl SessionModelWriter {
         self.inner.publish(&service_id, ChangeKind::Roster);
     }
 
+    /// Register an on-demand task, keeping up to `retained_runs` of its run logs.
+    pub(crate) fn insert_task(&self, snapshot: TaskSnapshot, retained_runs: usize) {
+        let name = snapshot.name.clone();
+        let mut tasks = self.inner.tasks.write();
+        if tasks.contains_key(&name) {
+            tracing::warn!(task = name, "ignoring duplicate task insertion");
+            return;
+        }
+        tasks.insert(name, TaskEntry::new(snapshot, retained_runs));
+    }
+
+    /// Record a new run of `task` and mint its sink, or `None` if the task is unknown or its
+    /// previous run is still in progress.
+    pub(crate) fn begin_task_run(&self, task: &str) -> Option<TaskSink> {
+        TaskSink::begin(&self.inner, task)
+    }
+
     /// Remove a fully drained service from the roster.
     pub(crate) fn remove_service(&self, id: &ServiceID) {
         let removed = self.inner.services.write().shift_remove(id);
@@ -1476,11 +2059,25 @@ impl SessionModelWriter {
         }
     }
 
+    /// Mint the hook sink for one run of `service_id`, or for the session when it is `None`.
+    pub(crate) fn hook_sink(
+        &self,
+        service_id: Option<&ServiceID>,
+        run_generation: u64,
+    ) -> HookSink {
+        HookSink {
+            inner: self.inner.clone(),
+            service_id: service_id.cloned(),
+            run_generation,
+        }
+    }
+
     pub(crate) fn run_sink(&self, service_id: &ServiceID, run_generation: u64) -> RunSink {
         RunSink {
             inner: self.inner.clone(),
             service_id: service_id.clone(),
             run_generation,
+            output_watch: None,
         }
     }
 
@@ -1538,6 +2135,22 @@ impl SessionModelWriter {
         self.inner.publish(id, ChangeKind::Health);
     }
 
+    /// Output of a run's latest failed healthcheck attempt, with stderr lines marked as in logs.
+    pub(crate) fn failed_health_output(&self, id: &ServiceID, run_generation: u64) -> Vec<String> {
+        let Some(entry) = self.inner.service_entry(id) else {
+            return Vec::new();
+        };
+        let entry = entry.read();
+        entry
+            .health
+            .iter()
+            .rev()
+            .filter(|attempt| attempt.run_generation == run_generation)
+            .find(|attempt| attempt.result.is_some_and(|result| !result.success))
+            .map(|attempt| marked_output(&attempt.output))
+            .unwrap_or_default()
+    }
+
     /// Apply updated log retention from a reloaded service definition.
     pub(crate) fn reconfigure_log_retention(&self, id: &ServiceID, log_retention: LogRetention) {
         {
@@ -1587,8 +2200,13 @@ impl SessionModelWriter {
         exit_code: i32,
         cancelled: bool,
     ) {
+        let result = if cancelled {
+            HealthResult::cancelled()
+        } else {
+            HealthResult::exited(success, exit_code)
+        };
         self.run_sink(id, run_generation)
-            .finish_health_attempt(attempt, success, exit_code, cancelled);
+            .finish_health_attempt(attempt, result);
     }
 }
 
@@ -1620,6 +2238,8 @@ pub(crate) fn new_with_retention(
     let (change_tx, _) = broadcast::channel(CHANGE_CHANNEL_CAPACITY);
     let inner = Arc::new(Inner {
         services: RwLock::new(services),
+        session_hooks: RwLock::new(HookHistory::default()),
+        tasks: RwLock::new(IndexMap::new()),
         change_tx,
         spool_dir,
         _spool_lock: spool_lock,
@@ -1861,11 +2481,18 @@ mod tests {
             LogUpdateKind::Append,
             "boom".into(),
         );
+        writer
+            .run_sink(&id, 1)
+            .append_terminal_log(LogUpdateKind::Append, "tty".into());
         let lines = reader.logs(&id, None);
         assert_eq!(
             lines.first().map(|l| l.line.clone()),
             Some("[stderr] boom".to_string())
         );
+        assert_eq!(
+            lines.iter().map(|l| l.stream).collect::<Vec<_>>(),
+            vec![Some(OutputStream::Stderr), None]
+        );
     }
 
     #[test]
@@ -2799,6 +3426,10 @@ mod tests {
                     pid: None,
                     delay_ms: None,
                     blocked_on: None,
+                    changed_paths: None,
+                    signal: None,
+                    caused_by: None,
+                    probe_output: None,
                 },
             );
         }
@@ -2847,6 +3478,10 @@ mod tests {
             pid: None,
             delay_ms: None,
             blocked_on: None,
+            changed_paths: None,
+            signal: None,
+            caused_by: None,
+            probe_output: None,
         };
         // A spawn and an exit bracket the run's own record and must leave it untouched.
         writer.append_event(&id, event(ServiceEventKind::Spawned, "spawned with pid 1"));
@@ -3130,6 +3765,10 @@ mod tests {
                 pid: None,
                 delay_ms: None,
                 blocked_on: None,
+                changed_paths: None,
+                signal: None,
+                caused_by: None,
+                probe_output: None,
             },
         );
 
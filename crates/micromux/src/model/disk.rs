@@ -11,6 +11,8 @@ use std::time::Duration;
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 
+use crate::scheduler::OutputStream;
+
 const DISK_FLUSH_TIMEOUT: Duration = Duration::from_secs(2);
 const DISK_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(2);
 const DISK_LOG_QUEUE_MAX_BYTES: usize = 8 * 1024 * 1024;
@@ -29,6 +31,8 @@ pub(super) struct DiskLogRecord {
     #[serde(default)]
     pub(super) timestamp_unix_ms: u64,
     pub(super) op: DiskLogOp,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) stream: Option<OutputStream>,
     pub(super) line: String,
 }
 
@@ -598,6 +602,7 @@ mod tests {
             run_generation: 1,
             timestamp_unix_ms: 0,
             op: DiskLogOp::Append,
+            stream: None,
             line: "first".to_string(),
         };
         let second = DiskLogRecord {
@@ -605,6 +610,7 @@ mod tests {
             run_generation: 1,
             timestamp_unix_ms: 0,
             op: DiskLogOp::Append,
+            stream: None,
             line: "second".to_string(),
         };
         let first_bytes = serde_json::to_vec(&first)?.len().saturating_add(1);
@@ -648,10 +654,12 @@ mod tests {
             run_generation: 1,
             timestamp_unix_ms: 0,
             op: DiskLogOp::Append,
+            stream: None,
             line: "first".to_string(),
         };
         let replacement = DiskLogRecord {
             op: DiskLogOp::ReplaceLast,
+            stream: None,
             line: "current frame".to_string(),
             ..first.clone()
         };
@@ -693,11 +701,13 @@ mod tests {
             run_generation: 1,
             timestamp_unix_ms: 0,
             op: DiskLogOp::Append,
+            stream: None,
             line: "first".to_string(),
         };
         let replacement = DiskLogRecord {
             seq: 2,
             op: DiskLogOp::ReplaceLast,
+            stream: None,
             line: "surviving frame".to_string(),
             ..first.clone()
         };
@@ -142,6 +142,7 @@ mod tests {
             seq,
             run_generation: 1,
             timestamp_unix_ms: 0,
+            stream: None,
             line: line.to_string(),
         }
     }
@@ -392,6 +392,7 @@ fn read_run_log_segment(
             seq: record.seq,
             run_generation: record.run_generation,
             timestamp_unix_ms: record.timestamp_unix_ms,
+            stream: record.stream,
             line: record.line,
         };
         match record.op {
@@ -494,6 +495,7 @@ mod tests {
             run_generation: 1,
             timestamp_unix_ms: 0,
             op: DiskLogOp::Append,
+            stream: None,
             line: "first".to_string(),
         };
         let second = DiskLogRecord {
@@ -633,6 +635,7 @@ mod tests {
             run_generation: 1,
             timestamp_unix_ms: 0,
             op: DiskLogOp::Append,
+            stream: None,
             line: "first".to_string(),
         };
         let mut file = File::create(&path)?;
@@ -0,0 +1,265 @@
+//! Retained on-demand task runs.
+//!
+//! Each task run is recorded as a run log exactly like a service run, spooled under `tasks/` in
+//! the session log directory so task names never collide with service ids.
+
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::scheduler::OutputStream;
+
+use super::disk::{DiskLogOp, DiskLogRecord, DiskLogWriter};
+use super::runlog::{RunLogEntry, unix_timestamp_ms};
+use super::{
+    ChangeKind, HealthResult, Inner, LogLine, LogRun, LogRunSummary, MODEL_STRING_MAX_BYTES,
+    PendingDiskWrite, RunLogSource, SessionChange, TASK_OUTPUT_MAX_LINES, TaskRun, TaskSnapshot,
+    truncate_to_first_bytes,
+};
+
+/// Subdirectory of the session spool that holds task run logs.
+const TASK_SPOOL_DIR: &str = "tasks";
+
+pub(super) struct TaskEntry {
+    snapshot: TaskSnapshot,
+    runs: VecDeque<TaskRunEntry>,
+    next_run_generation: u64,
+    next_log_seq: u64,
+    last_ingest_timestamp_unix_ms: u64,
+    retained_runs: usize,
+}
+
+struct TaskRunEntry {
+    started_at_unix_ms: u64,
+    result: Option<HealthResult>,
+    log: RunLogEntry,
+    /// Bounded output tail, kept only when the run has no spooled log file.
+    output: VecDeque<LogLine>,
+}
+
+/// Where the lines of one task run are read from.
+pub(super) enum TaskLogTarget {
+    Disk(RunLogSource),
+    Memory(LogRun),
+}
+
+impl TaskRunEntry {
+    fn summary(&self, current: bool) -> LogRunSummary {
+        if self.log.path.is_some() {
+            return self.log.summary(current);
+        }
+        LogRunSummary {
+            run_generation: self.log.run_generation,
+            current,
+            path: None,
+            line_count: self.output.len(),
+            first_seq: self.output.front().map(|line| line.seq),
+            last_seq: self.output.back().map(|line| line.seq),
+        }
+    }
+}
+
+impl TaskEntry {
+    pub(super) fn new(mut snapshot: TaskSnapshot, retained_runs: usize) -> Self {
+        snapshot.runs.clear();
+        Self {
+            snapshot,
+            runs: VecDeque::new(),
+            next_run_generation: 1,
+            next_log_seq: 1,
+            last_ingest_timestamp_unix_ms: 0,
+            retained_runs: retained_runs.max(1),
+        }
+    }
+
+    fn latest_run_generation(&self) -> Option<u64> {
+        self.runs.back().map(|run| run.log.run_generation)
+    }
+
+    pub(super) fn snapshot(&self) -> TaskSnapshot {
+        let latest = self.latest_run_generation();
+        TaskSnapshot {
+            runs: self
+                .runs
+                .iter()
+                .map(|run| TaskRun {
+                    run_generation: run.log.run_generation,
+                    started_at_unix_ms: run.started_at_unix_ms,
+                    result: run.result,
+                    log: run.summary(Some(run.log.run_generation) == latest),
+                })
+                .collect(),
+            ..self.snapshot.clone()
+        }
+    }
+
+    /// Start a new run, evicting the oldest retained runs beyond the limit. Returns `None` while
+    /// the previous run is still in progress: a task never runs concurrently with itself.
+    fn begin(&mut self, spool_dir: Option<&Path>, disk: Option<&DiskLogWriter>) -> Option<u64> {
+        if self.runs.back().is_some_and(|run| run.result.is_none()) {
+            return None;
+        }
+        let run_generation = self.next_run_generation;
+        self.next_run_generation = run_generation.saturating_add(1);
+        self.runs.push_back(TaskRunEntry {
+            started_at_unix_ms: unix_timestamp_ms(),
+            result: None,
+            log: RunLogEntry::new(&self.snapshot.name, run_generation, spool_dir, disk),
+            output: VecDeque::new(),
+        });
+        while self.runs.len() > self.retained_runs {
+            if let Some(mut evicted) = self.runs.pop_front() {
+                evicted.log.enqueue_remove(disk);
+            }
+        }
+        Some(run_generation)
+    }
+
+    fn append(
+        &mut self,
+        run_generation: u64,
+        stream: OutputStream,
+        line: String,
+        disk: Option<&DiskLogWriter>,
+    ) -> Option<PendingDiskWrite> {
+        let seq = self.next_log_seq;
+        let timestamp_unix_ms = unix_timestamp_ms().max(self.last_ingest_timestamp_unix_ms);
+        let run = self
+            .runs
+            .iter_mut()
+            .find(|run| run.log.run_generation == run_generation)?;
+        self.next_log_seq = seq.saturating_add(1);
+        self.last_ingest_timestamp_unix_ms = timestamp_unix_ms;
+        run.log.append_metadata(seq);
+        let Some(path) = run.log.path.clone() else {
+            while run.output.len() >= TASK_OUTPUT_MAX_LINES {
+                run.output.pop_front();
+            }
+            run.output.push_back(LogLine {
+                seq,
+                run_generation,
+                timestamp_unix_ms,
+                stream: Some(stream),
+                line,
+            });
+            return None;
+        };
+        disk.map(|writer| PendingDiskWrite {
+            writer: writer.clone(),
+            path,
+            metadata: run.log.disk_metadata.clone(),
+            record: DiskLogRecord {
+                seq,
+                run_generation,
+                timestamp_unix_ms,
+                op: DiskLogOp::Append,
+                stream: Some(stream),
+                line,
+            },
+        })
+    }
+
+    fn finish(&mut self, run_generation: u64, result: HealthResult) {
+        if let Some(run) = self
+            .runs
+            .iter_mut()
+            .find(|run| run.log.run_generation == run_generation)
+        {
+            run.result = Some(result);
+        }
+    }
+
+    pub(super) fn log_target(
+        &self,
+        run_generation: u64,
+        tail: Option<usize>,
+    ) -> Option<TaskLogTarget> {
+        let run = self
+            .runs
+            .iter()
+            .find(|run| run.log.run_generation == run_generation)?;
+        if let Some(path) = run.log.path.clone() {
+            return Some(TaskLogTarget::Disk(RunLogSource {
+                path,
+                index: run.log.read_index.clone(),
+                disk_metadata: run.log.disk_metadata.clone(),
+            }));
+        }
+        let skip = tail.map_or(0, |tail| run.output.len().saturating_sub(tail));
+        Some(TaskLogTarget::Memory(LogRun {
+            run_generation,
+            current: self.latest_run_generation() == Some(run_generation),
+            lines: run.output.iter().skip(skip).cloned().collect(),
+        }))
+    }
+
+    pub(super) fn is_current_run(&self, run_generation: u64) -> bool {
+        self.latest_run_generation() == Some(run_generation)
+    }
+}
+
+/// Write capability for one on-demand task run, minted by the scheduler when the run starts.
+#[derive(Clone)]
+pub(crate) struct TaskSink {
+    inner: Arc<Inner>,
+    task: String,
+    run_generation: u64,
+}
+
+impl TaskSink {
+    /// Record a new run of `task`, or `None` if the task is unknown or still running.
+    pub(super) fn begin(inner: &Arc<Inner>, task: &str) -> Option<Self> {
+        let spool_dir = inner.spool_dir.as_ref().map(|dir| dir.join(TASK_SPOOL_DIR));
+        let run_generation = inner
+            .tasks
+            .write()
+            .get_mut(task)?
+            .begin(spool_dir.as_deref(), inner.disk_writer.as_ref())?;
+        inner.publish(&SessionChange::SESSION_WIDE.to_string(), ChangeKind::Tasks);
+        Some(Self {
+            inner: Arc::clone(inner),
+            task: task.to_string(),
+            run_generation,
+        })
+    }
+
+    /// The run number this sink records.
+    pub(crate) fn run_generation(&self) -> u64 {
+        self.run_generation
+    }
+
+    /// Append a line read from one of the task's output pipes.
+    pub(crate) fn append_line(&self, stream: OutputStream, line: String) {
+        let line = match stream {
+            OutputStream::Stderr => format!("[stderr] {line}"),
+            OutputStream::Stdout | OutputStream::Unknown => line,
+        };
+        let line = truncate_to_first_bytes(line, MODEL_STRING_MAX_BYTES);
+        {
+            let mut tasks = self.inner.tasks.write();
+            let Some(entry) = tasks.get_mut(&self.task) else {
+                return;
+            };
+            // Enqueue under the lock so concurrent stdout and stderr lines reach disk in seq order.
+            if let Some(disk_write) = entry.append(
+                self.run_generation,
+                stream,
+                line,
+                self.inner.disk_writer.as_ref(),
+            ) {
+                disk_write.enqueue();
+            }
+        }
+        self.inner
+            .publish(&SessionChange::SESSION_WIDE.to_string(), ChangeKind::Tasks);
+    }
+
+    /// Record how the run ended.
+    pub(crate) fn finish(&self, result: HealthResult) {
+        if let Some(entry) = self.inner.tasks.write().get_mut(&self.task) {
+            entry.finish(self.run_generation, result);
+        }
+        self.inner
+            .publish(&SessionChange::SESSION_WIDE.to_string(), ChangeKind::Tasks);
+    }
+}
@@ -1,12 +1,21 @@
+//! Attribution of a listening TCP port to the process that holds it.
+//!
+//! Linux reads `/proc/net/tcp{,6}` and the `/proc/<pid>/fd` links; macOS asks `lsof`. Other
+//! platforms never attribute a port.
+
 #[cfg(target_os = "linux")]
 use std::path::Path;
 
-pub(crate) struct PortOwner {
-    pub(crate) pid: u32,
-    pub(crate) command: String,
+/// The process listening on a port.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortOwner {
+    /// Process id.
+    pub pid: u32,
+    /// Command line, truncated to 120 characters.
+    pub command: String,
 }
 
-// MCP may inherit a project-controlled PATH, so attribution executes only known absolute
+// micromux may inherit a project-controlled PATH, so attribution executes only known absolute
 // candidates.
 #[cfg(target_os = "macos")]
 const LSOF_PATHS: [&str; 4] = [
@@ -83,8 +92,13 @@ fn command_for_pid(pid_dir: &Path) -> String {
     command.chars().take(120).collect()
 }
 
+/// The process listening on `port` on a loopback or wildcard address, if it can be identified.
+///
+/// Blocking: this scans every process's open descriptors, so async callers should run it off the
+/// runtime.
 #[cfg(target_os = "linux")]
-pub(crate) fn listening_owner(port: u16) -> Option<PortOwner> {
+#[must_use]
+pub fn listening_owner(port: u16) -> Option<PortOwner> {
     let inode = socket_inode(port)?;
     let target = format!("socket:[{inode}]");
     for entry in std::fs::read_dir("/proc").ok()?.flatten() {
@@ -116,8 +130,10 @@ fn lsof_path() -> Option<&'static str> {
         .find(|candidate| std::path::Path::new(candidate).is_file())
 }
 
+/// The process listening on `port`, if `lsof` can identify it within half a second.
 #[cfg(target_os = "macos")]
-pub(crate) fn listening_owner(port: u16) -> Option<PortOwner> {
+#[must_use]
+pub fn listening_owner(port: u16) -> Option<PortOwner> {
     use std::process::{Command, Stdio};
     use std::time::{Duration, Instant};
 
@@ -163,8 +179,10 @@ pub(crate) fn listening_owner(port: u16) -> Option<PortOwner> {
     Some(PortOwner { pid, command })
 }
 
+/// Port attribution is not supported on this platform.
 #[cfg(not(any(target_os = "linux", target_os = "macos")))]
-pub(crate) fn listening_owner(_port: u16) -> Option<PortOwner> {
+#[must_use]
+pub fn listening_owner(_port: u16) -> Option<PortOwner> {
     None
 }
 
@@ -1,37 +1,38 @@
 use crate::{
-    DynamicOrigin, DynamicServiceParams, DynamicServicesPolicy, Lease, ReloadConfig, ServiceMap,
-    ServiceOrigin, ServiceSpec,
-    graph::ServiceGraph,
+    DynamicOrigin, DynamicServiceParams, DynamicServicesPolicy, Lease, OnUnhealthy, ReloadConfig,
+    RestartBackoffSpec, RunSchedule, ScaledReplicas, ServiceMap, ServiceOrigin, ServiceSpec,
+    ShutdownOrder,
+    config::replicas,
+    graph::{self, ServiceGraph},
     health_check::Health,
     model::{
-        Desired, DynamicServiceInfo, Execution, HealthcheckConfig, LogRetention, OriginKind,
-        RestartState, RetiredReason, ServiceEvent, ServiceEventKind, ServiceSnapshot,
-        SessionModelWriter,
+        Desired, DynamicServiceInfo, Execution, HealthcheckConfig, HookKind, LogRetention,
+        OriginKind, RestartPhase, RestartState, RetiredReason, ServiceEvent, ServiceEventKind,
+        ServiceSnapshot, SessionModelWriter,
     },
     service::{self, Service, StartupMode},
+    task::TaskMap,
+    watch::{ServiceWatcher, WatchTrigger},
 };
 use codespan_reporting::diagnostic::Severity;
-use std::collections::{HashMap, VecDeque};
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::future::Future;
-use std::hash::{Hash, Hasher};
+use std::hash::{BuildHasher, Hash, Hasher};
 use std::path::PathBuf;
 use std::time::Duration;
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
 
-/// Initial delay before automatically restarting a service after it exits.
-const RESTART_BACKOFF_BASE: Duration = Duration::from_millis(250);
-/// Maximum delay the (exponentially doubling) restart backoff grows to.
-const RESTART_BACKOFF_MAX: Duration = Duration::from_secs(10);
-/// Minimum uptime after which a service is considered stable and its backoff is reset.
-const RESTART_BACKOFF_RESET: Duration = RESTART_BACKOFF_MAX;
-
 /// Grace period for tail output before cancellation of a finished run's PTY reader is requested.
 const POST_EXIT_DRAIN_GRACE: Duration = Duration::from_secs(5);
 /// Minimum interval between operator-visible input-drop reports for one service.
 const INPUT_DROP_REPORT_INTERVAL: Duration = Duration::from_secs(1);
 const MAX_RETIRED_SERVICES: usize = 8;
 const IDEMPOTENCY_WINDOW: usize = 64;
+/// Debounced file-watch batches buffered before watchers wait for the scheduler.
+const WATCH_TRIGGER_BUFFER: usize = 16;
+/// Changed paths recorded on one `FilesChanged` event; larger batches report only the count.
+const MAX_REPORTED_CHANGED_PATHS: usize = 32;
 
 #[path = "scheduler/types.rs"]
 mod types;
@@ -52,7 +53,7 @@ pub(crate) use control::CommandAck;
 pub use control::{
     CommandRejection, DynamicServiceAck, DynamicServiceResult, ReconcileAction,
     ReconcileActionKind, ReconcileReceipt, ReconcileResult, SchedulerStopped, ServiceCommandAck,
-    ServiceCommandResult, ServiceControl,
+    ServiceCommandResult, ServiceControl, TaskRunAck, TaskRunResult,
 };
 
 #[path = "scheduler/pty.rs"]
@@ -61,6 +62,17 @@ mod pty;
 #[path = "scheduler/schedule.rs"]
 mod schedule;
 
+#[path = "scheduler/ports.rs"]
+mod ports;
+
+/// A lifecycle command that addresses either one service or every instance of a replica group.
+#[derive(Clone, Copy, Debug)]
+enum LifecycleCommand {
+    Restart,
+    Enable,
+    Disable,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum DesiredState {
     Enabled,
@@ -68,10 +80,18 @@ enum DesiredState {
 }
 
 struct RestartTracker {
+    backoff: RestartBackoffSpec,
     backoff_until: Option<tokio::time::Instant>,
+    /// The delay actually waited, jitter included; what snapshots and events report.
     backoff_delay: Option<Duration>,
+    /// The un-jittered delay the next crash grows from, so jitter never compounds.
+    backoff_step: Option<Duration>,
     on_failure_max: Option<usize>,
     on_failure_remaining: Option<usize>,
+    /// Automatic-restart exits still inside the crash-loop window, oldest first.
+    crashes: VecDeque<tokio::time::Instant>,
+    /// Set once the breaker trips; only an operator restart or enable clears it.
+    crash_loop: bool,
 }
 
 impl RestartTracker {
@@ -87,45 +107,96 @@ impl RestartTracker {
         }
     }
 
-    fn new(policy: &service::RestartPolicy) -> Self {
+    fn new(policy: &service::RestartPolicy, backoff: &RestartBackoffSpec) -> Self {
         let on_failure_max = Self::on_failure_max(policy);
 
         Self {
+            backoff: *backoff,
             backoff_until: None,
             backoff_delay: None,
+            backoff_step: None,
             on_failure_max,
             on_failure_remaining: on_failure_max,
+            crashes: VecDeque::new(),
+            crash_loop: false,
         }
     }
 
-    fn reconfigure(&mut self, policy: &service::RestartPolicy) {
+    fn reconfigure(&mut self, policy: &service::RestartPolicy, backoff: &RestartBackoffSpec) {
         let on_failure_max = Self::on_failure_max(policy);
         if self.on_failure_max != on_failure_max {
             self.on_failure_max = on_failure_max;
             self.on_failure_remaining = on_failure_max;
         }
+        if self.backoff.crash_loop != backoff.crash_loop {
+            self.clear_crash_loop();
+        }
+        self.backoff = *backoff;
     }
 
     fn clear_backoff(&mut self) {
         self.backoff_until = None;
         self.backoff_delay = None;
+        self.backoff_step = None;
     }
 
     fn reset_failure_budget(&mut self) {
         self.on_failure_remaining = self.on_failure_max;
     }
 
+    fn clear_crash_loop(&mut self) {
+        self.crashes.clear();
+        self.crash_loop = false;
+    }
+
     fn apply_backoff(&mut self, stable: bool) {
         if stable {
-            self.backoff_delay = None;
+            self.backoff_step = None;
             self.reset_failure_budget();
         }
 
-        let next = self.backoff_delay.map_or(RESTART_BACKOFF_BASE, |delay| {
-            (delay * 2).min(RESTART_BACKOFF_MAX)
-        });
-        self.backoff_delay = Some(next);
-        self.backoff_until = Some(tokio::time::Instant::now() + next);
+        let step = self.backoff.next_delay(self.backoff_step);
+        let delay = jittered(step, self.backoff.jitter);
+        self.backoff_step = Some(step);
+        self.backoff_delay = Some(delay);
+        self.backoff_until = Some(tokio::time::Instant::now() + delay);
+    }
+
+    /// Count an exit that would restart automatically. Returns `true` when it trips the
+    /// crash-loop breaker, which parks the service until an operator restarts it.
+    fn record_crash(&mut self) -> bool {
+        let Some(crash_loop) = self.backoff.crash_loop else {
+            return false;
+        };
+        let now = tokio::time::Instant::now();
+        while self
+            .crashes
+            .front()
+            .is_some_and(|crashed| now.duration_since(*crashed) >= crash_loop.window)
+        {
+            self.crashes.pop_front();
+        }
+        self.crashes.push_back(now);
+        if self.crashes.len() < crash_loop.crashes {
+            return false;
+        }
+        self.crash_loop = true;
+        self.clear_backoff();
+        true
+    }
+
+    /// Why the breaker parked the service, for the `CrashLoop` event.
+    fn crash_loop_detail(&self) -> String {
+        self.backoff.crash_loop.map_or_else(
+            || "automatic restarts stopped".to_string(),
+            |crash_loop| {
+                format!(
+                    "automatic restarts stopped after {} crashes within {}",
+                    crash_loop.crashes,
+                    humantime::format_duration(crash_loop.window)
+                )
+            },
+        )
     }
 
     fn remaining_failure_restarts(&self, policy: &service::RestartPolicy) -> Option<usize> {
@@ -151,11 +222,20 @@ impl RestartTracker {
     }
 
     /// The currently active backoff as snapshot data, or `None` once the deadline has passed (the
-    /// retained `backoff_delay` then only seeds the *next* doubling and is not an active delay).
+    /// retained step then only seeds the *next* delay and is not an active wait). A tripped
+    /// crash-loop breaker is reported until it is cleared.
     fn active_restart_state(&self, policy: &service::RestartPolicy) -> Option<RestartState> {
+        if self.crash_loop {
+            return Some(RestartState {
+                phase: RestartPhase::CrashLoop,
+                backoff_delay: Duration::ZERO,
+                restarts_remaining: self.remaining_failure_restarts(policy),
+            });
+        }
         match (self.backoff_until, self.backoff_delay) {
             (Some(deadline), Some(backoff_delay)) if deadline > tokio::time::Instant::now() => {
                 Some(RestartState {
+                    phase: RestartPhase::Backoff,
                     backoff_delay,
                     restarts_remaining: self.remaining_failure_restarts(policy),
                 })
@@ -165,9 +245,35 @@ impl RestartTracker {
     }
 }
 
+/// Spread `delay` uniformly by up to `jitter` of itself in either direction, so services that
+/// crash together do not restart in lockstep.
+fn jittered(delay: Duration, jitter: f64) -> Duration {
+    if jitter <= 0.0 {
+        return delay;
+    }
+    // A fresh `RandomState` is randomly keyed, which is all the randomness a spread needs.
+    let sample = std::collections::hash_map::RandomState::new().hash_one(delay);
+    let unit = f64::from(u32::try_from(sample >> 32).unwrap_or(u32::MAX)) / f64::from(u32::MAX);
+    let factor = 1.0 + jitter * (2.0 * unit - 1.0);
+    Duration::try_from_secs_f64(delay.as_secs_f64() * factor).unwrap_or(delay)
+}
+
+/// The healthcheck loop that stands in for the run of an external service: micromux probes the
+/// service but never spawns, signals, or stops it.
+pub(super) struct ExternalProbe {
+    run_id: RunId,
+    /// The healthcheck the loop runs; a reload that changes it restarts the loop.
+    healthcheck: crate::HealthcheckSpec,
+    terminate: CancellationToken,
+    /// Held so the loop never sees its pause channel close; an external service is never paused.
+    _paused: tokio::sync::watch::Sender<bool>,
+}
+
 pub(super) struct RunningService {
     run_id: RunId,
     pid: Option<u32>,
+    process_group_leader_id: Option<i32>,
+    paused: tokio::sync::watch::Sender<bool>,
     terminate: CancellationToken,
     log_reader: Option<pty::LogReaderHandle>,
     pty: pty::PtyHandles,
@@ -183,15 +289,63 @@ impl RunningService {
         self.terminate.cancel();
     }
 
-    fn stable(&self) -> bool {
-        self.since.elapsed() >= RESTART_BACKOFF_RESET
+    /// Deliver an operator-requested signal to the run's process group. Returns whether any
+    /// process accepted it; signals are never delivered off Unix.
+    fn signal(&self, signal: crate::ServiceSignal) -> bool {
+        #[cfg(unix)]
+        {
+            pty::signal_process(self.pid, self.process_group_leader_id, signal.into())
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = (self.process_group_leader_id, signal);
+            false
+        }
+    }
+
+    fn paused(&self) -> bool {
+        *self.paused.borrow()
+    }
+
+    /// Stop (`SIGSTOP`) or continue (`SIGCONT`) the run's process group. Returns whether the
+    /// signal was delivered; job control is unavailable off Unix.
+    ///
+    /// The flag is raised before the group stops and lowered only after it continues, so the
+    /// healthcheck task never probes a frozen process.
+    fn set_paused(&self, paused: bool) -> bool {
+        #[cfg(unix)]
+        {
+            use nix::sys::signal::Signal;
+
+            if paused {
+                self.paused.send_replace(true);
+                let delivered =
+                    pty::signal_process(self.pid, self.process_group_leader_id, Signal::SIGSTOP);
+                if !delivered {
+                    self.paused.send_replace(false);
+                }
+                delivered
+            } else {
+                let delivered =
+                    pty::signal_process(self.pid, self.process_group_leader_id, Signal::SIGCONT);
+                if delivered {
+                    self.paused.send_replace(false);
+                }
+                delivered
+            }
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = (&self.paused, paused);
+            false
+        }
     }
 
-    fn finish(mut self) -> (RunId, bool, Option<pty::LogReaderHandle>) {
+    fn finish(mut self) -> (RunId, Duration, Option<pty::LogReaderHandle>) {
         let run_id = self.run_id;
-        let stable = self.stable();
+        let uptime = self.since.elapsed();
         let log_reader = self.log_reader.take();
-        (run_id, stable, log_reader)
+        (run_id, uptime, log_reader)
     }
 }
 
@@ -239,6 +393,7 @@ pub(super) struct RunConfig {
     advertised_ports: Vec<u16>,
     healthcheck: Option<HealthcheckConfig>,
     stop_grace_period: Duration,
+    stop_signal: crate::ServiceSignal,
 }
 
 impl From<&Service> for RunConfig {
@@ -253,6 +408,7 @@ impl From<&Service> for RunConfig {
                 .as_ref()
                 .map(HealthcheckConfig::from),
             stop_grace_period: service.spec.stop_grace_period,
+            stop_signal: service.spec.stop_signal,
         }
     }
 }
@@ -277,6 +433,8 @@ pub(super) struct ServiceRuntime {
     last_exit_code: Option<i32>,
     /// Fields that describe the most recent run rather than the current config.
     run_config: Option<RunConfig>,
+    /// Auto ports the most recent run was started with; reported after its fixed ports.
+    allocated_ports: Vec<u16>,
     draining_log_readers: Vec<DrainingLogReader>,
     retired: Option<RetiredReason>,
     retired_at_unix_ms: Option<u64>,
@@ -285,6 +443,41 @@ pub(super) struct ServiceRuntime {
     /// start. Non-empty exactly while the service is held back by them, so the projection can report
     /// a blocked service as such instead of leaving it at its pre-start state.
     blocked_on: Vec<ServiceID>,
+    /// A declared port found held before the last start attempt. While `waiting`, the service is
+    /// held back like a dependency block until the port frees up.
+    port_conflict: Option<crate::PortConflict>,
+    /// When a service waiting for a held port probes it again.
+    port_recheck_at: Option<tokio::time::Instant>,
+    /// Run generation of each `on_dependency_restart: restart` dependency when the current run
+    /// started; a newer generation restarts this service.
+    dependency_runs: HashMap<ServiceID, u64>,
+    /// When the current run is stopped unless it has become healthy; `None` once it has, or when
+    /// the service has no healthcheck or `startup_timeout`.
+    startup_deadline: Option<tokio::time::Instant>,
+    /// When the current run has outlived its `max_runtime` and is restarted; `None` when no run is
+    /// live or the service has no `max_runtime`.
+    runtime_deadline: Option<tokio::time::Instant>,
+    /// Whether the latest run hit its startup timeout; cleared once a run becomes healthy.
+    startup_timed_out: bool,
+    /// The subset of `blocked_on` whose latest run hit its startup timeout.
+    timed_out_dependencies: Vec<ServiceID>,
+    /// Whether the latest run was stopped by `on_unhealthy: restart`. Its exit counts as a failure
+    /// that restarts even under `restart: no`; cleared when the next run starts.
+    unhealthy_restart: bool,
+    /// The current run while its `pre_start` hooks run, before its process is spawned.
+    pending_start: Option<schedule::PendingStart>,
+    /// `post_stop` hooks of the current run, run once it exits.
+    post_stop: Option<schedule::RunHooks>,
+    /// The `schedule` or `every` of a scheduled service, which starts a run at each tick and is
+    /// never restarted automatically.
+    schedule: Option<RunSchedule>,
+    /// The next tick of `schedule`; `None` until it is armed and while the service is disabled.
+    next_tick: Option<schedule::ScheduledTick>,
+    /// The probe loop of an external service, which has no process; `None` for every other
+    /// service and while an external service is disabled.
+    external_probe: Option<ExternalProbe>,
+    /// The finished run whose `post_stop` hooks are still running; the next run waits for them.
+    post_stop_run: Option<RunId>,
     input_drops: InputDropThrottle,
 }
 
@@ -344,20 +537,24 @@ fn roster_entry_state(
 #[derive(Clone, Copy)]
 struct ServiceRuntimeInit<'a> {
     restart_policy: &'a service::RestartPolicy,
+    restart_backoff: RestartBackoffSpec,
     startup_mode: StartupMode,
+    schedule: Option<&'a RunSchedule>,
 }
 
 impl<'a> From<&'a Service> for ServiceRuntimeInit<'a> {
     fn from(service: &'a Service) -> Self {
         Self {
             restart_policy: &service.spec.restart,
+            restart_backoff: service.spec.restart_backoff,
             startup_mode: service.startup_mode,
+            schedule: service.spec.schedule.as_ref(),
         }
     }
 }
 
 impl ServiceRuntime {
-    fn new(init: ServiceRuntimeInit<'_>) -> Self {
+    fn new(init: &ServiceRuntimeInit<'_>) -> Self {
         let desired = match init.startup_mode {
             StartupMode::Enabled => DesiredState::Enabled,
             StartupMode::Disabled => DesiredState::Disabled,
@@ -373,24 +570,52 @@ impl ServiceRuntime {
             clear_logs_on_start: false,
             next_run_id: 0,
             running: None,
-            restart: RestartTracker::new(init.restart_policy),
+            restart: RestartTracker::new(init.restart_policy, &init.restart_backoff),
             state,
             last_run_id: None,
             uptime_started_at: None,
             started_at_unix_ms: None,
             last_exit_code: None,
             run_config: None,
+            allocated_ports: Vec::new(),
             draining_log_readers: Vec::new(),
             retired: None,
             retired_at_unix_ms: None,
             expires_at: None,
             blocked_on: Vec::new(),
+            port_conflict: None,
+            port_recheck_at: None,
+            dependency_runs: HashMap::new(),
+            startup_deadline: None,
+            runtime_deadline: None,
+            startup_timed_out: false,
+            timed_out_dependencies: Vec::new(),
+            unhealthy_restart: false,
+            pending_start: None,
+            post_stop: None,
+            post_stop_run: None,
+            schedule: init.schedule.cloned(),
+            next_tick: None,
+            external_probe: None,
             input_drops: InputDropThrottle::default(),
         }
     }
 
-    fn reconfigure(&mut self, policy: &service::RestartPolicy) {
-        self.restart.reconfigure(policy);
+    fn reconfigure(&mut self, spec: &ServiceSpec) {
+        self.restart
+            .reconfigure(&spec.restart, &spec.restart_backoff);
+        if self.schedule != spec.schedule {
+            self.schedule.clone_from(&spec.schedule);
+            self.next_tick = None;
+        }
+        if self
+            .external_probe
+            .as_ref()
+            .is_some_and(|probe| spec.healthcheck.as_ref() != Some(&probe.healthcheck))
+        {
+            self.stop_external_probe();
+            self.state = State::Pending;
+        }
     }
 
     fn is_live(&self) -> bool {
@@ -402,7 +627,10 @@ impl ServiceRuntime {
     }
 
     fn current_run_id(&self) -> Option<RunId> {
-        self.running.as_ref().map(|running| running.run_id)
+        self.running
+            .as_ref()
+            .map(|running| running.run_id)
+            .or(self.external_probe.as_ref().map(|probe| probe.run_id))
     }
 
     /// The public run generation: the current run's id if running, else the last run's id, else 0
@@ -411,6 +639,8 @@ impl ServiceRuntime {
         self.running
             .as_ref()
             .map(|running| running.run_id)
+            .or(self.external_probe.as_ref().map(|probe| probe.run_id))
+            .or(self.pending_start.as_ref().map(|pending| pending.run_id))
             .or(self.last_run_id)
             .map_or(0, RunId::get)
     }
@@ -442,6 +672,10 @@ impl ServiceRuntime {
             self.state = State::Running {
                 health: Some(health),
             };
+            if health == Health::Healthy {
+                self.startup_deadline = None;
+                self.startup_timed_out = false;
+            }
             true
         } else {
             false
@@ -460,13 +694,26 @@ impl ServiceRuntime {
         RunId::new(self.next_run_id)
     }
 
+    /// Drop a start still waiting on its `pre_start` hooks, which cancels them. Returns whether
+    /// there was one.
+    fn abandon_pending_start(&mut self) -> bool {
+        let Some(pending) = self.pending_start.take() else {
+            return false;
+        };
+        self.last_run_id = Some(pending.run_id);
+        true
+    }
+
     fn request_restart(&mut self) {
         self.desired = DesiredState::Enabled;
         self.start_requested = true;
         self.clear_logs_on_start = true;
         self.restart.clear_backoff();
         self.restart.reset_failure_budget();
-        if matches!(self.state, State::Disabled) && self.running.is_none() {
+        self.restart.clear_crash_loop();
+        if self.abandon_pending_start()
+            || (matches!(self.state, State::Disabled) && self.running.is_none())
+        {
             self.state = State::Pending;
         }
         if let Some(running) = &mut self.running {
@@ -479,12 +726,16 @@ impl ServiceRuntime {
         self.desired = DesiredState::Enabled;
         self.restart.clear_backoff();
         self.restart.reset_failure_budget();
+        self.restart.clear_crash_loop();
 
-        if self.running.is_some() && !matches!(self.state, State::Disabled | State::Killed) {
+        if (self.running.is_some() && !matches!(self.state, State::Disabled | State::Killed))
+            || self.pending_start.is_some()
+        {
             return;
         }
 
-        self.start_requested = true;
+        // A scheduled service resumes its schedule rather than starting a run right away.
+        self.start_requested = self.schedule.is_none();
         self.clear_logs_on_start = false;
         if matches!(self.state, State::Disabled) && self.running.is_none() {
             self.state = State::Pending;
@@ -500,12 +751,26 @@ impl ServiceRuntime {
         self.start_requested = false;
         self.clear_logs_on_start = false;
         self.restart.clear_backoff();
+        self.next_tick = None;
         self.state = State::Disabled;
+        self.abandon_pending_start();
+        self.stop_external_probe();
         if let Some(running) = &mut self.running {
             running.cancel();
         }
     }
 
+    /// Stop probing an external service, keeping its probe loop as the latest run.
+    fn stop_external_probe(&mut self) {
+        let Some(probe) = self.external_probe.take() else {
+            return;
+        };
+        probe.terminate.cancel();
+        self.last_run_id = Some(probe.run_id);
+        self.uptime_started_at = None;
+        self.started_at_unix_ms = None;
+    }
+
     fn finish_current_run(&mut self, policy: &service::RestartPolicy, exit_code: i32) {
         // Preserve the generation and exit code before dropping the run handle so an exited or
         // disabled service can still be projected with an accurate `run_generation`/`last_exit_code`.
@@ -513,7 +778,8 @@ impl ServiceRuntime {
             self.finish_run_state(policy, exit_code, None, false);
             return;
         };
-        let (finished_run_id, stable, log_reader) = running.finish();
+        let (finished_run_id, uptime, log_reader) = running.finish();
+        let stable = uptime >= self.restart.backoff.reset_after();
         if let Some(log_reader) = log_reader {
             self.draining_log_readers.push(DrainingLogReader {
                 run_id: finished_run_id,
@@ -574,6 +840,8 @@ impl ServiceRuntime {
         if let Some(run_id) = finished_run_id {
             self.last_run_id = Some(run_id);
         }
+        self.startup_deadline = None;
+        self.runtime_deadline = None;
         self.uptime_started_at = None;
         self.started_at_unix_ms = None;
         self.last_exit_code = Some(exit_code);
@@ -585,7 +853,9 @@ impl ServiceRuntime {
             if self.start_requested {
                 self.restart.clear_backoff();
             } else if self.will_auto_restart(policy, exit_code) {
-                self.restart.apply_backoff(stable);
+                if !self.restart.record_crash() {
+                    self.restart.apply_backoff(stable);
+                }
             } else {
                 self.restart.clear_backoff();
             }
@@ -593,11 +863,14 @@ impl ServiceRuntime {
     }
 
     fn will_auto_restart(&self, policy: &service::RestartPolicy, exit_code: i32) -> bool {
+        if self.restart.crash_loop || self.schedule.is_some() {
+            return false;
+        }
         match policy {
             service::RestartPolicy::Always | service::RestartPolicy::UnlessStopped => true,
-            service::RestartPolicy::Never => false,
+            service::RestartPolicy::Never => self.unhealthy_restart,
             service::RestartPolicy::OnFailure { max_attempts } => {
-                if exit_code == 0 {
+                if exit_code == 0 && !self.unhealthy_restart {
                     false
                 } else if max_attempts.is_none() {
                     true
@@ -618,14 +891,23 @@ pub(super) fn project_snapshot(
     service: &Service,
     runtime: &ServiceRuntime,
 ) -> (ServiceSnapshot, Option<std::time::Instant>) {
-    let running = runtime.running.is_some();
+    let running = runtime.running.is_some() || runtime.external_probe.is_some();
     let ran_before = runtime.last_run_id.is_some();
-    let execution = project_execution(
+    let paused = runtime.running.as_ref().is_some_and(RunningService::paused);
+    let execution = match project_execution(
         running,
         &runtime.state,
         ran_before,
-        !runtime.blocked_on.is_empty(),
-    );
+        !runtime.blocked_on.is_empty()
+            || runtime
+                .port_conflict
+                .as_ref()
+                .is_some_and(|conflict| conflict.waiting),
+    ) {
+        // A stop request continues the group, so only a live, undisturbed run reads as paused.
+        Execution::Running if paused => Execution::Paused,
+        execution => execution,
+    };
     let health = match (execution, &runtime.state) {
         (Execution::Running, State::Running { health }) => *health,
         _ => None,
@@ -637,6 +919,7 @@ pub(super) fn project_snapshot(
         id: service.id.clone(),
         name: service.display_name().to_string(),
         origin: match service.origin {
+            ServiceOrigin::Configured if service.external => OriginKind::External,
             ServiceOrigin::Configured => OriginKind::Configured,
             ServiceOrigin::Dynamic(_) => OriginKind::Dynamic,
         },
@@ -660,10 +943,16 @@ pub(super) fn project_snapshot(
         run_generation: runtime.run_generation(),
         pid: runtime.running.as_ref().and_then(|running| running.pid),
         started_at_unix_ms: runtime.started_at_unix_ms,
-        advertised_ports: run_config.advertised_ports.clone(),
+        advertised_ports: run_config
+            .advertised_ports
+            .iter()
+            .chain(&runtime.allocated_ports)
+            .copied()
+            .collect(),
         healthcheck_configured: run_config.healthcheck.is_some(),
         healthcheck: run_config.healthcheck.clone(),
         stop_grace_period: run_config.stop_grace_period,
+        stop_signal: run_config.stop_signal,
         config_stale: runtime
             .run_config
             .as_ref()
@@ -674,6 +963,12 @@ pub(super) fn project_snapshot(
         working_dir: run_config.working_dir.clone(),
         uptime: None,
         restart_policy: service.spec.restart.clone(),
+        replica_group: service.replica_group.clone(),
+        port_conflict: runtime.port_conflict.clone(),
+        startup_timed_out: runtime.startup_timed_out,
+        timed_out_dependencies: runtime.timed_out_dependencies.clone(),
+        schedule: runtime.schedule.as_ref().map(ToString::to_string),
+        next_run_at_unix_ms: runtime.next_tick.map(|tick| tick.at_unix_ms),
     };
     (snapshot, runtime.uptime_started_at)
 }
@@ -717,6 +1012,10 @@ fn service_event(
         pid: None,
         delay_ms: None,
         blocked_on: None,
+        changed_paths: None,
+        signal: None,
+        caused_by: None,
+        probe_output: None,
     }
 }
 
@@ -765,22 +1064,29 @@ fn project_execution(running: bool, state: &State, ran_before: bool, blocked: bo
     }
 }
 
-fn load_services_from_disk(reload: &ReloadConfig) -> Result<ServiceMap, String> {
-    let raw = crate::config::read_config_file(&reload.config_path)
-        .map_err(|err| format!("read {}: {err}", reload.config_path.display()))?;
-    let config_dir = reload
-        .config_path
-        .parent()
-        .ok_or_else(|| format!("{} has no parent directory", reload.config_path.display()))?;
+fn load_config_from_disk(
+    reload: &ReloadConfig,
+) -> Result<crate::config::ConfigFile<crate::diagnostics::FileId>, String> {
+    // Same composition as session startup: `include:` files and the override file are merged.
+    let mut files = codespan_reporting::files::SimpleFiles::new();
     let mut diagnostics = Vec::new();
-    let config = crate::config::from_str(
-        &raw,
-        config_dir,
-        0usize,
+    let mut config = crate::config::load_config_file(
+        &reload.config_path,
         reload.strict_override,
+        &mut files,
         &mut diagnostics,
     )
-    .map_err(|err| format!("parse {}: {err}", reload.config_path.display()))?;
+    .map_err(|err| match err {
+        crate::config::LoadError::Io { path, source } => {
+            format!("read {}: {source}", path.display())
+        }
+        crate::config::LoadError::Config { file_id, source } => {
+            let file = codespan_reporting::files::Files::name(&files, file_id)
+                .unwrap_or_else(|_| reload.config_path.display().to_string());
+            format!("parse {file}: {source}")
+        }
+    })?;
+    config.profiles.clone_from(&reload.profiles);
     let errors = diagnostics
         .iter()
         .filter(|diagnostic| diagnostic.severity == Severity::Error)
@@ -793,19 +1099,95 @@ fn load_services_from_disk(reload: &ReloadConfig) -> Result<ServiceMap, String>
             errors.join("; ")
         ));
     }
-    let services = crate::service_map_from_config(&config)
+    // A scale survives reloads until the file's own count for the group changes.
+    let services = &config.config.services;
+    let scaled = reload
+        .replicas
+        .iter()
+        .filter(|(group, scaled)| {
+            replicas::count(services, &BTreeMap::new(), group) == Some(scaled.configured)
+                && replicas::check(services, group, scaled.replicas).is_ok()
+        })
+        .map(|(group, scaled)| (group.clone(), scaled.replicas))
+        .collect();
+    config.replicas = scaled;
+    Ok(config)
+}
+
+fn services_from_config(
+    config: &crate::config::ConfigFile<crate::diagnostics::FileId>,
+    reload: &ReloadConfig,
+) -> Result<ServiceMap, String> {
+    let services = crate::service_map_from_config(config)
         .map_err(|err| format!("normalize {}: {err}", reload.config_path.display()))?;
     ServiceGraph::new(&services)
         .map_err(|err| format!("validate {}: {err}", reload.config_path.display()))?;
     Ok(services)
 }
 
+fn load_services_from_disk(reload: &ReloadConfig) -> Result<ServiceMap, String> {
+    let config = load_config_from_disk(reload)?;
+    services_from_config(&config, reload)
+}
+
+/// Load the config with replica group `group` scaled to `replicas`, on top of the session's other
+/// scales. The outer error is a failed load; the inner one rejects the scale itself.
+fn scale_services_from_disk(
+    reload: &ReloadConfig,
+    group: &str,
+    replicas: usize,
+) -> Result<Result<(ServiceMap, ScaledReplicas), CommandRejection>, String> {
+    let mut config = load_config_from_disk(reload)?;
+    let services = &config.config.services;
+    let Some(configured) = replicas::count(services, &BTreeMap::new(), group) else {
+        return Ok(Err(if services.keys().any(|name| name.as_str() == group) {
+            CommandRejection::InvalidState(format!(
+                "`{group}` is not a replica group; set `replicas` on it in the config"
+            ))
+        } else {
+            CommandRejection::UnknownService
+        }));
+    };
+    if let Err(conflict) = replicas::check(services, group, replicas) {
+        return Ok(Err(CommandRejection::InvalidSpec(conflict.to_string())));
+    }
+    config.replicas.insert(group.to_string(), replicas);
+    let services = services_from_config(&config, reload)?;
+    Ok(Ok((
+        services,
+        ScaledReplicas {
+            configured,
+            replicas,
+        },
+    )))
+}
+
 async fn load_services_from_disk_async(reload: ReloadConfig) -> Result<ServiceMap, String> {
     tokio::task::spawn_blocking(move || load_services_from_disk(&reload))
         .await
         .map_err(|err| format!("config reload task failed: {err}"))?
 }
 
+async fn scale_services_from_disk_async(
+    reload: ReloadConfig,
+    group: String,
+    replicas: usize,
+) -> Result<Result<(ServiceMap, ScaledReplicas), CommandRejection>, String> {
+    tokio::task::spawn_blocking(move || scale_services_from_disk(&reload, &group, replicas))
+        .await
+        .map_err(|err| format!("config reload task failed: {err}"))?
+}
+
+/// The first configured service whose `external` flag differs between `current` and `updated`.
+fn changed_external<'a>(current: &ServiceMap, updated: &'a ServiceMap) -> Option<&'a ServiceID> {
+    updated.iter().find_map(|(id, service)| {
+        current
+            .get(id)
+            .is_some_and(|previous| previous.external != service.external)
+            .then_some(id)
+    })
+}
+
 fn validate_reloaded_services(
     current: &ServiceMap,
     runtimes: &HashMap<ServiceID, ServiceRuntime>,
@@ -820,6 +1202,11 @@ fn validate_reloaded_services(
             "`{id}` exists as a dynamic service in this session; rename it or restart the session to discard dynamic entries"
         ));
     }
+    if let Some(id) = changed_external(current, updated) {
+        return Err(format!(
+            "`{id}` changed whether it is external; restart the session to apply it"
+        ));
+    }
     let configured = current
         .iter()
         .filter_map(|(id, service)| {
@@ -890,10 +1277,23 @@ struct SchedulerRuntime {
     test_events: TestEventSink,
     writer: SessionModelWriter,
     shutdown: CancellationToken,
+    /// Watched by every spawned process in place of `shutdown`, so session shutdown can stop
+    /// services one dependency tier at a time.
+    stop_processes: CancellationToken,
+    shutdown_order: ShutdownOrder,
     config_dir: PathBuf,
     dynamic_policy: DynamicServicesPolicy,
     default_log_retention: LogRetention,
     idempotency: VecDeque<IdempotencyRecord>,
+    watch_tx: mpsc::Sender<WatchTrigger>,
+    watchers: HashMap<ServiceID, ServiceWatcher>,
+    ports: ports::PortAllocator,
+    /// On-demand tasks, latched when the session started; config reloads do not change them.
+    tasks: TaskMap,
+    /// Bounds `post_stop` hooks, which outlive the shutdown token.
+    cleanup: crate::hooks::Cleanup,
+    /// Task runs in flight; finished handles are pruned as new runs start.
+    task_runs: Vec<tokio::task::JoinHandle<()>>,
 }
 
 struct SchedulerResources {
@@ -903,9 +1303,13 @@ struct SchedulerResources {
     test_events: TestEventSink,
     writer: SessionModelWriter,
     shutdown: CancellationToken,
+    shutdown_order: ShutdownOrder,
     config_dir: PathBuf,
     dynamic_policy: DynamicServicesPolicy,
     default_log_retention: LogRetention,
+    watch_tx: mpsc::Sender<WatchTrigger>,
+    tasks: TaskMap,
+    cleanup: crate::hooks::Cleanup,
 }
 
 #[derive(Clone)]
@@ -924,7 +1328,7 @@ impl SchedulerRuntime {
             .map(|(service_id, service)| {
                 (
                     service_id.clone(),
-                    ServiceRuntime::new(ServiceRuntimeInit::from(service)),
+                    ServiceRuntime::new(&ServiceRuntimeInit::from(service)),
                 )
             })
             .collect();
@@ -935,9 +1339,13 @@ impl SchedulerRuntime {
             test_events,
             writer,
             shutdown,
+            shutdown_order,
             config_dir,
             dynamic_policy,
             default_log_retention,
+            watch_tx,
+            tasks,
+            cleanup,
         } = resources;
 
         Self {
@@ -954,10 +1362,18 @@ impl SchedulerRuntime {
             test_events,
             writer,
             shutdown,
+            stop_processes: CancellationToken::new(),
+            shutdown_order,
             config_dir,
             dynamic_policy,
             default_log_retention,
             idempotency: VecDeque::new(),
+            watch_tx,
+            watchers: HashMap::new(),
+            ports: ports::PortAllocator::default(),
+            tasks,
+            cleanup,
+            task_runs: Vec::new(),
         }
     }
 
@@ -1083,7 +1499,9 @@ impl SchedulerRuntime {
         commands_rx: &mut mpsc::Receiver<Command>,
         pty_input_rx: &mut mpsc::Receiver<PreparedPtyInput>,
         events_rx: &mut mpsc::Receiver<ProcessEvent>,
+        watch_rx: &mut mpsc::Receiver<WatchTrigger>,
     ) {
+        self.sync_watchers(services);
         tracing::debug!("started initial scheduling pass");
         self.schedule_pass(services, events_rx).await;
         tracing::debug!("completed initial scheduling pass");
@@ -1092,8 +1510,9 @@ impl SchedulerRuntime {
         loop {
             tracing::debug!("waiting for scheduling event");
             // Snapshot time-based work before selecting. Without explicit timer arms, restart
-            // backoffs, lease expiry, and capped reader drains would wait for an unrelated event.
-            let next_backoff = self.next_backoff();
+            // backoffs, port rechecks, startup timeouts, maximum runtimes, scheduled ticks, lease
+            // expiry, and capped reader drains would wait for an unrelated event.
+            let next_timer_deadline = self.next_timer_deadline();
             let next_expiry = self.next_expiry();
             let next_drain_deadline = self.next_drain_deadline();
             let next_input_drop_report = self.next_input_drop_report();
@@ -1133,8 +1552,14 @@ impl SchedulerRuntime {
                     };
                     self.handle_event(services, &event)
                 }
+                trigger = watch_rx.recv() => {
+                    let Some(trigger) = trigger else {
+                        break;
+                    };
+                    self.apply_watch_trigger(services, events_rx, trigger).await
+                }
                 () = async {
-                    match next_backoff {
+                    match next_timer_deadline {
                         Some(deadline) => tokio::time::sleep_until(deadline).await,
                         None => std::future::pending::<()>().await,
                     }
@@ -1172,6 +1597,9 @@ impl SchedulerRuntime {
             // so enforce the bounded tombstone roster after every scheduler wake rather than only
             // on insertion.
             self.evict_retired(services);
+            // Reloads, reconciliation, and dynamic mutations can all change which services are
+            // watched; diffing here keeps every one of those paths covered.
+            self.sync_watchers(services);
             if needs_schedule {
                 self.schedule_pass(services, events_rx).await;
             }
@@ -1203,6 +1631,23 @@ impl SchedulerRuntime {
         }
     }
 
+    /// Rejection for operating a retired service. A retired replica is revived through its group.
+    fn retired_rejection(
+        services: &ServiceMap,
+        service_id: &ServiceID,
+        reason: RetiredReason,
+    ) -> CommandRejection {
+        let group = services
+            .get(service_id)
+            .and_then(|service| service.replica_group.as_deref());
+        CommandRejection::InvalidState(match (group, reason) {
+            (Some(group), RetiredReason::Removed) => format!(
+                "the replica was scaled down or removed from micromux.yaml; scale `{group}` up or re-add it to revive it"
+            ),
+            _ => Self::retired_guidance(reason).to_string(),
+        })
+    }
+
     fn validate_service_id(id: &str) -> Result<(), CommandRejection> {
         if crate::spec::service_id_is_valid(id) {
             Ok(())
@@ -1327,6 +1772,9 @@ impl SchedulerRuntime {
             ServiceSpec::default()
         };
         let mut spec = params.spec.clone().apply_to(base);
+        // Watching is tied to the config file's working tree; a dynamic clone of a watched
+        // service must not restart whenever its source service's files change.
+        spec.watch = None;
         spec.normalize()
             .map_err(|err| CommandRejection::InvalidSpec(err.to_string()))?;
         spec.command.extend(params.extra_args.clone());
@@ -1504,7 +1952,7 @@ impl SchedulerRuntime {
         .map_err(|err| CommandRejection::InvalidSpec(err.to_string()))?;
         self.validate_candidate(services, &service)?;
 
-        let mut runtime = ServiceRuntime::new(ServiceRuntimeInit::from(&service));
+        let mut runtime = ServiceRuntime::new(&ServiceRuntimeInit::from(&service));
         runtime.expires_at = expires_at;
         runtime.request_enable();
         let (snapshot, _) = project_snapshot(&service, &runtime);
@@ -1601,7 +2049,7 @@ impl SchedulerRuntime {
         runtime.retired = None;
         runtime.retired_at_unix_ms = None;
         runtime.expires_at = expires_at;
-        runtime.reconfigure(&spec.restart);
+        runtime.reconfigure(&spec);
         runtime.request_restart();
         let service = services
             .get_mut(service_id)
@@ -1826,14 +2274,14 @@ impl SchedulerRuntime {
         }
     }
 
-    async fn await_loaded_services<F>(
+    async fn await_loaded_services<F, T>(
         &mut self,
         services: &ServiceMap,
         events_rx: &mut mpsc::Receiver<ProcessEvent>,
         load: F,
-    ) -> Result<ServiceMap, String>
+    ) -> Result<T, String>
     where
-        F: Future<Output = Result<ServiceMap, String>>,
+        F: Future<Output = Result<T, String>>,
     {
         // Process exits must keep freeing event-channel capacity during a slow reload, while
         // shutdown must stop waiting so service termination is not coupled to filesystem liveness.
@@ -1908,7 +2356,7 @@ impl SchedulerRuntime {
                 .get(service_id)
                 .filter(|service| matches!(service.origin, ServiceOrigin::Configured))
             {
-                runtime.reconfigure(&service.spec.restart);
+                runtime.reconfigure(&service.spec);
                 self.writer
                     .reconfigure_log_retention(service_id, service.log_retention);
             }
@@ -1998,6 +2446,11 @@ impl SchedulerRuntime {
                 "service `{service_id}` exists as a live or retired dynamic service; stop it or rename the configured service"
             )));
         }
+        if let Some(service_id) = changed_external(services, updated) {
+            return Err(CommandRejection::InvalidSpec(format!(
+                "service `{service_id}` changed whether it is external; restart the session to apply it"
+            )));
+        }
 
         let removed = actions
             .iter()
@@ -2045,11 +2498,14 @@ impl SchedulerRuntime {
             .map_err(|err| CommandRejection::InvalidSpec(err.to_string()))
     }
 
+    /// Apply `actions` computed against `updated`. `cause` names what produced them in the
+    /// service events.
     fn apply_reconcile(
         &mut self,
         services: &mut ServiceMap,
         updated: &ServiceMap,
         actions: &[ReconcileAction],
+        cause: &str,
     ) {
         let by_service = actions
             .iter()
@@ -2070,7 +2526,7 @@ impl SchedulerRuntime {
                             runtime.retired = None;
                             runtime.retired_at_unix_ms = None;
                             runtime.expires_at = None;
-                            runtime.reconfigure(&service.spec.restart);
+                            runtime.reconfigure(&service.spec);
                             match service.startup_mode {
                                 StartupMode::Enabled => runtime.request_restart(),
                                 StartupMode::Disabled => runtime.disable(),
@@ -2081,7 +2537,7 @@ impl SchedulerRuntime {
                             .reconfigure_log_retention(service_id, service.log_retention);
                         self.sync(services, service_id);
                     } else {
-                        let runtime = ServiceRuntime::new(ServiceRuntimeInit::from(service));
+                        let runtime = ServiceRuntime::new(&ServiceRuntimeInit::from(service));
                         let (snapshot, _) = project_snapshot(service, &runtime);
                         services.insert(service_id.clone(), service.clone());
                         self.services.insert(service_id.clone(), runtime);
@@ -2091,15 +2547,15 @@ impl SchedulerRuntime {
                         service_id,
                         ServiceEventKind::Created,
                         if reviving {
-                            "configured service revived by config reconciliation"
+                            format!("configured service revived by {cause}")
                         } else {
-                            "configured service added by config reconciliation"
+                            format!("configured service added by {cause}")
                         },
                     );
                 }
                 ReconcileActionKind::Changed => {
                     if let Some(runtime) = self.services.get_mut(service_id) {
-                        runtime.reconfigure(&service.spec.restart);
+                        runtime.reconfigure(&service.spec);
                     }
                     services.insert(service_id.clone(), service.clone());
                     self.writer
@@ -2108,7 +2564,7 @@ impl SchedulerRuntime {
                     self.append_event(
                         service_id,
                         ServiceEventKind::ConfigReloaded,
-                        format!("config reconciliation {}", action.detail),
+                        format!("{cause} {}", action.detail),
                     );
                 }
                 ReconcileActionKind::Removed => {}
@@ -2129,7 +2585,7 @@ impl SchedulerRuntime {
             self.append_event(
                 service_id,
                 ServiceEventKind::Retired,
-                "configured service retired because it was removed by config reconciliation",
+                format!("configured service retired because it was removed by {cause}"),
             );
         }
     }
@@ -2155,7 +2611,7 @@ impl SchedulerRuntime {
         // reject (dynamic-id collision, orphaned dependents, graph failure) is not a preview.
         self.validate_reconcile_candidate(services, &updated, &actions)?;
         if !dry_run {
-            self.apply_reconcile(services, &updated, &actions);
+            self.apply_reconcile(services, &updated, &actions, "config reconciliation");
         }
         Ok(ReconcileReceipt {
             config_path,
@@ -2164,6 +2620,61 @@ impl SchedulerRuntime {
         })
     }
 
+    /// Scale replica group `group` to `replicas` instances. Instances dropped by a scale-down
+    /// retire like services removed from the config; scaling back up revives them.
+    ///
+    /// Only the group's instances are added or retired: other pending config edits wait for
+    /// `reconcile_config`. The count lasts for the session unless the file changes the group's
+    /// own `replicas`.
+    async fn scale_replicas(
+        &mut self,
+        services: &mut ServiceMap,
+        events_rx: &mut mpsc::Receiver<ProcessEvent>,
+        group: &str,
+        replicas: usize,
+    ) -> Result<ReconcileReceipt, CommandRejection> {
+        let reload = self.reload_config.clone().ok_or_else(|| {
+            CommandRejection::ConfigReload(
+                "session has no config path; replica scaling is unavailable".to_string(),
+            )
+        })?;
+        let config_path = reload.config_path.display().to_string();
+        let (updated, scaled) = self
+            .await_loaded_services(
+                services,
+                events_rx,
+                scale_services_from_disk_async(reload, group.to_string(), replicas),
+            )
+            .await
+            .map_err(CommandRejection::ConfigReload)??;
+        let actions = self
+            .reconcile_actions(services, &updated)
+            .into_iter()
+            .filter(|action| {
+                action.action != ReconcileActionKind::Changed
+                    && updated
+                        .get(&action.service)
+                        .or_else(|| services.get(&action.service))
+                        .is_some_and(|service| service.replica_group.as_deref() == Some(group))
+            })
+            .collect::<Vec<_>>();
+        self.validate_reconcile_candidate(services, &updated, &actions)?;
+        self.apply_reconcile(services, &updated, &actions, "replica scaling");
+        if let Some(reload) = &mut self.reload_config {
+            if scaled.replicas == scaled.configured {
+                reload.replicas.remove(group);
+            } else {
+                reload.replicas.insert(group.to_string(), scaled);
+            }
+        }
+        tracing::info!(group, replicas, "scaled replica group");
+        Ok(ReconcileReceipt {
+            config_path,
+            dry_run: false,
+            actions,
+        })
+    }
+
     fn has_due_auto_restart(&self, services: &ServiceMap) -> bool {
         let now = tokio::time::Instant::now();
         services.iter().any(|(service_id, service)| {
@@ -2214,6 +2725,67 @@ impl SchedulerRuntime {
         }
     }
 
+    /// Restart runs that outlived their `max_runtime` through the manual-restart path: the latest
+    /// service definitions are reloaded, the run is stopped gracefully, and a fresh one starts once
+    /// it has exited.
+    async fn restart_overrun_runs(
+        &mut self,
+        services: &mut ServiceMap,
+        events_rx: &mut mpsc::Receiver<ProcessEvent>,
+    ) {
+        let now = tokio::time::Instant::now();
+        let mut overrun = Vec::new();
+        for (service_id, runtime) in &mut self.services {
+            if runtime
+                .runtime_deadline
+                .is_none_or(|deadline| deadline > now)
+            {
+                continue;
+            }
+            runtime.runtime_deadline = None;
+            let max_runtime = services
+                .get(service_id)
+                .and_then(|service| service.spec.max_runtime);
+            if let Some(max_runtime) = max_runtime
+                && runtime.desired == DesiredState::Enabled
+                && runtime.running.is_some()
+                && matches!(runtime.state, State::Starting | State::Running { .. })
+            {
+                overrun.push((service_id.clone(), max_runtime));
+            }
+        }
+        if overrun.is_empty() {
+            return;
+        }
+        if let Err(err) = self.reload_services(services, events_rx).await
+            && !self.shutdown.is_cancelled()
+        {
+            tracing::warn!(
+                ?err,
+                "config reload before max_runtime restart failed; keeping previous definitions"
+            );
+        }
+        for (service_id, max_runtime) in overrun {
+            let Some(runtime) = self.services.get_mut(&service_id) else {
+                continue;
+            };
+            // The run may have exited on its own while the config was reloading.
+            if runtime.running.is_none() || runtime.desired == DesiredState::Disabled {
+                continue;
+            }
+            runtime.request_restart();
+            self.sync(services, &service_id);
+            self.append_event(
+                &service_id,
+                ServiceEventKind::MaxRuntimeExceeded,
+                format!(
+                    "service ran longer than its max_runtime of {}; restarting it",
+                    humantime::format_duration(max_runtime)
+                ),
+            );
+        }
+    }
+
     async fn schedule_pass(
         &mut self,
         services: &mut ServiceMap,
@@ -2223,7 +2795,18 @@ impl SchedulerRuntime {
         if self.shutdown.is_cancelled() {
             return;
         }
-        schedule::schedule_ready(&mut schedule::ScheduleContext {
+        self.restart_overrun_runs(services, events_rx).await;
+        if self.shutdown.is_cancelled() {
+            return;
+        }
+        schedule::schedule_ready(&mut self.schedule_context(services));
+    }
+
+    fn schedule_context<'a>(
+        &'a mut self,
+        services: &'a ServiceMap,
+    ) -> schedule::ScheduleContext<'a> {
+        schedule::ScheduleContext {
             services,
             runtimes: &mut self.services,
             current_pty_size: self.current_pty_size,
@@ -2231,8 +2814,9 @@ impl SchedulerRuntime {
             #[cfg(test)]
             test_events: &mut self.test_events,
             writer: &self.writer,
-            shutdown: &self.shutdown,
-        });
+            shutdown: &self.stop_processes,
+            ports: &mut self.ports,
+        }
     }
 
     fn reply(ack: Option<CommandAck>, result: ServiceCommandResult) {
@@ -2259,6 +2843,41 @@ impl SchedulerRuntime {
         }
     }
 
+    fn reply_task(ack: Option<control::TaskCommandAck>, result: TaskRunResult) {
+        match ack {
+            Some(ack) => ack.send(result),
+            None => {
+                if let Err(rejection) = result {
+                    tracing::debug!(?rejection, "fire-and-forget task command rejected");
+                }
+            }
+        }
+    }
+
+    /// Start a run of an on-demand task in the background. A task never runs concurrently with
+    /// itself, and session shutdown kills a run still in progress.
+    fn run_task(&mut self, name: &str) -> TaskRunResult {
+        let Some(task) = self.tasks.get(name) else {
+            return Err(CommandRejection::UnknownTask);
+        };
+        let Some(sink) = self.writer.begin_task_run(name) else {
+            return Err(CommandRejection::InvalidState(
+                "the task is already running".to_string(),
+            ));
+        };
+        let ack = TaskRunAck {
+            task: task.name.clone(),
+            run_generation: sink.run_generation(),
+        };
+        let task = task.clone();
+        let cancel = self.shutdown.child_token();
+        self.task_runs.retain(|run| !run.is_finished());
+        self.task_runs.push(tokio::spawn(async move {
+            crate::task::run(&task, sink, &cancel).await;
+        }));
+        Ok(ack)
+    }
+
     /// Restart a service, latching the run generation *before* the restart. Restarting a disabled
     /// service is invalid for every caller: `enable` is the operation that starts disabled services.
     async fn apply_restart(
@@ -2275,9 +2894,7 @@ impl SchedulerRuntime {
             .get(service_id)
             .ok_or(CommandRejection::UnknownService)?;
         if let Some(reason) = runtime.retired {
-            return Err(CommandRejection::InvalidState(
-                Self::retired_guidance(reason).to_string(),
-            ));
+            return Err(Self::retired_rejection(services, service_id, reason));
         }
         if runtime.desired == DesiredState::Disabled {
             return Err(CommandRejection::InvalidState(
@@ -2321,9 +2938,7 @@ impl SchedulerRuntime {
             .get(service_id)
             .and_then(|runtime| runtime.retired)
         {
-            return Err(CommandRejection::InvalidState(
-                Self::retired_guidance(reason).to_string(),
-            ));
+            return Err(Self::retired_rejection(services, service_id, reason));
         }
         self.reload_services(services, events_rx).await?;
         let runtime = self
@@ -2366,6 +2981,89 @@ impl SchedulerRuntime {
         }])
     }
 
+    /// Signal a running service without touching its desired state or restart bookkeeping.
+    fn apply_signal(
+        &self,
+        service_id: &ServiceID,
+        signal: crate::ServiceSignal,
+    ) -> ServiceCommandResult {
+        let runtime = self
+            .services
+            .get(service_id)
+            .ok_or(CommandRejection::UnknownService)?;
+        let Some(running) = runtime.running.as_ref() else {
+            return Err(CommandRejection::InvalidState(
+                "the service is not running".to_string(),
+            ));
+        };
+        let observed_generation = runtime.run_generation();
+        if !running.signal(signal) {
+            return Err(CommandRejection::InvalidState(format!(
+                "{signal} could not be delivered to the service process"
+            )));
+        }
+        tracing::info!(service_id, %signal, "sent signal to service");
+        let mut signaled = service_event(
+            observed_generation,
+            ServiceEventKind::Signaled,
+            format!("sent {signal} to the service process"),
+        );
+        signaled.signal = Some(signal);
+        self.writer.append_event(service_id, signaled);
+        Ok(vec![ServiceCommandAck {
+            service: service_id.clone(),
+            observed_generation,
+        }])
+    }
+
+    /// Suspend or continue a running service's process group. Desired state and restart
+    /// bookkeeping are untouched; healthchecks wait out the pause.
+    fn apply_pause(
+        &self,
+        services: &ServiceMap,
+        service_id: &ServiceID,
+        paused: bool,
+    ) -> ServiceCommandResult {
+        let runtime = self
+            .services
+            .get(service_id)
+            .ok_or(CommandRejection::UnknownService)?;
+        let Some(running) = runtime
+            .running
+            .as_ref()
+            .filter(|_| matches!(runtime.state, State::Running { .. }))
+        else {
+            return Err(CommandRejection::InvalidState(
+                "the service is not running".to_string(),
+            ));
+        };
+        let (verb, kind) = if paused {
+            ("paused", ServiceEventKind::Paused)
+        } else {
+            ("resumed", ServiceEventKind::Resumed)
+        };
+        if running.paused() == paused {
+            return Err(CommandRejection::InvalidState(if paused {
+                "the service is already paused".to_string()
+            } else {
+                "the service is not paused".to_string()
+            }));
+        }
+        if !running.set_paused(paused) {
+            return Err(CommandRejection::InvalidState(format!(
+                "the service process could not be {verb}"
+            )));
+        }
+        let observed_generation = runtime.run_generation();
+        tracing::info!(service_id, paused, "changed service pause state");
+        self.sync(services, service_id);
+        self.append_event(service_id, kind, format!("service process group {verb}"));
+        Ok(vec![ServiceCommandAck {
+            service: service_id.clone(),
+            observed_generation,
+        }])
+    }
+
     async fn apply_restart_all(
         &mut self,
         services: &mut ServiceMap,
@@ -2373,20 +3071,32 @@ impl SchedulerRuntime {
     ) -> ServiceCommandResult {
         self.reload_services(services, events_rx).await?;
         let mut acks = Vec::new();
-        for service_id in services.keys() {
-            let restart = self
-                .services
-                .get_mut(service_id)
-                .filter(|runtime| runtime.desired == DesiredState::Enabled && runtime.is_live())
-                .map(|runtime| {
-                    let observed_generation = runtime.run_generation();
-                    runtime.request_restart();
-                    observed_generation
-                });
-            if let Some(observed_generation) = restart {
-                self.sync(services, service_id);
+        let mut tiers = self.stop_tiers(services).into_iter().peekable();
+        while let Some(tier) = tiers.next() {
+            let mut stopping = Vec::new();
+            for service_id in tier {
+                if services
+                    .get(&service_id)
+                    .is_some_and(|service| service.external)
+                {
+                    continue;
+                }
+                let restart = self
+                    .services
+                    .get_mut(&service_id)
+                    .filter(|runtime| runtime.desired == DesiredState::Enabled && runtime.is_live())
+                    .map(|runtime| {
+                        let observed_generation = runtime.run_generation();
+                        let running = runtime.running.is_some();
+                        runtime.request_restart();
+                        (observed_generation, running)
+                    });
+                let Some((observed_generation, running)) = restart else {
+                    continue;
+                };
+                self.sync(services, &service_id);
                 self.append_event(
-                    service_id,
+                    &service_id,
                     ServiceEventKind::RestartRequested,
                     "service restart requested by restart_all",
                 );
@@ -2394,11 +3104,206 @@ impl SchedulerRuntime {
                     service: service_id.clone(),
                     observed_generation,
                 });
+                if running {
+                    stopping.push(service_id);
+                }
+            }
+            // Replacements start in dependency order on the next scheduling pass, once every
+            // tier has been asked to stop.
+            if tiers.peek().is_some() && !self.await_tier_exit(services, events_rx, &stopping).await
+            {
+                break;
+            }
+        }
+        Ok(acks)
+    }
+
+    /// Running services grouped in the order `shutdown_order` stops them.
+    fn stop_tiers(&self, services: &ServiceMap) -> Vec<Vec<ServiceID>> {
+        match self.shutdown_order {
+            ShutdownOrder::Dependencies => graph::stop_tiers(services),
+            ShutdownOrder::Parallel => vec![services.keys().cloned().collect()],
+        }
+    }
+
+    /// Wait until none of `service_ids` has a live process, giving them up to their longest stop
+    /// grace period while still handling process events. Their termination tasks keep escalating
+    /// after the wait gives up. Returns `false` when session shutdown interrupted a wait that was
+    /// not itself part of shutdown.
+    async fn await_tier_exit(
+        &mut self,
+        services: &ServiceMap,
+        events_rx: &mut mpsc::Receiver<ProcessEvent>,
+        service_ids: &[ServiceID],
+    ) -> bool {
+        let interruptible = !self.shutdown.is_cancelled();
+        let running = |runtimes: &HashMap<ServiceID, ServiceRuntime>| {
+            service_ids.iter().any(|service_id| {
+                runtimes
+                    .get(service_id)
+                    .is_some_and(|runtime| runtime.running.is_some())
+            })
+        };
+        let stop_grace = service_ids
+            .iter()
+            .filter_map(|service_id| self.services.get(service_id))
+            .filter_map(|runtime| runtime.run_config.as_ref())
+            .map(|config| config.stop_grace_period)
+            .max()
+            .unwrap_or(crate::spec::DEFAULT_STOP_GRACE_PERIOD);
+        let now = tokio::time::Instant::now();
+        let deadline = now.checked_add(stop_grace).unwrap_or(now);
+        while running(&self.services) {
+            tokio::select! {
+                biased;
+                () = self.shutdown.cancelled(), if interruptible => return false,
+                () = tokio::time::sleep_until(deadline) => {
+                    tracing::warn!(
+                        services = ?service_ids,
+                        "timed out waiting for a shutdown tier to exit"
+                    );
+                    break;
+                }
+                event = events_rx.recv() => {
+                    let Some(event) = event else { break };
+                    let _ = self.handle_event(services, &event);
+                }
+            }
+        }
+        true
+    }
+
+    /// Ask every running service to stop, dependents before their dependencies unless
+    /// `shutdown_order` is `parallel`.
+    async fn stop_all(
+        &mut self,
+        services: &ServiceMap,
+        events_rx: &mut mpsc::Receiver<ProcessEvent>,
+    ) {
+        for (service_id, runtime) in &mut self.services {
+            if runtime.abandon_pending_start() {
+                runtime.state = State::Pending;
+                if let Some(service) = services.get(service_id) {
+                    sync_model(&self.writer, service, runtime);
+                }
+            }
+        }
+        for tier in self.stop_tiers(services) {
+            let mut stopping = Vec::new();
+            for service_id in tier {
+                if let Some(running) = self
+                    .services
+                    .get_mut(&service_id)
+                    .and_then(|runtime| runtime.running.as_mut())
+                {
+                    running.cancel();
+                    stopping.push(service_id);
+                }
+            }
+            if self.shutdown_order == ShutdownOrder::Dependencies {
+                self.await_tier_exit(services, events_rx, &stopping).await;
             }
         }
+        self.stop_processes.cancel();
+    }
+
+    /// Live instances of replica group `name`, in roster order. `None` when `name` is itself a
+    /// service or has no live instances, so the command addresses it as a single service.
+    fn replica_instances(&self, services: &ServiceMap, name: &str) -> Option<Vec<ServiceID>> {
+        if services.contains_key(name) {
+            return None;
+        }
+        let instances = services
+            .iter()
+            .filter(|(service_id, service)| {
+                service.replica_group.as_deref() == Some(name)
+                    && self
+                        .services
+                        .get(*service_id)
+                        .is_some_and(ServiceRuntime::is_live)
+            })
+            .map(|(service_id, _)| service_id.clone())
+            .collect::<Vec<_>>();
+        (!instances.is_empty()).then_some(instances)
+    }
+
+    /// Apply `command` to every instance of a replica group. Restart skips disabled instances, as
+    /// `restart_all` does; the first rejection stops the fan-out.
+    async fn apply_to_replicas(
+        &mut self,
+        services: &mut ServiceMap,
+        events_rx: &mut mpsc::Receiver<ProcessEvent>,
+        instances: &[ServiceID],
+        command: LifecycleCommand,
+    ) -> ServiceCommandResult {
+        let mut acks = Vec::new();
+        for service_id in instances {
+            let result = match command {
+                LifecycleCommand::Restart => {
+                    if self
+                        .services
+                        .get(service_id)
+                        .is_some_and(|runtime| runtime.desired == DesiredState::Disabled)
+                    {
+                        continue;
+                    }
+                    self.apply_restart(services, events_rx, service_id).await
+                }
+                LifecycleCommand::Enable => {
+                    self.apply_enable(services, events_rx, service_id).await
+                }
+                LifecycleCommand::Disable => self.apply_disable(services, service_id),
+            };
+            acks.extend(result?);
+        }
+        if acks.is_empty() {
+            return Err(CommandRejection::InvalidState(
+                "every replica of the group is disabled; enable the group before restarting"
+                    .to_string(),
+            ));
+        }
         Ok(acks)
     }
 
+    /// Apply `command` to `service`, or to every instance when it names a replica group.
+    async fn apply_lifecycle(
+        &mut self,
+        services: &mut ServiceMap,
+        events_rx: &mut mpsc::Receiver<ProcessEvent>,
+        service: &ServiceID,
+        command: LifecycleCommand,
+    ) -> ServiceCommandResult {
+        if let Some(instances) = self.replica_instances(services, service) {
+            return self
+                .apply_to_replicas(services, events_rx, &instances, command)
+                .await;
+        }
+        if services
+            .get(service)
+            .is_some_and(|service| service.external)
+        {
+            let action = match command {
+                LifecycleCommand::Restart => Some("restart"),
+                LifecycleCommand::Disable => Some("disable"),
+                LifecycleCommand::Enable => None,
+            };
+            if let Some(action) = action {
+                return Err(CommandRejection::InvalidState(format!(
+                    "`{service}` is an external service: micromux only probes it and cannot {action} it"
+                )));
+            }
+        }
+        match command {
+            LifecycleCommand::Restart => self.apply_restart(services, events_rx, service).await,
+            LifecycleCommand::Enable => self.apply_enable(services, events_rx, service).await,
+            LifecycleCommand::Disable => self.apply_disable(services, service),
+        }
+    }
+
+    #[expect(
+        clippy::too_many_lines,
+        reason = "one arm per command keeps the scheduler's command surface in a single dispatch"
+    )]
     async fn handle_command(
         &mut self,
         services: &mut ServiceMap,
@@ -2407,12 +3312,16 @@ impl SchedulerRuntime {
     ) -> bool {
         match command {
             Command::Restart { service, ack } => {
-                let result = self.apply_restart(services, events_rx, &service).await;
+                let result = self
+                    .apply_lifecycle(services, events_rx, &service, LifecycleCommand::Restart)
+                    .await;
                 Self::reply(ack, result);
                 true
             }
             Command::Enable { service, ack } => {
-                let result = self.apply_enable(services, events_rx, &service).await;
+                let result = self
+                    .apply_lifecycle(services, events_rx, &service, LifecycleCommand::Enable)
+                    .await;
                 Self::reply(ack, result);
                 true
             }
@@ -2422,19 +3331,54 @@ impl SchedulerRuntime {
                 true
             }
             Command::Disable { service, ack } => {
-                let result = self.apply_disable(services, &service);
+                let result = self
+                    .apply_lifecycle(services, events_rx, &service, LifecycleCommand::Disable)
+                    .await;
                 #[cfg(test)]
-                if result.is_ok() {
-                    self.test_events.forward(Event::Disabled(service.clone()));
+                if let Ok(acks) = &result {
+                    for ack in acks {
+                        self.test_events
+                            .forward(Event::Disabled(ack.service.clone()));
+                    }
                 }
                 Self::reply(ack, result);
                 true
             }
+            Command::Signal {
+                service,
+                signal,
+                ack,
+            } => {
+                let result = self.apply_signal(&service, signal);
+                Self::reply(ack, result);
+                false
+            }
+            Command::Pause { service, ack } => {
+                let result = self.apply_pause(services, &service, true);
+                Self::reply(ack, result);
+                false
+            }
+            Command::Resume { service, ack } => {
+                let result = self.apply_pause(services, &service, false);
+                Self::reply(ack, result);
+                false
+            }
             Command::ReconcileConfig { dry_run, ack } => {
                 let result = self.reconcile_config(services, events_rx, dry_run).await;
                 ack.send(result);
                 true
             }
+            Command::ScaleReplicas {
+                group,
+                replicas,
+                ack,
+            } => {
+                let result = self
+                    .scale_replicas(services, events_rx, &group, replicas)
+                    .await;
+                ack.send(result);
+                true
+            }
             Command::StartDynamic { params, ack } => {
                 let result = self.start_dynamic(services, params);
                 Self::reply_dynamic(ack, result);
@@ -2466,6 +3410,11 @@ impl SchedulerRuntime {
                 Self::reply_dynamic(ack, result);
                 true
             }
+            Command::RunTask { task, ack } => {
+                let result = self.run_task(&task);
+                Self::reply_task(ack, result);
+                false
+            }
             Command::ResizeAll { cols, rows } => {
                 self.current_pty_size = portable_pty::PtySize {
                     rows,
@@ -2505,6 +3454,90 @@ impl SchedulerRuntime {
         true
     }
 
+    /// Name the holder of a port conflict that is still recorded for `port` by the attempt that
+    /// looked it up; a lookup from an earlier attempt is dropped. The `PortConflict` event is
+    /// appended now that the holder is known, for a waiting service and a failed start alike.
+    fn record_port_owner(
+        &mut self,
+        services: &ServiceMap,
+        service_id: &ServiceID,
+        run_id: RunId,
+        port: u16,
+        owner: Option<&crate::PortOwner>,
+    ) {
+        let (Some(service), Some(runtime)) =
+            (services.get(service_id), self.services.get_mut(service_id))
+        else {
+            return;
+        };
+        if runtime.run_generation() != run_id.get() {
+            return;
+        }
+        let Some(conflict) = runtime
+            .port_conflict
+            .as_mut()
+            .filter(|conflict| conflict.port == port)
+        else {
+            return;
+        };
+        conflict.pid = owner.map(|owner| owner.pid);
+        conflict.command = owner.map(|owner| owner.command.clone());
+        let conflict = conflict.clone();
+        sync_model(&self.writer, service, runtime);
+        let outcome = if conflict.waiting {
+            "waiting"
+        } else {
+            "start failed"
+        };
+        let mut event = service_event(
+            run_id.get(),
+            ServiceEventKind::PortConflict,
+            format!("{outcome}: {}", conflict.describe()),
+        );
+        event.pid = conflict.pid;
+        self.writer.append_event(service_id, event);
+    }
+
+    /// Stop a run that turned unhealthy under `on_unhealthy: restart`. Its exit then restarts it
+    /// through the usual backoff, and the event carries the failing probe's output.
+    fn restart_unhealthy(&mut self, services: &ServiceMap, service_id: &ServiceID) {
+        let Some(service) = services.get(service_id) else {
+            return;
+        };
+        let Some(healthcheck) = &service.spec.healthcheck else {
+            return;
+        };
+        if healthcheck.on_unhealthy != OnUnhealthy::Restart {
+            return;
+        }
+        let Some(runtime) = self.services.get_mut(service_id) else {
+            return;
+        };
+        if !matches!(
+            runtime.state,
+            State::Running {
+                health: Some(Health::Unhealthy)
+            }
+        ) {
+            return;
+        }
+        let Some(running) = &mut runtime.running else {
+            return;
+        };
+        running.cancel();
+        runtime.mark_killed();
+        runtime.unhealthy_restart = true;
+        let generation = runtime.run_generation();
+        sync_model(&self.writer, service, runtime);
+        let mut restart = service_event(
+            generation,
+            ServiceEventKind::UnhealthyRestart,
+            "service stayed unhealthy; stopping it to restart",
+        );
+        restart.probe_output = Some(self.writer.failed_health_output(service_id, generation));
+        self.writer.append_event(service_id, restart);
+    }
+
     fn handle_exit_event(
         &mut self,
         services: &ServiceMap,
@@ -2516,6 +3549,20 @@ impl SchedulerRuntime {
             && let Some(runtime) = self.services.get_mut(service_id)
         {
             runtime.finish_current_run(&service.spec.restart, exit_code);
+            if let Some(hooks) = runtime.post_stop.take() {
+                // Cleanup outlives session shutdown, bounded by the hook timeout and the cleanup
+                // abort instead.
+                runtime.post_stop_run = Some(event.run_id());
+                hooks.with_timeout(self.cleanup.timeout).spawn(
+                    service_id.clone(),
+                    event.run_id(),
+                    HookKind::PostStop,
+                    self.writer
+                        .hook_sink(Some(service_id), event.run_id().get()),
+                    self.events_tx.clone(),
+                    self.cleanup.abort.clone(),
+                );
+            }
         }
         self.sync(services, service_id);
         let generation = event.run_id().get();
@@ -2539,6 +3586,17 @@ impl SchedulerRuntime {
             );
             backoff.delay_ms = u64::try_from(delay.as_millis()).ok();
             self.writer.append_event(service_id, backoff);
+        } else if let Some(runtime) = self.services.get(service_id)
+            && runtime.restart.crash_loop
+        {
+            self.writer.append_event(
+                service_id,
+                service_event(
+                    generation,
+                    ServiceEventKind::CrashLoop,
+                    runtime.restart.crash_loop_detail(),
+                ),
+            );
         }
         #[cfg(test)]
         self.test_events.forward(event.to_test_event());
@@ -2549,6 +3607,29 @@ impl SchedulerRuntime {
         tracing::debug!(?event, "received process event");
 
         let service_id = event.service_id().clone();
+        if let ProcessEvent::HooksFinished {
+            run_id,
+            kind,
+            result,
+            ..
+        } = event
+        {
+            #[cfg(test)]
+            self.test_events.forward(event.to_test_event());
+            return self.handle_hooks_finished(services, &service_id, *run_id, *kind, result);
+        }
+        if let ProcessEvent::PortOwnerResolved {
+            run_id,
+            port,
+            owner,
+            ..
+        } = event
+        {
+            #[cfg(test)]
+            self.test_events.forward(event.to_test_event());
+            self.record_port_owner(services, &service_id, *run_id, *port, owner.as_ref());
+            return false;
+        }
         let Some(runtime) = self.services.get(&service_id) else {
             return false;
         };
@@ -2580,13 +3661,17 @@ impl SchedulerRuntime {
                 ServiceEventKind::Healthy,
                 "service became healthy",
             ),
-            ProcessEvent::Unhealthy { .. } => self.handle_health_event(
-                services,
-                event,
-                Health::Unhealthy,
-                ServiceEventKind::Unhealthy,
-                "service became unhealthy",
-            ),
+            ProcessEvent::Unhealthy { .. } => {
+                let handled = self.handle_health_event(
+                    services,
+                    event,
+                    Health::Unhealthy,
+                    ServiceEventKind::Unhealthy,
+                    "service became unhealthy",
+                );
+                self.restart_unhealthy(services, &service_id);
+                handled
+            }
             ProcessEvent::Killed { .. } => {
                 if let Some(runtime) = self.services.get_mut(&service_id) {
                     runtime.mark_killed();
@@ -2615,15 +3700,154 @@ impl SchedulerRuntime {
                 self.test_events.forward(event.to_test_event());
                 false
             }
+            ProcessEvent::HooksFinished { .. } | ProcessEvent::PortOwnerResolved { .. } => false,
         }
     }
 
-    fn next_backoff(&self) -> Option<tokio::time::Instant> {
+    /// Spawn or fail a run whose `pre_start` hooks finished, or let the next run start once the
+    /// previous run's `post_stop` hooks did. A failed `post_stop` hook is reported but changes
+    /// nothing else.
+    fn handle_hooks_finished(
+        &mut self,
+        services: &ServiceMap,
+        service_id: &ServiceID,
+        run_id: RunId,
+        kind: HookKind,
+        result: &Result<(), String>,
+    ) -> bool {
+        if kind == HookKind::PreStart {
+            let mut ctx = self.schedule_context(services);
+            schedule::finish_pre_start(&mut ctx, service_id, run_id, result);
+            return true;
+        }
+        let Some(runtime) = self.services.get_mut(service_id) else {
+            return false;
+        };
+        if runtime.post_stop_run == Some(run_id) {
+            runtime.post_stop_run = None;
+        }
+        if let Err(message) = result {
+            self.writer.append_event(
+                service_id,
+                service_event(run_id.get(), ServiceEventKind::HookFailed, message.clone()),
+            );
+        }
+        true
+    }
+
+    /// Bring file watchers in line with the current roster: one watcher per live configured service
+    /// with a `watch` block, rebuilt only when its watch definition or working directory changed.
+    fn sync_watchers(&mut self, services: &ServiceMap) {
+        let config_dir = &self.config_dir;
+        let runtimes = &self.services;
+        let wanted = |service_id: &ServiceID| {
+            let service = services.get(service_id)?;
+            let watch = service.spec.watch.as_ref()?;
+            (roster_entry_state(runtimes, service_id, service)
+                == Some(RosterEntryState::LiveConfigured))
+            .then(|| {
+                (
+                    watch,
+                    service.spec.working_dir.as_deref().unwrap_or(config_dir),
+                )
+            })
+        };
+        self.watchers.retain(|service_id, watcher| {
+            wanted(service_id).is_some_and(|(watch, base)| watcher.is_current(watch, base))
+        });
+        for service_id in services.keys() {
+            if self.watchers.contains_key(service_id) {
+                continue;
+            }
+            let Some((watch, base)) = wanted(service_id) else {
+                continue;
+            };
+            match ServiceWatcher::start(
+                service_id,
+                watch,
+                base,
+                self.watch_tx.clone(),
+                &self.shutdown,
+            ) {
+                Ok(watcher) => {
+                    self.watchers.insert(service_id.clone(), watcher);
+                }
+                Err(err) => {
+                    tracing::warn!(
+                        service_id,
+                        error = %err,
+                        "failed to start file watcher; changes will not restart the service"
+                    );
+                }
+            }
+        }
+    }
+
+    /// Restart a service whose watched files changed, through the same reload-then-restart path
+    /// as an explicit restart. Changes to a disabled or retired service are dropped: a file save
+    /// never starts a service the operator stopped.
+    async fn apply_watch_trigger(
+        &mut self,
+        services: &mut ServiceMap,
+        events_rx: &mut mpsc::Receiver<ProcessEvent>,
+        trigger: WatchTrigger,
+    ) -> bool {
+        let WatchTrigger { service_id, paths } = trigger;
+        let Some(runtime) = self.services.get(&service_id) else {
+            return false;
+        };
+        if runtime.retired.is_some() || runtime.desired == DesiredState::Disabled {
+            tracing::debug!(
+                service_id,
+                changed = paths.len(),
+                "ignoring watched file changes for a stopped service"
+            );
+            return false;
+        }
+        let detail = match paths.as_slice() {
+            [path] => format!("{path} changed"),
+            [first, ..] => format!("{first} and {} more files changed", paths.len() - 1),
+            [] => "watched files changed".to_string(),
+        };
+        let mut changed = service_event(
+            runtime.run_generation(),
+            ServiceEventKind::FilesChanged,
+            detail,
+        );
+        changed.changed_paths = Some(paths.into_iter().take(MAX_REPORTED_CHANGED_PATHS).collect());
+        self.writer.append_event(&service_id, changed);
+        if let Err(rejection) = self.apply_restart(services, events_rx, &service_id).await {
+            tracing::warn!(
+                service_id,
+                ?rejection,
+                "restart after watched file changes was rejected"
+            );
+        }
+        true
+    }
+
+    /// The earliest per-service timer that is due for a scheduling pass: a restart backoff, a
+    /// held-port recheck, a startup or maximum-runtime deadline, or a scheduled tick. A tick is kept
+    /// even once it has passed: the pass that handles it always arms the next one.
+    fn next_timer_deadline(&self) -> Option<tokio::time::Instant> {
         let now = tokio::time::Instant::now();
         self.services
             .values()
-            .filter_map(|runtime| runtime.restart.backoff_until)
+            .flat_map(|runtime| {
+                [
+                    runtime.restart.backoff_until,
+                    runtime.port_recheck_at,
+                    runtime.startup_deadline,
+                    runtime.runtime_deadline,
+                ]
+            })
+            .flatten()
             .filter(|deadline| *deadline > now)
+            .chain(
+                self.services
+                    .values()
+                    .filter_map(|runtime| runtime.next_tick.map(|tick| tick.at)),
+            )
             .min()
     }
 
@@ -2634,14 +3858,6 @@ impl SchedulerRuntime {
             .count()
     }
 
-    fn cancel_all_running(&mut self) {
-        for runtime in self.services.values_mut() {
-            if let Some(running) = &mut runtime.running {
-                running.cancel();
-            }
-        }
-    }
-
     /// Keep the runtime alive after shutdown so per-service termination tasks can finish their
     /// configured graceful-stop window, forced termination, PTY hangup, and child reap.
     ///
@@ -2693,6 +3909,33 @@ impl SchedulerRuntime {
             }
         }
     }
+
+    /// Wait for the `post_stop` hooks of runs that exited during shutdown. Shutdown does not
+    /// cancel them, but each command is killed at the hook timeout or on the cleanup abort.
+    async fn drain_post_stop_hooks(
+        &mut self,
+        services: &ServiceMap,
+        events_rx: &mut mpsc::Receiver<ProcessEvent>,
+    ) {
+        while self
+            .services
+            .values()
+            .any(|runtime| runtime.post_stop_run.is_some())
+        {
+            let Some(event) = events_rx.recv().await else {
+                break;
+            };
+            let _ = self.handle_event(services, &event);
+        }
+    }
+
+    /// Wait for task runs still in flight. Shutdown has already cancelled them, so this only
+    /// waits for their processes to be killed and their results recorded.
+    async fn drain_task_runs(&mut self) {
+        for run in self.task_runs.drain(..) {
+            let _ = run.await;
+        }
+    }
 }
 
 pub(crate) struct SchedulerInput {
@@ -2709,6 +3952,9 @@ pub(crate) struct SchedulerInput {
     pub(crate) config_dir: PathBuf,
     pub(crate) dynamic_policy: DynamicServicesPolicy,
     pub(crate) default_log_retention: LogRetention,
+    pub(crate) shutdown_order: ShutdownOrder,
+    pub(crate) tasks: TaskMap,
+    pub(crate) cleanup: crate::hooks::Cleanup,
 }
 
 pub(crate) async fn scheduler(input: SchedulerInput) -> Result<(), crate::graph::Error> {
@@ -2726,8 +3972,12 @@ pub(crate) async fn scheduler(input: SchedulerInput) -> Result<(), crate::graph:
         config_dir,
         dynamic_policy,
         default_log_retention,
+        shutdown_order,
+        tasks,
+        cleanup,
     } = input;
     ServiceGraph::new(&services)?;
+    let (watch_tx, mut watch_rx) = mpsc::channel(WATCH_TRIGGER_BUFFER);
     #[cfg(test)]
     let test_events = {
         let tx = test_events_tx.unwrap_or_else(|| {
@@ -2745,9 +3995,13 @@ pub(crate) async fn scheduler(input: SchedulerInput) -> Result<(), crate::graph:
             test_events,
             writer,
             shutdown: shutdown.clone(),
+            shutdown_order,
             config_dir,
             dynamic_policy,
             default_log_retention,
+            watch_tx,
+            tasks,
+            cleanup,
         },
     );
 
@@ -2756,11 +4010,14 @@ pub(crate) async fn scheduler(input: SchedulerInput) -> Result<(), crate::graph:
         &mut commands_rx,
         &mut pty_input_rx,
         &mut events_rx,
+        &mut watch_rx,
     )
     .await;
 
-    rt.cancel_all_running();
+    rt.stop_all(&services, &mut events_rx).await;
     rt.drain_on_shutdown(&services, &mut events_rx).await;
+    rt.drain_post_stop_hooks(&services, &mut events_rx).await;
+    rt.drain_task_runs().await;
     Ok(())
 }
 
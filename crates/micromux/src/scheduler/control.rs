@@ -8,7 +8,7 @@
 //! `mpsc::Sender<Command>` instead.
 
 use super::types::{Command, ServiceID};
-use crate::{DynamicServiceParams, Lease, RestartPolicy, ServiceSpec};
+use crate::{DynamicServiceParams, Lease, RestartPolicy, ServiceSignal, ServiceSpec};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tokio::sync::{mpsc, oneshot};
@@ -89,12 +89,25 @@ impl DynamicServiceAck {
     }
 }
 
+/// Scheduler acknowledgement for a started on-demand task run. Reused directly as the
+/// control-protocol wire payload.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct TaskRunAck {
+    /// The task that was started.
+    pub task: String,
+    /// Run number of the new run; addresses its result and retained log.
+    pub run_generation: u64,
+}
+
 /// A typed rejection produced by the scheduler when it declines a command.
 #[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
 pub enum CommandRejection {
     /// No service with the given id exists.
     #[error("unknown service")]
     UnknownService,
+    /// No task with the given name is configured.
+    #[error("unknown task")]
+    UnknownTask,
     /// The command is not valid in the service's current state.
     #[error("command is invalid in the service's current state: {0}")]
     InvalidState(String),
@@ -126,6 +139,9 @@ pub type ServiceCommandResult = Result<Vec<ServiceCommandAck>, CommandRejection>
 /// Result of one dynamic-service mutation.
 pub type DynamicServiceResult = Result<DynamicServiceAck, CommandRejection>;
 
+/// Result of starting one on-demand task run.
+pub type TaskRunResult = Result<TaskRunAck, CommandRejection>;
+
 /// Kind of semantic change reported by config reconciliation.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -188,6 +204,12 @@ pub struct ReconcileCommandAck {
     tx: oneshot::Sender<ReconcileResult>,
 }
 
+/// Reply half for an on-demand task command.
+#[derive(Debug)]
+pub struct TaskCommandAck {
+    tx: oneshot::Sender<TaskRunResult>,
+}
+
 impl CommandAck {
     pub(crate) fn new() -> (Self, oneshot::Receiver<ServiceCommandResult>) {
         let (tx, rx) = oneshot::channel();
@@ -222,6 +244,17 @@ impl ReconcileCommandAck {
     }
 }
 
+impl TaskCommandAck {
+    pub(crate) fn new() -> (Self, oneshot::Receiver<TaskRunResult>) {
+        let (tx, rx) = oneshot::channel();
+        (Self { tx }, rx)
+    }
+
+    pub(crate) fn send(self, result: TaskRunResult) {
+        let _ = self.tx.send(result);
+    }
+}
+
 /// The restricted command port handed to untrusted adapters. Cloning shares one channel to the
 /// scheduler.
 #[derive(Clone)]
@@ -299,6 +332,55 @@ impl ServiceControl {
         .await
     }
 
+    /// Send `signal` to a running service's process group. The service's lifecycle state is left
+    /// alone: whatever the process does in response is observed like any other exit or output.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SchedulerStopped`] if the scheduler is no longer accepting commands.
+    pub async fn signal(
+        &self,
+        id: &ServiceID,
+        signal: ServiceSignal,
+    ) -> Result<ServiceCommandResult, SchedulerStopped> {
+        let id = id.clone();
+        self.dispatch(move |ack| Command::Signal {
+            service: id,
+            signal,
+            ack: Some(ack),
+        })
+        .await
+    }
+
+    /// Suspend a running service's process group until [`ServiceControl::resume`]. Healthchecks
+    /// are held while paused, so the service is never marked unhealthy for being stopped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SchedulerStopped`] if the scheduler is no longer accepting commands.
+    pub async fn pause(&self, id: &ServiceID) -> Result<ServiceCommandResult, SchedulerStopped> {
+        let id = id.clone();
+        self.dispatch(move |ack| Command::Pause {
+            service: id,
+            ack: Some(ack),
+        })
+        .await
+    }
+
+    /// Continue a service paused by [`ServiceControl::pause`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SchedulerStopped`] if the scheduler is no longer accepting commands.
+    pub async fn resume(&self, id: &ServiceID) -> Result<ServiceCommandResult, SchedulerStopped> {
+        let id = id.clone();
+        self.dispatch(move |ack| Command::Resume {
+            service: id,
+            ack: Some(ack),
+        })
+        .await
+    }
+
     /// Reconcile configured services against the current on-disk config.
     ///
     /// This operation has no dynamic-service capability gate: the config file is the authority.
@@ -318,6 +400,47 @@ impl ServiceControl {
         rx.await.map_err(|_| SchedulerStopped)
     }
 
+    /// Scale replica group `group` to `replicas` instances. The receipt lists the instances added
+    /// or retired, like a config reconciliation.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SchedulerStopped`] if the scheduler is no longer accepting commands.
+    pub async fn scale_replicas(
+        &self,
+        group: &str,
+        replicas: usize,
+    ) -> Result<ReconcileResult, SchedulerStopped> {
+        let (ack, rx) = ReconcileCommandAck::new();
+        self.tx
+            .send(Command::ScaleReplicas {
+                group: group.to_string(),
+                replicas,
+                ack,
+            })
+            .await
+            .map_err(|_| SchedulerStopped)?;
+        rx.await.map_err(|_| SchedulerStopped)
+    }
+
+    /// Start a run of an on-demand task. The reply arrives once the run has started; its result
+    /// and output are then read from the model.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SchedulerStopped`] if the scheduler is no longer accepting commands.
+    pub async fn run_task(&self, task: &str) -> Result<TaskRunResult, SchedulerStopped> {
+        let (ack, rx) = TaskCommandAck::new();
+        self.tx
+            .send(Command::RunTask {
+                task: task.to_string(),
+                ack: Some(ack),
+            })
+            .await
+            .map_err(|_| SchedulerStopped)?;
+        rx.await.map_err(|_| SchedulerStopped)
+    }
+
     /// Create and start a dynamic service.
     ///
     /// # Errors
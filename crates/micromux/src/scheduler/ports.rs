@@ -0,0 +1,315 @@
+//! Session-scoped allocation of `port: auto` ports.
+//!
+//! An auto port gets a number the first time it is needed: when its own service spawns, or when a
+//! service referencing it through `${services.<id>.ports.<name>}` spawns first. The number is then
+//! kept for the rest of the session, so restarts and config reloads hand out the same port.
+//!
+//! Before a spawn, every port the run will use is probed here too, so a port still held by a
+//! leftover process is reported by owner instead of surfacing later as a crash.
+
+use super::{ProcessEvent, RunId, ServiceID};
+use crate::{
+    PortConflict, ServiceMap,
+    env::{self, PortReference},
+    service::{self, Service},
+    spec::{PortBinding, port_env_var},
+};
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, TcpListener};
+use tokio::sync::mpsc;
+
+/// Binds tried before giving up on finding a port no other auto port already holds.
+const MAX_ALLOCATION_ATTEMPTS: usize = 16;
+
+/// How often a service waiting under `port_conflict: wait` probes its ports again.
+pub(super) const PORT_RECHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Why a run's ports could not be resolved.
+#[derive(Debug, thiserror::Error)]
+pub(super) enum Error {
+    /// A reference names a service or port that no longer exists.
+    #[error("invalid port reference `{reference}`: {reason}")]
+    Reference {
+        /// The reference as written.
+        reference: String,
+        /// Why it cannot be resolved.
+        reason: String,
+    },
+    /// No free loopback port could be bound.
+    #[error("failed to allocate port `{port}` for `{service}`: {source}")]
+    Allocate {
+        /// Service owning the port.
+        service: ServiceID,
+        /// Port name.
+        port: String,
+        /// Underlying bind error.
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+/// A service definition with every port reference substituted, ready to spawn.
+pub(super) struct ResolvedPorts {
+    /// The service with `PORT_<NAME>` exported and references replaced by numbers.
+    pub(super) service: Service,
+    /// Numbers of the service's own auto ports, in declaration order.
+    pub(super) allocated: Vec<u16>,
+}
+
+#[derive(Default)]
+pub(super) struct PortAllocator {
+    allocated: HashMap<(ServiceID, String), u16>,
+}
+
+impl PortAllocator {
+    /// The number behind `port` of `service_id`, allocating it on first use.
+    fn port(
+        &mut self,
+        service_id: &ServiceID,
+        port: &str,
+        binding: PortBinding,
+    ) -> Result<u16, Error> {
+        match binding {
+            PortBinding::Fixed(number) => Ok(number),
+            PortBinding::Auto => {
+                let key = (service_id.clone(), port.to_string());
+                if let Some(number) = self.allocated.get(&key) {
+                    return Ok(*number);
+                }
+                let number = self.free_port().map_err(|source| Error::Allocate {
+                    service: service_id.clone(),
+                    port: port.to_string(),
+                    source,
+                })?;
+                self.allocated.insert(key, number);
+                Ok(number)
+            }
+        }
+    }
+
+    /// Ask the OS for a free loopback port, skipping ports already handed to another auto port
+    /// whose service has not bound it yet.
+    fn free_port(&self) -> std::io::Result<u16> {
+        for _ in 0..MAX_ALLOCATION_ATTEMPTS {
+            let port = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?
+                .local_addr()?
+                .port();
+            if !self.allocated.values().any(|allocated| *allocated == port) {
+                return Ok(port);
+            }
+        }
+        Err(std::io::Error::new(
+            std::io::ErrorKind::AddrInUse,
+            "every free port offered was already allocated",
+        ))
+    }
+
+    /// Resolve the ports `service` needs to spawn: its own named ports are exported as
+    /// `PORT_<NAME>` unless its environment already sets that variable, and every reference in
+    /// its command, hooks, environment, and healthcheck test is replaced by the referenced
+    /// number.
+    pub(super) fn resolve(
+        &mut self,
+        services: &ServiceMap,
+        service_id: &ServiceID,
+        service: &Service,
+    ) -> Result<ResolvedPorts, Error> {
+        let mut resolved = service.clone();
+        let mut allocated = Vec::new();
+        for (name, binding) in &service.spec.named_ports {
+            let number = self.port(service_id, name, *binding)?;
+            if *binding == PortBinding::Auto {
+                allocated.push(number);
+            }
+            resolved
+                .spec
+                .environment
+                .entry(port_env_var(name))
+                .or_insert_with(|| number.to_string());
+        }
+
+        let mut substitute = |value: &mut String| -> Result<(), Error> {
+            *value = env::substitute_port_references(value.as_str(), |reference| {
+                self.referenced(services, reference)
+            })?;
+            Ok(())
+        };
+        let spec = &mut resolved.spec;
+        let values = spec
+            .command
+            .iter_mut()
+            .chain(spec.pre_start.iter_mut().flatten())
+            .chain(spec.post_stop.iter_mut().flatten())
+            .chain(spec.environment.values_mut());
+        for value in values {
+            substitute(value)?;
+        }
+        if let Some(healthcheck) = &mut spec.healthcheck {
+            for value in &mut healthcheck.test {
+                substitute(value)?;
+            }
+        }
+        Ok(ResolvedPorts {
+            service: resolved,
+            allocated,
+        })
+    }
+
+    fn referenced(
+        &mut self,
+        services: &ServiceMap,
+        reference: &PortReference,
+    ) -> Result<u16, Error> {
+        let binding =
+            service::referenced_port(services, reference).map_err(|reason| Error::Reference {
+                reference: reference.to_string(),
+                reason,
+            })?;
+        self.port(&reference.service, &reference.port, binding)
+    }
+}
+
+impl ResolvedPorts {
+    /// The first of the run's fixed and auto ports that another process already holds on the
+    /// loopback interface.
+    pub(super) fn held_port(&self) -> Option<u16> {
+        self.service
+            .spec
+            .ports
+            .iter()
+            .chain(&self.allocated)
+            .copied()
+            .find(|port| {
+                matches!(
+                    TcpListener::bind((Ipv4Addr::LOCALHOST, *port)),
+                    Err(err) if err.kind() == std::io::ErrorKind::AddrInUse
+                )
+            })
+    }
+}
+
+/// A held port whose holder is not known yet; [`spawn_owner_lookup`] fills it in.
+pub(super) fn conflict(port: u16, waiting: bool) -> PortConflict {
+    PortConflict {
+        port,
+        pid: None,
+        command: None,
+        waiting,
+    }
+}
+
+/// Look up the process holding `port` on a blocking thread and report it as
+/// [`ProcessEvent::PortOwnerResolved`]. Finding the owner scans `/proc` or runs `lsof`, which must
+/// not stall the scheduler.
+pub(super) fn spawn_owner_lookup(
+    events_tx: &mpsc::Sender<ProcessEvent>,
+    service_id: &ServiceID,
+    run_id: RunId,
+    port: u16,
+) {
+    let events_tx = events_tx.clone();
+    let service_id = service_id.clone();
+    tokio::spawn(async move {
+        let owner = tokio::task::spawn_blocking(move || crate::listening_owner(port))
+            .await
+            .ok()
+            .flatten();
+        let _ = events_tx
+            .send(ProcessEvent::PortOwnerResolved {
+                service_id,
+                run_id,
+                port,
+                owner,
+            })
+            .await;
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::service_config;
+    use color_eyre::eyre;
+    use similar_asserts::assert_eq;
+    use std::path::Path;
+
+    fn service(id: &str, ports: &[(&str, PortBinding)]) -> eyre::Result<Service> {
+        let mut service = Service::new(id, Path::new("."), service_config(id, ("true", &[])))?;
+        service.spec.named_ports = ports
+            .iter()
+            .map(|(name, binding)| ((*name).to_string(), *binding))
+            .collect();
+        Ok(service)
+    }
+
+    #[test]
+    fn auto_ports_are_exported_shared_with_referrers_and_stable() -> eyre::Result<()> {
+        let api = service(
+            "api",
+            &[
+                ("http", PortBinding::Auto),
+                ("admin", PortBinding::Fixed(9000)),
+            ],
+        )?;
+        let mut web = service("web", &[])?;
+        web.spec.environment.insert(
+            "API_URL".to_string(),
+            "http://127.0.0.1:${services.api.ports.http}".to_string(),
+        );
+        web.spec.command = vec![
+            "serve".to_string(),
+            "--admin=${services.api.ports.admin}".to_string(),
+        ];
+        let mut services = ServiceMap::new();
+        services.insert("api".to_string(), api.clone());
+        services.insert("web".to_string(), web.clone());
+
+        let mut allocator = PortAllocator::default();
+        // The referrer spawning first allocates the port its dependency later binds.
+        let resolved_web = allocator.resolve(&services, &"web".to_string(), &web)?;
+        let resolved_api = allocator.resolve(&services, &"api".to_string(), &api)?;
+        let [http] = resolved_api.allocated[..] else {
+            return Err(eyre::eyre!("expected one auto port"));
+        };
+        assert_ne!(http, 0);
+        assert_eq!(
+            resolved_api.service.spec.environment.get("PORT_HTTP"),
+            Some(&http.to_string())
+        );
+        assert_eq!(
+            resolved_api.service.spec.environment.get("PORT_ADMIN"),
+            Some(&"9000".to_string())
+        );
+        assert_eq!(
+            resolved_web.service.spec.environment.get("API_URL"),
+            Some(&format!("http://127.0.0.1:{http}"))
+        );
+        assert_eq!(resolved_web.service.spec.command[1], "--admin=9000");
+
+        let restarted = allocator.resolve(&services, &"api".to_string(), &api)?;
+        assert_eq!(restarted.allocated, vec![http]);
+
+        services.shift_remove("api");
+        assert!(matches!(
+            allocator.resolve(&services, &"web".to_string(), &web),
+            Err(Error::Reference { .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn held_port_reports_a_bound_listener() -> eyre::Result<()> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+        let port = listener.local_addr()?.port();
+        let mut api = service("api", &[])?;
+        api.spec.ports = vec![port];
+        let mut services = ServiceMap::new();
+        services.insert("api".to_string(), api.clone());
+
+        let resolved = PortAllocator::default().resolve(&services, &"api".to_string(), &api)?;
+        assert_eq!(resolved.held_port(), Some(port));
+        drop(listener);
+        assert_eq!(resolved.held_port(), None);
+        Ok(())
+    }
+}
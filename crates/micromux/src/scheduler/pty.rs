@@ -1,5 +1,5 @@
-use super::{LogUpdateKind, OutputStream, ProcessEvent, RunId, ServiceID, input::PreparedPtyInput};
-use crate::{health_check, model::RunSink, service::Service};
+use super::{LogUpdateKind, ProcessEvent, RunId, ServiceID, input::PreparedPtyInput};
+use crate::{ServiceSignal, health_check, model::RunSink, service::Service};
 use parking_lot::Mutex;
 use std::collections::HashMap;
 use std::io::{self, Read, Write};
@@ -9,9 +9,14 @@ use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::mpsc as std_mpsc;
 use std::thread;
 use std::time::{Duration, Instant};
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 use tokio_util::sync::CancellationToken;
 
+#[path = "pty/pipe.rs"]
+mod pipe;
+
+pub(super) use pipe::start_service_with_pipes;
+
 #[derive(Debug, thiserror::Error)]
 pub(super) enum Error {
     #[error("service command is empty")]
@@ -233,6 +238,8 @@ pub(super) enum PtyInputDrop {
     QueueFull,
     #[error("the PTY writer has stopped")]
     WriterStopped,
+    #[error("the service runs without a terminal (`tty: false`)")]
+    NoTerminal,
 }
 
 struct PtyWriter {
@@ -259,6 +266,8 @@ pub(super) struct PtyHandles {
     master: SharedPtyMaster,
     write_queue: SharedPtyWriteQueue,
     size: Arc<AtomicU32>,
+    /// Whether the run has a terminal at all; pipe-mode runs reject input and ignore resizes.
+    tty: bool,
 }
 
 /// A teardown capability for disconnecting every PTY endpoint associated with one run.
@@ -273,6 +282,10 @@ pub(super) struct StartedPty {
     /// Pid of the spawned child, when the pty backend reports one. A missing pid must never fail
     /// the start — it only degrades the runtime-identity snapshot.
     pub(super) pid: Option<u32>,
+    /// Process group led by the child, targeted by operator-sent signals.
+    pub(super) process_group_leader_id: Option<i32>,
+    /// Pause state shared with the run's healthcheck and termination tasks.
+    pub(super) paused: watch::Sender<bool>,
     pub(super) handles: PtyHandles,
     pub(super) log_reader: LogReaderHandle,
 }
@@ -344,12 +357,26 @@ impl PtyHandles {
                 master,
                 write_queue,
                 size,
+                tty: true,
             },
             shutdown,
         ))
     }
 
+    /// Handles for a run spawned on plain pipes, which has no terminal to write to or resize.
+    fn without_terminal() -> Self {
+        Self {
+            master: Arc::new(Mutex::new(None)),
+            write_queue: Arc::new(Mutex::new(None)),
+            size: Arc::new(AtomicU32::new(0)),
+            tty: false,
+        }
+    }
+
     pub(super) fn write_input(&self, input: PreparedPtyInput) -> Result<(), PtyInputDrop> {
+        if !self.tty {
+            return Err(PtyInputDrop::NoTerminal);
+        }
         let write_queue = self.write_queue.lock();
         let Some(write_queue) = write_queue.as_ref() else {
             return Err(PtyInputDrop::WriterStopped);
@@ -1235,11 +1262,22 @@ impl PollingPtyReader {
                 "master did not expose a raw fd",
             )
         })?;
-        let poll_read = FileDescriptor::dup(&BorrowedRawFd(pty_fd))
-            .map_err(|err| Error::operation("failed to clone pty poll fd", err))?;
         let reader = master
             .try_clone_reader()
             .map_err(|err| Error::operation("failed to clone pty reader", err))?;
+        Self::with_reader(reader, pty_fd, cancel_read, cancelled)
+    }
+
+    /// Poll `fd` for readiness and read its data through `reader`, which must refer to the same
+    /// open file.
+    fn with_reader(
+        reader: Box<dyn Read + Send>,
+        fd: RawFd,
+        cancel_read: FileDescriptor,
+        cancelled: Arc<AtomicBool>,
+    ) -> Result<Self, Error> {
+        let poll_read = FileDescriptor::dup(&BorrowedRawFd(fd))
+            .map_err(|err| Error::operation("failed to clone output poll fd", err))?;
         let poller =
             PtyPoller::new().map_err(|err| Error::operation("failed to create pty poller", err))?;
         Ok(Self {
@@ -1352,13 +1390,13 @@ struct LogReaderArgs {
     pty_size: Arc<AtomicU32>,
 }
 
-struct LogReaderFinishedGuard<'a> {
-    events_tx: &'a mpsc::Sender<ProcessEvent>,
-    service_id: &'a ServiceID,
+struct LogReaderFinishedGuard {
+    events_tx: mpsc::Sender<ProcessEvent>,
+    service_id: ServiceID,
     run_id: RunId,
 }
 
-impl Drop for LogReaderFinishedGuard<'_> {
+impl Drop for LogReaderFinishedGuard {
     fn drop(&mut self) {
         let _ = self
             .events_tx
@@ -1427,8 +1465,7 @@ fn spawn_log_reader_thread(args: LogReaderArgs) {
             match rate.snapshot_decision(force, Instant::now()) {
                 SnapshotDecision::Emit => {}
                 SnapshotDecision::Warn => {
-                    sink.append_log(
-                        OutputStream::Stdout,
+                    sink.append_terminal_log(
                         LogUpdateKind::Append,
                         "[micromux] interactive output rate-limited".to_string(),
                     );
@@ -1505,8 +1542,7 @@ fn spawn_log_reader_thread(args: LogReaderArgs) {
                 }
             }
 
-            sink.append_log(
-                OutputStream::Stdout,
+            sink.append_terminal_log(
                 LogUpdateKind::LiveSnapshot {
                     id: rate.snapshot_id(),
                 },
@@ -1530,7 +1566,7 @@ fn spawn_log_reader_thread(args: LogReaderArgs) {
             }
 
             let s = String::from_utf8_lossy(line).to_string();
-            sink.append_log(OutputStream::Stdout, LogUpdateKind::Append, s);
+            sink.append_terminal_log(LogUpdateKind::Append, s);
             line.clear();
         }
 
@@ -1548,7 +1584,7 @@ fn spawn_log_reader_thread(args: LogReaderArgs) {
         /// `line` never contains the terminating newline bytes themselves.
         fn flush_record(line: &mut Vec<u8>, sink: &RunSink) {
             let s = String::from_utf8_lossy(line).to_string();
-            sink.append_log(OutputStream::Stdout, LogUpdateKind::Append, s);
+            sink.append_terminal_log(LogUpdateKind::Append, s);
             line.clear();
         }
 
@@ -1581,8 +1617,8 @@ fn spawn_log_reader_thread(args: LogReaderArgs) {
         // The scheduler reserves this run ID until acknowledgement, so every thread exit path must
         // send one, including I/O errors and unwinding.
         let _finished = LogReaderFinishedGuard {
-            events_tx: &events_tx,
-            service_id: &service_id,
+            events_tx: events_tx.clone(),
+            service_id: service_id.clone(),
             run_id,
         };
         #[cfg(test)]
@@ -1755,9 +1791,11 @@ struct TerminationTaskArgs {
     killer: Box<dyn portable_pty::ChildKiller + Send + Sync>,
     pid: Option<u32>,
     process_group_leader_id: Option<i32>,
-    pty_shutdown: PtyShutdown,
+    pty_shutdown: Option<PtyShutdown>,
     child: Box<dyn portable_pty::Child + Send + Sync>,
     health_task: Option<tokio::task::JoinHandle<()>>,
+    stop_signal: ServiceSignal,
+    paused: watch::Receiver<bool>,
     #[cfg(windows)]
     process_job: win32job::Job,
 }
@@ -1786,18 +1824,24 @@ impl TerminationTarget {
         events_tx: &mpsc::Sender<ProcessEvent>,
         service_id: &ServiceID,
         run_id: RunId,
+        stop_signal: ServiceSignal,
+        paused: &watch::Receiver<bool>,
         force_kill_after: Duration,
     ) -> TerminationStart {
         #[cfg(not(unix))]
-        let _ = force_kill_after;
+        let _ = (stop_signal, paused, force_kill_after);
 
-        tracing::info!(pid = self.pid, service_id, "killing process");
+        tracing::info!(pid = self.pid, service_id, %stop_signal, "killing process");
 
         #[cfg(unix)]
         let (kill_deadline, escalated) = {
-            if !self.signal(Signal::SIGTERM) {
+            if !self.signal(stop_signal.into()) {
                 self.kill_with_backend();
             }
+            // A stopped group only acts on the stop signal once it is continued.
+            if *paused.borrow() {
+                self.signal(Signal::SIGCONT);
+            }
             let now = tokio::time::Instant::now();
             (
                 Some(now.checked_add(force_kill_after).unwrap_or(now)),
@@ -1867,30 +1911,42 @@ impl TerminationTarget {
 
     #[cfg(unix)]
     fn signal(&self, signal: Signal) -> bool {
-        if let Some(pgid) = self.process_group_leader_id {
-            match nix::sys::signal::killpg(Pid::from_raw(pgid), signal) {
-                Ok(()) => return true,
-                Err(Errno::ESRCH) => {
-                    tracing::debug!(?signal, pgid, "process group exited before signal delivery");
-                }
-                Err(err) => {
-                    tracing::warn!(?err, ?signal, pgid, "failed to signal process group");
-                }
+        signal_process(self.pid, self.process_group_leader_id, signal)
+    }
+}
+
+/// Send `signal` to a run's process group, falling back to the leader alone when there is no group.
+///
+/// Returns whether any process accepted the signal.
+#[cfg(unix)]
+pub(super) fn signal_process(
+    pid: Option<u32>,
+    process_group_leader_id: Option<i32>,
+    signal: Signal,
+) -> bool {
+    if let Some(pgid) = process_group_leader_id {
+        match nix::sys::signal::killpg(Pid::from_raw(pgid), signal) {
+            Ok(()) => return true,
+            Err(Errno::ESRCH) => {
+                tracing::debug!(?signal, pgid, "process group exited before signal delivery");
+            }
+            Err(err) => {
+                tracing::warn!(?err, ?signal, pgid, "failed to signal process group");
             }
         }
-        if let Some(pid) = self.pid.and_then(|pid| i32::try_from(pid).ok()) {
-            match nix::sys::signal::kill(Pid::from_raw(pid), signal) {
-                Ok(()) => return true,
-                Err(Errno::ESRCH) => {
-                    tracing::debug!(?signal, pid, "process exited before signal delivery");
-                }
-                Err(err) => {
-                    tracing::warn!(?err, ?signal, pid, "failed to signal process");
-                }
+    }
+    if let Some(pid) = pid.and_then(|pid| i32::try_from(pid).ok()) {
+        match nix::sys::signal::kill(Pid::from_raw(pid), signal) {
+            Ok(()) => return true,
+            Err(Errno::ESRCH) => {
+                tracing::debug!(?signal, pid, "process exited before signal delivery");
+            }
+            Err(err) => {
+                tracing::warn!(?err, ?signal, pid, "failed to signal process");
             }
         }
-        false
     }
+    false
 }
 
 struct SpawnedChildGuard {
@@ -1981,6 +2037,27 @@ async fn stop_health_task(
     }
 }
 
+/// Close the PTY of a run that escalation did not reap.
+///
+/// A descendant in another session can retain the slave after process-group escalation. Closing
+/// every master endpoint keeps the terminal from blocking the original child's wait.
+fn close_unreaped_pty(
+    pty_shutdown: Option<PtyShutdown>,
+    pid: Option<u32>,
+    service_id: &ServiceID,
+    run_id: RunId,
+) {
+    tracing::warn!(
+        ?pid,
+        %service_id,
+        run_id = run_id.get(),
+        "termination escalated but the process is still unreaped; closing its pty"
+    );
+    if let Some(shutdown) = pty_shutdown {
+        shutdown.close();
+    }
+}
+
 fn spawn_termination_task_with_timing(args: TerminationTaskArgs, timing: TerminationTiming) {
     tokio::spawn(async move {
         let TerminationTaskArgs {
@@ -1991,10 +2068,12 @@ fn spawn_termination_task_with_timing(args: TerminationTaskArgs, timing: Termina
             terminate,
             pid,
             process_group_leader_id,
-            pty_shutdown,
+            mut pty_shutdown,
             killer,
             mut child,
             mut health_task,
+            stop_signal,
+            paused,
             #[cfg(windows)]
             process_job,
         } = args;
@@ -2014,7 +2093,6 @@ fn spawn_termination_task_with_timing(args: TerminationTaskArgs, timing: Termina
         let mut kill_deadline: Option<tokio::time::Instant> = None;
         let mut pty_hangup_deadline: Option<tokio::time::Instant> = None;
         let mut pending_killed_notification = None;
-        let mut pty_shutdown = Some(pty_shutdown);
         loop {
             tokio::select! {
                 biased;
@@ -2043,13 +2121,14 @@ fn spawn_termination_task_with_timing(args: TerminationTaskArgs, timing: Termina
                         &events_tx,
                         &service_id,
                         run_id,
+                        stop_signal,
+                        &paused,
                         timing.force_kill_after,
                     ).await;
                     kill_deadline = started.kill_deadline;
                     termination_escalated = started.escalated;
                     pending_killed_notification = started.pending_notification;
-                    pty_hangup_deadline = started
-                        .escalated
+                    pty_hangup_deadline = (started.escalated && pty_shutdown.is_some())
                         .then(|| tokio::time::Instant::now() + timing.pty_hangup_after);
                     termination_started = true;
                 }
@@ -2063,8 +2142,9 @@ fn spawn_termination_task_with_timing(args: TerminationTaskArgs, timing: Termina
                     // survive the leader and keep its PTY open.
                     target.force_kill();
                     termination_escalated = true;
-                    pty_hangup_deadline =
-                        Some(tokio::time::Instant::now() + timing.pty_hangup_after);
+                    pty_hangup_deadline = pty_shutdown
+                        .is_some()
+                        .then(|| tokio::time::Instant::now() + timing.pty_hangup_after);
                 }
                 () = async {
                     match pty_hangup_deadline {
@@ -2072,20 +2152,7 @@ fn spawn_termination_task_with_timing(args: TerminationTaskArgs, timing: Termina
                         None => std::future::pending::<()>().await,
                     }
                 }, if pty_hangup_deadline.is_some() => {
-                    // A descendant in another session can retain the slave after process-group
-                    // escalation.
-                    //
-                    // Close every master endpoint so the terminal cannot keep the original child's
-                    // wait from completing.
-                    tracing::warn!(
-                        ?pid,
-                        %service_id,
-                        run_id = run_id.get(),
-                        "termination escalated but the process is still unreaped; closing its pty"
-                    );
-                    if let Some(shutdown) = pty_shutdown.take() {
-                        shutdown.close();
-                    }
+                    close_unreaped_pty(pty_shutdown.take(), pid, &service_id, run_id);
                     pty_hangup_deadline = None;
                 }
             }
@@ -2105,7 +2172,7 @@ pub(super) fn start_service_with_pty_size(
     let StartServiceParams {
         service,
         run_id,
-        sink,
+        mut sink,
         events_tx,
         shutdown,
         terminate,
@@ -2205,6 +2272,12 @@ pub(super) fn start_service_with_pty_size(
         &log_reader,
     )?;
 
+    let log_match = service
+        .spec
+        .healthcheck
+        .as_ref()
+        .and_then(|health_check| health_check::watch_log_pattern(health_check, &mut sink));
+
     spawn_log_reader_thread(LogReaderArgs {
         service_id: service_id.clone(),
         run_id,
@@ -2217,9 +2290,78 @@ pub(super) fn start_service_with_pty_size(
         pty_size: size.clone(),
     });
 
+    let pause = spawn_run_tasks(RunTasks {
+        service,
+        run_id,
+        sink,
+        working_dir,
+        log_match,
+        events_tx,
+        shutdown,
+        terminate,
+        killer,
+        child,
+        pid,
+        process_group_leader_id: process_group_leader,
+        pty_shutdown: Some(pty_shutdown),
+        #[cfg(windows)]
+        process_job,
+    });
+    child_guard.disarm();
+
+    Ok(StartedPty {
+        pid,
+        process_group_leader_id: process_group_leader,
+        paused: pause,
+        handles,
+        log_reader,
+    })
+}
+
+/// A spawned run handed to its healthcheck and termination tasks.
+struct RunTasks<'a> {
+    service: &'a Service,
+    run_id: RunId,
+    sink: RunSink,
+    working_dir: Option<crate::service::SpawnWorkingDirectory>,
+    log_match: Option<tokio::sync::oneshot::Receiver<String>>,
+    events_tx: &'a mpsc::Sender<ProcessEvent>,
+    shutdown: &'a CancellationToken,
+    terminate: &'a CancellationToken,
+    killer: Box<dyn portable_pty::ChildKiller + Send + Sync>,
+    child: Box<dyn portable_pty::Child + Send + Sync>,
+    pid: Option<u32>,
+    process_group_leader_id: Option<i32>,
+    /// Terminal endpoints closed if escalation cannot reap the child; `None` for pipe-mode runs.
+    pty_shutdown: Option<PtyShutdown>,
+    #[cfg(windows)]
+    process_job: win32job::Job,
+}
+
+/// Start the healthcheck and termination tasks for a spawned run and return its pause control.
+fn spawn_run_tasks(tasks: RunTasks<'_>) -> watch::Sender<bool> {
+    let RunTasks {
+        service,
+        run_id,
+        sink,
+        working_dir,
+        log_match,
+        events_tx,
+        shutdown,
+        terminate,
+        killer,
+        child,
+        pid,
+        process_group_leader_id,
+        pty_shutdown,
+        #[cfg(windows)]
+        process_job,
+    } = tasks;
+    let service_id = service.id.clone();
+    let (pause, paused) = watch::channel(false);
+
     let health_task = service.spec.healthcheck.clone().map(|health_check| {
         let service_id = service_id.clone();
-        let working_dir = working_dir.clone();
         let environment: std::collections::HashMap<String, String> = service
             .spec
             .environment
@@ -2229,6 +2371,7 @@ pub(super) fn start_service_with_pty_size(
         let events_tx = events_tx.clone();
         let shutdown = shutdown.clone();
         let terminate = terminate.clone();
+        let paused = paused.clone();
         tokio::spawn(async move {
             health_check::run_loop(
                 health_check,
@@ -2241,6 +2384,8 @@ pub(super) fn start_service_with_pty_size(
                     events_tx,
                     shutdown,
                     terminate,
+                    log_match,
+                    paused,
                 },
             )
             .await;
@@ -2249,29 +2394,25 @@ pub(super) fn start_service_with_pty_size(
 
     spawn_termination_task(
         TerminationTaskArgs {
-            service_id: service_id.clone(),
+            service_id,
             run_id,
             events_tx: events_tx.clone(),
             shutdown: shutdown.clone(),
             terminate: terminate.clone(),
             killer,
             pid,
-            process_group_leader_id: process_group_leader,
+            process_group_leader_id,
             pty_shutdown,
             child,
             health_task,
+            stop_signal: service.spec.stop_signal,
+            paused,
             #[cfg(windows)]
             process_job,
         },
         service.spec.stop_grace_period,
     );
-    child_guard.disarm();
-
-    Ok(StartedPty {
-        pid,
-        handles,
-        log_reader,
-    })
+    pause
 }
 
 #[cfg(test)]
@@ -2289,8 +2430,8 @@ mod tests {
 
         {
             let _finished = LogReaderFinishedGuard {
-                events_tx: &events_tx,
-                service_id: &service_id,
+                events_tx,
+                service_id: service_id.clone(),
                 run_id,
             };
         }
@@ -2514,6 +2655,8 @@ mod tests {
                     &events_tx,
                     &service_id,
                     run_id,
+                    ServiceSignal::Term,
+                    &watch::channel(false).1,
                     crate::spec::DEFAULT_STOP_GRACE_PERIOD,
                 )
                 .await;
@@ -2550,6 +2693,8 @@ mod tests {
                     &events_tx,
                     &"svc".to_string(),
                     RunId::new(7),
+                    ServiceSignal::Term,
+                    &watch::channel(false).1,
                     Duration::from_secs(1),
                 )
                 .await;
@@ -2594,6 +2739,7 @@ mod tests {
                 master: Arc::new(Mutex::new(None)),
                 write_queue: Arc::new(Mutex::new(Some(write_queue))),
                 size: Arc::new(AtomicU32::new(0)),
+                tty: true,
             };
             let paste = vec![b'x'; MAX_PTY_INPUT_BATCH_BYTES + 17];
             let budget = InputBudget::shared_with_limit(MAX_PTY_PASTE_BYTES);
@@ -2627,12 +2773,14 @@ mod tests {
                 master: Arc::new(Mutex::new(None)),
                 write_queue: Arc::new(Mutex::new(Some(first_queue))),
                 size: Arc::new(AtomicU32::new(0)),
+                tty: true,
             };
             let (second_queue, second_queued) = std_mpsc::sync_channel(2);
             let second = PtyHandles {
                 master: Arc::new(Mutex::new(None)),
                 write_queue: Arc::new(Mutex::new(Some(second_queue))),
                 size: Arc::new(AtomicU32::new(0)),
+                tty: true,
             };
             let paste = vec![b'x'; MAX_PTY_PASTE_BYTES];
 
@@ -3299,9 +3447,11 @@ mod tests {
                     }),
                     pid: None,
                     process_group_leader_id: None,
-                    pty_shutdown,
+                    pty_shutdown: Some(pty_shutdown),
                     child: Box::new(child),
                     health_task: None,
+                    stop_signal: ServiceSignal::Term,
+                    paused: watch::channel(false).1,
                 },
                 TerminationTiming {
                     force_kill_after: Duration::from_millis(10),
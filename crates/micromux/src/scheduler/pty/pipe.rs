@@ -0,0 +1,288 @@
+//! Plain-pipe process spawning for services with `tty: false`.
+//!
+//! stdout and stderr are read by separate threads and logged line by line with the stream that
+//! produced them. There is no terminal emulator and no terminal input: stdin is `/dev/null`.
+
+use super::{
+    AnsiFilter, Error, LogReaderFinishedGuard, LogReaderHandle, PTY_LOG_LINE_MAX_BYTES, PtyHandles,
+    PtyOutputReader, RunTasks, SpawnedChildGuard, StartServiceParams, StartedPty,
+    bounded_line_split, env_vars_for_service, spawn_run_tasks,
+};
+use crate::scheduler::{LogUpdateKind, OutputStream, RunId, ServiceID};
+use crate::{health_check, model::RunSink};
+use std::process::{ChildStderr, ChildStdout, Stdio};
+use std::sync::Arc;
+use std::thread;
+
+#[cfg(test)]
+use super::ActiveLogReaderGuard;
+
+/// Completion bookkeeping shared by a run's stdout and stderr readers.
+///
+/// The scheduler expects one `LogReaderFinished` per run, so it is sent when the last reader drops
+/// its reference.
+struct PipeReadersFinished {
+    #[cfg(test)]
+    _active: ActiveLogReaderGuard,
+    _finished: LogReaderFinishedGuard,
+}
+
+struct PipeReaderArgs {
+    service_id: ServiceID,
+    run_id: RunId,
+    stream: OutputStream,
+    sink: RunSink,
+    reader: PtyOutputReader,
+    finished: Arc<PipeReadersFinished>,
+}
+
+#[expect(
+    clippy::too_many_lines,
+    reason = "process startup wires pipes, readers, waiter, and ownership guards in one fallible path"
+)]
+pub(in crate::scheduler) fn start_service_with_pipes(
+    params: StartServiceParams<'_>,
+) -> Result<StartedPty, Error> {
+    let StartServiceParams {
+        service,
+        run_id,
+        mut sink,
+        events_tx,
+        shutdown,
+        terminate,
+        pty_size: _,
+    } = params;
+    let service_id = service.id.clone();
+    let Some((prog, args)) = service.spec.command.split_first() else {
+        return Err(Error::EmptyCommand);
+    };
+    let working_dir = service
+        .spawn_working_directory()
+        .map_err(|err| Error::operation("failed to resolve anchored working directory", err))?;
+
+    let env_vars = env_vars_for_service(service);
+    let mut env_keys = env_vars.keys().map(String::as_str).collect::<Vec<_>>();
+    env_keys.sort_unstable();
+    // Values may contain secrets from env files; log only the key names.
+    tracing::info!(
+        service_id,
+        prog,
+        args_count = args.len(),
+        ?env_keys,
+        "start service without a terminal"
+    );
+
+    let mut cmd = std::process::Command::new(prog);
+    cmd.args(args)
+        .envs(&env_vars)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(dir) = &working_dir {
+        cmd.current_dir(dir.as_path());
+    }
+    // Lead a new process group so stop and pause signals reach the service's descendants, as they
+    // do for the session a PTY child leads.
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt as _;
+        cmd.process_group(0);
+    }
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|err| Error::operation("failed to spawn service", err))?;
+    let pid = child.id();
+    #[cfg(unix)]
+    let process_group_leader = i32::try_from(pid).ok();
+    #[cfg(not(unix))]
+    let process_group_leader = None;
+
+    let killer = portable_pty::ChildKiller::clone_killer(&child);
+    let mut child_guard =
+        SpawnedChildGuard::new(killer.clone_killer(), Some(pid), process_group_leader);
+
+    #[cfg(windows)]
+    let process_job = {
+        use std::os::windows::io::AsRawHandle as _;
+        crate::windows_job::attach_kill_on_close(child.as_raw_handle() as isize)
+            .map_err(|err| Error::operation("failed to contain service process", err))?
+    };
+
+    let (stdout, stderr) = child
+        .stdout
+        .take()
+        .zip(child.stderr.take())
+        .ok_or_else(|| {
+            Error::operation(
+                "failed to capture service output",
+                "child did not expose its output pipes",
+            )
+        })?;
+    let (stdout, stderr, log_reader) = pipe_readers(stdout, stderr)?;
+
+    let log_match = service
+        .spec
+        .healthcheck
+        .as_ref()
+        .and_then(|health_check| health_check::watch_log_pattern(health_check, &mut sink));
+
+    let finished = Arc::new(PipeReadersFinished {
+        #[cfg(test)]
+        _active: ActiveLogReaderGuard::new(service_id.clone(), run_id),
+        _finished: LogReaderFinishedGuard {
+            events_tx: events_tx.clone(),
+            service_id: service_id.clone(),
+            run_id,
+        },
+    });
+    for (stream, reader) in [
+        (OutputStream::Stdout, stdout),
+        (OutputStream::Stderr, stderr),
+    ] {
+        spawn_pipe_reader_thread(PipeReaderArgs {
+            service_id: service_id.clone(),
+            run_id,
+            stream,
+            sink: sink.clone(),
+            reader,
+            finished: finished.clone(),
+        });
+    }
+    drop(finished);
+
+    let pause = spawn_run_tasks(RunTasks {
+        service,
+        run_id,
+        sink,
+        working_dir,
+        log_match,
+        events_tx,
+        shutdown,
+        terminate,
+        killer,
+        child: Box::new(child),
+        pid: Some(pid),
+        process_group_leader_id: process_group_leader,
+        pty_shutdown: None,
+        #[cfg(windows)]
+        process_job,
+    });
+    child_guard.disarm();
+
+    Ok(StartedPty {
+        pid: Some(pid),
+        process_group_leader_id: process_group_leader,
+        paused: pause,
+        handles: PtyHandles::without_terminal(),
+        log_reader,
+    })
+}
+
+/// Wrap both output pipes in readers that one [`LogReaderHandle`] cancels together.
+#[cfg(unix)]
+fn pipe_readers(
+    stdout: ChildStdout,
+    stderr: ChildStderr,
+) -> Result<(PtyOutputReader, PtyOutputReader, LogReaderHandle), Error> {
+    use super::{PollingPtyReader, PtyCancellation};
+    use std::os::fd::AsRawFd as _;
+
+    let (cancel_read, cancellation) = PtyCancellation::pipe()?;
+    let stderr_cancel_read = cancel_read
+        .try_clone()
+        .map_err(|err| Error::operation("failed to clone output cancellation fd", err))?;
+    let stdout_fd = stdout.as_raw_fd();
+    let stdout = PollingPtyReader::with_reader(
+        Box::new(stdout),
+        stdout_fd,
+        cancel_read,
+        cancellation.cancelled.clone(),
+    )?;
+    let stderr_fd = stderr.as_raw_fd();
+    let stderr = PollingPtyReader::with_reader(
+        Box::new(stderr),
+        stderr_fd,
+        stderr_cancel_read,
+        cancellation.cancelled.clone(),
+    )?;
+    Ok((
+        PtyOutputReader::Polling(stdout),
+        PtyOutputReader::Polling(stderr),
+        LogReaderHandle { cancellation },
+    ))
+}
+
+#[cfg(not(unix))]
+#[expect(
+    clippy::unnecessary_wraps,
+    reason = "Unix setup is fallible; one constructor keeps the caller portable"
+)]
+fn pipe_readers(
+    stdout: ChildStdout,
+    stderr: ChildStderr,
+) -> Result<(PtyOutputReader, PtyOutputReader, LogReaderHandle), Error> {
+    Ok((
+        PtyOutputReader::Blocking(std::io::BufReader::new(Box::new(stdout))),
+        PtyOutputReader::Blocking(std::io::BufReader::new(Box::new(stderr))),
+        LogReaderHandle::new(),
+    ))
+}
+
+fn spawn_pipe_reader_thread(args: PipeReaderArgs) {
+    thread::spawn(move || {
+        let PipeReaderArgs {
+            service_id,
+            run_id,
+            stream,
+            sink,
+            mut reader,
+            finished: _finished,
+        } = args;
+
+        let emit = |line: &mut Vec<u8>| {
+            let text = String::from_utf8_lossy(line).into_owned();
+            sink.append_log(stream, LogUpdateKind::Append, text);
+            line.clear();
+        };
+
+        let mut buf = [0u8; 4096];
+        let mut line: Vec<u8> = Vec::new();
+        // Colors survive; cursor movement and other controls have nothing to move without a screen.
+        let mut filter = AnsiFilter::new();
+        loop {
+            let n = match reader.read(&mut buf) {
+                Ok(Some(n)) => n.get(),
+                Ok(None) => break,
+                Err(err) => {
+                    tracing::warn!(
+                        ?err,
+                        %service_id,
+                        run_id = run_id.get(),
+                        ?stream,
+                        "failed to read service output pipe"
+                    );
+                    break;
+                }
+            };
+            let Some(chunk) = buf.get(..n) else {
+                continue;
+            };
+            for &b in chunk {
+                if b == b'\n' && filter.captures_line_break() {
+                    emit(&mut line);
+                    continue;
+                }
+                let _ = filter.push(b, &mut line);
+                if line.len() >= PTY_LOG_LINE_MAX_BYTES {
+                    let suffix = line.split_off(bounded_line_split(&line));
+                    emit(&mut line);
+                    line = suffix;
+                }
+            }
+        }
+        if !line.is_empty() {
+            emit(&mut line);
+        }
+    });
+}
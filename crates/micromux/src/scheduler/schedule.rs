@@ -1,12 +1,18 @@
 use super::{
-    DesiredState, ProcessEvent, RunConfig, RunningService, ServiceID, ServiceRuntime,
-    SessionModelWriter, State, pty, service_event, sync_model,
+    DesiredState, ExternalProbe, ProcessEvent, RunConfig, RunId, RunningService, ServiceID,
+    ServiceRuntime, SessionModelWriter, State, ports, pty, service_event, sync_model, unix_now_ms,
 };
 #[cfg(test)]
 use super::{Event, ServiceRuntimeInit, TestEventSink};
-use crate::config::DependencyCondition;
-use crate::{ServiceEventKind, ServiceMap, health_check::Health};
+use crate::config::{DependencyCondition, OnDependencyRestart};
+use crate::model::{HookKind, HookSink, RunSink};
+use crate::service::{Service, SpawnWorkingDirectory};
+use crate::{
+    EventCause, PortConflictPolicy, RunSchedule, ServiceEventKind, ServiceMap, health_check::Health,
+};
+use indexmap::IndexMap;
 use std::collections::HashMap;
+use std::time::Duration;
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
 
@@ -19,6 +25,107 @@ pub(super) struct ScheduleContext<'a> {
     pub(super) test_events: &'a mut TestEventSink,
     pub(super) writer: &'a SessionModelWriter,
     pub(super) shutdown: &'a CancellationToken,
+    pub(super) ports: &'a mut ports::PortAllocator,
+}
+
+/// A run held in `Starting` while its `pre_start` hooks run; its process is spawned only once they
+/// all succeed. Dropping it cancels the hooks.
+pub(super) struct PendingStart {
+    pub(super) run_id: RunId,
+    terminate: CancellationToken,
+    sink: RunSink,
+    service: Service,
+    #[cfg(test)]
+    clear_logs: bool,
+    _hooks: tokio_util::sync::DropGuard,
+}
+
+/// The next tick of a scheduled service, on both clocks: the monotonic deadline the scheduler
+/// sleeps until and the wall-clock time snapshots report.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct ScheduledTick {
+    pub(super) at: tokio::time::Instant,
+    pub(super) at_unix_ms: u64,
+}
+
+impl ScheduledTick {
+    /// The first tick of `schedule` after both `after_unix_ms` and now, so a timer that fires a
+    /// little early by the wall clock never repeats the tick it fired for.
+    fn next(schedule: &RunSchedule, after_unix_ms: u64) -> Option<Self> {
+        let now_unix_ms = unix_now_ms()?;
+        let at_unix_ms = schedule.next_after(after_unix_ms.max(now_unix_ms))?;
+        let at = tokio::time::Instant::now().checked_add(std::time::Duration::from_millis(
+            at_unix_ms.saturating_sub(now_unix_ms),
+        ))?;
+        Some(Self { at, at_unix_ms })
+    }
+}
+
+/// Hook commands of one run, resolved with the environment and working directory it runs with.
+pub(super) struct RunHooks {
+    commands: Vec<Vec<String>>,
+    environment: IndexMap<String, String>,
+    working_dir: Option<SpawnWorkingDirectory>,
+    timeout: Option<Duration>,
+}
+
+impl RunHooks {
+    /// `None` when there are no commands to run.
+    fn new(
+        service: &Service,
+        commands: &[Vec<String>],
+    ) -> Result<Option<Self>, crate::service::Error> {
+        if commands.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            commands: commands.to_vec(),
+            environment: service.spec.environment.clone(),
+            working_dir: service.spawn_working_directory()?,
+            timeout: None,
+        }))
+    }
+
+    /// Kill each command still running after `timeout`.
+    pub(super) fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Run the hooks on a task that reports [`ProcessEvent::HooksFinished`] for `run_id`.
+    pub(super) fn spawn(
+        self,
+        service_id: ServiceID,
+        run_id: RunId,
+        kind: HookKind,
+        sink: HookSink,
+        events_tx: mpsc::Sender<ProcessEvent>,
+        cancel: CancellationToken,
+    ) {
+        tokio::spawn(async move {
+            let result = crate::hooks::run(
+                kind,
+                &self.commands,
+                &self.environment,
+                self.working_dir
+                    .as_ref()
+                    .map(SpawnWorkingDirectory::as_path),
+                &sink,
+                &cancel,
+                self.timeout,
+            )
+            .await
+            .map_err(|err| err.summary());
+            let _ = events_tx
+                .send(ProcessEvent::HooksFinished {
+                    service_id,
+                    run_id,
+                    kind,
+                    result,
+                })
+                .await;
+        });
+    }
 }
 
 /// One dependency that is holding a service back, kept with its condition so the wait can be
@@ -27,6 +134,8 @@ pub(super) struct ScheduleContext<'a> {
 struct BlockedDependency {
     service: ServiceID,
     condition: DependencyCondition,
+    /// The dependency's latest run hit its startup timeout.
+    timed_out: bool,
 }
 
 impl BlockedDependency {
@@ -36,7 +145,12 @@ impl BlockedDependency {
             DependencyCondition::Healthy => "to become healthy",
             DependencyCondition::CompletedSuccessfully => "to complete successfully",
         };
-        format!("{} {condition}", self.service)
+        let timed_out = if self.timed_out {
+            " (its last start timed out)"
+        } else {
+            ""
+        };
+        format!("{} {condition}{timed_out}", self.service)
     }
 }
 
@@ -58,11 +172,13 @@ fn blocking_dependencies(
         .iter()
         .filter_map(|dep| {
             let condition = dep.condition;
+            let runtime = ctx.runtimes.get(dep.service.as_str());
             let blocked = || BlockedDependency {
                 service: dep.service.clone(),
                 condition,
+                timed_out: runtime.is_some_and(|runtime| runtime.startup_timed_out),
             };
-            let Some(runtime) = ctx.runtimes.get(dep.service.as_str()) else {
+            let Some(runtime) = runtime else {
                 return Some(blocked());
             };
 
@@ -70,24 +186,89 @@ fn blocking_dependencies(
                 return Some(blocked());
             }
 
-            let ready = match condition {
-                DependencyCondition::Started => matches!(runtime.state, State::Running { .. }),
-                DependencyCondition::Healthy => matches!(
-                    runtime.state,
-                    State::Running {
-                        health: Some(Health::Healthy),
-                        ..
-                    }
-                ),
-                DependencyCondition::CompletedSuccessfully => {
-                    matches!(runtime.state, State::Exited { exit_code: 0, .. })
-                }
-            };
-            (!ready).then(blocked)
+            (!satisfies(runtime, condition)).then(blocked)
         })
         .collect()
 }
 
+fn satisfies(runtime: &ServiceRuntime, condition: DependencyCondition) -> bool {
+    match condition {
+        DependencyCondition::Started => matches!(runtime.state, State::Running { .. }),
+        DependencyCondition::Healthy => matches!(
+            runtime.state,
+            State::Running {
+                health: Some(Health::Healthy),
+                ..
+            }
+        ),
+        DependencyCondition::CompletedSuccessfully => {
+            matches!(runtime.state, State::Exited { exit_code: 0, .. })
+        }
+    }
+}
+
+/// The first `on_dependency_restart: restart` dependency of a running service that has started a
+/// newer run than the one the service started against, and whose new run satisfies the
+/// dependency's condition again.
+fn restarted_dependency(
+    ctx: &ScheduleContext<'_>,
+    runtime: &ServiceRuntime,
+    service: &crate::service::Service,
+) -> Option<EventCause> {
+    service
+        .spec
+        .depends_on
+        .iter()
+        .filter(|dep| dep.on_dependency_restart == OnDependencyRestart::Restart)
+        .find_map(|dep| {
+            let started_against = *runtime.dependency_runs.get(&dep.service)?;
+            let dependency = ctx.runtimes.get(&dep.service)?;
+            let run_generation = dependency.run_generation();
+            (run_generation > started_against
+                && !dependency.start_requested
+                && satisfies(dependency, dep.condition))
+            .then(|| EventCause {
+                service: dep.service.clone(),
+                run_generation,
+            })
+        })
+}
+
+/// Restart running services whose `on_dependency_restart: restart` dependencies came back with a
+/// new run. Each restart is recorded with the dependency run that caused it, and the replacement
+/// starts only once its dependencies are ready, so a chain of dependents restarts in order.
+fn restart_after_dependencies(ctx: &mut ScheduleContext<'_>) {
+    for (service_id, service) in ctx.services {
+        let Some(runtime) = ctx.runtimes.get(service_id) else {
+            continue;
+        };
+        if runtime.desired == DesiredState::Disabled
+            || !matches!(runtime.state, State::Running { .. })
+        {
+            continue;
+        }
+        let Some(cause) = restarted_dependency(ctx, runtime, service) else {
+            continue;
+        };
+        let Some(runtime) = ctx.runtimes.get_mut(service_id) else {
+            continue;
+        };
+        let generation = runtime.run_generation();
+        runtime.request_restart();
+        sync_model(ctx.writer, service, runtime);
+        let mut event = service_event(
+            generation,
+            ServiceEventKind::DependencyRestarted,
+            format!(
+                "restarting after {} restarted (run {})",
+                cause.service, cause.run_generation
+            ),
+        );
+        event.caused_by = Some(cause);
+        ctx.writer.append_event(service_id, event);
+    }
+}
+
 enum StartCheck {
     Skip,
     Consider { exited_code: Option<i32> },
@@ -102,7 +283,18 @@ fn should_consider_start(
         return StartCheck::Skip;
     };
 
-    if runtime.desired == DesiredState::Disabled || runtime.running.is_some() {
+    // The next run waits for the previous run's `post_stop` hooks. An external service is only
+    // ever probed, never started.
+    if runtime.desired == DesiredState::Disabled
+        || runtime.running.is_some()
+        || runtime.post_stop_run.is_some()
+        || service.external
+    {
+        return StartCheck::Skip;
+    }
+
+    // A scheduled service starts only at its ticks, or when an operator restarts it.
+    if runtime.schedule.is_some() && !runtime.start_requested {
         return StartCheck::Skip;
     }
 
@@ -139,7 +331,7 @@ fn decrement_failure_budget(
     exited_code: Option<i32>,
 ) {
     if !explicit_start
-        && exited_code.is_some_and(|exit_code| exit_code != 0)
+        && exited_code.is_some_and(|exit_code| exit_code != 0 || runtime.unhealthy_restart)
         && matches!(
             service.spec.restart,
             crate::service::RestartPolicy::OnFailure { .. }
@@ -161,13 +353,19 @@ fn record_dependency_block(
         .iter()
         .map(|dependency| dependency.service.clone())
         .collect::<Vec<_>>();
+    let timed_out = blocked
+        .iter()
+        .filter(|dependency| dependency.timed_out)
+        .map(|dependency| dependency.service.clone())
+        .collect::<Vec<_>>();
     let Some(runtime) = ctx.runtimes.get_mut(service_id) else {
         return;
     };
-    if runtime.blocked_on == blocked_on {
+    if runtime.blocked_on == blocked_on && runtime.timed_out_dependencies == timed_out {
         return;
     }
     runtime.blocked_on.clone_from(&blocked_on);
+    runtime.timed_out_dependencies = timed_out;
     let generation = runtime.run_generation();
     sync_model(ctx.writer, service, runtime);
 
@@ -180,10 +378,10 @@ fn record_dependency_block(
     ctx.writer.append_event(service_id, event);
 }
 
-/// Forget a recorded dependency block once the service stops being a start candidate — it was
-/// disabled, retired, or parked in a restart backoff. Without this the snapshot would keep
-/// reporting `Blocked` for a service nothing is waiting to start.
-fn clear_dependency_block(
+/// Forget a recorded dependency block or port wait once the service stops being a start
+/// candidate — it was disabled, retired, or parked in a restart backoff. Without this the snapshot
+/// would keep reporting `Blocked` for a service nothing is waiting to start.
+fn clear_start_block(
     ctx: &mut ScheduleContext<'_>,
     service_id: &ServiceID,
     service: &crate::service::Service,
@@ -191,11 +389,50 @@ fn clear_dependency_block(
     let Some(runtime) = ctx.runtimes.get_mut(service_id) else {
         return;
     };
-    if runtime.blocked_on.is_empty() {
+    let waiting_for_port = runtime
+        .port_conflict
+        .as_ref()
+        .is_some_and(|conflict| conflict.waiting);
+    runtime.port_recheck_at = None;
+    if runtime.blocked_on.is_empty() && !waiting_for_port {
         return;
     }
     runtime.blocked_on.clear();
+    runtime.timed_out_dependencies.clear();
+    if waiting_for_port {
+        runtime.port_conflict = None;
+    }
+    sync_model(ctx.writer, service, runtime);
+}
+
+/// Hold a `port_conflict: wait` service back while `port` is held. The conflict is recorded, and
+/// its holder looked up, only when it first appears, so rechecks stay cheap and quiet. The
+/// `PortConflict` event is appended once the holder is known.
+fn record_port_wait(
+    ctx: &mut ScheduleContext<'_>,
+    service_id: &ServiceID,
+    service: &crate::service::Service,
+    port: u16,
+    unblocked: bool,
+) {
+    let Some(runtime) = ctx.runtimes.get_mut(service_id) else {
+        return;
+    };
+    runtime.port_recheck_at = tokio::time::Instant::now().checked_add(ports::PORT_RECHECK_INTERVAL);
+    let known = runtime
+        .port_conflict
+        .as_ref()
+        .is_some_and(|conflict| conflict.waiting && conflict.port == port);
+    if known {
+        if unblocked {
+            sync_model(ctx.writer, service, runtime);
+        }
+        return;
+    }
+    runtime.port_conflict = Some(ports::conflict(port, true));
     sync_model(ctx.writer, service, runtime);
+    let run_id = RunId::new(runtime.run_generation());
+    ports::spawn_owner_lookup(ctx.events_tx, service_id, run_id, port);
 }
 
 fn finish_service_start(
@@ -216,11 +453,22 @@ fn finish_service_start(
             runtime.mark_started(RunningService {
                 run_id,
                 pid,
+                process_group_leader_id: started.process_group_leader_id,
+                paused: started.paused,
                 terminate,
                 log_reader: Some(started.log_reader),
                 pty: started.handles,
                 since: tokio::time::Instant::now(),
             });
+            runtime.startup_deadline = service
+                .spec
+                .startup_timeout
+                .filter(|_| service.spec.healthcheck.is_some())
+                .and_then(|timeout| tokio::time::Instant::now().checked_add(timeout));
+            runtime.runtime_deadline = service
+                .spec
+                .max_runtime
+                .and_then(|max_runtime| tokio::time::Instant::now().checked_add(max_runtime));
             sync_model(ctx.writer, service, runtime);
             let mut event = service_event(
                 run_id.get(),
@@ -271,6 +519,15 @@ fn finish_service_start(
                 );
                 backoff.delay_ms = u64::try_from(delay.as_millis()).ok();
                 ctx.writer.append_event(service_id, backoff);
+            } else if runtime.restart.crash_loop {
+                ctx.writer.append_event(
+                    service_id,
+                    service_event(
+                        run_id.get(),
+                        ServiceEventKind::CrashLoop,
+                        runtime.restart.crash_loop_detail(),
+                    ),
+                );
             }
             #[cfg(test)]
             ctx.test_events
@@ -280,6 +537,90 @@ fn finish_service_start(
     }
 }
 
+/// The run generation of each dependency whose restart restarts `service`.
+fn dependency_runs(ctx: &ScheduleContext<'_>, service: &Service) -> HashMap<ServiceID, u64> {
+    service
+        .spec
+        .depends_on
+        .iter()
+        .filter(|dep| dep.on_dependency_restart == OnDependencyRestart::Restart)
+        .filter_map(|dep| {
+            let runtime = ctx.runtimes.get(&dep.service)?;
+            Some((dep.service.clone(), runtime.run_generation()))
+        })
+        .collect()
+}
+
+/// Clear a service's blocked dependencies now that they are ready. Returns whether it was blocked.
+fn mark_dependencies_ready(
+    writer: &SessionModelWriter,
+    service_id: &ServiceID,
+    runtime: &mut ServiceRuntime,
+) -> bool {
+    if runtime.blocked_on.is_empty() {
+        return false;
+    }
+    runtime.blocked_on.clear();
+    runtime.timed_out_dependencies.clear();
+    writer.append_event(
+        service_id,
+        service_event(
+            runtime.run_generation(),
+            ServiceEventKind::DependencyReady,
+            "dependencies are ready",
+        ),
+    );
+    true
+}
+
+/// Stop waiting under `port_conflict: wait`, recording that the port came free.
+fn release_port_wait(
+    writer: &SessionModelWriter,
+    service_id: &ServiceID,
+    runtime: &mut ServiceRuntime,
+) {
+    runtime.port_recheck_at = None;
+    if let Some(conflict) = runtime.port_conflict.take_if(|conflict| conflict.waiting) {
+        writer.append_event(
+            service_id,
+            service_event(
+                runtime.run_generation(),
+                ServiceEventKind::PortReleased,
+                format!("port {} is free", conflict.port),
+            ),
+        );
+    }
+}
+
+/// The service a run spawns with its ports resolved, and its `pre_start` hooks if it has any.
+fn prepare_run(
+    resolved: Result<ports::ResolvedPorts, ports::Error>,
+    conflict: Option<&crate::PortConflict>,
+) -> Result<(Service, Option<RunHooks>), pty::Error> {
+    let resolved = match (resolved, conflict) {
+        (Ok(_), Some(conflict)) => {
+            return Err(pty::Error::Operation {
+                operation: "check ports",
+                message: conflict.describe(),
+            });
+        }
+        (Ok(resolved), None) => resolved.service,
+        (Err(err), _) => {
+            return Err(pty::Error::Operation {
+                operation: "resolve ports",
+                message: err.to_string(),
+            });
+        }
+    };
+    let hooks = RunHooks::new(&resolved, &resolved.spec.pre_start).map_err(|err| {
+        pty::Error::Operation {
+            operation: "resolve pre_start working directory",
+            message: err.to_string(),
+        }
+    })?;
+    Ok((resolved, hooks))
+}
+
 fn start_service_if_ready(
     ctx: &mut ScheduleContext<'_>,
     service_id: &ServiceID,
@@ -290,25 +631,35 @@ fn start_service_if_ready(
         return false;
     }
 
+    let resolved = ctx.ports.resolve(ctx.services, service_id, service);
+    let held_port = match (&resolved, service.spec.port_conflict) {
+        (Ok(resolved), PortConflictPolicy::Wait | PortConflictPolicy::Fail) => resolved.held_port(),
+        _ => None,
+    };
+    let dependency_runs = dependency_runs(ctx, service);
+    let Some(runtime) = ctx.runtimes.get_mut(service_id) else {
+        return false;
+    };
+    let unblocked = mark_dependencies_ready(ctx.writer, service_id, runtime);
+    if let Some(port) = held_port
+        && service.spec.port_conflict == PortConflictPolicy::Wait
+    {
+        record_port_wait(ctx, service_id, service, port, unblocked);
+        return false;
+    }
     tracing::info!(service_id, "starting service");
 
     let Some(runtime) = ctx.runtimes.get_mut(service_id) else {
         return false;
     };
-    if !runtime.blocked_on.is_empty() {
-        runtime.blocked_on.clear();
-        ctx.writer.append_event(
-            service_id,
-            service_event(
-                runtime.run_generation(),
-                ServiceEventKind::DependencyReady,
-                "dependencies are ready",
-            ),
-        );
-    }
+    release_port_wait(ctx.writer, service_id, runtime);
+    // Under `fail` the conflict stays on the snapshot after the failed start, naming the holder.
+    let conflict = held_port.map(|port| ports::conflict(port, false));
+    runtime.port_conflict.clone_from(&conflict);
     let explicit_start = runtime.start_requested;
     let clear_logs = runtime.clear_logs_on_start;
     decrement_failure_budget(runtime, service, explicit_start, exited_code);
+    runtime.unhealthy_restart = false;
 
     runtime.start_requested = false;
     runtime.clear_logs_on_start = false;
@@ -319,10 +670,18 @@ fn start_service_if_ready(
         draining.request_cancel();
     }
     runtime.mark_starting();
+    runtime.dependency_runs = dependency_runs;
     runtime.run_config = Some(RunConfig::from(service));
+    runtime.allocated_ports = resolved
+        .as_ref()
+        .map(|resolved| resolved.allocated.clone())
+        .unwrap_or_default();
     sync_model(ctx.writer, service, runtime);
 
     let run_id = runtime.allocate_run_id();
+    if let Some(port) = held_port {
+        ports::spawn_owner_lookup(ctx.events_tx, service_id, run_id, port);
+    }
     let terminate = CancellationToken::new();
     ctx.writer.begin_run(service_id, run_id.get());
     if clear_logs {
@@ -330,16 +689,125 @@ fn start_service_if_ready(
     }
     let sink = ctx.writer.run_sink(service_id, run_id.get());
 
-    let result = pty::start_service_with_pty_size(pty::StartServiceParams {
+    let (result, resolved) = match prepare_run(resolved, conflict.as_ref()) {
+        Ok((resolved, Some(hooks))) => {
+            let cancel = ctx.shutdown.child_token();
+            hooks.spawn(
+                service_id.clone(),
+                run_id,
+                HookKind::PreStart,
+                ctx.writer.hook_sink(Some(service_id), run_id.get()),
+                ctx.events_tx.clone(),
+                cancel.clone(),
+            );
+            if let Some(runtime) = ctx.runtimes.get_mut(service_id) {
+                runtime.pending_start = Some(PendingStart {
+                    run_id,
+                    terminate,
+                    sink,
+                    service: resolved,
+                    #[cfg(test)]
+                    clear_logs,
+                    _hooks: cancel.drop_guard(),
+                });
+            }
+            return false;
+        }
+        Ok((resolved, None)) => (
+            spawn_run(ctx, &resolved, run_id, sink, &terminate),
+            Some(resolved),
+        ),
+        Err(err) => (Err(err), None),
+    };
+    let started = finish_service_start(
+        ctx,
+        service_id,
+        service,
+        run_id,
+        terminate,
+        #[cfg(test)]
+        clear_logs,
+        result,
+    );
+    if started && let Some(resolved) = resolved {
+        record_post_stop_hooks(ctx, service_id, &resolved);
+    }
+    started
+}
+
+fn spawn_run(
+    ctx: &ScheduleContext<'_>,
+    service: &Service,
+    run_id: RunId,
+    sink: RunSink,
+    terminate: &CancellationToken,
+) -> Result<pty::StartedPty, pty::Error> {
+    let params = pty::StartServiceParams {
         service,
         run_id,
         sink,
         events_tx: ctx.events_tx,
         shutdown: ctx.shutdown,
-        terminate: &terminate,
+        terminate,
         pty_size: ctx.current_pty_size,
+    };
+    if service.spec.tty {
+        pty::start_service_with_pty_size(params)
+    } else {
+        pty::start_service_with_pipes(params)
+    }
+}
+
+/// Keep the `post_stop` hooks of a run that started, resolved as it was, for when it exits.
+fn record_post_stop_hooks(
+    ctx: &mut ScheduleContext<'_>,
+    service_id: &ServiceID,
+    resolved: &Service,
+) {
+    let Some(runtime) = ctx.runtimes.get_mut(service_id) else {
+        return;
+    };
+    runtime.post_stop = RunHooks::new(resolved, &resolved.spec.post_stop).unwrap_or_else(|err| {
+        tracing::warn!(service_id, %err, "post_stop hooks will not run");
+        None
     });
-    finish_service_start(
+}
+
+/// Spawn a run held back by its `pre_start` hooks once they finish, or fail its start with their
+/// error. Reports for a start that was since abandoned are ignored.
+pub(super) fn finish_pre_start(
+    ctx: &mut ScheduleContext<'_>,
+    service_id: &ServiceID,
+    run_id: RunId,
+    result: &Result<(), String>,
+) -> bool {
+    let Some(service) = ctx.services.get(service_id) else {
+        return false;
+    };
+    let Some(pending) = ctx.runtimes.get_mut(service_id).and_then(|runtime| {
+        runtime
+            .pending_start
+            .take_if(|pending| pending.run_id == run_id)
+    }) else {
+        return false;
+    };
+    let PendingStart {
+        run_id,
+        terminate,
+        sink,
+        service: resolved,
+        #[cfg(test)]
+        clear_logs,
+        _hooks,
+    } = pending;
+    let result = match result {
+        Ok(()) => spawn_run(ctx, &resolved, run_id, sink, &terminate),
+        Err(message) => Err(pty::Error::Operation {
+            operation: "run pre_start hooks",
+            message: message.clone(),
+        }),
+    };
+    let started = finish_service_start(
         ctx,
         service_id,
         service,
@@ -348,7 +816,11 @@ fn start_service_if_ready(
         #[cfg(test)]
         clear_logs,
         result,
-    )
+    );
+    if started {
+        record_post_stop_hooks(ctx, service_id, &resolved);
+    }
+    started
 }
 
 /// Records only blockers that remain after every startable dependency has been started.
@@ -367,13 +839,181 @@ fn record_stable_dependency_blocks(ctx: &mut ScheduleContext<'_>) {
     }
 }
 
+/// Stop runs whose startup deadline passed before they became healthy. Their exit then goes
+/// through the restart policy like any other, and dependents waiting on them report the timeout.
+fn stop_timed_out_starts(ctx: &mut ScheduleContext<'_>) {
+    let now = tokio::time::Instant::now();
+    for (service_id, service) in ctx.services {
+        let Some(runtime) = ctx.runtimes.get_mut(service_id) else {
+            continue;
+        };
+        if runtime
+            .startup_deadline
+            .is_none_or(|deadline| deadline > now)
+        {
+            continue;
+        }
+        runtime.startup_deadline = None;
+        if !matches!(runtime.state, State::Running { .. }) {
+            continue;
+        }
+        let Some(running) = &mut runtime.running else {
+            continue;
+        };
+        running.cancel();
+        runtime.mark_killed();
+        runtime.startup_timed_out = true;
+        sync_model(ctx.writer, service, runtime);
+        let timeout = service.spec.startup_timeout.unwrap_or_default();
+        ctx.writer.append_event(
+            service_id,
+            service_event(
+                runtime.run_generation(),
+                ServiceEventKind::StartupTimedOut,
+                format!(
+                    "service did not become healthy within {} ms; stopping it",
+                    timeout.as_millis()
+                ),
+            ),
+        );
+    }
+}
+
+/// Start probing every enabled external service that is waiting for its probe. The probe loop
+/// stands in for a run: its health events drive the service's state, so dependents gate on it like
+/// on any other service.
+fn start_external_probes(ctx: &mut ScheduleContext<'_>) {
+    for (service_id, service) in ctx.services {
+        let Some(healthcheck) = service
+            .spec
+            .healthcheck
+            .clone()
+            .filter(|_| service.external)
+        else {
+            continue;
+        };
+        let Some(runtime) = ctx.runtimes.get_mut(service_id) else {
+            continue;
+        };
+        if runtime.external_probe.is_some()
+            || runtime.desired == DesiredState::Disabled
+            || runtime.is_retired()
+            || !matches!(runtime.state, State::Pending)
+        {
+            continue;
+        }
+        runtime.start_requested = false;
+        let run_id = runtime.allocate_run_id();
+        let working_dir = match service.spawn_working_directory() {
+            Ok(working_dir) => working_dir,
+            Err(err) => {
+                runtime.last_run_id = Some(run_id);
+                runtime.state = State::Exited { exit_code: -1 };
+                sync_model(ctx.writer, service, runtime);
+                ctx.writer.append_event(
+                    service_id,
+                    service_event(
+                        run_id.get(),
+                        ServiceEventKind::SpawnFailed,
+                        format!("could not probe external service: {err}"),
+                    ),
+                );
+                continue;
+            }
+        };
+        ctx.writer.begin_run(service_id, run_id.get());
+        let terminate = CancellationToken::new();
+        let (pause, paused) = tokio::sync::watch::channel(false);
+        tokio::spawn(crate::health_check::run_loop(
+            healthcheck.clone(),
+            crate::health_check::RunLoopParams {
+                service_id: service_id.clone(),
+                run_id,
+                sink: ctx.writer.run_sink(service_id, run_id.get()),
+                working_dir,
+                environment: service
+                    .spec
+                    .environment
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect(),
+                events_tx: ctx.events_tx.clone(),
+                shutdown: ctx.shutdown.clone(),
+                terminate: terminate.clone(),
+                log_match: None,
+                paused,
+            },
+        ));
+        runtime.external_probe = Some(ExternalProbe {
+            run_id,
+            healthcheck,
+            terminate,
+            _paused: pause,
+        });
+        runtime.uptime_started_at = Some(std::time::Instant::now());
+        runtime.started_at_unix_ms = unix_now_ms();
+        runtime.state = State::Running { health: None };
+        runtime.run_config = Some(RunConfig::from(service));
+        sync_model(ctx.writer, service, runtime);
+    }
+}
+
+/// Arm the next tick of every enabled scheduled service and request a run for each tick that is
+/// due. A tick that finds the previous run still alive is skipped and recorded; missed ticks are
+/// never made up.
+fn start_due_ticks(ctx: &mut ScheduleContext<'_>) {
+    let now = tokio::time::Instant::now();
+    for (service_id, service) in ctx.services {
+        let Some(runtime) = ctx.runtimes.get_mut(service_id) else {
+            continue;
+        };
+        let Some(schedule) = &runtime.schedule else {
+            continue;
+        };
+        if runtime.desired == DesiredState::Disabled || runtime.is_retired() {
+            runtime.next_tick = None;
+            continue;
+        }
+        let due = match runtime.next_tick {
+            Some(tick) if tick.at > now => continue,
+            Some(tick) => tick,
+            None => {
+                runtime.next_tick = ScheduledTick::next(schedule, 0);
+                sync_model(ctx.writer, service, runtime);
+                continue;
+            }
+        };
+        runtime.next_tick = ScheduledTick::next(schedule, due.at_unix_ms);
+        let alive = runtime.running.is_some()
+            || runtime.pending_start.is_some()
+            || runtime.post_stop_run.is_some();
+        if alive {
+            sync_model(ctx.writer, service, runtime);
+            ctx.writer.append_event(
+                service_id,
+                service_event(
+                    runtime.run_generation(),
+                    ServiceEventKind::ScheduledRunSkipped,
+                    "skipping a scheduled run: the previous run is still running",
+                ),
+            );
+            continue;
+        }
+        runtime.start_requested = true;
+        sync_model(ctx.writer, service, runtime);
+    }
+}
+
 pub(super) fn schedule_ready(ctx: &mut ScheduleContext<'_>) {
+    stop_timed_out_starts(ctx);
+    start_external_probes(ctx);
+    start_due_ticks(ctx);
     loop {
         let mut started = false;
         for (service_id, service) in ctx.services {
             let exited_code = match should_consider_start(ctx, service_id, service) {
                 StartCheck::Skip => {
-                    clear_dependency_block(ctx, service_id, service);
+                    clear_start_block(ctx, service_id, service);
                     continue;
                 }
                 StartCheck::Consider { exited_code } => exited_code,
@@ -395,6 +1035,7 @@ pub(super) fn schedule_ready(ctx: &mut ScheduleContext<'_>) {
             break;
         }
     }
+    restart_after_dependencies(ctx);
 }
 
 #[cfg(test)]
@@ -425,6 +1066,7 @@ mod tests {
                 span: yaml_spanned::spanned::Span::default(),
                 inner: condition,
             }),
+            on_dependency_restart: None,
         }
     }
 
@@ -434,6 +1076,7 @@ mod tests {
         events_tx: &'a mpsc::Sender<ProcessEvent>,
         writer: &'a SessionModelWriter,
         shutdown: &'a CancellationToken,
+        ports: &'a mut ports::PortAllocator,
         #[cfg(test)] test_events: &'a mut TestEventSink,
     ) -> ScheduleContext<'a> {
         ScheduleContext {
@@ -450,6 +1093,7 @@ mod tests {
             test_events,
             writer,
             shutdown,
+            ports,
         }
     }
 
@@ -468,6 +1112,7 @@ mod tests {
                 .condition
                 .map(Spanned::into_inner)
                 .unwrap_or_default(),
+            on_dependency_restart: crate::OnDependencyRestart::Ignore,
         })
         .collect();
 
@@ -476,17 +1121,18 @@ mod tests {
         services.insert("svc".to_string(), svc.clone());
 
         let mut runtimes = HashMap::new();
-        let mut runtime = ServiceRuntime::new(ServiceRuntimeInit::from(&dep));
+        let mut runtime = ServiceRuntime::new(&ServiceRuntimeInit::from(&dep));
         runtime.state = State::Running { health: None };
         runtimes.insert("dep".to_string(), runtime);
         runtimes.insert(
             "svc".to_string(),
-            ServiceRuntime::new(ServiceRuntimeInit::from(&svc)),
+            ServiceRuntime::new(&ServiceRuntimeInit::from(&svc)),
         );
 
         let (events_tx, _events_rx) = mpsc::channel(1);
         let (_reader, writer) = crate::model::new([]);
         let shutdown = CancellationToken::new();
+        let mut ports = ports::PortAllocator::default();
         let (test_tx, _test_rx) = mpsc::channel(1);
         let mut test_events = TestEventSink::new(test_tx);
         let ctx = test_context(
@@ -495,6 +1141,7 @@ mod tests {
             &events_tx,
             &writer,
             &shutdown,
+            &mut ports,
             &mut test_events,
         );
 
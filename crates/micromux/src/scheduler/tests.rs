@@ -37,6 +37,9 @@ async fn run_test_scheduler(
         config_dir: Path::new(".").to_path_buf(),
         dynamic_policy: DynamicServicesPolicy::default(),
         default_log_retention: crate::LogRetention::default(),
+        shutdown_order: ShutdownOrder::default(),
+        tasks: crate::task::TaskMap::new(),
+        cleanup: crate::hooks::Cleanup::default(),
     })
     .await
 }
@@ -93,6 +96,22 @@ fn spawn_harness_with_policy(
     reload_config: Option<ReloadConfig>,
     config_dir: PathBuf,
     dynamic_policy: DynamicServicesPolicy,
+) -> Harness {
+    spawn_harness_with_shutdown_order(
+        services,
+        reload_config,
+        config_dir,
+        dynamic_policy,
+        ShutdownOrder::default(),
+    )
+}
+
+fn spawn_harness_with_shutdown_order(
+    services: ServiceMap,
+    reload_config: Option<ReloadConfig>,
+    config_dir: PathBuf,
+    dynamic_policy: DynamicServicesPolicy,
+    shutdown_order: ShutdownOrder,
 ) -> Harness {
     raise_test_file_descriptor_limit();
     let (commands_tx, commands_rx) = mpsc::channel(64);
@@ -117,6 +136,9 @@ fn spawn_harness_with_policy(
                 config_dir,
                 dynamic_policy,
                 default_log_retention: crate::LogRetention::default(),
+                shutdown_order,
+                tasks: crate::task::TaskMap::new(),
+                cleanup: crate::hooks::Cleanup::default(),
             })
             .await
         }
@@ -145,9 +167,13 @@ async fn config_reload_wait_keeps_draining_process_events() -> eyre::Result<()>
             test_events: TestEventSink::new(test_events_tx),
             writer,
             shutdown: CancellationToken::new(),
+            shutdown_order: ShutdownOrder::default(),
             config_dir: PathBuf::new(),
             dynamic_policy: DynamicServicesPolicy::default(),
             default_log_retention: LogRetention::default(),
+            watch_tx: mpsc::channel(1).0,
+            tasks: TaskMap::default(),
+            cleanup: crate::hooks::Cleanup::default(),
         },
     );
     events_tx
@@ -197,9 +223,13 @@ async fn config_reload_wait_yields_to_session_shutdown() -> eyre::Result<()> {
             test_events: TestEventSink::new(test_events_tx),
             writer,
             shutdown: shutdown.clone(),
+            shutdown_order: ShutdownOrder::default(),
             config_dir: PathBuf::new(),
             dynamic_policy: DynamicServicesPolicy::default(),
             default_log_retention: LogRetention::default(),
+            watch_tx: mpsc::channel(1).0,
+            tasks: TaskMap::default(),
+            cleanup: crate::hooks::Cleanup::default(),
         },
     );
     shutdown.cancel();
@@ -327,6 +357,7 @@ async fn assert_dynamic_retirement(harness: &Harness) -> eyre::Result<()> {
     blocked.spec.depends_on = Some(vec![crate::DependencySpec {
         service: "debug".to_string(),
         condition: config::DependencyCondition::Started,
+        on_dependency_restart: crate::OnDependencyRestart::Ignore,
     }]);
     let blocked = harness
         .control
@@ -579,11 +610,11 @@ fn roster_entry_state_classifies_origin_and_retirement() -> eyre::Result<()> {
     let mut runtimes = HashMap::from([
         (
             configured.id.clone(),
-            ServiceRuntime::new(ServiceRuntimeInit::from(&configured)),
+            ServiceRuntime::new(&ServiceRuntimeInit::from(&configured)),
         ),
         (
             dynamic.id.clone(),
-            ServiceRuntime::new(ServiceRuntimeInit::from(&dynamic)),
+            ServiceRuntime::new(&ServiceRuntimeInit::from(&dynamic)),
         ),
     ]);
 
@@ -617,9 +648,11 @@ fn roster_entry_state_classifies_origin_and_retirement() -> eyre::Result<()> {
 
 #[test]
 fn failed_start_advances_generation_and_records_exit() {
-    let mut runtime = ServiceRuntime::new(ServiceRuntimeInit {
+    let mut runtime = ServiceRuntime::new(&ServiceRuntimeInit {
         restart_policy: &crate::service::RestartPolicy::Never,
+        restart_backoff: crate::RestartBackoffSpec::default(),
         startup_mode: StartupMode::Enabled,
+        schedule: None,
     });
     runtime.mark_starting();
     let run_id = runtime.allocate_run_id();
@@ -639,7 +672,7 @@ fn project_snapshot_reports_runtime_identity_and_config_drift() -> eyre::Result<
         service_config("svc", ("sh", &["-c", "sleep 60"])),
     )?;
     let policy = service.spec.restart.clone();
-    let mut runtime = ServiceRuntime::new(ServiceRuntimeInit::from(&service));
+    let mut runtime = ServiceRuntime::new(&ServiceRuntimeInit::from(&service));
     runtime.run_config = Some(RunConfig::from(&service));
     start_dummy_run(&mut runtime)?;
 
@@ -674,7 +707,7 @@ fn project_snapshot_reports_active_restart_backoff() -> eyre::Result<()> {
     let mut config = service_config("svc", ("false", &[]));
     config.restart_policy = crate::service::RestartPolicy::Always;
     let service = Service::new("svc", Path::new("."), config)?;
-    let mut runtime = ServiceRuntime::new(ServiceRuntimeInit::from(&service));
+    let mut runtime = ServiceRuntime::new(&ServiceRuntimeInit::from(&service));
     runtime.run_config = Some(RunConfig::from(&service));
     start_dummy_run(&mut runtime)?;
     runtime.finish_current_run(&service.spec.restart, 1);
@@ -684,11 +717,80 @@ fn project_snapshot_reports_active_restart_backoff() -> eyre::Result<()> {
     let restart = snapshot
         .restart_state
         .ok_or_else(|| eyre::eyre!("missing active restart state"))?;
-    assert_eq!(restart.backoff_delay, RESTART_BACKOFF_BASE);
+    assert_eq!(restart.phase, crate::RestartPhase::Backoff);
+    assert_eq!(
+        restart.backoff_delay,
+        crate::spec::DEFAULT_RESTART_BACKOFF_INITIAL
+    );
     assert_eq!(restart.restarts_remaining, None);
     Ok(())
 }
 
+#[test]
+fn restart_backoff_jitter_does_not_compound_into_growth() {
+    let mut tracker = RestartTracker::new(
+        &crate::service::RestartPolicy::Always,
+        &crate::RestartBackoffSpec {
+            initial: Duration::from_millis(200),
+            jitter: 0.5,
+            ..crate::RestartBackoffSpec::default()
+        },
+    );
+
+    for step in [200, 400, 800] {
+        tracker.apply_backoff(false);
+        assert_eq!(tracker.backoff_step, Some(Duration::from_millis(step)));
+        let delay = tracker.backoff_delay.unwrap_or_default();
+        assert!(delay >= Duration::from_millis(step / 2));
+        assert!(delay <= Duration::from_millis(step * 3 / 2));
+    }
+
+    tracker.apply_backoff(true);
+    assert_eq!(tracker.backoff_step, Some(Duration::from_millis(200)));
+}
+
+#[test]
+fn crash_loop_breaker_parks_until_an_operator_restart() -> eyre::Result<()> {
+    let mut config = service_config("svc", ("false", &[]));
+    config.restart_policy = crate::service::RestartPolicy::Always;
+    config.restart_backoff = Some(crate::RestartBackoffSpec {
+        crash_loop: Some(crate::CrashLoopSpec {
+            crashes: 3,
+            window: Duration::from_mins(1),
+        }),
+        ..crate::RestartBackoffSpec::default()
+    });
+    let service = Service::new("svc", Path::new("."), config)?;
+    let mut runtime = ServiceRuntime::new(&ServiceRuntimeInit::from(&service));
+    runtime.run_config = Some(RunConfig::from(&service));
+
+    for _ in 0..2 {
+        start_dummy_run(&mut runtime)?;
+        runtime.finish_current_run(&service.spec.restart, 1);
+        let (snapshot, _) = project_snapshot(&service, &runtime);
+        assert_eq!(
+            snapshot.restart_state.map(|restart| restart.phase),
+            Some(crate::RestartPhase::Backoff)
+        );
+    }
+
+    start_dummy_run(&mut runtime)?;
+    runtime.finish_current_run(&service.spec.restart, 1);
+    let (snapshot, _) = project_snapshot(&service, &runtime);
+    let restart = snapshot
+        .restart_state
+        .ok_or_else(|| eyre::eyre!("missing crash-loop state"))?;
+    assert_eq!(restart.phase, crate::RestartPhase::CrashLoop);
+    assert_eq!(restart.backoff_delay, Duration::ZERO);
+    assert!(!runtime.will_auto_restart(&service.spec.restart, 1));
+
+    runtime.request_restart();
+    let (snapshot, _) = project_snapshot(&service, &runtime);
+    assert_eq!(snapshot.restart_state, None);
+    assert!(runtime.will_auto_restart(&service.spec.restart, 1));
+    Ok(())
+}
+
 /// Builds a run with inert PTY handles so lifecycle tests do not need a child process.
 fn start_dummy_run(runtime: &mut ServiceRuntime) -> eyre::Result<RunId> {
     runtime.mark_starting();
@@ -696,6 +798,8 @@ fn start_dummy_run(runtime: &mut ServiceRuntime) -> eyre::Result<RunId> {
     runtime.mark_started(RunningService {
         run_id,
         pid: Some(42),
+        process_group_leader_id: None,
+        paused: tokio::sync::watch::channel(false).0,
         terminate: CancellationToken::new(),
         log_reader: Some(pty::LogReaderHandle::test_dummy()),
         pty: pty::PtyHandles::test_dummy()?,
@@ -710,9 +814,11 @@ fn start_dummy_run(runtime: &mut ServiceRuntime) -> eyre::Result<RunId> {
 async fn post_exit_drain_cap_keeps_run_reserved_until_reader_finishes() -> eyre::Result<()> {
     let policy = crate::service::RestartPolicy::Never;
     let service_id = "svc".to_string();
-    let mut runtime = ServiceRuntime::new(ServiceRuntimeInit {
+    let mut runtime = ServiceRuntime::new(&ServiceRuntimeInit {
         restart_policy: &policy,
+        restart_backoff: crate::RestartBackoffSpec::default(),
         startup_mode: StartupMode::Enabled,
+        schedule: None,
     });
 
     let run_id = start_dummy_run(&mut runtime)?;
@@ -739,9 +845,11 @@ async fn post_exit_drain_cap_keeps_run_reserved_until_reader_finishes() -> eyre:
 #[tokio::test]
 async fn log_reader_finished_leaves_no_draining_handle_in_either_order() -> eyre::Result<()> {
     let policy = crate::service::RestartPolicy::Never;
-    let mut runtime = ServiceRuntime::new(ServiceRuntimeInit {
+    let mut runtime = ServiceRuntime::new(&ServiceRuntimeInit {
         restart_policy: &policy,
+        restart_backoff: crate::RestartBackoffSpec::default(),
         startup_mode: StartupMode::Enabled,
+        schedule: None,
     });
 
     // Reader finishes first (EOF/cancel before the exit is processed).
@@ -969,6 +1077,7 @@ async fn dynamic_policy_limit_ttl_and_dependency_gating() -> eyre::Result<()> {
     params.spec.depends_on = Some(vec![crate::DependencySpec {
         service: "dep".to_string(),
         condition: config::DependencyCondition::Started,
+        on_dependency_restart: crate::OnDependencyRestart::Ignore,
     }]);
     let created = dynamic_accepted(harness.control.start_dynamic(params).await)?;
     let now = unix_now_ms().unwrap_or_default();
@@ -1327,6 +1436,7 @@ async fn dynamic_replace_rejects_cycles_before_mutating() -> eyre::Result<()> {
     b.spec.depends_on = Some(vec![crate::DependencySpec {
         service: "a".to_string(),
         condition: config::DependencyCondition::Started,
+        on_dependency_restart: crate::OnDependencyRestart::Ignore,
     }]);
     dynamic_accepted(harness.control.start_dynamic(b).await)?;
     let before = wait_until(&harness.reader, "a", |snapshot| {
@@ -1338,6 +1448,7 @@ async fn dynamic_replace_rejects_cycles_before_mutating() -> eyre::Result<()> {
     replacement.spec.depends_on = Some(vec![crate::DependencySpec {
         service: "b".to_string(),
         condition: config::DependencyCondition::Started,
+        on_dependency_restart: crate::OnDependencyRestart::Ignore,
     }]);
     let result = harness
         .control
@@ -1381,6 +1492,7 @@ async fn retired_eviction_keeps_live_dependency_targets() -> eyre::Result<()> {
     dependent.spec.depends_on = Some(vec![crate::DependencySpec {
         service: "anchor".to_string(),
         condition: config::DependencyCondition::Started,
+        on_dependency_restart: crate::OnDependencyRestart::Ignore,
     }]);
     dynamic_accepted(harness.control.start_dynamic(dependent).await)?;
     wait_until(&harness.reader, "dependent", |snapshot| {
@@ -1455,6 +1567,7 @@ async fn retired_eviction_keeps_dependencies_until_their_dependents_are_evicted(
     dependent.spec.depends_on = Some(vec![crate::DependencySpec {
         service: "anchor".to_string(),
         condition: config::DependencyCondition::Started,
+        on_dependency_restart: crate::OnDependencyRestart::Ignore,
     }]);
     dynamic_accepted(harness.control.start_dynamic(dependent).await)?;
     wait_until(&harness.reader, "dependent", |snapshot| {
@@ -1644,6 +1757,7 @@ services:
   b:
     command: ["sh", "-c", {b_command}]
     ports: [{b_port}]
+    port_conflict: ignore
 "#
     ))
 }
@@ -1676,6 +1790,8 @@ services:
     let reload = ReloadConfig {
         config_path: config_path.clone(),
         strict_override: None,
+        profiles: Vec::new(),
+        replicas: BTreeMap::new(),
     };
 
     fs::write(&config_path, yaml("false"))?;
@@ -1687,6 +1803,51 @@ services:
     Ok(())
 }
 
+#[test]
+fn reload_merges_includes_and_override_file() -> eyre::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let config_path = dir.path().join("micromux.yaml");
+    fs::write(
+        &config_path,
+        r#"version: "1"
+include: [shared.yaml]
+services:
+  api:
+    command: ["sh", "-c", "true"]
+"#,
+    )?;
+    fs::write(
+        dir.path().join("shared.yaml"),
+        r#"services:
+  db:
+    command: ["sh", "-c", "true"]
+"#,
+    )?;
+    let reload = ReloadConfig {
+        config_path: config_path.clone(),
+        strict_override: None,
+        profiles: Vec::new(),
+        replicas: BTreeMap::new(),
+    };
+
+    let services = load_services_from_disk(&reload).map_err(eyre::Report::msg)?;
+    assert_eq!(services.keys().collect::<Vec<_>>(), vec!["db", "api"]);
+
+    fs::write(
+        dir.path().join("micromux.override.yaml"),
+        r"services:
+  db:
+    disabled: true
+",
+    )?;
+    let services = load_services_from_disk(&reload).map_err(eyre::Report::msg)?;
+    assert_eq!(
+        services.get("db").map(|service| service.startup_mode),
+        Some(StartupMode::Disabled)
+    );
+    Ok(())
+}
+
 async fn assert_reconcile_dry_run(
     harness: &Harness,
     before_x: &crate::model::ServiceSnapshot,
@@ -1835,6 +1996,8 @@ services:
         Some(ReloadConfig {
             config_path: config_path.clone(),
             strict_override: None,
+            profiles: Vec::new(),
+            replicas: BTreeMap::new(),
         }),
     );
     wait_for_log(&harness.reader, "x", "x-old").await?;
@@ -1881,6 +2044,8 @@ async fn reconcile_rejects_dynamic_dependencies_collisions_and_invalid_files_wit
         Some(ReloadConfig {
             config_path: config_path.clone(),
             strict_override: None,
+            profiles: Vec::new(),
+            replicas: BTreeMap::new(),
         }),
         dir.path().to_path_buf(),
         enabled_dynamic_policy(dir.path())?,
@@ -1890,6 +2055,7 @@ async fn reconcile_rejects_dynamic_dependencies_collisions_and_invalid_files_wit
     params.spec.depends_on = Some(vec![crate::DependencySpec {
         service: "svc".to_string(),
         condition: config::DependencyCondition::Started,
+        on_dependency_restart: crate::OnDependencyRestart::Ignore,
     }]);
     dynamic_accepted(harness.control.start_dynamic(params).await)?;
     wait_until(&harness.reader, "job", |snapshot| {
@@ -1999,6 +2165,8 @@ async fn reconcile_add_honors_disabled_startup_mode() -> eyre::Result<()> {
         Some(ReloadConfig {
             config_path: config_path.clone(),
             strict_override: None,
+            profiles: Vec::new(),
+            replicas: BTreeMap::new(),
         }),
     );
     fs::write(
@@ -2026,6 +2194,114 @@ services:
     Ok(())
 }
 
+#[tokio::test]
+async fn replica_groups_fan_out_commands_and_scale_at_runtime() -> eyre::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let config_path = dir.path().join("micromux.yaml");
+    fs::write(
+        &config_path,
+        r#"version: 1
+services:
+  worker:
+    command: ["sh", "-c", "echo replica-$MICROMUX_REPLICA-$MICROMUX_REPLICA_INDEX; sleep 60"]
+    replicas: 2
+"#,
+    )?;
+    let services = services_from_config_path(&config_path)?;
+    let harness = spawn_harness(
+        services,
+        Some(ReloadConfig {
+            config_path: config_path.clone(),
+            strict_override: None,
+            profiles: Vec::new(),
+            replicas: BTreeMap::new(),
+        }),
+    );
+    wait_for_log(&harness.reader, "worker-1", "replica-1-0").await?;
+    wait_for_log(&harness.reader, "worker-2", "replica-2-1").await?;
+    assert_eq!(
+        harness
+            .reader
+            .service("worker-2")
+            .and_then(|snapshot| snapshot.replica_group),
+        Some("worker".to_string())
+    );
+
+    let group = "worker".to_string();
+    let acks = accepted(harness.control.disable(&group).await)?;
+    let disabled = acks
+        .iter()
+        .map(|ack| ack.service.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(disabled, vec!["worker-1", "worker-2"]);
+    for id in ["worker-1", "worker-2"] {
+        wait_until(&harness.reader, id, |snapshot| {
+            snapshot.desired == Desired::Disabled
+        })
+        .await?;
+    }
+    assert!(matches!(
+        harness.control.restart(&group).await,
+        Ok(Err(CommandRejection::InvalidState(message))) if message.contains("every replica")
+    ));
+    accepted(harness.control.enable(&group).await)?;
+
+    let receipt = reconcile_accepted(harness.control.scale_replicas("worker", 3).await)?;
+    assert_eq!(
+        receipt
+            .actions
+            .iter()
+            .map(|action| (action.service.as_str(), action.action))
+            .collect::<Vec<_>>(),
+        vec![("worker-3", ReconcileActionKind::Added)]
+    );
+    wait_for_log(&harness.reader, "worker-3", "replica-3-2").await?;
+
+    let receipt = reconcile_accepted(harness.control.scale_replicas("worker", 1).await)?;
+    assert_eq!(
+        receipt
+            .actions
+            .iter()
+            .map(|action| (action.service.as_str(), action.action))
+            .collect::<Vec<_>>(),
+        vec![
+            ("worker-2", ReconcileActionKind::Removed),
+            ("worker-3", ReconcileActionKind::Removed),
+        ]
+    );
+    assert!(
+        harness
+            .reader
+            .service("worker-3")
+            .is_some_and(|snapshot| snapshot.retired == Some(RetiredReason::Removed))
+    );
+    assert!(matches!(
+        harness.control.restart(&"worker-3".to_string()).await,
+        Ok(Err(CommandRejection::InvalidState(message))) if message.contains("scale `worker` up")
+    ));
+    // The scale outlives a restart's config reload.
+    accepted(harness.control.restart(&group).await)?;
+    assert!(
+        harness
+            .reader
+            .service("worker-2")
+            .is_some_and(|snapshot| snapshot.retired.is_some())
+    );
+
+    assert!(matches!(
+        harness.control.scale_replicas("worker-1", 2).await,
+        Ok(Err(CommandRejection::UnknownService))
+    ));
+    assert!(matches!(
+        harness.control.scale_replicas("worker", 0).await,
+        Ok(Err(CommandRejection::InvalidSpec(message))) if message.contains("between 1 and 64")
+    ));
+
+    harness.shutdown.cancel();
+    harness.handle.await??;
+    Ok(())
+}
+
 #[tokio::test]
 async fn restart_reloads_latest_service_config_before_spawning() -> eyre::Result<()> {
     let dir = tempfile::tempdir()?;
@@ -2037,6 +2313,8 @@ async fn restart_reloads_latest_service_config_before_spawning() -> eyre::Result
         Some(ReloadConfig {
             config_path: config_path.clone(),
             strict_override: None,
+            profiles: Vec::new(),
+            replicas: BTreeMap::new(),
         }),
     );
     let id = "svc".to_string();
@@ -2072,6 +2350,8 @@ async fn reload_preserves_dynamic_roster_and_rejects_collisions() -> eyre::Resul
         Some(ReloadConfig {
             config_path: config_path.clone(),
             strict_override: None,
+            profiles: Vec::new(),
+            replicas: BTreeMap::new(),
         }),
         dir.path().to_path_buf(),
         enabled_dynamic_policy(dir.path())?,
@@ -2133,6 +2413,8 @@ async fn restart_rejects_invalid_reloaded_config_without_killing_current_run() -
         Some(ReloadConfig {
             config_path: config_path.clone(),
             strict_override: None,
+            profiles: Vec::new(),
+            replicas: BTreeMap::new(),
         }),
     );
     let id = "svc".to_string();
@@ -2183,6 +2465,8 @@ async fn automatic_restart_reloads_latest_service_config_before_spawning() -> ey
         Some(ReloadConfig {
             config_path: config_path.clone(),
             strict_override: None,
+            profiles: Vec::new(),
+            replicas: BTreeMap::new(),
         }),
     );
 
@@ -2217,6 +2501,8 @@ async fn reload_does_not_rewrite_snapshot_for_unrestarted_run() -> eyre::Result<
         Some(ReloadConfig {
             config_path: config_path.clone(),
             strict_override: None,
+            profiles: Vec::new(),
+            replicas: BTreeMap::new(),
         }),
     );
     let a = "a".to_string();
@@ -2277,71 +2563,699 @@ async fn reload_does_not_rewrite_snapshot_for_unrestarted_run() -> eyre::Result<
     Ok(())
 }
 
-async fn recv_event(mut rx: mpsc::Receiver<Event>) -> eyre::Result<(Event, mpsc::Receiver<Event>)> {
-    let ev = timeout(Duration::from_secs(5), rx.recv())
-        .await
-        .map_err(|_| eyre::eyre!("timeout waiting for event"))?
-        .ok_or_else(|| eyre::eyre!("event channel closed"))?;
-    Ok((ev, rx))
-}
+#[tokio::test]
+async fn auto_ports_are_exported_to_referrers_and_stable_across_restarts() -> eyre::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let config_path = dir.path().join("micromux.yaml");
+    fs::write(
+        &config_path,
+        r#"version: "1"
+services:
+  api:
+    command: ["sh", "-c", "echo api-port=$PORT_HTTP; sleep 60"]
+    port_conflict: ignore
+    ports:
+      - 3000
+      - name: http
+        port: auto
+  web:
+    command: ["sh", "-c", "echo web-sees=$API_PORT; sleep 60"]
+    environment:
+      API_PORT: ${services.api.ports.http}
+"#,
+    )?;
+    let services = services_from_config_path(&config_path)?;
+    let harness = spawn_harness(services, None);
 
-fn healthcheck_always_ok() -> config::HealthCheck {
-    config::HealthCheck {
-        test: (
-            spanned_string("sh"),
-            vec![spanned_string("-c"), spanned_string("exit 0")],
-        ),
-        start_delay: None,
-        interval: Some(Spanned {
-            span: yaml_spanned::spanned::Span::default(),
-            inner: Duration::from_millis(25),
-        }),
-        timeout: Some(Spanned {
-            span: yaml_spanned::spanned::Span::default(),
-            inner: Duration::from_millis(500),
-        }),
-        retries: Some(Spanned {
-            span: yaml_spanned::spanned::Span::default(),
-            inner: 10,
-        }),
-    }
+    let api = wait_until(&harness.reader, "api", |snapshot| {
+        snapshot.execution == Execution::Running && snapshot.advertised_ports.len() == 2
+    })
+    .await?;
+    let [fixed, http] = api.advertised_ports[..] else {
+        return Err(eyre::eyre!("expected a fixed and an auto port"));
+    };
+    assert_eq!(fixed, 3000);
+    wait_for_log(&harness.reader, "api", &format!("api-port={http}")).await?;
+    wait_for_log(&harness.reader, "web", &format!("web-sees={http}")).await?;
+
+    accepted(harness.control.restart(&"api".to_string()).await)?;
+    let restarted = wait_until(&harness.reader, "api", |snapshot| {
+        snapshot.run_generation == 2 && snapshot.execution == Execution::Running
+    })
+    .await?;
+    assert_eq!(restarted.advertised_ports, vec![3000, http]);
+
+    harness.shutdown.cancel();
+    harness.handle.await??;
+    Ok(())
 }
 
 #[tokio::test]
-async fn healthcheck_inherits_environment() -> eyre::Result<()> {
-    let config_dir = Path::new(".");
-    let mut cfg = service_config("svc", ("sh", &["-c", "sleep 60"]));
-    cfg.environment
-        .insert(spanned_string("HC_FOO"), spanned_string("bar"));
-    cfg.healthcheck = Some(config::HealthCheck {
-        test: (
-            spanned_string("sh"),
-            vec![
-                spanned_string("-c"),
-                spanned_string("[ \"$HC_FOO\" = \"bar\" ]"),
-            ],
+async fn held_ports_fail_or_wait_according_to_port_conflict() -> eyre::Result<()> {
+    let listener = std::net::TcpListener::bind((std::net::Ipv4Addr::LOCALHOST, 0))?;
+    let port = listener.local_addr()?.port();
+    let dir = tempfile::tempdir()?;
+    let config_path = dir.path().join("micromux.yaml");
+    fs::write(
+        &config_path,
+        format!(
+            r#"version: "1"
+services:
+  strict:
+    command: ["sh", "-c", "sleep 60"]
+    restart: "no"
+    ports: [{port}]
+  patient:
+    command: ["sh", "-c", "echo patient-started; sleep 60"]
+    port_conflict: wait
+    ports: [{port}]
+"#
         ),
-        start_delay: None,
-        interval: Some(Spanned {
-            span: yaml_spanned::spanned::Span::default(),
-            inner: Duration::from_millis(25),
-        }),
-        timeout: Some(Spanned {
-            span: yaml_spanned::spanned::Span::default(),
-            inner: Duration::from_millis(500),
-        }),
-        retries: Some(Spanned {
-            span: yaml_spanned::spanned::Span::default(),
-            inner: 1,
-        }),
-    });
-
-    let mut services: ServiceMap = ServiceMap::new();
-    services.insert("svc".to_string(), Service::new("svc", config_dir, cfg)?);
+    )?;
+    let services = services_from_config_path(&config_path)?;
     let harness = spawn_harness(services, None);
 
-    let attempt = wait_for_finished_health_attempt(&harness.reader, "svc").await?;
-    assert_eq!(attempt.result.map(|result| result.success), Some(true));
+    // The holder is looked up off the event loop, so it lands on the snapshot after the failure.
+    let owner_known =
+        |conflict: &crate::PortConflict| conflict.pid.is_some() || !cfg!(target_os = "linux");
+    let strict = wait_until(&harness.reader, "strict", |snapshot| {
+        snapshot.execution == Execution::Exited
+            && snapshot.port_conflict.as_ref().is_some_and(owner_known)
+    })
+    .await?;
+    let conflict = strict
+        .port_conflict
+        .ok_or_else(|| eyre::eyre!("expected a port conflict"))?;
+    assert_eq!((conflict.port, conflict.waiting), (port, false));
+    #[cfg(target_os = "linux")]
+    assert_eq!(conflict.pid, Some(std::process::id()));
+    // The spawn failure is reported before the holder is known; the conflict event names it.
+    let conflict_event = || {
+        harness
+            .reader
+            .events("strict", None, None)
+            .0
+            .into_iter()
+            .find(|event| event.kind == ServiceEventKind::PortConflict)
+    };
+    wait_until(&harness.reader, "strict", |_| conflict_event().is_some()).await?;
+    let event = conflict_event().ok_or_else(|| eyre::eyre!("expected a port conflict event"))?;
+    assert_eq!(event.run_generation, 1);
+    assert!(
+        event.detail.starts_with("start failed: "),
+        "{}",
+        event.detail
+    );
+    assert_eq!(event.pid, conflict.pid);
+    #[cfg(target_os = "linux")]
+    {
+        let command = conflict
+            .command
+            .ok_or_else(|| eyre::eyre!("expected the holder's command"))?;
+        assert!(
+            event
+                .detail
+                .contains(&format!("pid {} ({command})", std::process::id())),
+            "{}",
+            event.detail
+        );
+    }
+
+    wait_until(&harness.reader, "patient", |snapshot| {
+        snapshot.execution == Execution::Blocked
+            && snapshot.port_conflict.as_ref().is_some_and(|conflict| {
+                conflict.waiting && conflict.port == port && owner_known(conflict)
+            })
+    })
+    .await?;
+
+    drop(listener);
+    let patient = wait_until(&harness.reader, "patient", |snapshot| {
+        snapshot.execution == Execution::Running
+    })
+    .await?;
+    assert_eq!(patient.port_conflict, None);
+    wait_for_log(&harness.reader, "patient", "patient-started").await?;
+    let kinds = harness
+        .reader
+        .events("patient", None, None)
+        .0
+        .into_iter()
+        .map(|event| event.kind)
+        .collect::<Vec<_>>();
+    assert!(kinds.contains(&ServiceEventKind::PortConflict));
+    assert!(kinds.contains(&ServiceEventKind::PortReleased));
+
+    harness.shutdown.cancel();
+    harness.handle.await??;
+    Ok(())
+}
+
+/// `api` takes a moment to wind down after SIGTERM; `db` records its stop immediately. The order of
+/// the two lines shows whether `api` had exited before `db` was asked to stop.
+async fn shutdown_stop_order(shutdown_order: ShutdownOrder) -> eyre::Result<String> {
+    let dir = tempfile::tempdir()?;
+    let stopped = dir.path().join("stopped");
+    let stopped = stopped.to_string_lossy();
+    let mut services = ServiceMap::new();
+    services.insert(
+        "db".to_string(),
+        Service::new(
+            "db",
+            dir.path(),
+            service_config(
+                "db",
+                (
+                    "sh",
+                    &[
+                        "-c",
+                        &format!(
+                            "trap 'echo db >> {stopped}; exit 0' TERM; while :; do sleep 0.05; done"
+                        ),
+                    ],
+                ),
+            ),
+        )?,
+    );
+    let mut api_cfg = service_config(
+        "api",
+        (
+            "sh",
+            &[
+                "-c",
+                &format!(
+                    "trap 'sleep 0.3; echo api >> {stopped}; exit 0' TERM; while :; do sleep 0.05; done"
+                ),
+            ],
+        ),
+    );
+    api_cfg.depends_on = vec![config::Dependency {
+        name: spanned_string("db"),
+        condition: None,
+        on_dependency_restart: None,
+    }];
+    services.insert("api".to_string(), Service::new("api", dir.path(), api_cfg)?);
+    let harness = spawn_harness_with_shutdown_order(
+        services,
+        None,
+        dir.path().to_path_buf(),
+        DynamicServicesPolicy::default(),
+        shutdown_order,
+    );
+    wait_until(&harness.reader, "api", |snapshot| {
+        snapshot.execution == Execution::Running
+    })
+    .await?;
+
+    harness.shutdown.cancel();
+    harness.handle.await??;
+    Ok(fs::read_to_string(dir.path().join("stopped"))?)
+}
+
+#[tokio::test]
+async fn shutdown_stops_dependents_before_their_dependencies() -> eyre::Result<()> {
+    assert_eq!(
+        shutdown_stop_order(ShutdownOrder::Dependencies).await?,
+        "api\ndb\n"
+    );
+    assert_eq!(
+        shutdown_stop_order(ShutdownOrder::Parallel).await?,
+        "db\napi\n"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn dependency_restart_cascades_to_opted_in_dependents() -> eyre::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let mut services = ServiceMap::new();
+    services.insert(
+        "db".to_string(),
+        Service::new("db", dir.path(), service_config("db", ("sleep", &["60"])))?,
+    );
+    for (name, on_dependency_restart) in [
+        ("api", config::OnDependencyRestart::Restart),
+        ("worker", config::OnDependencyRestart::Ignore),
+    ] {
+        let mut cfg = service_config(name, ("sleep", &["60"]));
+        cfg.depends_on = vec![config::Dependency {
+            name: spanned_string("db"),
+            condition: None,
+            on_dependency_restart: Some(Spanned {
+                span: yaml_spanned::spanned::Span::default(),
+                inner: on_dependency_restart,
+            }),
+        }];
+        services.insert(name.to_string(), Service::new(name, dir.path(), cfg)?);
+    }
+    let harness = spawn_harness(services, None);
+    for id in ["api", "worker"] {
+        wait_until(&harness.reader, id, |snapshot| {
+            snapshot.run_generation == 1 && snapshot.execution == Execution::Running
+        })
+        .await?;
+    }
+
+    accepted(harness.control.restart(&"db".to_string()).await)?;
+    wait_until(&harness.reader, "api", |snapshot| {
+        snapshot.run_generation == 2 && snapshot.execution == Execution::Running
+    })
+    .await?;
+    let events = harness.reader.events("api", None, None).0;
+    let cascade = events
+        .iter()
+        .find(|event| event.kind == ServiceEventKind::DependencyRestarted)
+        .ok_or_else(|| eyre::eyre!("missing DependencyRestarted event"))?;
+    assert_eq!(cascade.run_generation, 1);
+    assert_eq!(
+        cascade.caused_by,
+        Some(crate::EventCause {
+            service: "db".to_string(),
+            run_generation: 2,
+        })
+    );
+
+    let worker = harness
+        .reader
+        .service("worker")
+        .ok_or_else(|| eyre::eyre!("missing worker"))?;
+    assert_eq!(worker.run_generation, 1);
+    assert_eq!(worker.execution, Execution::Running);
+
+    harness.shutdown.cancel();
+    harness.handle.await??;
+    Ok(())
+}
+
+async fn recv_event(mut rx: mpsc::Receiver<Event>) -> eyre::Result<(Event, mpsc::Receiver<Event>)> {
+    let ev = timeout(Duration::from_secs(5), rx.recv())
+        .await
+        .map_err(|_| eyre::eyre!("timeout waiting for event"))?
+        .ok_or_else(|| eyre::eyre!("event channel closed"))?;
+    Ok((ev, rx))
+}
+
+#[tokio::test]
+async fn startup_timeout_stops_a_run_that_never_becomes_healthy() -> eyre::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let mut db_cfg = service_config("db", ("sleep", &["60"]));
+    let mut healthcheck = healthcheck_always_ok();
+    healthcheck.probe = config::HealthProbe::Command((
+        spanned_string("sh"),
+        vec![spanned_string("-c"), spanned_string("exit 1")],
+    ));
+    db_cfg.healthcheck = Some(healthcheck);
+    db_cfg.startup_timeout = Some(Spanned {
+        span: yaml_spanned::spanned::Span::default(),
+        inner: Duration::from_millis(300),
+    });
+    let mut api_cfg = service_config("api", ("sleep", &["60"]));
+    api_cfg.depends_on = vec![config::Dependency {
+        name: spanned_string("db"),
+        condition: Some(Spanned {
+            span: yaml_spanned::spanned::Span::default(),
+            inner: config::DependencyCondition::Healthy,
+        }),
+        on_dependency_restart: None,
+    }];
+    let mut services = ServiceMap::new();
+    services.insert("db".to_string(), Service::new("db", dir.path(), db_cfg)?);
+    services.insert("api".to_string(), Service::new("api", dir.path(), api_cfg)?);
+    let harness = spawn_harness(services, None);
+
+    let db = wait_until(&harness.reader, "db", |snapshot| {
+        snapshot.execution == Execution::Exited
+    })
+    .await?;
+    assert!(db.startup_timed_out);
+    assert!(
+        harness
+            .reader
+            .events("db", None, None)
+            .0
+            .iter()
+            .any(|event| event.kind == ServiceEventKind::StartupTimedOut)
+    );
+    let api = wait_until(&harness.reader, "api", |snapshot| {
+        !snapshot.timed_out_dependencies.is_empty()
+    })
+    .await?;
+    assert_eq!(api.execution, Execution::Blocked);
+    assert_eq!(api.timed_out_dependencies, vec!["db".to_string()]);
+
+    harness.shutdown.cancel();
+    harness.handle.await??;
+    Ok(())
+}
+
+#[tokio::test]
+async fn on_unhealthy_restart_restarts_a_wedged_run_with_the_probe_output() -> eyre::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let mut cfg = service_config("worker", ("sleep", &["60"]));
+    let mut healthcheck = healthcheck_always_ok();
+    healthcheck.probe = config::HealthProbe::Command((
+        spanned_string("sh"),
+        vec![
+            spanned_string("-c"),
+            spanned_string("echo deadlocked; exit 1"),
+        ],
+    ));
+    healthcheck.retries = Some(Spanned {
+        span: yaml_spanned::spanned::Span::default(),
+        inner: 2,
+    });
+    healthcheck.on_unhealthy = Some(Spanned {
+        span: yaml_spanned::spanned::Span::default(),
+        inner: config::OnUnhealthy::Restart,
+    });
+    cfg.healthcheck = Some(healthcheck);
+    let mut services = ServiceMap::new();
+    services.insert(
+        "worker".to_string(),
+        Service::new("worker", dir.path(), cfg)?,
+    );
+    let harness = spawn_harness(services, None);
+
+    wait_until(&harness.reader, "worker", |snapshot| {
+        snapshot.run_generation >= 2
+    })
+    .await?;
+    let events = harness.reader.events("worker", None, None).0;
+    let restart = events
+        .iter()
+        .find(|event| event.kind == ServiceEventKind::UnhealthyRestart)
+        .ok_or_else(|| eyre::eyre!("missing UnhealthyRestart event"))?;
+    assert_eq!(restart.run_generation, 1);
+    assert_eq!(restart.probe_output, Some(vec!["deadlocked".to_string()]));
+
+    harness.shutdown.cancel();
+    harness.handle.await??;
+    Ok(())
+}
+
+#[tokio::test]
+async fn max_runtime_restarts_a_run_that_outlives_it() -> eyre::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let mut cfg = service_config("leaky", ("sleep", &["60"]));
+    // The restart goes through the manual-restart path, so even `restart: no` is replaced.
+    cfg.restart_policy = crate::service::RestartPolicy::Never;
+    cfg.max_runtime = Some(Spanned {
+        span: yaml_spanned::spanned::Span::default(),
+        inner: Duration::from_millis(300),
+    });
+    let mut services = ServiceMap::new();
+    services.insert("leaky".to_string(), Service::new("leaky", dir.path(), cfg)?);
+    let harness = spawn_harness(services, None);
+
+    let leaky = wait_until(&harness.reader, "leaky", |snapshot| {
+        snapshot.run_generation >= 2 && snapshot.execution == Execution::Running
+    })
+    .await?;
+    assert_eq!(leaky.restart_state, None);
+    let events = harness.reader.events("leaky", None, None).0;
+    let exceeded = events
+        .iter()
+        .find(|event| event.kind == ServiceEventKind::MaxRuntimeExceeded)
+        .ok_or_else(|| eyre::eyre!("missing MaxRuntimeExceeded event"))?;
+    assert_eq!(exceeded.run_generation, 1);
+    assert!(exceeded.detail.contains("300ms"), "{}", exceeded.detail);
+
+    harness.shutdown.cancel();
+    harness.handle.await??;
+    Ok(())
+}
+
+#[tokio::test]
+async fn external_service_is_probed_and_gates_dependents_but_rejects_lifecycle() -> eyre::Result<()>
+{
+    let dir = tempfile::tempdir()?;
+    let mut db_cfg = service_config("db", ("sleep", &["60"]));
+    db_cfg.external = true;
+    db_cfg.command = None;
+    db_cfg.healthcheck = Some(healthcheck_always_ok());
+    let mut api_cfg = service_config("api", ("sleep", &["60"]));
+    api_cfg.depends_on = vec![config::Dependency {
+        name: spanned_string("db"),
+        condition: Some(Spanned {
+            span: yaml_spanned::spanned::Span::default(),
+            inner: config::DependencyCondition::Healthy,
+        }),
+        on_dependency_restart: None,
+    }];
+    let mut services = ServiceMap::new();
+    services.insert("db".to_string(), Service::new("db", dir.path(), db_cfg)?);
+    services.insert("api".to_string(), Service::new("api", dir.path(), api_cfg)?);
+    let harness = spawn_harness(services, None);
+
+    let db = wait_until(&harness.reader, "db", |snapshot| {
+        snapshot.health == Some(crate::Health::Healthy)
+    })
+    .await?;
+    assert_eq!(db.origin, crate::OriginKind::External);
+    assert_eq!(db.execution, Execution::Running);
+    wait_until(&harness.reader, "api", |snapshot| {
+        snapshot.execution == Execution::Running
+    })
+    .await?;
+
+    let id = "db".to_string();
+    for rejected in [
+        harness.control.restart(&id).await,
+        harness.control.disable(&id).await,
+    ] {
+        let rejected = rejected.map_err(|_| eyre::eyre!("scheduler stopped"))?;
+        assert!(matches!(
+            rejected,
+            Err(CommandRejection::InvalidState(message)) if message.contains("external service")
+        ));
+    }
+    let db = harness
+        .reader
+        .service("db")
+        .ok_or_else(|| eyre::eyre!("missing service snapshot"))?;
+    assert_eq!(db.desired, Desired::Enabled);
+    assert_eq!(db.run_generation, 1);
+
+    harness.shutdown.cancel();
+    harness.handle.await??;
+    Ok(())
+}
+
+#[tokio::test]
+async fn scheduled_service_runs_at_each_tick_and_skips_ticks_while_alive() -> eyre::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let every = |millis| {
+        Some(Spanned {
+            span: yaml_spanned::spanned::Span::default(),
+            inner: crate::RunSchedule::Every(Duration::from_millis(millis)),
+        })
+    };
+    let mut job_cfg = service_config("job", ("sh", &["-c", "exit 3"]));
+    job_cfg.schedule = every(300);
+    let mut slow_cfg = service_config("slow", ("sleep", &["60"]));
+    slow_cfg.schedule = every(200);
+    let mut services = ServiceMap::new();
+    services.insert("job".to_string(), Service::new("job", dir.path(), job_cfg)?);
+    services.insert(
+        "slow".to_string(),
+        Service::new("slow", dir.path(), slow_cfg)?,
+    );
+    let harness = spawn_harness(services, None);
+
+    // Nothing starts with the session; the first run waits for the first tick.
+    let armed = wait_until(&harness.reader, "job", |snapshot| {
+        snapshot.next_run_at_unix_ms.is_some()
+    })
+    .await?;
+    assert_eq!(armed.run_generation, 0);
+    assert_eq!(armed.execution, Execution::Pending);
+    assert_eq!(armed.schedule.as_deref(), Some("every 300ms"));
+
+    // A failed run is not restarted; the next tick starts the next one.
+    let job = wait_until(&harness.reader, "job", |snapshot| {
+        snapshot.run_generation >= 2 && snapshot.execution == Execution::Exited
+    })
+    .await?;
+    assert_eq!(job.last_exit_code, Some(3));
+    assert_eq!(job.restart_state, None);
+    assert!(job.next_run_at_unix_ms.is_some());
+
+    // The long-running first run makes later ticks find it still alive.
+    let slow = wait_until(&harness.reader, "slow", |_| {
+        harness
+            .reader
+            .events("slow", None, None)
+            .0
+            .iter()
+            .any(|event| event.kind == ServiceEventKind::ScheduledRunSkipped)
+    })
+    .await?;
+    assert_eq!(slow.run_generation, 1);
+    assert_eq!(slow.execution, Execution::Running);
+
+    harness.shutdown.cancel();
+    harness.handle.await??;
+    Ok(())
+}
+
+#[tokio::test]
+async fn service_hooks_gate_the_start_and_clean_up_after_each_run() -> eyre::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let config_path = dir.path().join("micromux.yaml");
+    fs::write(
+        &config_path,
+        r#"version: "1"
+services:
+  api:
+    command: ["sh", "-c", "echo api-started; sleep 60"]
+    working_dir: .
+    pre_start: ["echo migrating", ["sh", "-c", "echo $GREETING > migrated"]]
+    post_stop: [["sh", "-c", "echo cleaned > cleaned"]]
+    environment:
+      GREETING: hello
+  broken:
+    command: ["sh", "-c", "echo broken-started; sleep 60"]
+    restart: "no"
+    pre_start: [["sh", "-c", "echo boom >&2; exit 3"]]
+"#,
+    )?;
+    let services = services_from_config_path(&config_path)?;
+    let harness = spawn_harness(services, None);
+
+    wait_for_log(&harness.reader, "api", "api-started").await?;
+    assert_eq!(fs::read_to_string(dir.path().join("migrated"))?, "hello\n");
+    let hooks = harness.reader.hooks("api");
+    let migrate = hooks
+        .first()
+        .ok_or_else(|| eyre::eyre!("missing pre_start hook run"))?;
+    assert_eq!(migrate.kind, crate::HookKind::PreStart);
+    assert_eq!(migrate.run_generation, 1);
+    assert_eq!(
+        migrate
+            .output
+            .iter()
+            .map(|line| line.line.as_str())
+            .collect::<Vec<_>>(),
+        vec!["migrating"]
+    );
+    assert!(migrate.result.is_some_and(|result| result.success));
+
+    let broken = wait_until(&harness.reader, "broken", |snapshot| {
+        snapshot.execution == Execution::Exited
+    })
+    .await?;
+    assert_eq!(broken.last_exit_code, Some(-1));
+    assert!(
+        !harness
+            .reader
+            .logs("broken", None)
+            .iter()
+            .any(|line| line.line.contains("broken-started"))
+    );
+    let failed = harness
+        .reader
+        .events("broken", None, None)
+        .0
+        .into_iter()
+        .find(|event| event.kind == ServiceEventKind::SpawnFailed)
+        .ok_or_else(|| eyre::eyre!("missing SpawnFailed event"))?;
+    assert!(
+        failed.detail.contains("exited with code 3"),
+        "{}",
+        failed.detail
+    );
+    let hooks = harness.reader.hooks("broken");
+    let output = hooks
+        .first()
+        .map(|run| {
+            (
+                run.output.clone(),
+                run.result.map(|result| result.exit_code),
+            )
+        })
+        .ok_or_else(|| eyre::eyre!("missing failed pre_start hook run"))?;
+    assert_eq!(
+        output.0.first().map(|line| line.stream),
+        Some(OutputStream::Stderr)
+    );
+    assert_eq!(
+        output.0.first().map(|line| line.line.as_str()),
+        Some("boom")
+    );
+    assert_eq!(output.1, Some(3));
+
+    harness.shutdown.cancel();
+    harness.handle.await??;
+    assert_eq!(fs::read_to_string(dir.path().join("cleaned"))?, "cleaned\n");
+    let post_stop = harness
+        .reader
+        .hooks("api")
+        .into_iter()
+        .find(|run| run.kind == crate::HookKind::PostStop)
+        .ok_or_else(|| eyre::eyre!("missing post_stop hook run"))?;
+    assert!(post_stop.result.is_some_and(|result| result.success));
+    Ok(())
+}
+
+fn healthcheck_always_ok() -> config::HealthCheck {
+    config::HealthCheck {
+        probe: config::HealthProbe::Command((
+            spanned_string("sh"),
+            vec![spanned_string("-c"), spanned_string("exit 0")],
+        )),
+        start_delay: None,
+        interval: Some(Spanned {
+            span: yaml_spanned::spanned::Span::default(),
+            inner: Duration::from_millis(25),
+        }),
+        timeout: Some(Spanned {
+            span: yaml_spanned::spanned::Span::default(),
+            inner: Duration::from_millis(500),
+        }),
+        retries: Some(Spanned {
+            span: yaml_spanned::spanned::Span::default(),
+            inner: 10,
+        }),
+        on_unhealthy: None,
+    }
+}
+
+#[tokio::test]
+async fn healthcheck_inherits_environment() -> eyre::Result<()> {
+    let config_dir = Path::new(".");
+    let mut cfg = service_config("svc", ("sh", &["-c", "sleep 60"]));
+    cfg.environment
+        .insert(spanned_string("HC_FOO"), spanned_string("bar"));
+    cfg.healthcheck = Some(config::HealthCheck {
+        probe: config::HealthProbe::Command((
+            spanned_string("sh"),
+            vec![
+                spanned_string("-c"),
+                spanned_string("[ \"$HC_FOO\" = \"bar\" ]"),
+            ],
+        )),
+        start_delay: None,
+        interval: Some(Spanned {
+            span: yaml_spanned::spanned::Span::default(),
+            inner: Duration::from_millis(25),
+        }),
+        timeout: Some(Spanned {
+            span: yaml_spanned::spanned::Span::default(),
+            inner: Duration::from_millis(500),
+        }),
+        retries: Some(Spanned {
+            span: yaml_spanned::spanned::Span::default(),
+            inner: 1,
+        }),
+        on_unhealthy: None,
+    });
+
+    let mut services: ServiceMap = ServiceMap::new();
+    services.insert("svc".to_string(), Service::new("svc", config_dir, cfg)?);
+    let harness = spawn_harness(services, None);
+
+    let attempt = wait_for_finished_health_attempt(&harness.reader, "svc").await?;
+    assert_eq!(attempt.result.map(|result| result.success), Some(true));
 
     harness.shutdown.cancel();
     harness.handle.await??;
@@ -2360,10 +3274,10 @@ async fn service_exit_stops_and_reaps_its_active_healthcheck() -> eyre::Result<(
     let probe = format!("echo $$ > {}; sleep 60", pid_path.to_string_lossy());
     let mut cfg = service_config("svc", ("sh", &["-c", "sleep 0.3"]));
     cfg.healthcheck = Some(config::HealthCheck {
-        test: (
+        probe: config::HealthProbe::Command((
             spanned_string("sh"),
             vec![spanned_string("-c"), spanned_string(&probe)],
-        ),
+        )),
         start_delay: None,
         interval: Some(Spanned {
             span: yaml_spanned::spanned::Span::default(),
@@ -2377,6 +3291,7 @@ async fn service_exit_stops_and_reaps_its_active_healthcheck() -> eyre::Result<(
             span: yaml_spanned::spanned::Span::default(),
             inner: 1,
         }),
+        on_unhealthy: None,
     });
     let mut services = ServiceMap::new();
     services.insert("svc".to_string(), Service::new("svc", &dir, cfg)?);
@@ -2410,10 +3325,10 @@ async fn healthcheck_inherits_working_dir() -> eyre::Result<()> {
     let mut cfg = service_config("svc", ("sh", &["-c", "sleep 60"]));
     cfg.working_dir = Some(spanned_string(dir.to_string_lossy().as_ref()));
     cfg.healthcheck = Some(config::HealthCheck {
-        test: (
+        probe: config::HealthProbe::Command((
             spanned_string("sh"),
             vec![spanned_string("-c"), spanned_string("test -f marker.txt")],
-        ),
+        )),
         start_delay: None,
         interval: Some(Spanned {
             span: yaml_spanned::spanned::Span::default(),
@@ -2427,6 +3342,7 @@ async fn healthcheck_inherits_working_dir() -> eyre::Result<()> {
             span: yaml_spanned::spanned::Span::default(),
             inner: 1,
         }),
+        on_unhealthy: None,
     });
 
     let mut services: ServiceMap = ServiceMap::new();
@@ -2477,6 +3393,8 @@ services:
         Some(ReloadConfig {
             config_path: config_path.clone(),
             strict_override: None,
+            profiles: Vec::new(),
+            replicas: BTreeMap::new(),
         }),
     );
     let initial = wait_for_finished_health_attempt(&harness.reader, "svc").await?;
@@ -2517,10 +3435,10 @@ async fn healthcheck_spawn_error_emits_log_line() -> eyre::Result<()> {
     let config_dir = Path::new(".");
     let mut cfg = service_config("svc", ("sh", &["-c", "sleep 60"]));
     cfg.healthcheck = Some(config::HealthCheck {
-        test: (
+        probe: config::HealthProbe::Command((
             spanned_string("definitely-not-a-real-binary"),
             vec![spanned_string("--version")],
-        ),
+        )),
         start_delay: None,
         interval: Some(Spanned {
             span: yaml_spanned::spanned::Span::default(),
@@ -2534,6 +3452,7 @@ async fn healthcheck_spawn_error_emits_log_line() -> eyre::Result<()> {
             span: yaml_spanned::spanned::Span::default(),
             inner: 1,
         }),
+        on_unhealthy: None,
     });
 
     let mut services: ServiceMap = ServiceMap::new();
@@ -2961,6 +3880,180 @@ async fn restart_timeline_orders_request_spawn_and_exit() -> eyre::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn watched_file_change_restarts_service_and_records_changed_paths() -> eyre::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let root = fs::canonicalize(dir.path())?;
+    fs::create_dir_all(root.join("src"))?;
+    let mut config = service_config("svc", ("sh", &["-c", "sleep 60"]));
+    config.working_dir = Some(spanned_string(root.to_string_lossy().as_ref()));
+    config.watch = Some(config::Watch {
+        paths: vec![spanned_string("src")],
+        ignore: vec![spanned_string("**/*.tmp")],
+        debounce: Some(Spanned {
+            span: yaml_spanned::spanned::Span::default(),
+            inner: Duration::from_millis(50),
+        }),
+    });
+    let mut services = ServiceMap::new();
+    services.insert("svc".to_string(), Service::new("svc", &root, config)?);
+    let harness = spawn_harness(services, None);
+    wait_until(&harness.reader, "svc", |snapshot| {
+        snapshot.execution == Execution::Running
+    })
+    .await?;
+
+    fs::write(root.join("src").join("scratch.tmp"), "ignored")?;
+    fs::write(root.join("src").join("main.rs"), "fn main() {}")?;
+    wait_until(&harness.reader, "svc", |snapshot| {
+        snapshot.run_generation >= 2 && snapshot.execution == Execution::Running
+    })
+    .await?;
+
+    let events = harness.reader.events("svc", None, None).0;
+    let changed = events
+        .iter()
+        .position(|event| event.kind == ServiceEventKind::FilesChanged)
+        .ok_or_else(|| eyre::eyre!("files-changed event missing"))?;
+    assert_eq!(
+        events
+            .get(changed)
+            .and_then(|event| event.changed_paths.clone()),
+        Some(vec!["src/main.rs".to_string()])
+    );
+    assert_eq!(
+        events.get(changed + 1).map(|event| event.kind),
+        Some(ServiceEventKind::RestartRequested)
+    );
+
+    harness.shutdown.cancel();
+    harness.handle.await??;
+    Ok(())
+}
+
+#[tokio::test]
+async fn signal_reaches_the_running_process_and_is_recorded() -> eyre::Result<()> {
+    let script = "trap 'echo got-usr1' USR1; echo ready; while :; do sleep 0.05; done";
+    let mut services = ServiceMap::new();
+    services.insert(
+        "svc".to_string(),
+        Service::new(
+            "svc",
+            Path::new("."),
+            service_config("svc", ("sh", &["-c", script])),
+        )?,
+    );
+    let harness = spawn_harness(services, None);
+    let id = "svc".to_string();
+    wait_for_log(&harness.reader, "svc", "ready").await?;
+
+    let acks = accepted(
+        harness
+            .control
+            .signal(&id, crate::ServiceSignal::Usr1)
+            .await,
+    )?;
+    assert_eq!(acks.first().map(|ack| ack.observed_generation), Some(1));
+    wait_for_log(&harness.reader, "svc", "got-usr1").await?;
+    let signaled = harness
+        .reader
+        .events("svc", None, None)
+        .0
+        .into_iter()
+        .find(|event| event.kind == ServiceEventKind::Signaled)
+        .ok_or_else(|| eyre::eyre!("signaled event missing"))?;
+    assert_eq!(signaled.signal, Some(crate::ServiceSignal::Usr1));
+    assert_eq!(signaled.detail, "sent SIGUSR1 to the service process");
+    // The trap handled the signal, so the run is untouched.
+    let snapshot = wait_until(&harness.reader, "svc", |_| true).await?;
+    assert_eq!(snapshot.execution, Execution::Running);
+    assert_eq!(snapshot.run_generation, 1);
+
+    accepted(harness.control.disable(&id).await)?;
+    wait_until(&harness.reader, "svc", |snapshot| {
+        snapshot.desired == Desired::Disabled && snapshot.execution == Execution::Exited
+    })
+    .await?;
+    let rejected = harness
+        .control
+        .signal(&id, crate::ServiceSignal::Hup)
+        .await
+        .map_err(|_| eyre::eyre!("scheduler stopped"))?;
+    assert!(matches!(rejected, Err(CommandRejection::InvalidState(_))));
+
+    harness.shutdown.cancel();
+    harness.handle.await??;
+    Ok(())
+}
+
+#[tokio::test]
+async fn pause_and_resume_suspend_the_run_without_stopping_it() -> eyre::Result<()> {
+    let script = "trap 'echo got-term; exit 0' TERM; echo ready; while :; do sleep 0.05; done";
+    let mut services = ServiceMap::new();
+    services.insert(
+        "svc".to_string(),
+        Service::new(
+            "svc",
+            Path::new("."),
+            service_config("svc", ("sh", &["-c", script])),
+        )?,
+    );
+    let harness = spawn_harness(services, None);
+    let id = "svc".to_string();
+    wait_for_log(&harness.reader, "svc", "ready").await?;
+
+    let acks = accepted(harness.control.pause(&id).await)?;
+    assert_eq!(acks.first().map(|ack| ack.observed_generation), Some(1));
+    wait_until(&harness.reader, "svc", |snapshot| {
+        snapshot.execution == Execution::Paused
+    })
+    .await?;
+    let again = harness
+        .control
+        .pause(&id)
+        .await
+        .map_err(|_| eyre::eyre!("scheduler stopped"))?;
+    assert!(matches!(again, Err(CommandRejection::InvalidState(_))));
+
+    accepted(harness.control.resume(&id).await)?;
+    let snapshot = wait_until(&harness.reader, "svc", |snapshot| {
+        snapshot.execution == Execution::Running
+    })
+    .await?;
+    assert_eq!(snapshot.run_generation, 1);
+    let kinds = harness
+        .reader
+        .events("svc", None, None)
+        .0
+        .into_iter()
+        .map(|event| event.kind)
+        .filter(|kind| matches!(kind, ServiceEventKind::Paused | ServiceEventKind::Resumed))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        vec![ServiceEventKind::Paused, ServiceEventKind::Resumed]
+    );
+
+    // Stopping a paused run continues it, so the stop signal is handled rather than escalated.
+    accepted(harness.control.pause(&id).await)?;
+    accepted(harness.control.disable(&id).await)?;
+    wait_for_log(&harness.reader, "svc", "got-term").await?;
+    wait_until(&harness.reader, "svc", |snapshot| {
+        snapshot.desired == Desired::Disabled && snapshot.execution == Execution::Exited
+    })
+    .await?;
+    let rejected = harness
+        .control
+        .resume(&id)
+        .await
+        .map_err(|_| eyre::eyre!("scheduler stopped"))?;
+    assert!(matches!(rejected, Err(CommandRejection::InvalidState(_))));
+
+    harness.shutdown.cancel();
+    harness.handle.await??;
+    Ok(())
+}
+
 #[tokio::test]
 async fn crash_timeline_records_backoff_delay() -> eyre::Result<()> {
     let mut cfg = service_config("svc", ("sh", &["-c", "exit 1"]));
@@ -2990,6 +4083,53 @@ async fn crash_timeline_records_backoff_delay() -> eyre::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn crash_loop_breaker_records_event_and_restart_clears_it() -> eyre::Result<()> {
+    let mut cfg = service_config("svc", ("sh", &["-c", "exit 1"]));
+    cfg.restart_policy = crate::service::RestartPolicy::Always;
+    cfg.restart_backoff = Some(crate::RestartBackoffSpec {
+        initial: Duration::from_millis(10),
+        crash_loop: Some(crate::CrashLoopSpec {
+            crashes: 2,
+            window: Duration::from_mins(1),
+        }),
+        ..crate::RestartBackoffSpec::default()
+    });
+    let mut services = ServiceMap::new();
+    services.insert("svc".to_string(), Service::new("svc", Path::new("."), cfg)?);
+    let harness = spawn_harness(services, None);
+    let id = "svc".to_string();
+
+    let parked = wait_until(&harness.reader, "svc", |snapshot| {
+        snapshot
+            .restart_state
+            .as_ref()
+            .is_some_and(|restart| restart.phase == crate::RestartPhase::CrashLoop)
+    })
+    .await?;
+    assert_eq!(parked.run_generation, 2);
+    assert!(
+        harness
+            .reader
+            .events("svc", None, None)
+            .0
+            .iter()
+            .any(|event| event.kind == ServiceEventKind::CrashLoop
+                && event.detail.contains("after 2 crashes within 1m"))
+    );
+
+    // Parked means parked: nothing restarts it until an operator does.
+    accepted(harness.control.restart(&id).await)?;
+    wait_until(&harness.reader, "svc", |snapshot| {
+        snapshot.run_generation >= 3
+    })
+    .await?;
+
+    harness.shutdown.cancel();
+    harness.handle.await??;
+    Ok(())
+}
+
 #[tokio::test]
 async fn auto_restarts_failing_service_without_manual_command() -> eyre::Result<()> {
     let config_dir = Path::new(".");
@@ -3061,10 +4201,10 @@ async fn stale_log_from_previous_run_is_ignored() -> eyre::Result<()> {
     );
 
     let mut cfg = service_config("svc", ("sh", &["-c", "true"]));
-    cfg.command = (
+    cfg.command = Some((
         spanned_string("sh"),
         vec![spanned_string("-c"), spanned_string(&script)],
-    );
+    ));
     cfg.restart_policy = crate::service::RestartPolicy::Always;
 
     let mut services: ServiceMap = ServiceMap::new();
@@ -3090,6 +4230,42 @@ async fn stale_log_from_previous_run_is_ignored() -> eyre::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn pipe_mode_tags_stdout_and_stderr_lines() -> eyre::Result<()> {
+    let mut cfg = service_config(
+        "svc",
+        ("sh", &["-c", "echo pipe-out; echo pipe-err >&2; sleep 60"]),
+    );
+    cfg.tty = Some(Spanned {
+        span: yaml_spanned::spanned::Span::default(),
+        inner: false,
+    });
+    let mut services: ServiceMap = ServiceMap::new();
+    services.insert("svc".to_string(), Service::new("svc", Path::new("."), cfg)?);
+    let harness = spawn_harness(services, None);
+
+    wait_for_log(&harness.reader, "svc", "pipe-out").await?;
+    wait_for_log(&harness.reader, "svc", "pipe-err").await?;
+    let mut tagged = harness
+        .reader
+        .logs("svc", None)
+        .into_iter()
+        .filter_map(|line| line.stream.map(|stream| (stream, line.line)))
+        .collect::<Vec<_>>();
+    tagged.sort_by_key(|(stream, _)| *stream == OutputStream::Stderr);
+    assert_eq!(
+        tagged,
+        vec![
+            (OutputStream::Stdout, "pipe-out".to_string()),
+            (OutputStream::Stderr, "[stderr] pipe-err".to_string()),
+        ]
+    );
+
+    harness.shutdown.cancel();
+    harness.handle.await??;
+    Ok(())
+}
+
 #[tokio::test]
 async fn pty_append_records_are_lossless_under_load() -> eyre::Result<()> {
     let config_dir = Path::new(".");
@@ -3636,6 +4812,7 @@ async fn disabling_dependency_blocks_pending_dependents_immediately() -> eyre::R
                 span: yaml_spanned::spanned::Span::default(),
                 inner: config::DependencyCondition::Started,
             }),
+            on_dependency_restart: None,
         },
         config::Dependency {
             name: spanned_string("gate"),
@@ -3643,6 +4820,7 @@ async fn disabling_dependency_blocks_pending_dependents_immediately() -> eyre::R
                 span: yaml_spanned::spanned::Span::default(),
                 inner: config::DependencyCondition::CompletedSuccessfully,
             }),
+            on_dependency_restart: None,
         },
     ];
     services.insert("app".to_string(), Service::new("app", config_dir, app_cfg)?);
@@ -3701,6 +4879,7 @@ async fn started_dependencies_schedule_independently_of_declaration_order() -> e
             span: yaml_spanned::spanned::Span::default(),
             inner: config::DependencyCondition::Started,
         }),
+        on_dependency_restart: None,
     }];
     services.insert("app".to_string(), Service::new("app", config_dir, app_cfg)?);
     services.insert(
@@ -3761,6 +4940,7 @@ async fn restart_all_leaves_a_gated_dependent_blocked_with_an_explained_log() ->
             span: yaml_spanned::spanned::Span::default(),
             inner: config::DependencyCondition::Started,
         }),
+        on_dependency_restart: None,
     }];
     services.insert("app".to_string(), Service::new("app", config_dir, app_cfg)?);
     let harness = spawn_harness(services, None);
@@ -4071,6 +5251,7 @@ async fn depends_on_service_healthy_delays_start() -> eyre::Result<()> {
             span: yaml_spanned::spanned::Span::default(),
             inner: config::DependencyCondition::Healthy,
         }),
+        on_dependency_restart: None,
     }];
     services.insert("app".to_string(), Service::new("app", config_dir, app_cfg)?);
 
@@ -4143,6 +5324,7 @@ async fn resize_all_changes_stty_size_for_new_service() -> eyre::Result<()> {
             span: yaml_spanned::spanned::Span::default(),
             inner: config::DependencyCondition::CompletedSuccessfully,
         }),
+        on_dependency_restart: None,
     }];
     services.insert("app".to_string(), Service::new("app", config_dir, app_cfg)?);
     let harness = spawn_harness(services, None);
@@ -1,7 +1,8 @@
 //! Scheduler command and lifecycle types.
 
-use super::control::{CommandAck, DynamicCommandAck, ReconcileCommandAck};
+use super::control::{CommandAck, DynamicCommandAck, ReconcileCommandAck, TaskCommandAck};
 use crate::health_check::Health;
+use crate::model::HookKind;
 use crate::{DynamicServiceParams, Lease};
 
 /// Unique identifier for a service.
@@ -72,6 +73,21 @@ pub(crate) enum ProcessEvent {
         input_kind: &'static str,
         reason: String,
     },
+    /// A run's `pre_start` or `post_stop` hooks finished; the error is a one-line summary.
+    HooksFinished {
+        service_id: ServiceID,
+        run_id: RunId,
+        kind: HookKind,
+        result: Result<(), String>,
+    },
+    /// The process holding a conflicting port, looked up off the event loop. `run_id` is the run
+    /// generation the conflict was recorded under.
+    PortOwnerResolved {
+        service_id: ServiceID,
+        run_id: RunId,
+        port: u16,
+        owner: Option<crate::PortOwner>,
+    },
 }
 
 impl ProcessEvent {
@@ -82,7 +98,9 @@ impl ProcessEvent {
             | Self::LogReaderFinished { service_id, .. }
             | Self::Healthy { service_id, .. }
             | Self::Unhealthy { service_id, .. }
-            | Self::InputDropped { service_id, .. } => service_id,
+            | Self::InputDropped { service_id, .. }
+            | Self::HooksFinished { service_id, .. }
+            | Self::PortOwnerResolved { service_id, .. } => service_id,
         }
     }
 
@@ -93,7 +111,9 @@ impl ProcessEvent {
             | Self::LogReaderFinished { run_id, .. }
             | Self::Healthy { run_id, .. }
             | Self::Unhealthy { run_id, .. }
-            | Self::InputDropped { run_id, .. } => *run_id,
+            | Self::InputDropped { run_id, .. }
+            | Self::HooksFinished { run_id, .. }
+            | Self::PortOwnerResolved { run_id, .. } => *run_id,
         }
     }
 
@@ -112,6 +132,12 @@ impl ProcessEvent {
             Self::Healthy { service_id, .. } => Event::Healthy(service_id.clone()),
             Self::Unhealthy { service_id, .. } => Event::Unhealthy(service_id.clone()),
             Self::InputDropped { service_id, .. } => Event::InputDropped(service_id.clone()),
+            Self::HooksFinished {
+                service_id, kind, ..
+            } => Event::HooksFinished(service_id.clone(), *kind),
+            Self::PortOwnerResolved { service_id, .. } => {
+                Event::PortOwnerResolved(service_id.clone())
+            }
         }
     }
 }
@@ -143,6 +169,10 @@ pub(crate) enum Event {
     Unhealthy(ServiceID),
     /// Terminal input was discarded while writing to the service.
     InputDropped(ServiceID),
+    /// A run's `pre_start` or `post_stop` hooks finished.
+    HooksFinished(ServiceID, HookKind),
+    /// The holder of a conflicting port was looked up.
+    PortOwnerResolved(ServiceID),
     /// A service was disabled.
     Disabled(ServiceID),
     /// Clear the log buffer for a service (e.g. on restart).
@@ -186,6 +216,10 @@ impl std::fmt::Display for Event {
             Self::Healthy(service_id) => write!(f, "Healthy({service_id})"),
             Self::Unhealthy(service_id) => write!(f, "Unhealthy({service_id})"),
             Self::InputDropped(service_id) => write!(f, "InputDropped({service_id})"),
+            Self::HooksFinished(service_id, kind) => {
+                write!(f, "HooksFinished({service_id}, {kind})")
+            }
+            Self::PortOwnerResolved(service_id) => write!(f, "PortOwnerResolved({service_id})"),
             Self::Disabled(service_id) => write!(f, "Disabled({service_id})"),
             Self::ClearLogs(service_id) => write!(f, "ClearLogs({service_id})"),
         }
@@ -226,6 +260,29 @@ pub enum Command {
         /// Optional reply channel for acknowledged commands.
         ack: Option<CommandAck>,
     },
+    /// Send a signal to a running service's process group.
+    Signal {
+        /// Service to signal.
+        service: ServiceID,
+        /// Signal to deliver.
+        signal: crate::ServiceSignal,
+        /// Optional reply channel for acknowledged commands.
+        ack: Option<CommandAck>,
+    },
+    /// Suspend a running service's process group.
+    Pause {
+        /// Service to pause.
+        service: ServiceID,
+        /// Optional reply channel for acknowledged commands.
+        ack: Option<CommandAck>,
+    },
+    /// Continue a paused service's process group.
+    Resume {
+        /// Service to resume.
+        service: ServiceID,
+        /// Optional reply channel for acknowledged commands.
+        ack: Option<CommandAck>,
+    },
     /// Reconcile configured services against the on-disk config.
     ReconcileConfig {
         /// Compute the semantic diff without mutating the session.
@@ -233,6 +290,15 @@ pub enum Command {
         /// Required reply channel.
         ack: ReconcileCommandAck,
     },
+    /// Scale a replica group to a number of instances.
+    ScaleReplicas {
+        /// Replica group, the name of the replicated service definition.
+        group: String,
+        /// Instance count to scale to.
+        replicas: usize,
+        /// Required reply channel.
+        ack: ReconcileCommandAck,
+    },
     /// Create a dynamic service.
     StartDynamic {
         /// Definition and lease request.
@@ -269,6 +335,13 @@ pub enum Command {
         /// Optional reply channel for acknowledged commands.
         ack: Option<DynamicCommandAck>,
     },
+    /// Start a run of an on-demand task.
+    RunTask {
+        /// Task to run.
+        task: String,
+        /// Optional reply channel for acknowledged commands.
+        ack: Option<TaskCommandAck>,
+    },
     /// Resize all PTYs.
     ResizeAll {
         /// Terminal width in columns.
@@ -303,6 +376,34 @@ impl Command {
         Self::Enable { service, ack: None }
     }
 
+    /// Fire-and-forget signal delivery (no acknowledgement).
+    #[must_use]
+    pub fn signal(service: ServiceID, signal: crate::ServiceSignal) -> Self {
+        Self::Signal {
+            service,
+            signal,
+            ack: None,
+        }
+    }
+
+    /// Fire-and-forget pause (no acknowledgement).
+    #[must_use]
+    pub fn pause(service: ServiceID) -> Self {
+        Self::Pause { service, ack: None }
+    }
+
+    /// Fire-and-forget resume (no acknowledgement).
+    #[must_use]
+    pub fn resume(service: ServiceID) -> Self {
+        Self::Resume { service, ack: None }
+    }
+
+    /// Fire-and-forget task run (no acknowledgement).
+    #[must_use]
+    pub fn run_task(task: String) -> Self {
+        Self::RunTask { task, ack: None }
+    }
+
     /// Fire-and-forget retirement of a dynamic service.
     #[must_use]
     pub fn stop_dynamic(service: ServiceID) -> Self {
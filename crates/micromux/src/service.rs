@@ -3,7 +3,7 @@ use crate::{
     env,
     model::LogRetention,
     scheduler::ServiceID,
-    spec::{DependencySpec, HealthcheckSpec, ServiceOrigin, ServiceSpec},
+    spec::{DependencySpec, HealthcheckSpec, PortBinding, ServiceOrigin, ServiceSpec, WatchSpec},
 };
 use std::path::{Path, PathBuf};
 #[cfg(unix)]
@@ -24,6 +24,24 @@ pub enum Error {
         #[source]
         source: std::num::ParseIntError,
     },
+    /// A declared port shifted by the worktree port offset no longer fits in a port number.
+    #[error("port {port} plus the worktree port offset {offset} exceeds 65535")]
+    PortOffset {
+        /// Declared port.
+        port: u16,
+        /// Worktree port offset.
+        offset: u16,
+    },
+    /// A `${services.<id>.ports.<name>}` reference names no such service or port.
+    #[error("invalid port reference `{reference}` in service `{service}`: {reason}")]
+    PortReference {
+        /// Service whose definition contains the reference.
+        service: ServiceID,
+        /// The reference as written.
+        reference: String,
+        /// Why it cannot be resolved.
+        reason: String,
+    },
     /// A configured working directory could not be opened or validated.
     #[error("failed to access working directory {}: {source}", path.display())]
     WorkingDirectory {
@@ -212,7 +230,10 @@ mod tests {
         }];
         cfg.environment
             .insert(spanned_string("PORT"), spanned_string("${BASE}23"));
-        cfg.ports.push(spanned_string("${PORT}"));
+        cfg.ports.push(config::Port {
+            name: None,
+            port: spanned_string("${PORT}"),
+        });
 
         let svc = Service::new("svc", &dir, cfg)?;
         assert_eq!(
@@ -224,6 +245,39 @@ mod tests {
     }
 
     #[test]
+    fn command_arguments_interpolate_and_keep_shell_references() -> eyre::Result<()> {
+        let dir = unique_tmp_dir("command-interpolate");
+        fs::create_dir_all(&dir)?;
+        let mut cfg = service_config(
+            "svc",
+            (
+                "sh",
+                &[
+                    "-c",
+                    "serve --port $PORT ${LEVEL:-info} $$HOME; echo $local ${local%.x} $$ $${PORT}",
+                ],
+            ),
+        );
+        cfg.environment
+            .insert(spanned_string("PORT"), spanned_string("8080"));
+
+        let svc = Service::new("svc", &dir, cfg)?;
+        assert_eq!(
+            svc.argv(),
+            vec![
+                "sh",
+                "-c",
+                "serve --port 8080 info $HOME; echo $local ${local%.x} $$ ${PORT}"
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[expect(
+        clippy::too_many_lines,
+        reason = "the expected spec spells out every normalized field in one literal"
+    )]
     fn config_service_materializes_one_complete_normalized_spec() -> eyre::Result<()> {
         let dir = unique_tmp_dir("normalized-spec");
         fs::create_dir_all(dir.join("work"))?;
@@ -244,9 +298,10 @@ mod tests {
                 span: yaml_spanned::spanned::Span::default(),
                 inner: config::DependencyCondition::Healthy,
             }),
+            on_dependency_restart: None,
         }];
         cfg.healthcheck = Some(config::HealthCheck {
-            test: (spanned_string("true"), Vec::new()),
+            probe: config::HealthProbe::Command((spanned_string("true"), Vec::new())),
             start_delay: Some(Spanned {
                 span: yaml_spanned::spanned::Span::default(),
                 inner: Duration::from_millis(250),
@@ -263,8 +318,23 @@ mod tests {
                 span: yaml_spanned::spanned::Span::default(),
                 inner: 0,
             }),
+            on_unhealthy: None,
         });
-        cfg.ports = vec![spanned_string("${PORT}")];
+        cfg.ports = vec![
+            config::Port {
+                name: None,
+                port: spanned_string("${PORT}"),
+            },
+            config::Port {
+                name: Some(spanned_string("debug")),
+                port: spanned_string("auto"),
+            },
+        ];
+        cfg.pre_start = vec![(
+            spanned_string("migrate"),
+            vec![spanned_string("--port"), spanned_string("${PORT}")],
+        )];
+        cfg.post_stop = vec![(spanned_string("cleanup"), Vec::new())];
         cfg.restart_policy = RestartPolicy::Always;
 
         let service = Service::new("worker", &dir, cfg)?;
@@ -274,6 +344,12 @@ mod tests {
             ServiceSpec {
                 name: Some("worker".to_string()),
                 command: vec!["sh".to_string(), "-c".to_string(), "echo ok".to_string()],
+                pre_start: vec![vec![
+                    "migrate".to_string(),
+                    "--port".to_string(),
+                    "1023".to_string()
+                ]],
+                post_stop: vec![vec!["cleanup".to_string()]],
                 working_dir: Some(dir.join("work")),
                 environment: indexmap::IndexMap::from([
                     ("BASE".to_string(), "10".to_string()),
@@ -283,17 +359,34 @@ mod tests {
                 depends_on: vec![DependencySpec {
                     service: "database".to_string(),
                     condition: config::DependencyCondition::Healthy,
+                    on_dependency_restart: config::OnDependencyRestart::Ignore,
                 }],
                 healthcheck: Some(HealthcheckSpec {
                     test: vec!["true".to_string()],
+                    http: None,
+                    tcp: None,
+                    log_pattern: None,
                     start_delay: Some(Duration::from_millis(250)),
                     interval: Duration::from_secs(2),
                     timeout: Duration::from_secs(1),
                     retries: 1,
+                    on_unhealthy: config::OnUnhealthy::Ignore,
                 }),
                 ports: vec![1023],
+                named_ports: indexmap::IndexMap::from([(
+                    "debug".to_string(),
+                    crate::PortBinding::Auto
+                )]),
+                port_conflict: crate::PortConflictPolicy::Fail,
                 restart: RestartPolicy::Always,
                 stop_grace_period: crate::spec::DEFAULT_STOP_GRACE_PERIOD,
+                stop_signal: crate::ServiceSignal::Term,
+                startup_timeout: None,
+                max_runtime: None,
+                schedule: None,
+                restart_backoff: crate::RestartBackoffSpec::default(),
+                watch: None,
+                tty: true,
             }
         );
         Ok(())
@@ -387,8 +480,16 @@ pub struct Service {
     pub spec: ServiceSpec,
     pub origin: ServiceOrigin,
     pub startup_mode: StartupMode,
+    // Set for a service that runs outside micromux, which only probes its healthcheck.
+    pub external: bool,
+    // Set when none of the service's profiles is active, which is what disabled it at startup.
+    pub excluded_by_profile: bool,
+    // Name of the replica group this service is an instance of, if any.
+    pub replica_group: Option<String>,
     pub enable_color: bool,
     pub log_retention: LogRetention,
+    // Which config layer set each `spec.environment` key; empty for dynamic services.
+    pub environment_origins: indexmap::IndexMap<String, env::EnvOrigin>,
     // Keeps each spawn tied to the directory that passed validation even if its path is replaced.
     #[cfg(unix)]
     working_directory: Option<Arc<std::fs::File>>,
@@ -432,8 +533,12 @@ impl Service {
             spec,
             origin,
             startup_mode: StartupMode::Enabled,
+            external: false,
+            excluded_by_profile: false,
+            replica_group: None,
             enable_color: true,
             log_retention,
+            environment_origins: indexmap::IndexMap::new(),
             #[cfg(unix)]
             working_directory,
         })
@@ -444,7 +549,6 @@ impl Service {
         config_dir: &Path,
         config: config::Service,
     ) -> Result<Self, Error> {
-        let (prog, args) = config.command;
         let id: ServiceID = id.into();
 
         let working_dir = resolve_working_directory(config_dir, config.working_dir.as_ref())?;
@@ -453,54 +557,50 @@ impl Service {
         #[cfg(not(unix))]
         validate_working_directory(working_dir.as_deref())?;
 
-        let mut env_files = Vec::new();
-        for env_file in &config.env_file {
-            let path = env::resolve_path(config_dir, env_file.path.as_ref())?;
-            // A missing optional file is skipped; a present one still
-            // participates fully, including parse errors.
-            if env_file.optional && !path.exists() {
-                continue;
-            }
-            env_files.push(path);
-        }
-
-        let base_env: std::collections::HashMap<String, String> = std::env::vars().collect();
         let mut missing_env = Vec::new();
-        let env_file_env = env::load_env_files_sync(&env_files)?;
-        let env_file_env =
-            env::expand_env_values_tracking(&env_file_env, &base_env, &mut missing_env);
-
-        let mut base_with_env_file = base_env.clone();
-        for (k, v) in env_file_env.iter() {
-            base_with_env_file.insert(k.clone(), v.clone());
-        }
+        let layered = layer_environment(config_dir, &config, &mut missing_env)?;
+        let offset = config.port_offset.unwrap_or(0);
+        let ports = resolve_ports(&config.ports, offset, layered.scope(), &mut missing_env)?;
 
-        let mut config_env_map = env::EnvMap::new();
-        for (k, v) in &config.environment {
-            config_env_map.insert(k.as_ref().clone(), v.as_ref().clone());
-        }
-        let config_env_map =
-            env::expand_env_values_tracking(&config_env_map, &base_with_env_file, &mut missing_env);
-
-        let mut full_env = base_with_env_file.clone();
-        for (k, v) in config_env_map.iter() {
-            full_env.insert(k.clone(), v.clone());
+        let mut healthcheck = config.healthcheck.map(HealthcheckSpec::from);
+        // A TCP probe of one of the service's own declared ports follows it to the shifted port.
+        if let Some(tcp) = healthcheck
+            .as_mut()
+            .and_then(|healthcheck| healthcheck.tcp.as_mut())
+            .filter(|tcp| ports.declared.contains(&tcp.port))
+        {
+            tcp.port = tcp.port.checked_add(offset).ok_or(Error::PortOffset {
+                port: tcp.port,
+                offset,
+            })?;
         }
-
-        let advertised_ports = config
-            .ports
-            .iter()
-            .map(|port| {
-                let expanded =
-                    env::interpolate_str_tracking(port.as_ref(), &full_env, &mut missing_env);
-                expanded
-                    .parse::<u16>()
-                    .map_err(|source| Error::InvalidPort {
-                        port: expanded,
-                        source,
-                    })
+        let depends_on = config
+            .depends_on
+            .into_iter()
+            .map(|dependency| DependencySpec {
+                service: dependency.name.into_inner(),
+                condition: dependency
+                    .condition
+                    .map(yaml_spanned::Spanned::into_inner)
+                    .unwrap_or_default(),
+                on_dependency_restart: dependency
+                    .on_dependency_restart
+                    .map(yaml_spanned::Spanned::into_inner)
+                    .unwrap_or_default(),
             })
-            .collect::<Result<Vec<_>, _>>()?;
+            .collect();
+        // An external service has no command: micromux only probes it.
+        let command = config
+            .command
+            .map(|(prog, args)| interpolate_command(prog, args, layered.scope(), &mut missing_env))
+            .transpose()?
+            .unwrap_or_default();
+        let pre_start = interpolate_hooks(config.pre_start, layered.scope(), &mut missing_env)?;
+        let post_stop = interpolate_hooks(config.post_stop, layered.scope(), &mut missing_env)?;
+        let watch = config
+            .watch
+            .map(|watch| resolve_watch(working_dir.as_deref().unwrap_or(config_dir), watch))
+            .transpose()?;
 
         missing_env.sort_unstable();
         missing_env.dedup();
@@ -512,46 +612,45 @@ impl Service {
             );
         }
 
-        let mut environment = indexmap::IndexMap::new();
-        for (k, v) in env_file_env.iter() {
-            environment.insert(k.clone(), v.clone());
-        }
-        for (k, v) in config_env_map.iter() {
-            environment.insert(k.clone(), v.clone());
-        }
-
-        let healthcheck = config.healthcheck.map(HealthcheckSpec::from);
-        let depends_on = config
-            .depends_on
-            .into_iter()
-            .map(|dependency| DependencySpec {
-                service: dependency.name.into_inner(),
-                condition: dependency
-                    .condition
-                    .map(yaml_spanned::Spanned::into_inner)
-                    .unwrap_or_default(),
-            })
-            .collect();
-        let mut command = vec![prog.into_inner()];
-        command.extend(args.into_iter().map(yaml_spanned::Spanned::into_inner));
+        let (environment, environment_origins) = layered.into_parts();
 
         Ok(Self {
             id,
             spec: ServiceSpec {
                 name: Some(config.name.into_inner()),
                 command,
+                pre_start,
+                post_stop,
                 working_dir,
                 environment,
                 depends_on,
                 healthcheck,
-                ports: advertised_ports,
+                ports: ports.advertised,
+                named_ports: ports.named,
+                port_conflict: config.port_conflict,
                 restart: config.restart_policy,
                 stop_grace_period: config.stop_grace_period.into_inner(),
+                stop_signal: config
+                    .stop_signal
+                    .map(yaml_spanned::Spanned::into_inner)
+                    .unwrap_or_default(),
+                startup_timeout: config
+                    .startup_timeout
+                    .map(yaml_spanned::Spanned::into_inner),
+                max_runtime: config.max_runtime.map(yaml_spanned::Spanned::into_inner),
+                schedule: config.schedule.map(yaml_spanned::Spanned::into_inner),
+                restart_backoff: config.restart_backoff.unwrap_or_default(),
+                watch,
+                tty: config.tty.as_deref().copied().unwrap_or(true),
             },
             origin: ServiceOrigin::Configured,
             startup_mode: config.startup_mode,
+            external: config.external,
+            excluded_by_profile: false,
+            replica_group: config.replica.map(|replica| replica.group),
             enable_color: config.color.as_deref().copied().unwrap_or(true),
             log_retention: config.log_retention,
+            environment_origins,
             #[cfg(unix)]
             working_directory,
         })
@@ -639,6 +738,25 @@ impl Service {
         }
     }
 
+    /// The `${services.<id>.ports.<name>}` references in the command, hooks, environment values
+    /// and healthcheck test, which are resolved when a run spawns.
+    pub(crate) fn port_references(&self) -> Vec<env::PortReference> {
+        self.spec
+            .command
+            .iter()
+            .chain(self.spec.pre_start.iter().flatten())
+            .chain(self.spec.post_stop.iter().flatten())
+            .chain(self.spec.environment.values())
+            .chain(
+                self.spec
+                    .healthcheck
+                    .iter()
+                    .flat_map(|healthcheck| &healthcheck.test),
+            )
+            .flat_map(|value| env::port_references(value))
+            .collect()
+    }
+
     pub(crate) fn replace_spec(&mut self, spec: ServiceSpec) -> Result<(), Error> {
         #[cfg(unix)]
         {
@@ -651,6 +769,340 @@ impl Service {
     }
 }
 
+/// The named port `reference` points at, or why it points at nothing.
+pub(crate) fn referenced_port(
+    services: &crate::ServiceMap,
+    reference: &env::PortReference,
+) -> Result<PortBinding, String> {
+    let service = services
+        .get(&reference.service)
+        .ok_or_else(|| format!("no service `{}`", reference.service))?;
+    service
+        .spec
+        .named_ports
+        .get(&reference.port)
+        .copied()
+        .ok_or_else(|| {
+            format!(
+                "service `{}` has no port named `{}`",
+                reference.service, reference.port
+            )
+        })
+}
+
+/// Check that every port reference in `services` names an existing service and port.
+pub(crate) fn validate_port_references(services: &crate::ServiceMap) -> Result<(), Error> {
+    for (service_id, service) in services {
+        for reference in service.port_references() {
+            referenced_port(services, &reference).map_err(|reason| Error::PortReference {
+                service: service_id.clone(),
+                reference: reference.to_string(),
+                reason,
+            })?;
+        }
+    }
+    Ok(())
+}
+
+/// The session-level environment: the top-level `env_file` and `environment` layered over the
+/// supervisor's own environment, the same layers every service starts from.
+fn session_env(
+    config_dir: &Path,
+    config: &config::Config,
+    missing_env: &mut Vec<String>,
+) -> Result<env::LayeredEnv, Error> {
+    let mut layered = env::LayeredEnv::new(std::env::vars().collect());
+    if let Some(worktree) = &config.worktree {
+        layered.push(
+            env::EnvLayer::Worktree,
+            config::worktree::PORT_OFFSET_ENV,
+            &worktree.port_offset.to_string(),
+            missing_env,
+        )?;
+    }
+    push_env_files(
+        &mut layered,
+        env::EnvLayer::SessionEnvFile,
+        config_dir,
+        &config.env_file,
+        missing_env,
+    )?;
+    push_environment(
+        &mut layered,
+        env::EnvLayer::SessionEnvironment,
+        &config.environment,
+        missing_env,
+    )?;
+    Ok(layered)
+}
+
+/// Resolve the session-level `before` and `after` hooks. They run with the session environment and
+/// interpolate their arguments against it like a service command.
+pub(crate) fn session_hooks(
+    config_dir: &Path,
+    config: &config::Config,
+) -> Result<crate::hooks::SessionHooks, Error> {
+    let mut missing_env = Vec::new();
+    let layered = session_env(config_dir, config, &mut missing_env)?;
+    let before = interpolate_hooks(config.before.clone(), layered.scope(), &mut missing_env)?;
+    let after = interpolate_hooks(config.after.clone(), layered.scope(), &mut missing_env)?;
+
+    missing_env.sort_unstable();
+    missing_env.dedup();
+    if !missing_env.is_empty() {
+        tracing::warn!(
+            missing = ?missing_env,
+            "session hook interpolation referenced unset variables"
+        );
+    }
+    let (environment, _) = layered.into_parts();
+    Ok(crate::hooks::SessionHooks {
+        before,
+        after,
+        environment,
+        timeout: config
+            .hook_timeout
+            .as_ref()
+            .map_or(crate::hooks::DEFAULT_HOOK_TIMEOUT, |timeout| timeout.inner),
+    })
+}
+
+/// Resolve the on-demand tasks. Each task layers its own `env_file` and `environment` over the
+/// session environment, exactly like a service, and interpolates its command against the result.
+pub(crate) fn tasks(
+    config_dir: &Path,
+    config: &config::Config,
+) -> Result<crate::task::TaskMap, Error> {
+    if config.tasks.is_empty() {
+        return Ok(crate::task::TaskMap::new());
+    }
+    let mut missing_env = Vec::new();
+    let session = session_env(config_dir, config, &mut missing_env)?;
+    let tasks = config
+        .tasks
+        .iter()
+        .map(
+            |(name, task)| -> Result<(String, crate::task::Task), Error> {
+                let mut layered = session.clone();
+                push_env_files(
+                    &mut layered,
+                    env::EnvLayer::ServiceEnvFile,
+                    config_dir,
+                    &task.env_file,
+                    &mut missing_env,
+                )?;
+                push_environment(
+                    &mut layered,
+                    env::EnvLayer::ServiceEnvironment,
+                    &task.environment,
+                    &mut missing_env,
+                )?;
+                let (prog, args) = task.command.clone();
+                let command = interpolate_command(prog, args, layered.scope(), &mut missing_env)?;
+                let working_dir = resolve_working_directory(config_dir, task.working_dir.as_ref())?;
+                let (environment, _) = layered.into_parts();
+                let task = crate::task::Task {
+                    name: name.to_string(),
+                    description: task
+                        .description
+                        .clone()
+                        .map(yaml_spanned::Spanned::into_inner),
+                    command,
+                    working_dir,
+                    environment,
+                };
+                Ok((task.name.clone(), task))
+            },
+        )
+        .collect::<Result<crate::task::TaskMap, Error>>()?;
+
+    missing_env.sort_unstable();
+    missing_env.dedup();
+    if !missing_env.is_empty() {
+        tracing::warn!(
+            missing = ?missing_env,
+            "task interpolation referenced unset variables"
+        );
+    }
+    Ok(tasks)
+}
+
+/// Layer a service's environment over the process environment.
+///
+/// Session-level env sits beneath the service's own, env files beneath inline values. The worktree
+/// offset and a replica's own variables come first so every layer can refer to them.
+fn layer_environment(
+    config_dir: &Path,
+    config: &config::Service,
+    missing_env: &mut Vec<String>,
+) -> Result<env::LayeredEnv, Error> {
+    let mut layered = env::LayeredEnv::new(std::env::vars().collect());
+    if let Some(offset) = config.port_offset {
+        layered.push(
+            env::EnvLayer::Worktree,
+            config::worktree::PORT_OFFSET_ENV,
+            &offset.to_string(),
+            missing_env,
+        )?;
+    }
+    if let Some(replica) = &config.replica {
+        for (key, value) in replica.environment() {
+            layered.push(env::EnvLayer::Replica, key, &value, missing_env)?;
+        }
+    }
+    push_env_files(
+        &mut layered,
+        env::EnvLayer::SessionEnvFile,
+        config_dir,
+        &config.inherited_env_file,
+        missing_env,
+    )?;
+    push_environment(
+        &mut layered,
+        env::EnvLayer::SessionEnvironment,
+        &config.inherited_environment,
+        missing_env,
+    )?;
+    push_env_files(
+        &mut layered,
+        env::EnvLayer::ServiceEnvFile,
+        config_dir,
+        &config.env_file,
+        missing_env,
+    )?;
+    push_environment(
+        &mut layered,
+        env::EnvLayer::ServiceEnvironment,
+        &config.environment,
+        missing_env,
+    )?;
+    Ok(layered)
+}
+
+/// A service's ports once interpolated and shifted by its port offset.
+struct ResolvedPorts {
+    /// Fixed ports as written, before the offset.
+    declared: Vec<u16>,
+    /// Fixed ports as the service binds them.
+    advertised: Vec<u16>,
+    named: indexmap::IndexMap<String, PortBinding>,
+}
+
+fn resolve_ports(
+    ports: &[config::Port],
+    offset: u16,
+    scope: &std::collections::HashMap<String, String>,
+    missing_env: &mut Vec<String>,
+) -> Result<ResolvedPorts, Error> {
+    let mut resolved = ResolvedPorts {
+        declared: Vec::new(),
+        advertised: Vec::new(),
+        named: indexmap::IndexMap::new(),
+    };
+    for port in ports {
+        let binding = if port.is_auto() {
+            PortBinding::Auto
+        } else {
+            let expanded = env::interpolate_str_tracking(port.port.as_ref(), scope, missing_env)?;
+            let number = expanded
+                .parse::<u16>()
+                .map_err(|source| Error::InvalidPort {
+                    port: expanded,
+                    source,
+                })?;
+            resolved.declared.push(number);
+            let number = number.checked_add(offset).ok_or(Error::PortOffset {
+                port: number,
+                offset,
+            })?;
+            resolved.advertised.push(number);
+            PortBinding::Fixed(number)
+        };
+        if let Some(name) = &port.name {
+            resolved.named.insert(name.inner.clone(), binding);
+        }
+    }
+    Ok(resolved)
+}
+
+// Unresolved references stay in place for commands like `sh -c 'echo $1'`.
+fn interpolate_command(
+    prog: yaml_spanned::Spanned<String>,
+    args: Vec<yaml_spanned::Spanned<String>>,
+    scope: &std::collections::HashMap<String, String>,
+    missing: &mut Vec<String>,
+) -> Result<Vec<String>, env::Error> {
+    std::iter::once(prog)
+        .chain(args)
+        .map(|arg| env::interpolate(arg.as_ref(), scope, env::Unresolved::Keep, missing))
+        .collect()
+}
+
+fn interpolate_hooks(
+    hooks: Vec<config::CommandLine>,
+    scope: &std::collections::HashMap<String, String>,
+    missing: &mut Vec<String>,
+) -> Result<Vec<Vec<String>>, env::Error> {
+    hooks
+        .into_iter()
+        .map(|(prog, args)| interpolate_command(prog, args, scope, missing))
+        .collect()
+}
+
+fn push_env_files(
+    layered: &mut env::LayeredEnv,
+    layer: env::EnvLayer,
+    config_dir: &Path,
+    env_files: &[config::EnvFile],
+    missing: &mut Vec<String>,
+) -> Result<(), env::Error> {
+    for env_file in env_files {
+        let path = env::resolve_path(config_dir, env_file.path.as_ref())?;
+        // A missing optional file is skipped; a present one still
+        // participates fully, including parse errors.
+        if env_file.optional && !path.exists() {
+            continue;
+        }
+        let entries = env::load_env_files_sync(std::slice::from_ref(&path))?;
+        layered.push_file(layer, &path, &entries, missing)?;
+    }
+    Ok(())
+}
+
+fn push_environment(
+    layered: &mut env::LayeredEnv,
+    layer: env::EnvLayer,
+    environment: &indexmap::IndexMap<yaml_spanned::Spanned<String>, yaml_spanned::Spanned<String>>,
+    missing: &mut Vec<String>,
+) -> Result<(), env::Error> {
+    for (key, value) in environment {
+        layered.push(layer, key.as_ref(), value.as_ref(), missing)?;
+    }
+    Ok(())
+}
+
+/// Anchor watched paths at the service's working directory. Ignore patterns stay relative: they
+/// are matched against the changed path relative to that same directory.
+fn resolve_watch(root: &Path, watch: config::Watch) -> Result<WatchSpec, env::Error> {
+    let paths = watch
+        .paths
+        .iter()
+        .map(|path| env::resolve_path(root, path.as_ref()))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(WatchSpec {
+        paths,
+        ignore: watch
+            .ignore
+            .into_iter()
+            .map(yaml_spanned::Spanned::into_inner)
+            .collect(),
+        debounce: watch.debounce.map_or(
+            crate::spec::DEFAULT_WATCH_DEBOUNCE,
+            yaml_spanned::Spanned::into_inner,
+        ),
+    })
+}
+
 fn resolve_working_directory(
     config_dir: &Path,
     configured: Option<&yaml_spanned::Spanned<String>>,
@@ -0,0 +1,159 @@
+//! Signals micromux delivers to a service's process group.
+
+/// A POSIX signal micromux can send to a service.
+///
+/// Names parse case-insensitively, with or without the `SIG` prefix (`SIGINT`, `int`), and render
+/// in the canonical `SIGINT` form. Job-control signals such as `SIGSTOP` are deliberately absent:
+/// suspending a process outside the scheduler would leave its reported state wrong, so pausing goes
+/// through the dedicated pause and resume commands instead.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    schemars::JsonSchema,
+)]
+pub enum ServiceSignal {
+    /// `SIGHUP`, conventionally "reload configuration".
+    #[serde(rename = "SIGHUP")]
+    Hup,
+    /// `SIGINT`, the signal Ctrl-C sends.
+    #[serde(rename = "SIGINT")]
+    Int,
+    /// `SIGQUIT`, often used to request a thread dump.
+    #[serde(rename = "SIGQUIT")]
+    Quit,
+    /// `SIGKILL`, which cannot be caught.
+    #[serde(rename = "SIGKILL")]
+    Kill,
+    /// `SIGUSR1`, application defined.
+    #[serde(rename = "SIGUSR1")]
+    Usr1,
+    /// `SIGUSR2`, application defined.
+    #[serde(rename = "SIGUSR2")]
+    Usr2,
+    /// `SIGALRM`.
+    #[serde(rename = "SIGALRM")]
+    Alrm,
+    /// `SIGTERM`, the default graceful-stop request.
+    #[default]
+    #[serde(rename = "SIGTERM")]
+    Term,
+    /// `SIGWINCH`, the terminal-resize notification.
+    #[serde(rename = "SIGWINCH")]
+    Winch,
+}
+
+impl ServiceSignal {
+    /// Every supported signal, in signal-number order.
+    pub const ALL: [Self; 9] = [
+        Self::Hup,
+        Self::Int,
+        Self::Quit,
+        Self::Kill,
+        Self::Usr1,
+        Self::Usr2,
+        Self::Alrm,
+        Self::Term,
+        Self::Winch,
+    ];
+
+    /// The canonical name, e.g. `SIGTERM`.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Hup => "SIGHUP",
+            Self::Int => "SIGINT",
+            Self::Quit => "SIGQUIT",
+            Self::Kill => "SIGKILL",
+            Self::Usr1 => "SIGUSR1",
+            Self::Usr2 => "SIGUSR2",
+            Self::Alrm => "SIGALRM",
+            Self::Term => "SIGTERM",
+            Self::Winch => "SIGWINCH",
+        }
+    }
+}
+
+impl std::fmt::Display for ServiceSignal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A signal name that micromux does not support.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "unknown signal `{0}`; expected one of SIGHUP, SIGINT, SIGQUIT, SIGKILL, SIGUSR1, SIGUSR2, SIGALRM, SIGTERM, SIGWINCH"
+)]
+pub struct UnknownSignal(pub String);
+
+impl std::str::FromStr for ServiceSignal {
+    type Err = UnknownSignal;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        let upper = raw.trim().to_ascii_uppercase();
+        let name = upper.strip_prefix("SIG").unwrap_or(&upper);
+        Self::ALL
+            .into_iter()
+            .find(|signal| signal.name().strip_prefix("SIG") == Some(name))
+            .ok_or_else(|| UnknownSignal(raw.to_string()))
+    }
+}
+
+#[cfg(unix)]
+impl From<ServiceSignal> for nix::sys::signal::Signal {
+    fn from(signal: ServiceSignal) -> Self {
+        match signal {
+            ServiceSignal::Hup => Self::SIGHUP,
+            ServiceSignal::Int => Self::SIGINT,
+            ServiceSignal::Quit => Self::SIGQUIT,
+            ServiceSignal::Kill => Self::SIGKILL,
+            ServiceSignal::Usr1 => Self::SIGUSR1,
+            ServiceSignal::Usr2 => Self::SIGUSR2,
+            ServiceSignal::Alrm => Self::SIGALRM,
+            ServiceSignal::Term => Self::SIGTERM,
+            ServiceSignal::Winch => Self::SIGWINCH,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use similar_asserts::assert_eq;
+
+    #[test]
+    fn names_parse_with_or_without_prefix_in_any_case() {
+        for raw in ["SIGINT", "sigint", "INT", " int "] {
+            assert_eq!(raw.parse::<ServiceSignal>(), Ok(ServiceSignal::Int));
+        }
+        assert_eq!(
+            "SIGSTOP".parse::<ServiceSignal>(),
+            Err(UnknownSignal("SIGSTOP".to_string()))
+        );
+        assert_eq!(
+            "SIG".parse::<ServiceSignal>(),
+            Err(UnknownSignal("SIG".to_string()))
+        );
+    }
+
+    #[test]
+    fn display_and_serde_use_the_canonical_name() -> serde_json::Result<()> {
+        for signal in ServiceSignal::ALL {
+            assert_eq!(
+                serde_json::to_string(&signal)?,
+                format!("\"{}\"", signal.name())
+            );
+            assert_eq!(signal.to_string().parse::<ServiceSignal>(), Ok(signal));
+        }
+        Ok(())
+    }
+}
@@ -7,9 +7,11 @@ use indexmap::IndexMap;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::config::DependencyCondition;
+use crate::config::{DependencyCondition, OnDependencyRestart, OnUnhealthy};
+use crate::cron::CronSchedule;
 use crate::scheduler::ServiceID;
 use crate::service::RestartPolicy;
+use crate::signal::ServiceSignal;
 
 /// Default probe interval when none is configured (matches Docker Compose).
 const DEFAULT_HEALTHCHECK_INTERVAL: Duration = Duration::from_secs(30);
@@ -19,6 +21,14 @@ const DEFAULT_HEALTHCHECK_TIMEOUT: Duration = Duration::from_secs(30);
 pub(crate) const DEFAULT_STOP_GRACE_PERIOD: Duration = Duration::from_secs(10);
 /// Longest graceful-stop window accepted for one service.
 pub(crate) const MAX_STOP_GRACE_PERIOD: Duration = Duration::from_mins(5);
+/// Default quiet period before a burst of watched file changes restarts a service.
+pub(crate) const DEFAULT_WATCH_DEBOUNCE: Duration = Duration::from_millis(500);
+/// Default delay before the first automatic restart after a crash.
+pub(crate) const DEFAULT_RESTART_BACKOFF_INITIAL: Duration = Duration::from_millis(250);
+/// Default ceiling the automatic-restart delay grows to.
+const DEFAULT_RESTART_BACKOFF_MAX: Duration = Duration::from_secs(10);
+/// Default growth factor between consecutive automatic-restart delays.
+const DEFAULT_RESTART_BACKOFF_MULTIPLIER: f64 = 2.0;
 
 /// Whether a service id is safe for control selectors and filesystem-backed log names.
 #[must_use]
@@ -38,6 +48,13 @@ pub struct ServiceSpec {
     /// Resolved command argv.
     #[serde(default)]
     pub command: Vec<String>,
+    /// Commands run in order before each run spawns, as resolved argvs. The first failing command
+    /// fails the start.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pre_start: Vec<Vec<String>>,
+    /// Commands run in order after each run exits, as resolved argvs.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub post_stop: Vec<Vec<String>>,
     /// Resolved absolute working directory, or the session directory when absent.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub working_dir: Option<PathBuf>,
@@ -53,6 +70,13 @@ pub struct ServiceSpec {
     /// Informational ports advertised by this service.
     #[serde(default)]
     pub ports: Vec<u16>,
+    /// Ports that other services reference as `${services.<id>.ports.<name>}` and that reach this
+    /// service as `PORT_<NAME>`. Fixed entries are also listed in `ports`.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub named_ports: IndexMap<String, PortBinding>,
+    /// What happens when a declared port is held by another process when the service starts.
+    #[serde(default)]
+    pub port_conflict: PortConflictPolicy,
     /// Automatic restart behavior.
     #[serde(default)]
     pub restart: RestartPolicy,
@@ -62,6 +86,41 @@ pub struct ServiceSpec {
     #[serde(with = "duration", default = "default_stop_grace_period")]
     #[schemars(with = "String")]
     pub stop_grace_period: Duration,
+    /// Signal sent to request graceful termination before `stop_grace_period` escalates.
+    #[serde(default)]
+    pub stop_signal: ServiceSignal,
+    /// Time a run has to become healthy before it is stopped and handled like a crash. Only
+    /// services with a healthcheck are timed.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "duration::option"
+    )]
+    #[schemars(with = "Option<String>")]
+    pub startup_timeout: Option<Duration>,
+    /// Wall-clock age at which a run is stopped gracefully and restarted, for services that
+    /// degrade the longer they run.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "duration::option"
+    )]
+    #[schemars(with = "Option<String>")]
+    pub max_runtime: Option<Duration>,
+    /// When a scheduled service starts its runs. A scheduled service does not start with the
+    /// session and is never restarted automatically; each tick starts a fresh run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<RunSchedule>,
+    /// Spacing of automatic restarts and the crash-loop breaker that ends them.
+    #[serde(default)]
+    pub restart_backoff: RestartBackoffSpec,
+    /// Files whose changes restart this service. Only configured services are watched.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watch: Option<WatchSpec>,
+    /// Run the service on a pseudo-terminal. When `false`, stdout and stderr are plain pipes whose
+    /// lines are logged with their originating stream and the service receives no terminal input.
+    #[serde(default = "default_tty")]
+    pub tty: bool,
 }
 
 impl Default for ServiceSpec {
@@ -69,13 +128,24 @@ impl Default for ServiceSpec {
         Self {
             name: None,
             command: Vec::new(),
+            pre_start: Vec::new(),
+            post_stop: Vec::new(),
             working_dir: None,
             environment: IndexMap::new(),
             depends_on: Vec::new(),
             healthcheck: None,
             ports: Vec::new(),
+            named_ports: IndexMap::new(),
+            port_conflict: PortConflictPolicy::default(),
             restart: RestartPolicy::default(),
             stop_grace_period: DEFAULT_STOP_GRACE_PERIOD,
+            stop_signal: ServiceSignal::default(),
+            startup_timeout: None,
+            max_runtime: None,
+            schedule: None,
+            restart_backoff: RestartBackoffSpec::default(),
+            watch: None,
+            tty: true,
         }
     }
 }
@@ -85,18 +155,67 @@ impl ServiceSpec {
     ///
     /// # Errors
     ///
-    /// Returns an error when either command is empty, a recurring duration is zero, or the graceful
-    /// stop window is outside the supported range.
+    /// Returns an error when the command, a hook, or the healthcheck command is empty, a port name
+    /// is invalid, a recurring duration is zero, the graceful stop window is outside the supported
+    /// range, or the restart backoff is inconsistent.
     pub fn normalize(&mut self) -> Result<(), SpecError> {
         self.command = normalize_command(&self.command)?;
+        for hook in self.pre_start.iter_mut().chain(&mut self.post_stop) {
+            *hook = normalize_command(hook)?;
+        }
+        if let Some(name) = self
+            .named_ports
+            .keys()
+            .find(|name| !port_name_is_valid(name))
+        {
+            return Err(SpecError::InvalidPortName(name.clone()));
+        }
         if self.stop_grace_period.is_zero() {
             return Err(SpecError::ZeroStopGracePeriod);
         }
         if self.stop_grace_period > MAX_STOP_GRACE_PERIOD {
             return Err(SpecError::StopGracePeriodTooLong);
         }
+        if self
+            .startup_timeout
+            .is_some_and(|timeout| timeout.is_zero())
+        {
+            return Err(SpecError::ZeroStartupTimeout);
+        }
+        if self
+            .max_runtime
+            .is_some_and(|max_runtime| max_runtime.is_zero())
+        {
+            return Err(SpecError::ZeroMaxRuntime);
+        }
+        if matches!(self.schedule, Some(RunSchedule::Every(interval)) if interval.is_zero()) {
+            return Err(SpecError::ZeroScheduleInterval);
+        }
+        self.restart_backoff
+            .validate()
+            .map_err(SpecError::InvalidRestartBackoff)?;
         if let Some(healthcheck) = &mut self.healthcheck {
-            healthcheck.test = normalize_command(&healthcheck.test)?;
+            let native_probes = usize::from(healthcheck.http.is_some())
+                + usize::from(healthcheck.tcp.is_some())
+                + usize::from(healthcheck.log_pattern.is_some());
+            match (healthcheck.test.is_empty(), native_probes) {
+                (true, 0) => return Err(SpecError::EmptyCommand),
+                (false, 0) | (true, 1) => {}
+                _ => return Err(SpecError::ConflictingHealthcheckProbes),
+            }
+            if !healthcheck.test.is_empty() {
+                healthcheck.test = normalize_command(&healthcheck.test)?;
+            }
+            if let Some(http) = &healthcheck.http {
+                validate_http_probe(http)?;
+            }
+            if let Some(log_pattern) = &healthcheck.log_pattern {
+                regex::Regex::new(&log_pattern.pattern)
+                    .map_err(|err| SpecError::InvalidLogPattern(err.to_string()))?;
+                if log_pattern.timeout.is_some_and(|timeout| timeout.is_zero()) {
+                    return Err(SpecError::ZeroHealthcheckTimeout);
+                }
+            }
             healthcheck.retries = healthcheck.retries.max(1);
             if healthcheck.interval.is_zero() {
                 return Err(SpecError::ZeroHealthcheckInterval);
@@ -117,6 +236,47 @@ impl ServiceSpec {
     }
 }
 
+/// How a named port gets its number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PortBinding {
+    /// A port number written in the config.
+    Fixed(u16),
+    /// A free loopback port picked when the port is first needed, then kept for the session.
+    Auto,
+}
+
+/// What the scheduler does when a declared port is already held by another process at spawn time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PortConflictPolicy {
+    /// Keep the service blocked and start it once the port is released.
+    Wait,
+    /// Fail the start like a spawn error, naming the process that holds the port.
+    #[default]
+    Fail,
+    /// Spawn without checking.
+    Ignore,
+}
+
+/// Whether `name` is usable as a port name: an ASCII letter followed by alphanumerics, `_`, or
+/// `-`, so it maps onto a `PORT_<NAME>` variable and fits a `${services.<id>.ports.<name>}`
+/// reference.
+#[must_use]
+pub fn port_name_is_valid(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-'))
+}
+
+/// The environment variable a named port is exported as, e.g. `PORT_HTTP` for `http`.
+#[must_use]
+pub fn port_env_var(name: &str) -> String {
+    format!("PORT_{}", name.to_ascii_uppercase().replace('-', "_"))
+}
+
 /// A normalized dependency edge.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct DependencySpec {
@@ -125,14 +285,29 @@ pub struct DependencySpec {
     /// State the target must reach.
     #[serde(default)]
     pub condition: DependencyCondition,
+    /// Whether a running dependent restarts after the target does.
+    #[serde(default)]
+    pub on_dependency_restart: OnDependencyRestart,
 }
 
 /// A fully resolved healthcheck definition.
+///
+/// Exactly one probe kind is set: a `test` command, an in-process `http` request, a `tcp`
+/// connect, or a `log_pattern` matched against the service's own output.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct HealthcheckSpec {
-    /// Probe command argv.
+    /// Probe command argv. Empty when the healthcheck uses a network probe.
     #[serde(default)]
     pub test: Vec<String>,
+    /// HTTP request sent by micromux itself instead of running a command.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http: Option<HttpProbeSpec>,
+    /// TCP connect attempted by micromux itself instead of running a command.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tcp: Option<TcpProbeSpec>,
+    /// Output pattern that marks the run ready, replacing periodic probing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_pattern: Option<LogPatternSpec>,
     /// Delay before the first probe.
     #[serde(
         default,
@@ -155,6 +330,9 @@ pub struct HealthcheckSpec {
     /// `retries * timeout + (retries - 1) * interval` after the first attempt begins.
     #[serde(default = "default_healthcheck_retries")]
     pub retries: usize,
+    /// Whether the run is restarted once it turns unhealthy.
+    #[serde(default)]
+    pub on_unhealthy: OnUnhealthy,
 }
 
 // Serde field defaults so hand-written dynamic-service healthchecks only need `test`;
@@ -175,25 +353,43 @@ pub(crate) fn default_stop_grace_period() -> Duration {
     DEFAULT_STOP_GRACE_PERIOD
 }
 
+fn default_tty() -> bool {
+    true
+}
+
 impl Default for HealthcheckSpec {
     fn default() -> Self {
         Self {
             test: Vec::new(),
+            http: None,
+            tcp: None,
+            log_pattern: None,
             start_delay: None,
             interval: default_healthcheck_interval(),
             timeout: default_healthcheck_timeout(),
             retries: default_healthcheck_retries(),
+            on_unhealthy: OnUnhealthy::default(),
         }
     }
 }
 
 impl From<crate::config::HealthCheck> for HealthcheckSpec {
     fn from(healthcheck: crate::config::HealthCheck) -> Self {
-        let (program, args) = healthcheck.test;
-        let mut test = vec![program.into_inner()];
-        test.extend(args.into_iter().map(yaml_spanned::Spanned::into_inner));
+        let (mut test, mut http, mut tcp, mut log_pattern) = (Vec::new(), None, None, None);
+        match healthcheck.probe {
+            crate::config::HealthProbe::Command((program, args)) => {
+                test.push(program.into_inner());
+                test.extend(args.into_iter().map(yaml_spanned::Spanned::into_inner));
+            }
+            crate::config::HealthProbe::Http(probe) => http = Some((*probe).into()),
+            crate::config::HealthProbe::Tcp(probe) => tcp = Some(probe.into()),
+            crate::config::HealthProbe::LogPattern(probe) => log_pattern = Some(probe.into()),
+        }
         Self {
             test,
+            http,
+            tcp,
+            log_pattern,
             start_delay: healthcheck
                 .start_delay
                 .map(yaml_spanned::Spanned::into_inner),
@@ -210,10 +406,391 @@ impl From<crate::config::HealthCheck> for HealthcheckSpec {
                 .map(yaml_spanned::Spanned::into_inner)
                 .unwrap_or(1)
                 .max(1),
+            on_unhealthy: healthcheck
+                .on_unhealthy
+                .map(yaml_spanned::Spanned::into_inner)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Inclusive range of HTTP status codes an `http` probe accepts as healthy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct StatusRange {
+    /// Lowest accepted status code.
+    pub min: u16,
+    /// Highest accepted status code.
+    pub max: u16,
+}
+
+impl Default for StatusRange {
+    /// Any success or redirect response, like a Kubernetes HTTP probe.
+    fn default() -> Self {
+        Self { min: 200, max: 399 }
+    }
+}
+
+impl StatusRange {
+    /// Whether the range is non-empty and only spans real HTTP status codes.
+    #[must_use]
+    pub fn is_valid(self) -> bool {
+        100 <= self.min && self.min <= self.max && self.max <= 599
+    }
+
+    /// Whether `status` falls inside the range.
+    #[must_use]
+    pub fn contains(self, status: u16) -> bool {
+        (self.min..=self.max).contains(&status)
+    }
+}
+
+impl std::fmt::Display for StatusRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.min == self.max {
+            write!(f, "{}", self.min)
+        } else {
+            write!(f, "{}-{}", self.min, self.max)
+        }
+    }
+}
+
+/// An HTTP `GET` probe run in-process.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct HttpProbeSpec {
+    /// Plain `http://` URL to request.
+    pub url: String,
+    /// Status codes counted as healthy.
+    #[serde(default)]
+    pub status: StatusRange,
+    /// Regular expression the response body must match.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    /// Extra request headers.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub headers: IndexMap<String, String>,
+}
+
+impl From<crate::config::HttpProbe> for HttpProbeSpec {
+    fn from(probe: crate::config::HttpProbe) -> Self {
+        Self {
+            url: probe.url.into_inner(),
+            status: probe
+                .status
+                .map(yaml_spanned::Spanned::into_inner)
+                .unwrap_or_default(),
+            body: probe.body.map(yaml_spanned::Spanned::into_inner),
+            headers: probe
+                .headers
+                .into_iter()
+                .map(|(name, value)| (name.into_inner(), value.into_inner()))
+                .collect(),
+        }
+    }
+}
+
+/// The parts of an `http` probe URL needed to send a request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct HttpTarget {
+    /// Host name or address to connect to, without IPv6 brackets.
+    pub host: String,
+    /// TCP port, `80` unless the URL names one.
+    pub port: u16,
+    /// `Host` header value as written in the URL.
+    pub authority: String,
+    /// Request target, always starting with `/`.
+    pub path: String,
+}
+
+impl HttpProbeSpec {
+    /// Split the probe URL into connection and request parts.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the problem when the URL is not a plain `http://` URL with a host.
+    pub(crate) fn target(&self) -> Result<HttpTarget, String> {
+        parse_http_url(&self.url)
+    }
+}
+
+/// Parse a plain `http://` URL. TLS is deliberately out of scope: probes target local services.
+pub(crate) fn parse_http_url(url: &str) -> Result<HttpTarget, String> {
+    let Some((scheme, rest)) = url.split_once("://") else {
+        return Err(format!("`{url}` is not an absolute http:// URL"));
+    };
+    if !scheme.eq_ignore_ascii_case("http") {
+        return Err(format!(
+            "unsupported URL scheme `{scheme}`; http probes only speak plain http://"
+        ));
+    }
+    let rest = rest.split_once('#').map_or(rest, |(rest, _fragment)| rest);
+    let split = rest.find(['/', '?']).unwrap_or(rest.len());
+    let (authority, path) = rest.split_at(split);
+    let path = match path.chars().next() {
+        None => "/".to_string(),
+        Some('?') => format!("/{path}"),
+        Some(_) => path.to_string(),
+    };
+    if authority.contains('@') {
+        return Err(format!("`{url}` must not contain credentials"));
+    }
+    let (host, port) = if let Some(bracketed) = authority.strip_prefix('[') {
+        let Some((host, port)) = bracketed.split_once(']') else {
+            return Err(format!("`{url}` has an unterminated IPv6 address"));
+        };
+        (host, port.strip_prefix(':'))
+    } else {
+        match authority.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        }
+    };
+    if host.is_empty() {
+        return Err(format!("`{url}` has no host"));
+    }
+    let port = match port {
+        None => 80,
+        Some(port) => port
+            .parse::<u16>()
+            .ok()
+            .filter(|port| *port != 0)
+            .ok_or_else(|| format!("`{url}` has an invalid port `{port}`"))?,
+    };
+    if path.chars().any(char::is_whitespace) {
+        return Err(format!("`{url}` must not contain whitespace"));
+    }
+    Ok(HttpTarget {
+        host: host.to_string(),
+        port,
+        authority: authority.to_string(),
+        path,
+    })
+}
+
+/// A TCP connect probe run in-process.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct TcpProbeSpec {
+    /// Host name or address to connect to.
+    #[serde(default = "default_tcp_probe_host")]
+    pub host: String,
+    /// TCP port to connect to.
+    pub port: u16,
+}
+
+fn default_tcp_probe_host() -> String {
+    "localhost".to_string()
+}
+
+impl From<crate::config::TcpProbe> for TcpProbeSpec {
+    fn from(probe: crate::config::TcpProbe) -> Self {
+        Self {
+            host: probe.host.into_inner(),
+            port: probe.port.into_inner(),
+        }
+    }
+}
+
+/// Readiness taken from the service's output rather than from a probe.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct LogPatternSpec {
+    /// Regular expression matched against each output line of the current run.
+    pub pattern: String,
+    /// How long after start to wait for the first match; unbounded when unset.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "duration::option"
+    )]
+    #[schemars(with = "Option<String>")]
+    pub timeout: Option<Duration>,
+}
+
+impl From<crate::config::LogPatternProbe> for LogPatternSpec {
+    fn from(probe: crate::config::LogPatternProbe) -> Self {
+        Self {
+            pattern: probe.pattern.into_inner(),
+            timeout: probe.timeout.map(yaml_spanned::Spanned::into_inner),
+        }
+    }
+}
+
+/// A resolved file-watch trigger.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct WatchSpec {
+    /// Absolute paths or glob patterns whose changes restart the service.
+    pub paths: Vec<PathBuf>,
+    /// Glob patterns, relative to the working directory, for changes that are ignored.
+    #[serde(default)]
+    pub ignore: Vec<String>,
+    /// Quiet period that coalesces a burst of changes into one restart.
+    #[serde(with = "duration", default = "default_watch_debounce")]
+    #[schemars(with = "String")]
+    pub debounce: Duration,
+}
+
+fn default_watch_debounce() -> Duration {
+    DEFAULT_WATCH_DEBOUNCE
+}
+
+/// When a scheduled service starts a run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RunSchedule {
+    /// At every tick of a five-field cron expression, evaluated in UTC.
+    Cron(CronSchedule),
+    /// Once per interval, the first one interval after the schedule is armed.
+    Every(
+        #[serde(with = "duration")]
+        #[schemars(with = "String")]
+        Duration,
+    ),
+}
+
+impl RunSchedule {
+    /// The first tick strictly after `unix_ms`, in Unix milliseconds, or `None` when there is
+    /// none.
+    #[must_use]
+    pub fn next_after(&self, unix_ms: u64) -> Option<u64> {
+        match self {
+            Self::Cron(cron) => cron.next_after(unix_ms),
+            Self::Every(interval) => unix_ms.checked_add(u64::try_from(interval.as_millis()).ok()?),
+        }
+    }
+}
+
+impl std::fmt::Display for RunSchedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cron(cron) => f.write_str(cron.as_str()),
+            Self::Every(interval) => write!(f, "every {}", humantime::format_duration(*interval)),
         }
     }
 }
 
+/// How automatic restarts are spaced out, and when a crash loop stops them.
+///
+/// The first restart waits `initial`; each consecutive crash multiplies the delay by `multiplier`
+/// up to `max`. A run that stays up for `reset_after` starts the sequence over.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RestartBackoffSpec {
+    /// Delay before the first automatic restart.
+    #[serde(with = "duration", default = "default_restart_backoff_initial")]
+    #[schemars(with = "String")]
+    pub initial: Duration,
+    /// Ceiling the delay grows to.
+    #[serde(with = "duration", default = "default_restart_backoff_max")]
+    #[schemars(with = "String")]
+    pub max: Duration,
+    /// Factor applied to the delay after each consecutive crash; at least 1.
+    #[serde(default = "default_restart_backoff_multiplier")]
+    pub multiplier: f64,
+    /// Random spread of each delay, as a fraction of it between 0 and 1.
+    #[serde(default)]
+    pub jitter: f64,
+    /// Uptime after which a run counts as stable and the delay starts over. Defaults to `max`.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "duration::option"
+    )]
+    #[schemars(with = "Option<String>")]
+    pub reset_after: Option<Duration>,
+    /// Breaker that stops automatic restarts after too many crashes in a window.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crash_loop: Option<CrashLoopSpec>,
+}
+
+// `validate` rejects non-finite factors, so equality over accepted specs is reflexive.
+impl Eq for RestartBackoffSpec {}
+
+/// A windowed crash-loop breaker: give up after `crashes` crashes within `window`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct CrashLoopSpec {
+    /// Crashes inside the window that stop automatic restarts.
+    pub crashes: usize,
+    /// Sliding window the crashes are counted over.
+    #[serde(with = "duration")]
+    #[schemars(with = "String")]
+    pub window: Duration,
+}
+
+fn default_restart_backoff_initial() -> Duration {
+    DEFAULT_RESTART_BACKOFF_INITIAL
+}
+
+fn default_restart_backoff_max() -> Duration {
+    DEFAULT_RESTART_BACKOFF_MAX
+}
+
+fn default_restart_backoff_multiplier() -> f64 {
+    DEFAULT_RESTART_BACKOFF_MULTIPLIER
+}
+
+impl Default for RestartBackoffSpec {
+    fn default() -> Self {
+        Self {
+            initial: default_restart_backoff_initial(),
+            max: default_restart_backoff_max(),
+            multiplier: default_restart_backoff_multiplier(),
+            jitter: 0.0,
+            reset_after: None,
+            crash_loop: None,
+        }
+    }
+}
+
+impl RestartBackoffSpec {
+    /// Uptime after which a run counts as stable.
+    #[must_use]
+    pub fn reset_after(&self) -> Duration {
+        self.reset_after.unwrap_or(self.max)
+    }
+
+    /// The un-jittered delay that follows `previous`, or `initial` for the first restart.
+    #[must_use]
+    pub fn next_delay(&self, previous: Option<Duration>) -> Duration {
+        let next = previous.map_or(self.initial, |previous| {
+            Duration::try_from_secs_f64(previous.as_secs_f64() * self.multiplier)
+                .unwrap_or(self.max)
+        });
+        next.min(self.max)
+    }
+
+    /// Check the invariants the scheduler relies on.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the first violated invariant.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.initial.is_zero() {
+            return Err("initial must be greater than zero".to_string());
+        }
+        if self.max < self.initial {
+            return Err("max must not be shorter than initial".to_string());
+        }
+        if !self.multiplier.is_finite() || self.multiplier < 1.0 {
+            return Err("multiplier must be a number of at least 1".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.jitter) {
+            return Err("jitter must be between 0 and 1".to_string());
+        }
+        if self
+            .reset_after
+            .is_some_and(|reset_after| reset_after.is_zero())
+        {
+            return Err("reset_after must be greater than zero".to_string());
+        }
+        if let Some(crash_loop) = &self.crash_loop {
+            if crash_loop.crashes == 0 {
+                return Err("crash_loop.crashes must be greater than zero".to_string());
+            }
+            if crash_loop.window.is_zero() {
+                return Err("crash_loop.window must be greater than zero".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Where a supervised service came from.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub enum ServiceOrigin {
@@ -348,6 +925,9 @@ pub struct PartialServiceSpec {
     /// Advertised-port replacement.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub ports: Option<Vec<u16>>,
+    /// Port-conflict policy replacement.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port_conflict: Option<PortConflictPolicy>,
     /// Restart-policy replacement.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub restart: Option<RestartPolicy>,
@@ -359,6 +939,35 @@ pub struct PartialServiceSpec {
     )]
     #[schemars(with = "Option<String>")]
     pub stop_grace_period: Option<Duration>,
+    /// Graceful-stop signal replacement.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_signal: Option<ServiceSignal>,
+    /// Startup-timeout replacement.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "duration::option"
+    )]
+    #[schemars(with = "Option<String>")]
+    pub startup_timeout: Option<Duration>,
+    /// Maximum-runtime replacement.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "duration::option"
+    )]
+    #[schemars(with = "Option<String>")]
+    pub max_runtime: Option<Duration>,
+    /// Run-schedule replacement; `null` makes a cloned scheduled service a long-running one.
+    #[serde(default, skip_serializing_if = "SpecField::is_unspecified")]
+    #[schemars(with = "Option<RunSchedule>")]
+    pub schedule: SpecField<RunSchedule>,
+    /// Restart backoff replacement.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restart_backoff: Option<RestartBackoffSpec>,
+    /// Pseudo-terminal replacement; `false` runs the service on plain stdout/stderr pipes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tty: Option<bool>,
 }
 
 impl PartialServiceSpec {
@@ -392,12 +1001,35 @@ impl PartialServiceSpec {
         if let Some(ports) = self.ports {
             base.ports = ports;
         }
+        if let Some(port_conflict) = self.port_conflict {
+            base.port_conflict = port_conflict;
+        }
         if let Some(restart) = self.restart {
             base.restart = restart;
         }
         if let Some(stop_grace_period) = self.stop_grace_period {
             base.stop_grace_period = stop_grace_period;
         }
+        if let Some(stop_signal) = self.stop_signal {
+            base.stop_signal = stop_signal;
+        }
+        if let Some(startup_timeout) = self.startup_timeout {
+            base.startup_timeout = Some(startup_timeout);
+        }
+        if let Some(max_runtime) = self.max_runtime {
+            base.max_runtime = Some(max_runtime);
+        }
+        match self.schedule {
+            SpecField::Unspecified => {}
+            SpecField::Clear => base.schedule = None,
+            SpecField::Value(schedule) => base.schedule = Some(schedule),
+        }
+        if let Some(restart_backoff) = self.restart_backoff {
+            base.restart_backoff = restart_backoff;
+        }
+        if let Some(tty) = self.tty {
+            base.tty = tty;
+        }
         base
     }
 }
@@ -433,6 +1065,15 @@ pub enum SpecError {
     /// The command has no executable component.
     #[error("command is empty")]
     EmptyCommand,
+    /// A healthcheck must use exactly one of `test`, `http`, `tcp`, and `log_pattern`.
+    #[error("healthcheck must configure exactly one of `test`, `http`, `tcp`, or `log_pattern`")]
+    ConflictingHealthcheckProbes,
+    /// An `http` probe cannot be sent as configured.
+    #[error("invalid http healthcheck: {0}")]
+    InvalidHttpProbe(String),
+    /// A `log_pattern` is not a valid regular expression.
+    #[error("invalid healthcheck log pattern: {0}")]
+    InvalidLogPattern(String),
     /// A zero interval would run probes continuously.
     #[error("healthcheck interval must be greater than zero")]
     ZeroHealthcheckInterval,
@@ -445,6 +1086,53 @@ pub enum SpecError {
     /// An excessive stop grace would make supervisor shutdown unreasonably long.
     #[error("stop grace period must not exceed 5m")]
     StopGracePeriodTooLong,
+    /// A zero startup timeout would stop every run before its first probe.
+    #[error("startup timeout must be greater than zero")]
+    ZeroStartupTimeout,
+    /// A zero maximum runtime would restart every run as soon as it starts.
+    #[error("max runtime must be greater than zero")]
+    ZeroMaxRuntime,
+    /// A zero `every` interval would start runs continuously.
+    #[error("schedule interval must be greater than zero")]
+    ZeroScheduleInterval,
+    /// The restart backoff cannot produce a sensible delay schedule.
+    #[error("invalid restart_backoff: {0}")]
+    InvalidRestartBackoff(String),
+    /// A named port cannot be exported as an environment variable or referenced.
+    #[error("invalid port name `{0}`")]
+    InvalidPortName(String),
+}
+
+fn validate_http_probe(probe: &HttpProbeSpec) -> Result<(), SpecError> {
+    probe.target().map_err(SpecError::InvalidHttpProbe)?;
+    if !probe.status.is_valid() {
+        return Err(SpecError::InvalidHttpProbe(format!(
+            "status range {} is not within 100-599",
+            probe.status
+        )));
+    }
+    if let Some(body) = &probe.body {
+        regex::Regex::new(body).map_err(|err| SpecError::InvalidHttpProbe(err.to_string()))?;
+    }
+    if let Some((name, _value)) = probe
+        .headers
+        .iter()
+        .find(|(name, value)| !http_header_is_valid(name, value))
+    {
+        return Err(SpecError::InvalidHttpProbe(format!(
+            "header `{name}` is not a valid HTTP header"
+        )));
+    }
+    Ok(())
+}
+
+/// Whether a probe header can be written verbatim into a request without breaking its framing.
+pub(crate) fn http_header_is_valid(name: &str, value: &str) -> bool {
+    !name.is_empty()
+        && name
+            .bytes()
+            .all(|byte| byte.is_ascii_graphic() && byte != b':')
+        && !value.contains(['\r', '\n'])
 }
 
 /// Lower a plain argv or Compose-style `CMD`/`CMD-SHELL` form for the current platform.
@@ -538,10 +1226,14 @@ mod tests {
             command: vec!["CMD-SHELL".to_string(), "echo hi".to_string()],
             healthcheck: Some(HealthcheckSpec {
                 test: vec!["CMD".to_string(), "true".to_string()],
+                http: None,
+                tcp: None,
+                log_pattern: None,
                 start_delay: Some(Duration::from_millis(250)),
                 interval: Duration::from_secs(2),
                 timeout: Duration::from_secs(1),
                 retries: 3,
+                on_unhealthy: OnUnhealthy::Ignore,
             }),
             ..ServiceSpec::default()
         };
@@ -644,12 +1336,168 @@ mod tests {
         }
     }
 
+    #[test]
+    fn restart_backoff_grows_geometrically_up_to_max() {
+        let backoff = RestartBackoffSpec {
+            initial: Duration::from_secs(1),
+            max: Duration::from_secs(5),
+            multiplier: 3.0,
+            ..RestartBackoffSpec::default()
+        };
+        let mut delays = Vec::new();
+        let mut previous = None;
+        for _ in 0..4 {
+            let delay = backoff.next_delay(previous);
+            delays.push(delay.as_secs());
+            previous = Some(delay);
+        }
+        assert_eq!(delays, vec![1, 3, 5, 5]);
+        assert_eq!(backoff.reset_after(), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn normalization_rejects_inconsistent_restart_backoff() {
+        let cases = [
+            (
+                RestartBackoffSpec {
+                    initial: Duration::from_secs(20),
+                    ..RestartBackoffSpec::default()
+                },
+                "max must not be shorter than initial",
+            ),
+            (
+                RestartBackoffSpec {
+                    multiplier: 0.5,
+                    ..RestartBackoffSpec::default()
+                },
+                "multiplier must be a number of at least 1",
+            ),
+            (
+                RestartBackoffSpec {
+                    jitter: f64::NAN,
+                    ..RestartBackoffSpec::default()
+                },
+                "jitter must be between 0 and 1",
+            ),
+            (
+                RestartBackoffSpec {
+                    crash_loop: Some(CrashLoopSpec {
+                        crashes: 0,
+                        window: Duration::from_mins(1),
+                    }),
+                    ..RestartBackoffSpec::default()
+                },
+                "crash_loop.crashes must be greater than zero",
+            ),
+        ];
+
+        for (restart_backoff, expected) in cases {
+            let mut spec = ServiceSpec {
+                command: vec!["true".to_string()],
+                restart_backoff,
+                ..ServiceSpec::default()
+            };
+            assert_eq!(
+                spec.normalize(),
+                Err(SpecError::InvalidRestartBackoff(expected.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn normalization_requires_exactly_one_valid_healthcheck_probe() {
+        let http = HttpProbeSpec {
+            url: "http://localhost:8080/health".to_string(),
+            status: StatusRange::default(),
+            body: None,
+            headers: IndexMap::new(),
+        };
+        let spec = |healthcheck: HealthcheckSpec| ServiceSpec {
+            command: vec!["true".to_string()],
+            healthcheck: Some(healthcheck),
+            ..ServiceSpec::default()
+        };
+        let cases = [
+            (
+                HealthcheckSpec {
+                    http: Some(http.clone()),
+                    ..HealthcheckSpec::default()
+                },
+                Ok(()),
+            ),
+            (
+                HealthcheckSpec {
+                    test: vec!["true".to_string()],
+                    tcp: Some(TcpProbeSpec {
+                        host: "localhost".to_string(),
+                        port: 5432,
+                    }),
+                    ..HealthcheckSpec::default()
+                },
+                Err(SpecError::ConflictingHealthcheckProbes),
+            ),
+            (
+                HealthcheckSpec {
+                    http: Some(HttpProbeSpec {
+                        url: "https://localhost/".to_string(),
+                        ..http.clone()
+                    }),
+                    ..HealthcheckSpec::default()
+                },
+                Err(SpecError::InvalidHttpProbe(
+                    "unsupported URL scheme `https`; http probes only speak plain http://"
+                        .to_string(),
+                )),
+            ),
+            (
+                HealthcheckSpec {
+                    http: Some(HttpProbeSpec {
+                        headers: IndexMap::from([(
+                            "X-Injected".to_string(),
+                            "a\r\nHost: elsewhere".to_string(),
+                        )]),
+                        ..http
+                    }),
+                    ..HealthcheckSpec::default()
+                },
+                Err(SpecError::InvalidHttpProbe(
+                    "header `X-Injected` is not a valid HTTP header".to_string(),
+                )),
+            ),
+        ];
+
+        for (healthcheck, expected) in cases {
+            assert_eq!(spec(healthcheck).normalize(), expected);
+        }
+    }
+
+    #[test]
+    fn http_url_parsing_splits_authority_and_request_target() -> eyre::Result<()> {
+        let target = parse_http_url("http://[::1]:3000?ready").map_err(|err| eyre::eyre!(err))?;
+        assert_eq!(
+            target,
+            HttpTarget {
+                host: "::1".to_string(),
+                port: 3000,
+                authority: "[::1]:3000".to_string(),
+                path: "/?ready".to_string(),
+            }
+        );
+        let target =
+            parse_http_url("HTTP://localhost/health#frag").map_err(|err| eyre::eyre!(err))?;
+        assert_eq!((target.port, target.path.as_str()), (80, "/health"));
+        assert!(parse_http_url("http://localhost:0/").is_err());
+        assert!(parse_http_url("http://user@localhost/").is_err());
+        Ok(())
+    }
+
     #[test]
     fn partial_spec_distinguishes_omitted_fields_from_explicit_null() -> eyre::Result<()> {
         let partial = serde_json::from_value::<PartialServiceSpec>(serde_json::json!({
             "name": null,
             "healthcheck": null,
-            "environment": {"NEW": "value"}
+            "environment": {"NEW": "value"},
+            "stop_signal": "SIGINT"
         }))?;
         let base = ServiceSpec {
             name: Some("base".to_string()),
@@ -667,6 +1515,7 @@ mod tests {
         let applied = partial.apply_to(base);
         assert_eq!(applied.name, None);
         assert_eq!(applied.healthcheck, None);
+        assert_eq!(applied.stop_signal, ServiceSignal::Int);
         assert_eq!(
             applied.environment.get("KEPT").map(String::as_str),
             Some("yes")
@@ -0,0 +1,72 @@
+//! On-demand tasks: named commands from the `tasks:` config section, run to completion on request.
+//!
+//! A task has a service's command, environment and working directory but no restart policy,
+//! healthcheck or dependencies. Each run's output is recorded through a [`TaskSink`] as a retained
+//! run log.
+
+use crate::hooks::{CommandExit, run_command};
+use crate::model::{HealthResult, TaskSink, TaskSnapshot};
+use crate::scheduler::OutputStream;
+use indexmap::IndexMap;
+use itertools::Itertools;
+use std::path::PathBuf;
+use tokio_util::sync::CancellationToken;
+
+/// An on-demand task, materialized from the config with its environment.
+#[derive(Debug, Clone)]
+pub(crate) struct Task {
+    pub(crate) name: String,
+    pub(crate) description: Option<String>,
+    pub(crate) command: Vec<String>,
+    pub(crate) working_dir: Option<PathBuf>,
+    pub(crate) environment: IndexMap<String, String>,
+}
+
+/// Configured tasks keyed by name, in configuration order.
+pub(crate) type TaskMap = IndexMap<String, Task>;
+
+impl Task {
+    /// The model entry for this task, before it has run.
+    pub(crate) fn snapshot(&self) -> TaskSnapshot {
+        TaskSnapshot {
+            name: self.name.clone(),
+            description: self.description.clone(),
+            command: self.command.iter().join(" "),
+            working_dir: self
+                .working_dir
+                .as_ref()
+                .map(|dir| dir.display().to_string()),
+            runs: Vec::new(),
+        }
+    }
+}
+
+/// Run `task` to completion, recording its output and result in `sink`. Cancelling `cancel` kills
+/// the run, which is then recorded as cancelled.
+pub(crate) async fn run(task: &Task, sink: TaskSink, cancel: &CancellationToken) {
+    let on_line = {
+        let sink = sink.clone();
+        move |stream, line| sink.append_line(stream, line)
+    };
+    let result = match run_command(
+        &task.command,
+        &task.environment,
+        task.working_dir.as_deref(),
+        cancel,
+        on_line,
+    )
+    .await
+    {
+        Ok(CommandExit::Exited { status, elapsed }) => {
+            HealthResult::exited(status.success(), status.code().unwrap_or(-1))
+                .with_latency(elapsed)
+        }
+        Ok(CommandExit::Cancelled) => HealthResult::cancelled(),
+        Err(err) => {
+            tracing::warn!(task = task.name, %err, "task could not be started");
+            sink.append_line(OutputStream::Stderr, err.to_string());
+            HealthResult::exited(false, -1)
+        }
+    };
+    sink.finish(result);
+}
@@ -19,27 +19,44 @@ pub(crate) fn service_config(name: &str, command: (&str, &[&str])) -> config::Se
     config::Service {
         name: spanned_string(name),
         startup_mode: StartupMode::Enabled,
-        command: (
+        external: false,
+        command: Some((
             spanned_string(command.0),
             command
                 .1
                 .iter()
                 .map(|value| spanned_string(value))
                 .collect(),
-        ),
+        )),
+        pre_start: Vec::new(),
+        post_stop: Vec::new(),
         working_dir: None,
         env_file: Vec::new(),
         environment: IndexMap::new(),
+        inherited_env_file: Vec::new(),
+        inherited_environment: IndexMap::new(),
         depends_on: Vec::new(),
         healthcheck: None,
         ports: Vec::new(),
+        port_conflict: crate::PortConflictPolicy::Fail,
         restart: None,
         restart_policy: RestartPolicy::Never,
         stop_grace_period: Spanned {
             span: yaml_spanned::spanned::Span::default(),
             inner: crate::spec::DEFAULT_STOP_GRACE_PERIOD,
         },
+        stop_signal: None,
+        startup_timeout: None,
+        max_runtime: None,
+        schedule: None,
+        restart_backoff: None,
+        profiles: Vec::new(),
+        watch: None,
         color: None,
+        tty: None,
+        replicas: None,
+        replica: None,
+        port_offset: None,
         log_retention: LogRetention::default(),
     }
 }
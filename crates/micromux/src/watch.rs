@@ -0,0 +1,361 @@
+//! File watchers that restart services when their watched sources change.
+//!
+//! Each watched service owns one [`ServiceWatcher`]: an OS-level `notify` watcher feeding a
+//! debounce task that coalesces a burst of changes into a single [`WatchTrigger`]. The scheduler
+//! turns that trigger into an ordinary restart, so a watched service keeps its process group, its
+//! exit codes, and the reload-before-spawn behavior of an explicit restart.
+
+use crate::scheduler::ServiceID;
+use crate::spec::WatchSpec;
+use globset::{Glob, GlobMatcher, GlobSet, GlobSetBuilder};
+use notify::{RecursiveMode, Watcher as _};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+/// Changes below these paths never trigger a restart.
+const BUILTIN_IGNORE: &[&str] = &["**/.git/**"];
+/// Raw notifications buffered between the watcher thread and the debounce task.
+const RAW_EVENT_BUFFER: usize = 256;
+
+/// Errors from setting up a service's file watcher.
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum Error {
+    /// A watch or ignore pattern is not a valid glob.
+    #[error("invalid watch pattern `{pattern}`: {source}")]
+    Pattern {
+        /// The offending pattern.
+        pattern: String,
+        /// Underlying glob parse error.
+        #[source]
+        source: globset::Error,
+    },
+    /// The platform watcher could not be created.
+    #[error("failed to create file watcher: {0}")]
+    Watcher(#[from] notify::Error),
+}
+
+/// A debounced batch of watched-file changes for one service.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct WatchTrigger {
+    pub(crate) service_id: ServiceID,
+    /// Changed paths, relative to the service working directory where possible, sorted.
+    pub(crate) paths: Vec<String>,
+}
+
+/// What a changed path must satisfy to count for one configured watch entry.
+#[derive(Debug)]
+enum WatchRoot {
+    /// The path itself or anything below it.
+    Under(PathBuf),
+    /// An absolute glob pattern.
+    Glob(GlobMatcher),
+}
+
+/// Decides whether a changed path belongs to a service's watch set.
+#[derive(Debug)]
+struct Matcher {
+    base: PathBuf,
+    roots: Vec<WatchRoot>,
+    ignore: GlobSet,
+}
+
+impl Matcher {
+    fn new(base: &Path, spec: &WatchSpec) -> Result<Self, Error> {
+        let roots = spec
+            .paths
+            .iter()
+            .map(|path| {
+                if split_glob(path).is_none() {
+                    return Ok(WatchRoot::Under(path.clone()));
+                }
+                let pattern = path.to_string_lossy();
+                glob(&pattern).map(|glob| WatchRoot::Glob(glob.compile_matcher()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut ignore = GlobSetBuilder::new();
+        for pattern in BUILTIN_IGNORE
+            .iter()
+            .copied()
+            .chain(spec.ignore.iter().map(String::as_str))
+        {
+            ignore.add(glob(pattern)?);
+        }
+        let ignore = ignore.build().map_err(|source| Error::Pattern {
+            pattern: spec.ignore.join(", "),
+            source,
+        })?;
+        Ok(Self {
+            base: base.to_path_buf(),
+            roots,
+            ignore,
+        })
+    }
+
+    fn relative<'a>(&self, path: &'a Path) -> &'a Path {
+        path.strip_prefix(&self.base).unwrap_or(path)
+    }
+
+    fn matches(&self, path: &Path) -> bool {
+        if self.ignore.is_match(self.relative(path)) {
+            return false;
+        }
+        self.roots.iter().any(|root| match root {
+            WatchRoot::Under(root) => path.starts_with(root),
+            WatchRoot::Glob(glob) => glob.is_match(path),
+        })
+    }
+
+    /// The directories handed to the OS watcher.
+    ///
+    /// A glob is watched from its longest literal prefix. A plain file is watched through its
+    /// parent directory, because editors commonly replace files by rename and a watch on the
+    /// original inode would go silent after the first save.
+    fn watched(spec: &WatchSpec) -> Vec<(PathBuf, RecursiveMode)> {
+        spec.paths
+            .iter()
+            .map(|path| match split_glob(path) {
+                Some(prefix) => (prefix, RecursiveMode::Recursive),
+                None if path.is_file() => (
+                    path.parent()
+                        .map_or_else(|| path.clone(), Path::to_path_buf),
+                    RecursiveMode::NonRecursive,
+                ),
+                None => (path.clone(), RecursiveMode::Recursive),
+            })
+            .collect()
+    }
+}
+
+fn glob(pattern: &str) -> Result<Glob, Error> {
+    Glob::new(pattern).map_err(|source| Error::Pattern {
+        pattern: pattern.to_string(),
+        source,
+    })
+}
+
+/// The literal directory prefix of a glob pattern, or `None` when the path has no glob syntax.
+fn split_glob(path: &Path) -> Option<PathBuf> {
+    let mut prefix = PathBuf::new();
+    for component in path.components() {
+        if component
+            .as_os_str()
+            .to_string_lossy()
+            .contains(['*', '?', '[', '{'])
+        {
+            return Some(prefix);
+        }
+        prefix.push(component);
+    }
+    None
+}
+
+/// A running watcher for one service. Dropping it stops both the OS watcher and its debounce task.
+pub(crate) struct ServiceWatcher {
+    spec: WatchSpec,
+    base: PathBuf,
+    _watcher: notify::RecommendedWatcher,
+    cancel: CancellationToken,
+}
+
+impl Drop for ServiceWatcher {
+    fn drop(&mut self) {
+        self.cancel.cancel();
+    }
+}
+
+impl ServiceWatcher {
+    /// Start watching `spec` for `service_id`. Paths that cannot be watched (for example because
+    /// they do not exist yet) are logged and skipped rather than failing the whole watcher.
+    pub(crate) fn start(
+        service_id: &ServiceID,
+        spec: &WatchSpec,
+        base: &Path,
+        triggers: mpsc::Sender<WatchTrigger>,
+        shutdown: &CancellationToken,
+    ) -> Result<Self, Error> {
+        let matcher = Matcher::new(base, spec)?;
+        let (raw_tx, raw_rx) = mpsc::channel(RAW_EVENT_BUFFER);
+        // The callback runs on the watcher's own thread, outside the runtime, so it may block.
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                let _ = raw_tx.blocking_send(event);
+            })?;
+        for (path, mode) in Matcher::watched(spec) {
+            if let Err(err) = watcher.watch(&path, mode) {
+                tracing::warn!(
+                    service_id,
+                    path = %path.display(),
+                    error = %err,
+                    "cannot watch path; changes below it will not restart the service"
+                );
+            }
+        }
+        let cancel = shutdown.child_token();
+        tokio::spawn(debounce(
+            service_id.clone(),
+            matcher,
+            raw_rx,
+            spec.debounce,
+            triggers,
+            cancel.clone(),
+        ));
+        Ok(Self {
+            spec: spec.clone(),
+            base: base.to_path_buf(),
+            _watcher: watcher,
+            cancel,
+        })
+    }
+
+    /// Whether this watcher already implements `spec` anchored at `base`.
+    pub(crate) fn is_current(&self, spec: &WatchSpec, base: &Path) -> bool {
+        self.spec == *spec && self.base == base
+    }
+}
+
+/// Collect matching changes until `window` passes without a new one, then emit one trigger.
+async fn debounce(
+    service_id: ServiceID,
+    matcher: Matcher,
+    mut raw: mpsc::Receiver<notify::Result<notify::Event>>,
+    window: Duration,
+    triggers: mpsc::Sender<WatchTrigger>,
+    cancel: CancellationToken,
+) {
+    let mut changed = BTreeSet::new();
+    let mut quiet_at = None;
+    loop {
+        tokio::select! {
+            () = cancel.cancelled() => return,
+            event = raw.recv() => {
+                let Some(event) = event else {
+                    return;
+                };
+                match event {
+                    Ok(event) if !event.kind.is_access() => {
+                        let mut hit = false;
+                        for path in event.paths {
+                            if matcher.matches(&path) {
+                                changed.insert(matcher.relative(&path).display().to_string());
+                                hit = true;
+                            }
+                        }
+                        if hit {
+                            let now = tokio::time::Instant::now();
+                            quiet_at = Some(now.checked_add(window).unwrap_or(now));
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(err) => {
+                        tracing::warn!(service_id, error = %err, "file watcher reported an error");
+                    }
+                }
+            }
+            () = async {
+                match quiet_at {
+                    Some(deadline) => tokio::time::sleep_until(deadline).await,
+                    None => std::future::pending::<()>().await,
+                }
+            } => {
+                quiet_at = None;
+                let trigger = WatchTrigger {
+                    service_id: service_id.clone(),
+                    paths: std::mem::take(&mut changed).into_iter().collect(),
+                };
+                if triggers.send(trigger).await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use color_eyre::eyre;
+    use similar_asserts::assert_eq;
+
+    fn spec(paths: &[&str], ignore: &[&str]) -> WatchSpec {
+        WatchSpec {
+            paths: paths.iter().map(PathBuf::from).collect(),
+            ignore: ignore.iter().map(ToString::to_string).collect(),
+            debounce: Duration::from_millis(10),
+        }
+    }
+
+    #[test]
+    fn glob_prefix_stops_at_the_first_wildcard_component() {
+        assert_eq!(
+            split_glob(Path::new("/repo/src/**/*.rs")),
+            Some(PathBuf::from("/repo/src"))
+        );
+        assert_eq!(
+            split_glob(Path::new("/repo/{a,b}/x")),
+            Some(PathBuf::from("/repo"))
+        );
+        assert_eq!(split_glob(Path::new("/repo/src")), None);
+    }
+
+    #[test]
+    fn matcher_applies_roots_globs_and_ignores() -> eyre::Result<()> {
+        let matcher = Matcher::new(
+            Path::new("/repo"),
+            &spec(
+                &["/repo/src", "/repo/config/*.toml"],
+                &["**/*.tmp", "src/generated/**"],
+            ),
+        )?;
+
+        assert!(matcher.matches(Path::new("/repo/src/main.rs")));
+        assert!(matcher.matches(Path::new("/repo/config/app.toml")));
+        assert!(!matcher.matches(Path::new("/repo/config/app.yaml")));
+        assert!(!matcher.matches(Path::new("/repo/docs/readme.md")));
+        assert!(!matcher.matches(Path::new("/repo/src/scratch.tmp")));
+        assert!(!matcher.matches(Path::new("/repo/src/generated/api.rs")));
+        assert!(!matcher.matches(Path::new("/repo/src/.git/HEAD")));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn debounce_coalesces_a_burst_into_one_trigger() -> eyre::Result<()> {
+        let matcher = Matcher::new(Path::new("/repo"), &spec(&["/repo"], &[]))?;
+        let (raw_tx, raw_rx) = mpsc::channel(8);
+        let (triggers_tx, mut triggers_rx) = mpsc::channel(8);
+        let cancel = CancellationToken::new();
+        let task = tokio::spawn(debounce(
+            "svc".to_string(),
+            matcher,
+            raw_rx,
+            Duration::from_millis(50),
+            triggers_tx,
+            cancel.clone(),
+        ));
+
+        for path in ["/repo/b.rs", "/repo/a.rs", "/repo/b.rs"] {
+            let event =
+                notify::Event::new(notify::EventKind::Modify(notify::event::ModifyKind::Any))
+                    .add_path(PathBuf::from(path));
+            raw_tx.send(Ok(event)).await?;
+        }
+
+        let trigger = tokio::time::timeout(Duration::from_secs(5), triggers_rx.recv())
+            .await?
+            .ok_or_else(|| eyre::eyre!("debounce task stopped without a trigger"))?;
+        assert_eq!(
+            trigger,
+            WatchTrigger {
+                service_id: "svc".to_string(),
+                paths: vec!["a.rs".to_string(), "b.rs".to_string()],
+            }
+        );
+        assert!(triggers_rx.try_recv().is_err());
+
+        cancel.cancel();
+        task.await?;
+        Ok(())
+    }
+}